@@ -0,0 +1,106 @@
+//! Times programs on the stack interpreter alone and then with the register interpreter
+//! running the methods it can, and reports how much faster the second runs are.
+//!
+//! `jvm-bench [-cp classpath] [-jre path] [-n runs] class...`
+
+extern crate jvm;
+
+use std::env;
+use std::process;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use jvm::classpath::classpath::parse;
+use jvm::interpreter::{interpret, set_trace, Schedule};
+use jvm::rtda::frame::Frame;
+use jvm::rtda::heap::class_loader::ClassLoader;
+use jvm::rtda::thread::Thread;
+
+const RUNS: usize = 5;
+
+struct Options {
+    cp_opt: Option<String>,
+    jre_opt: Option<String>,
+    runs: usize,
+    class_names: Vec<String>,
+}
+
+fn usage() -> ! {
+    eprintln!("Usage: jvm-bench [-cp classpath] [-jre path] [-n runs] class...");
+    process::exit(2)
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Options {
+    let mut options = Options {
+        cp_opt: None,
+        jre_opt: None,
+        runs: RUNS,
+        class_names: Vec::new(),
+    };
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-cp" | "-classpath" => options.cp_opt = Some(args.next().unwrap_or_else(|| usage())),
+            "-jre" => options.jre_opt = Some(args.next().unwrap_or_else(|| usage())),
+            "-n" => {
+                let runs = args.next().and_then(|x| x.parse().ok());
+                options.runs = runs.filter(|x| *x > 0).unwrap_or_else(|| usage());
+            }
+            _ if arg.starts_with('-') => usage(),
+            _ => options.class_names.push(arg.replace('.', "/")),
+        }
+    }
+    if options.class_names.is_empty() {
+        usage()
+    }
+    options
+}
+
+/// How long running `main` of `class_name` takes.
+fn time(options: &Options, class_name: &str, stack_only: bool) -> Duration {
+    let class_path = parse(options.jre_opt.clone(), options.cp_opt.clone());
+    let class_loader = ClassLoader::new(class_path);
+    let class = class_loader.load(class_name);
+    let mut thread = Thread::new();
+    thread.vm.stack_only.set(stack_only);
+    // Compiled code would run the hot methods either way
+    #[cfg(feature = "jit")]
+    thread.vm.jit.enabled.set(false);
+    thread.push_frame(Frame::new(Rc::clone(&class), class.main_method()));
+    let start = Instant::now();
+    interpret(&class_loader, thread, Schedule::default());
+    start.elapsed()
+}
+
+fn median(options: &Options, class_name: &str, stack_only: bool) -> Duration {
+    let mut times: Vec<Duration> = (0..options.runs)
+        .map(|_| time(options, class_name, stack_only))
+        .collect();
+    times.sort();
+    times[times.len() / 2]
+}
+
+fn main() {
+    let options = parse_args(env::args().skip(1));
+    // Only the stack interpreter traces, which would dominate its times
+    set_trace(false);
+    let results: Vec<(&String, Duration, Duration)> = options
+        .class_names
+        .iter()
+        .map(|x| (x, median(&options, x, true), median(&options, x, false)))
+        .collect();
+    // The programs' own output comes first, the results last
+    println!(
+        "{:<32} {:>12} {:>12} {:>8}",
+        "class", "stack", "registers", "speedup"
+    );
+    for (class_name, stack, registers) in results {
+        println!(
+            "{:<32} {:>10.3}ms {:>10.3}ms {:>7.2}x",
+            class_name,
+            stack.as_secs_f64() * 1000.0,
+            registers.as_secs_f64() * 1000.0,
+            stack.as_secs_f64() / registers.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,70 @@
+//! Lists the classes more than one directory or jar of a class path has, each with where
+//! it is in the order the class path is searched: the first one is loaded and shadows the
+//! others. Exits with 1 if the class files of a class aren't all the same.
+//!
+//! `jvm-cp-check [-cp classpath] [-jre path] [--release version]`
+
+extern crate jvm;
+
+use std::env;
+use std::process;
+
+use jvm::classpath::classpath::parse;
+
+struct Options {
+    cp_opt: Option<String>,
+    jre_opt: Option<String>,
+    release_opt: Option<u32>,
+}
+
+fn usage() -> ! {
+    eprintln!("Usage: jvm-cp-check [-cp classpath] [-jre path] [--release version]");
+    process::exit(2)
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Options {
+    let mut options = Options {
+        cp_opt: None,
+        jre_opt: None,
+        release_opt: None,
+    };
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-cp" | "-classpath" => options.cp_opt = Some(args.next().unwrap_or_else(|| usage())),
+            "-jre" => options.jre_opt = Some(args.next().unwrap_or_else(|| usage())),
+            "--release" => {
+                let release = args.next().and_then(|x| x.parse().ok());
+                options.release_opt = Some(release.unwrap_or_else(|| usage()));
+            }
+            _ => usage(),
+        }
+    }
+    options
+}
+
+fn main() {
+    let options = parse_args(env::args().skip(1));
+    let mut class_path = parse(options.jre_opt, options.cp_opt);
+    if let Some(release) = options.release_opt {
+        class_path.set_release(release);
+    }
+    let duplicates = class_path.duplicates();
+    for duplicate in &duplicates {
+        let differs = if duplicate.differs { " (differs)" } else { "" };
+        println!("{}{}", duplicate.name.replace('/', "."), differs);
+        for (i, source) in duplicate.sources.iter().enumerate() {
+            let state = if i == 0 { "loaded" } else { "shadowed" };
+            println!("    {:8} {}", state, source);
+        }
+    }
+    let differing = duplicates.iter().filter(|x| x.differs).count();
+    println!(
+        "{} duplicate classes, {} of them differing",
+        duplicates.len(),
+        differing
+    );
+    if differing > 0 {
+        process::exit(1)
+    }
+}
@@ -1,62 +1,132 @@
 extern crate jvm;
 
+use std::cell::RefCell;
+use std::env;
+use std::process;
 use std::rc::Rc;
 
-use jvm::classpath::classpath::parse;
-use jvm::instruction::instruction;
-use jvm::instruction::instruction::ExecuteResult;
+use jvm::classpath::classpath::{jar_main_class, parse_with_module_path};
+use jvm::gc::heap::{Collector, Heap, DEFAULT_MAX_HEAP_SIZE};
+use jvm::instruction::inline_cache;
+use jvm::interpreter;
+use jvm::interpreter::{Schedule, TIME_SLICE};
 use jvm::rtda::frame::Frame;
 use jvm::rtda::heap::class::Class;
 use jvm::rtda::heap::class_loader::ClassLoader;
 use jvm::rtda::heap::method::Method;
+use jvm::rtda::heap::module::ModuleOptions;
 use jvm::rtda::thread::Thread;
-use jvm::shell::command::Command;
+use jvm::shell::command::{parse_memory_size, Command, USAGE};
+use jvm::verifier::Verification;
 
 fn main() {
-    let class_name = "src.test_data.MyObject";
-    let class_name = class_name.replace('.', "/");
-    let command = Command {
-        class_name,
-        cp_opt: None,
-        jre_opt: None,
-        args: vec![],
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = match Command::parse(&args) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            process::exit(2);
+        }
     };
     start_jvm(command);
 }
 
 fn start_jvm(command: Command) {
-    let class_path = parse(command.jre_opt, command.cp_opt);
+    let max_heap_size = match command.xmx_opt {
+        Some(ref xmx) => parse_memory_size(xmx).expect("Invalid maximum heap size"),
+        None => DEFAULT_MAX_HEAP_SIZE,
+    };
+    let collector = match command.gc_opt {
+        Some(ref gc) => gc.parse::<Collector>().unwrap_or_else(|e| panic!("{}", e)),
+        None => Collector::MarkSweep,
+    };
+    let time_slice = match command.time_slice_opt {
+        Some(ref time_slice) => time_slice.parse().expect("Invalid time slice"),
+        None => TIME_SLICE,
+    };
+    let seed = command
+        .seed_opt
+        .as_ref()
+        .map(|x| x.parse().expect("Invalid scheduler seed"));
+    let schedule = Schedule { time_slice, seed };
+    // -jar app.jar runs the Main-Class of app.jar, the jar alone being the class path
+    let (cp_opt, jar_class_name) = match command.jar_opt {
+        Some(ref jar) => {
+            let class_name = jar_main_class(jar)
+                .unwrap_or_else(|| panic!("no main manifest attribute, in {}", jar));
+            (Some(jar.clone()), Some(class_name))
+        }
+        None => (command.cp_opt, None),
+    };
+    let mut class_path = parse_with_module_path(command.jre_opt, cp_opt, command.module_path_opt);
+    if let Some(ref version) = command.jar_version_opt {
+        class_path.set_release(version.parse().expect("Invalid jdk.util.jar.version"));
+    }
     let class_loader = ClassLoader::new(class_path);
-    let (main_class, _) = class_loader.load(command.class_name);
+    class_loader.set_verbose_class(command.verbose_class);
+    if let Some(ref verify) = command.verify_opt {
+        let verification = verify
+            .parse::<Verification>()
+            .unwrap_or_else(|e| panic!("{}", e));
+        class_loader.set_verification(verification);
+    }
+    // -m module/Main runs Main, -m module runs the main class the module names
+    let (main_module, class_name) = match command.module_opt {
+        Some(ref module) => match module.split_once('/') {
+            Some((module, class_name)) => (Some(module), Some(class_name.replace('.', "/"))),
+            None => (Some(module.as_str()), None),
+        },
+        None => (None, jar_class_name.or(Some(command.class_name.clone()))),
+    };
+    class_loader.resolve_modules(&ModuleOptions {
+        main_module: main_module.map(|x| x.to_owned()),
+        add_modules: command.add_modules.clone(),
+        add_exports: command.add_exports.clone(),
+        add_opens: command.add_opens.clone(),
+    });
+    let class_name = class_name.unwrap_or_else(|| {
+        let modules = class_loader.modules().unwrap();
+        let module = main_module.unwrap();
+        modules
+            .descriptor(module)
+            .and_then(|x| x.main_class.clone())
+            .unwrap_or_else(|| {
+                panic!(
+                    "Module {} does not have a ModuleMainClass attribute",
+                    module
+                )
+            })
+    });
+    let main_class = class_loader.load(&class_name);
     let main_method = main_class.main_method();
-    interpret(main_class, main_method)
-}
-
-fn interpret(class: Rc<Class>, method: Rc<Method>) {
-    let thread = Thread::new();
-    let frame = Frame::new(class, method);
-    let thread = thread.push_frame(frame);
-    execute(thread);
+    let heap = Rc::new(RefCell::new(Heap::with_collector(max_heap_size, collector)));
+    interpret(
+        &class_loader,
+        heap,
+        main_class,
+        main_method,
+        schedule,
+        command.xint,
+    );
+    if command.print_inline_caches {
+        print!("{}", inline_cache::dump(&class_loader));
+    }
 }
 
-fn execute(thread: Thread) {
-    let mut mut_pc = 0usize;
-    let mut mut_thread = thread;
-    while !mut_thread.is_stack_empty() {
-        let thread = mut_thread;
-        let pc = mut_pc;
-
-        let (execute_result, after_execute) = instruction::execute(mut_pc, thread);
-        let ExecuteResult { thread, offset } = execute_result;
-
-        mut_pc = match offset {
-            0 => after_execute.pc,
-            i => (pc as isize + i) as usize,
-        };
-        mut_thread = thread;
-
-        println!("pc: {}", pc);
-        println!("offset: {}", offset);
-        println!("mut_pc: {}", mut_pc);
+fn interpret(
+    class_loader: &Rc<ClassLoader>,
+    heap: Rc<RefCell<Heap>>,
+    class: Rc<Class>,
+    method: Rc<Method>,
+    schedule: Schedule,
+    xint: bool,
+) {
+    let mut thread = Thread::with_heap(heap);
+    if xint {
+        // Without the jit feature everything is interpreted anyway
+        #[cfg(feature = "jit")]
+        thread.vm.jit.enabled.set(false);
     }
+    thread.push_frame(Frame::new(class, method));
+    interpreter::interpret(class_loader, thread, schedule);
 }
@@ -1,6 +1,6 @@
 use std::rc::Rc;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ExceptionTableEntry {
     pub start_pc: u16,
     pub end_pc: u16,
@@ -23,6 +23,87 @@ pub struct LocalVariableTableEntry {
     pub index: u16,
 }
 
+/// A module a module requires, see
+/// [§4.7.25](https://docs.oracle.com/javase/specs/jvms/se9/html/jvms-4.html#jvms-4.7.25).
+#[derive(Debug)]
+pub struct ModuleRequires {
+    pub requires_index: u16,
+    pub requires_flags: u16,
+    pub requires_version_index: u16,
+}
+
+/// A package a module exports or opens, to the modules of `to_index` or to every module
+/// if there's none.
+#[derive(Debug)]
+pub struct ModulePackage {
+    pub package_index: u16,
+    pub flags: u16,
+    pub to_index: Vec<u16>,
+}
+
+/// A service a module provides, with the classes that implement it.
+#[derive(Debug)]
+pub struct ModuleProvides {
+    pub provides_index: u16,
+    pub provides_with_index: Vec<u16>,
+}
+
+/// See [§4.7.4](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-4.html#jvms-4.7.4).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationTypeInfo {
+    Top,
+    Integer,
+    Float,
+    Double,
+    Long,
+    Null,
+    UninitializedThis,
+    Object {
+        cpool_index: u16,
+    },
+    /// Created by the `new` instruction at `offset`.
+    Uninitialized {
+        offset: u16,
+    },
+}
+
+/// A frame of the `StackMapTable` attribute, which applies at the offset of the previous
+/// frame plus `offset_delta` plus 1, or at `offset_delta` for the first frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StackMapFrame {
+    /// The locals of the previous frame and an empty stack.
+    Same { offset_delta: u16 },
+    /// The locals of the previous frame and a stack of one item.
+    SameLocals1StackItem {
+        offset_delta: u16,
+        stack: VerificationTypeInfo,
+    },
+    /// The locals of the previous frame but the last `k`, and an empty stack.
+    Chop { offset_delta: u16, k: u8 },
+    /// The locals of the previous frame plus `locals`, and an empty stack.
+    Append {
+        offset_delta: u16,
+        locals: Vec<VerificationTypeInfo>,
+    },
+    Full {
+        offset_delta: u16,
+        locals: Vec<VerificationTypeInfo>,
+        stack: Vec<VerificationTypeInfo>,
+    },
+}
+
+impl StackMapFrame {
+    pub fn offset_delta(&self) -> u16 {
+        match self {
+            StackMapFrame::Same { offset_delta }
+            | StackMapFrame::SameLocals1StackItem { offset_delta, .. }
+            | StackMapFrame::Chop { offset_delta, .. }
+            | StackMapFrame::Append { offset_delta, .. }
+            | StackMapFrame::Full { offset_delta, .. } => *offset_delta,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum AttributeInfo {
     Code {
@@ -53,4 +134,23 @@ pub enum AttributeInfo {
     LocalVariableTable {
         local_variable_table: Vec<LocalVariableTableEntry>,
     },
+    StackMapTable {
+        entries: Vec<StackMapFrame>,
+    },
+    Module {
+        module_name_index: u16,
+        module_flags: u16,
+        module_version_index: u16,
+        requires: Vec<ModuleRequires>,
+        exports: Vec<ModulePackage>,
+        opens: Vec<ModulePackage>,
+        uses_index: Vec<u16>,
+        provides: Vec<ModuleProvides>,
+    },
+    ModulePackages {
+        package_index: Vec<u16>,
+    },
+    ModuleMainClass {
+        main_class_index: u16,
+    },
 }
@@ -36,4 +36,11 @@ impl ClassFile {
             ""
         }
     }
+
+    pub fn interface_names(&self) -> Vec<String> {
+        self.interfaces
+            .iter()
+            .map(|x| self.constant_pool.get_class_name(*x as usize).to_owned())
+            .collect()
+    }
 }
@@ -6,6 +6,7 @@ use vec_map::VecMap;
 
 use crate::classfile::attribute_info::{
     AttributeInfo, ExceptionTableEntry, LineNumberTableEntry, LocalVariableTableEntry,
+    ModulePackage, ModuleProvides, ModuleRequires, StackMapFrame, VerificationTypeInfo,
 };
 use crate::classfile::class_file::ClassFile;
 use crate::classfile::constant_info::ConstantInfo;
@@ -26,6 +27,11 @@ const CONSTANT_FIELDREF: u8 = 9;
 const CONSTANT_METHODREF: u8 = 10;
 const CONSTANT_INTERFACE_METHODREF: u8 = 11;
 const CONSTANT_NAME_AND_TYPE: u8 = 12;
+const CONSTANT_MODULE: u8 = 19;
+const CONSTANT_PACKAGE: u8 = 20;
+
+/// The access flag of `module-info.class`, which may have a later version than classes.
+const ACC_MODULE: u16 = 0x8000;
 
 #[derive(Debug)]
 pub struct VersionInfo {
@@ -44,7 +50,7 @@ pub trait ClassReader {
     fn read_f64(&self) -> (f64, &[u8]);
     fn read_bytes(&self, n: usize) -> (&[u8], &[u8]);
     fn read_and_check_magic(&self) -> (u32, &[u8]);
-    fn read_and_check_version(&self) -> (VersionInfo, &[u8]);
+    fn read_version(&self) -> (VersionInfo, &[u8]);
     fn read_constant_info(&self) -> (ConstantInfo, &[u8]);
     fn read_constant_pool(&self) -> (ConstantPool, &[u8]);
     fn read_access_flags(&self) -> (u16, &[u8]);
@@ -56,6 +62,13 @@ pub trait ClassReader {
     fn read_exception_table(&self) -> (Vec<ExceptionTableEntry>, &[u8]);
     fn read_line_number_table(&self) -> (Vec<LineNumberTableEntry>, &[u8]);
     fn read_local_variable_table(&self) -> (Vec<LocalVariableTableEntry>, &[u8]);
+    fn read_verification_type_info(&self) -> (VerificationTypeInfo, &[u8]);
+    fn read_verification_type_infos(&self, n: usize) -> (Vec<VerificationTypeInfo>, &[u8]);
+    fn read_stack_map_frame(&self) -> (StackMapFrame, &[u8]);
+    fn read_stack_map_table(&self) -> (Vec<StackMapFrame>, &[u8]);
+    fn read_module_requires(&self) -> (Vec<ModuleRequires>, &[u8]);
+    fn read_module_packages(&self) -> (Vec<ModulePackage>, &[u8]);
+    fn read_module_provides(&self) -> (Vec<ModuleProvides>, &[u8]);
     fn read_attribute(&self, constant_pool: &ConstantPool) -> (AttributeInfo, &[u8]);
     fn read_attributes(&self, constant_pool: &ConstantPool) -> (Vec<AttributeInfo>, &[u8]);
     fn parse(&self) -> ClassFile;
@@ -120,11 +133,9 @@ impl ClassReader for [u8] {
         result
     }
 
-    fn read_and_check_version(&self) -> (VersionInfo, &[u8]) {
+    fn read_version(&self) -> (VersionInfo, &[u8]) {
         let (minor_version, after_minor_version) = self.read_u16();
         let (major_version, after_major_version) = after_minor_version.read_u16();
-        assert_eq!(major_version, 52);
-        assert_eq!(minor_version, 0);
         let version_info = VersionInfo {
             major_version,
             minor_version,
@@ -209,6 +220,14 @@ impl ClassReader for [u8] {
                     rest,
                 )
             }
+            CONSTANT_MODULE => {
+                let (name_index, rest) = after_tag.read_u16();
+                (ConstantInfo::Module { name_index }, rest)
+            }
+            CONSTANT_PACKAGE => {
+                let (name_index, rest) = after_tag.read_u16();
+                (ConstantInfo::Package { name_index }, rest)
+            }
             _ => {
                 panic!("Wrong tag type");
             }
@@ -357,6 +376,179 @@ impl ClassReader for [u8] {
         (local_variable_table, rest)
     }
 
+    fn read_verification_type_info(&self) -> (VerificationTypeInfo, &[u8]) {
+        let (tag, after_tag) = self.read_u8();
+        match tag {
+            0 => (VerificationTypeInfo::Top, after_tag),
+            1 => (VerificationTypeInfo::Integer, after_tag),
+            2 => (VerificationTypeInfo::Float, after_tag),
+            3 => (VerificationTypeInfo::Double, after_tag),
+            4 => (VerificationTypeInfo::Long, after_tag),
+            5 => (VerificationTypeInfo::Null, after_tag),
+            6 => (VerificationTypeInfo::UninitializedThis, after_tag),
+            7 => {
+                let (cpool_index, after_cpool_index) = after_tag.read_u16();
+                (
+                    VerificationTypeInfo::Object { cpool_index },
+                    after_cpool_index,
+                )
+            }
+            8 => {
+                let (offset, after_offset) = after_tag.read_u16();
+                (VerificationTypeInfo::Uninitialized { offset }, after_offset)
+            }
+            _ => panic!("java.lang.ClassFormatError: Unknown verification type {}", tag),
+        }
+    }
+
+    fn read_verification_type_infos(&self, n: usize) -> (Vec<VerificationTypeInfo>, &[u8]) {
+        let mut infos = Vec::with_capacity(n);
+        let mut rest = self;
+        for _ in 0..n {
+            let (info, next_rest) = rest.read_verification_type_info();
+            infos.push(info);
+            rest = next_rest;
+        }
+        (infos, rest)
+    }
+
+    fn read_stack_map_frame(&self) -> (StackMapFrame, &[u8]) {
+        let (frame_type, after_frame_type) = self.read_u8();
+        match frame_type {
+            0..=63 => (
+                StackMapFrame::Same {
+                    offset_delta: frame_type as u16,
+                },
+                after_frame_type,
+            ),
+            64..=127 => {
+                let (stack, after_stack) = after_frame_type.read_verification_type_info();
+                (
+                    StackMapFrame::SameLocals1StackItem {
+                        offset_delta: frame_type as u16 - 64,
+                        stack,
+                    },
+                    after_stack,
+                )
+            }
+            247 => {
+                let (offset_delta, after_offset_delta) = after_frame_type.read_u16();
+                let (stack, after_stack) = after_offset_delta.read_verification_type_info();
+                (
+                    StackMapFrame::SameLocals1StackItem {
+                        offset_delta,
+                        stack,
+                    },
+                    after_stack,
+                )
+            }
+            248..=250 => {
+                let (offset_delta, after_offset_delta) = after_frame_type.read_u16();
+                let k = 251 - frame_type;
+                (StackMapFrame::Chop { offset_delta, k }, after_offset_delta)
+            }
+            251 => {
+                let (offset_delta, after_offset_delta) = after_frame_type.read_u16();
+                (StackMapFrame::Same { offset_delta }, after_offset_delta)
+            }
+            252..=254 => {
+                let (offset_delta, after_offset_delta) = after_frame_type.read_u16();
+                let n = (frame_type - 251) as usize;
+                let (locals, after_locals) = after_offset_delta.read_verification_type_infos(n);
+                (
+                    StackMapFrame::Append {
+                        offset_delta,
+                        locals,
+                    },
+                    after_locals,
+                )
+            }
+            255 => {
+                let (offset_delta, after_offset_delta) = after_frame_type.read_u16();
+                let (number_of_locals, after_number_of_locals) = after_offset_delta.read_u16();
+                let (locals, after_locals) =
+                    after_number_of_locals.read_verification_type_infos(number_of_locals as usize);
+                let (number_of_stack_items, after_number_of_stack_items) =
+                    after_locals.read_u16();
+                let (stack, after_stack) = after_number_of_stack_items
+                    .read_verification_type_infos(number_of_stack_items as usize);
+                (
+                    StackMapFrame::Full {
+                        offset_delta,
+                        locals,
+                        stack,
+                    },
+                    after_stack,
+                )
+            }
+            _ => panic!("java.lang.ClassFormatError: Unknown frame type {}", frame_type),
+        }
+    }
+
+    fn read_stack_map_table(&self) -> (Vec<StackMapFrame>, &[u8]) {
+        let (number_of_entries, after_number_of_entries) = self.read_u16();
+        let mut entries = Vec::with_capacity(number_of_entries as usize);
+        let mut rest = after_number_of_entries;
+        for _ in 0..number_of_entries {
+            let (frame, next_rest) = rest.read_stack_map_frame();
+            entries.push(frame);
+            rest = next_rest;
+        }
+        (entries, rest)
+    }
+
+    fn read_module_requires(&self) -> (Vec<ModuleRequires>, &[u8]) {
+        let (count, after_count) = self.read_u16();
+        let mut requires = Vec::with_capacity(count as usize);
+        let mut rest = after_count;
+        for _ in 0..count {
+            let (requires_index, after_requires_index) = rest.read_u16();
+            let (requires_flags, after_requires_flags) = after_requires_index.read_u16();
+            let (requires_version_index, next_rest) = after_requires_flags.read_u16();
+            requires.push(ModuleRequires {
+                requires_index,
+                requires_flags,
+                requires_version_index,
+            });
+            rest = next_rest;
+        }
+        (requires, rest)
+    }
+
+    fn read_module_packages(&self) -> (Vec<ModulePackage>, &[u8]) {
+        let (count, after_count) = self.read_u16();
+        let mut packages = Vec::with_capacity(count as usize);
+        let mut rest = after_count;
+        for _ in 0..count {
+            let (package_index, after_package_index) = rest.read_u16();
+            let (flags, after_flags) = after_package_index.read_u16();
+            let (to_index, next_rest) = after_flags.read_u16s();
+            packages.push(ModulePackage {
+                package_index,
+                flags,
+                to_index,
+            });
+            rest = next_rest;
+        }
+        (packages, rest)
+    }
+
+    fn read_module_provides(&self) -> (Vec<ModuleProvides>, &[u8]) {
+        let (count, after_count) = self.read_u16();
+        let mut provides = Vec::with_capacity(count as usize);
+        let mut rest = after_count;
+        for _ in 0..count {
+            let (provides_index, after_provides_index) = rest.read_u16();
+            let (provides_with_index, next_rest) = after_provides_index.read_u16s();
+            provides.push(ModuleProvides {
+                provides_index,
+                provides_with_index,
+            });
+            rest = next_rest;
+        }
+        (provides, rest)
+    }
+
     fn read_attribute(&self, constant_pool: &ConstantPool) -> (AttributeInfo, &[u8]) {
         let (attribute_name_index, after_attribute_name_index) = self.read_u16();
         let attribute_name = match constant_pool.get(attribute_name_index as usize) {
@@ -435,6 +627,50 @@ impl ClassReader for [u8] {
                     after_local_variable_table,
                 )
             }
+            "StackMapTable" => {
+                let (entries, after_entries) = after_attribute_length.read_stack_map_table();
+                (AttributeInfo::StackMapTable { entries }, after_entries)
+            }
+            "Module" => {
+                let (module_name_index, after_module_name_index) =
+                    after_attribute_length.read_u16();
+                let (module_flags, after_module_flags) = after_module_name_index.read_u16();
+                let (module_version_index, after_module_version_index) =
+                    after_module_flags.read_u16();
+                let (requires, after_requires) = after_module_version_index.read_module_requires();
+                let (exports, after_exports) = after_requires.read_module_packages();
+                let (opens, after_opens) = after_exports.read_module_packages();
+                let (uses_index, after_uses_index) = after_opens.read_u16s();
+                let (provides, after_provides) = after_uses_index.read_module_provides();
+                (
+                    AttributeInfo::Module {
+                        module_name_index,
+                        module_flags,
+                        module_version_index,
+                        requires,
+                        exports,
+                        opens,
+                        uses_index,
+                        provides,
+                    },
+                    after_provides,
+                )
+            }
+            "ModulePackages" => {
+                let (package_index, after_package_index) = after_attribute_length.read_u16s();
+                (
+                    AttributeInfo::ModulePackages { package_index },
+                    after_package_index,
+                )
+            }
+            "ModuleMainClass" => {
+                let (main_class_index, after_main_class_index) =
+                    after_attribute_length.read_u16();
+                (
+                    AttributeInfo::ModuleMainClass { main_class_index },
+                    after_main_class_index,
+                )
+            }
             _ => {
                 let (_, after_attribute_info) =
                     after_attribute_length.read_bytes(attribute_length as usize);
@@ -464,13 +700,24 @@ impl ClassReader for [u8] {
 
     fn parse(&self) -> ClassFile {
         let (_, after_magic) = self.read_and_check_magic();
-        let (version_info, after_version_info) = after_magic.read_and_check_version();
+        let (version_info, after_version_info) = after_magic.read_version();
         let VersionInfo {
             major_version,
             minor_version,
         } = version_info;
         let (constant_pool, after_constant_pool) = after_version_info.read_constant_pool();
         let (access_flags, after_access_flags) = after_constant_pool.read_access_flags();
+        let supported = if access_flags & ACC_MODULE != 0 {
+            major_version >= 53
+        } else {
+            (45..=52).contains(&major_version)
+        };
+        if !supported || (major_version > 45 && minor_version != 0) {
+            panic!(
+                "java.lang.UnsupportedClassVersionError: {}.{}",
+                major_version, minor_version
+            )
+        }
         let (this_class, after_this_class) = after_access_flags.read_this_class();
         let (super_class, after_super_class) = after_this_class.read_this_class();
         let (interfaces, after_interfaces) = after_super_class.read_interfaces();
@@ -521,8 +768,12 @@ mod tests {
             methods,
             attributes,
         } = bytes.parse();
-        assert_eq!(major_version, 52);
-        assert_eq!(minor_version, 0);
+        if !(45..=52).contains(&major_version) || (major_version > 45 && minor_version != 0) {
+            panic!(
+                "java.lang.UnsupportedClassVersionError: {}.{}",
+                major_version, minor_version
+            )
+        }
         assert_eq!(constant_pool.capacity(), 79);
         match constant_pool.get(1) {
             ConstantInfo::Class { name_index } => assert_eq!(*name_index, 49u16),
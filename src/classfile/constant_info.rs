@@ -25,4 +25,12 @@ pub enum ConstantInfo {
         class_index: u16,
         name_and_type_index: u16,
     },
+    /// Only in `module-info.class`.
+    Module {
+        name_index: u16,
+    },
+    /// Only in `module-info.class`.
+    Package {
+        name_index: u16,
+    },
 }
@@ -20,7 +20,7 @@ impl ConstantPool {
         self.vec_map.capacity()
     }
 
-    fn get_utf8(&self, index: usize) -> &str {
+    pub fn get_utf8(&self, index: usize) -> &str {
         match self.get(index) {
             ConstantInfo::UTF8(ref name) => name,
             _ => panic!("index isn't to UTF8"),
@@ -36,7 +36,31 @@ impl ConstantPool {
         self.get_utf8(*name_index as usize)
     }
 
-    // fn get_class_ref(&self, index: usize) -> &str {
+    pub fn get_module_name(&self, index: usize) -> &str {
+        match self.get(index) {
+            ConstantInfo::Module { name_index } => self.get_utf8(*name_index as usize),
+            _ => panic!("index isn't to Module"),
+        }
+    }
+
+    /// The name of a package in internal form, like `java/lang`.
+    pub fn get_package_name(&self, index: usize) -> &str {
+        match self.get(index) {
+            ConstantInfo::Package { name_index } => self.get_utf8(*name_index as usize),
+            _ => panic!("index isn't to Package"),
+        }
+    }
 
-    // }
+    pub fn get_name_and_type(&self, index: usize) -> (&str, &str) {
+        match self.get(index) {
+            ConstantInfo::NameAndType {
+                name_index,
+                descriptor_index,
+            } => (
+                self.get_utf8(*name_index as usize),
+                self.get_utf8(*descriptor_index as usize),
+            ),
+            _ => panic!("index isn't to NameAndType"),
+        }
+    }
 }
@@ -1,8 +1,11 @@
 extern crate zip;
 
+use std::cell::OnceCell;
+use std::collections::{HashMap, HashSet};
 use std::env;
-use std::fs::File;
+use std::fmt;
 use std::fs::read_dir;
+use std::fs::File;
 use std::io;
 use std::io::Error;
 use std::io::ErrorKind;
@@ -10,37 +13,80 @@ use std::io::Read;
 use std::path::Path;
 use std::path::PathBuf;
 
+use crate::classpath::jar::{Jar, NESTED_SEPARATOR};
+use crate::classpath::jimage::Jimage;
+
+/// Separates the entries of a class path, like `File.pathSeparatorChar`.
+pub const PATH_SEPARATOR: char = if cfg!(windows) { ';' } else { ':' };
+
+/// The feature version multi-release jars are read for by default, the last one whose
+/// classes the VM runs.
+pub const DEFAULT_RELEASE: u32 = 8;
+
 #[derive(Debug)]
 enum Entry {
     Dir { path: PathBuf },
-    Wildcard { path_vec: Vec<PathBuf> },
-    Zip { path: PathBuf },
+    /// The jars of a directory.
+    Wildcard { entries: Vec<Entry> },
+    /// A jar, or a jar nested in one like `app.jar!/BOOT-INF/lib/x.jar`.
+    Zip { jar: Jar },
+    /// Entries separated by `PATH_SEPARATOR`, searched in order.
+    Composite { entries: Vec<Entry> },
+    /// The `lib/modules` runtime image of JDK 9 and later.
+    Jimage { image: Jimage },
+    /// A `.jmod` file, a zip after a `JM` header with its classes under `classes/`.
+    Jmod { jar: Jar },
+    /// A module exploded to a directory, with its `module-info.class` at the top.
+    Exploded { path: PathBuf },
 }
 
+/// Where a `.jmod` file keeps its classes.
+const JMOD_CLASSES: &str = "classes/";
+
 impl Entry {
     fn new(path: &str) -> Entry {
-        if path.ends_with("*") {
+        if path.contains(PATH_SEPARATOR) {
+            let entries = path
+                .split(PATH_SEPARATOR)
+                .filter(|x| !x.is_empty())
+                .map(Entry::new)
+                .collect();
+            Entry::Composite { entries }
+        } else if path.ends_with("*") {
             //            println!("Entry::new Wildcard {}", path);
             let len = path.len();
             let base_path = &path[..len - 1];
             //            println!("base_path {:?}", base_path);
-            let path_vec: Vec<PathBuf> = read_dir(base_path)
-                .unwrap()
-                .map(|x| x.unwrap())
-                .map(|x| x.path())
-                .filter(|x| {
-                    x.extension()
-                        .map(|x| x.to_str().unwrap() == "jar")
-                        .unwrap_or(false)
-                })
-                .collect();
+            if let Some((outer, dir)) = base_path.split_once(NESTED_SEPARATOR) {
+                return Entry::Wildcard {
+                    entries: nested_jars(Path::new(outer), dir),
+                };
+            }
+            // A directory that doesn't exist has no jars, like `jre/lib/ext` often
+            let mut path_vec: Vec<PathBuf> = match read_dir(base_path) {
+                Ok(dir) => dir
+                    .map(|x| x.unwrap())
+                    .map(|x| x.path())
+                    .filter(|x| {
+                        x.extension()
+                            .map(|x| x.to_str().unwrap() == "jar")
+                            .unwrap_or(false)
+                    })
+                    .collect(),
+                Err(_) => Vec::new(),
+            };
             //            println!("path_vec {:?}", path_vec);
+            path_vec.sort();
+            let entries = path_vec
+                .iter()
+                .map(|x| Entry::Zip { jar: Jar::new(x) })
+                .collect();
 
-            Entry::Wildcard { path_vec }
+            Entry::Wildcard { entries }
         } else if path.ends_with(".jar") {
             //            println!("Entry::new Zip {}", path);
             Entry::Zip {
-                path: Path::new(path).to_owned(),
+                jar: Jar::new(Path::new(path)),
             }
         } else {
             //            println!("Entry::new Dir {}", path);
@@ -50,9 +96,45 @@ impl Entry {
         }
     }
 
+    /// A module on a module path: a `.jmod` file, a jar or an exploded module, or a
+    /// directory of those.
+    fn new_module(path: &str) -> Entry {
+        let path = Path::new(path);
+        if path.join("module-info.class").is_file() {
+            return Entry::Exploded {
+                path: path.to_owned(),
+            };
+        }
+        match path.extension().and_then(|x| x.to_str()) {
+            Some("jmod") => Entry::Jmod {
+                jar: Jar::new(path),
+            },
+            Some("jar") => Entry::Zip {
+                jar: Jar::new(path),
+            },
+            _ => {
+                let mut paths: Vec<PathBuf> = match read_dir(path) {
+                    Ok(dir) => dir.filter_map(|x| x.ok()).map(|x| x.path()).collect(),
+                    Err(_) => Vec::new(),
+                };
+                paths.sort();
+                let entries = paths
+                    .iter()
+                    .filter(|x| {
+                        let extension = x.extension().and_then(|x| x.to_str());
+                        x.join("module-info.class").is_file()
+                            || matches!(extension, Some("jmod" | "jar"))
+                    })
+                    .map(|x| Entry::new_module(x.to_str().unwrap()))
+                    .collect();
+                Entry::Composite { entries }
+            }
+        }
+    }
+
     fn read_class(&self, class_file_name: &str) -> Result<Vec<u8>, io::Error> {
         match self {
-            Entry::Dir { path } => {
+            Entry::Dir { path } | Entry::Exploded { path } => {
                 //                println!("read class {} using Dir", class_file_name);
                 let filepath = Path::new(path).join(class_file_name);
                 let mut file = File::open(filepath)?;
@@ -61,46 +143,506 @@ impl Entry {
                 file.read_to_end(&mut buf)?;
                 Ok(buf)
             }
-            Entry::Wildcard { path_vec } => {
-                //                println!("read class {} using Wildcard", class_file_name);
-                path_vec
-                    .iter()
-                    .map(|x| Entry::new(x.to_str().unwrap()))
-                    .map(|x| x.read_class(class_file_name))
-                    .find(|x| x.is_ok())
-                    .unwrap_or(Err(Error::new(ErrorKind::Other, "Class not found")))
-            }
-            Entry::Zip { path } => {
-                //                println!("read class {} using Zip", class_file_name);
-                let file = File::open(path)?;
-                let mut zip = zip::ZipArchive::new(file)?;
-                let mut file = zip.by_name(&class_file_name)?;
-                let mut buf = Vec::<u8>::with_capacity(file.size() as usize);
-                file.read_to_end(&mut buf);
-                Ok(buf)
+            Entry::Zip { jar } => jar.read(class_file_name),
+            Entry::Jimage { image } => image.read_class(class_file_name),
+            Entry::Jmod { jar } => jar.read(&(JMOD_CLASSES.to_owned() + class_file_name)),
+            Entry::Wildcard { entries } | Entry::Composite { entries } => entries
+                .iter()
+                .map(|x| x.read_class(class_file_name))
+                .find(|x| x.is_ok())
+                .unwrap_or(Err(Error::other("Class not found"))),
+        }
+    }
+
+    /// The module at the entry, if it's a `.jmod` file, a jar or an exploded module.
+    fn module(&self) -> Option<ModuleLocation> {
+        let (path, names) = match self {
+            Entry::Dir { path } | Entry::Exploded { path } => {
+                let mut names = Vec::new();
+                class_files(path, "", &mut names);
+                (path.clone(), names)
+            }
+            Entry::Jmod { jar } | Entry::Zip { jar } => {
+                let prefix = if let Entry::Jmod { .. } = self {
+                    JMOD_CLASSES
+                } else {
+                    ""
+                };
+                let names = jar
+                    .names()
+                    .into_iter()
+                    .filter_map(|(x, _)| Some(x.strip_prefix(prefix)?.to_owned()))
+                    .collect();
+                (jar.path().to_owned(), names)
+            }
+            _ => return None,
+        };
+        let module_info = self.read_class("module-info.class").ok();
+        let mut packages: Vec<String> = names
+            .iter()
+            .filter(|x| x.ends_with(".class") && !x.starts_with("META-INF/"))
+            .filter_map(|x| Some(x.rsplit_once('/')?.0.to_owned()))
+            .collect();
+        packages.sort();
+        packages.dedup();
+        Some(ModuleLocation {
+            path,
+            module_info,
+            packages,
+        })
+    }
+
+    /// Whether the directory or jar has the file `name`, the one for `release` in a
+    /// multi-release jar.
+    fn has(&self, name: &str, release: u32) -> bool {
+        match self {
+            Entry::Dir { path } | Entry::Exploded { path } => path.join(name).is_file(),
+            Entry::Zip { jar } => jar.find(name, release).is_some(),
+            Entry::Jmod { jar } => jar.contains(&(JMOD_CLASSES.to_owned() + name)),
+            Entry::Jimage { image } => image.url(name).is_some(),
+            Entry::Wildcard { .. } | Entry::Composite { .. } => false,
+        }
+    }
+
+    /// The URL of the file `name` of the directory or jar, like `file:/classes/pkg/A.class`,
+    /// `jar:file:/lib/a.jar!/pkg/A.class` or `jrt:/java.base/java/lang/Object.class`.
+    fn url(&self, name: &str) -> Option<String> {
+        let file = |x: &Path| canonical(x).display().to_string();
+        match self {
+            Entry::Dir { path } | Entry::Exploded { path } => {
+                Some(format!("file:{}", file(&path.join(name))))
+            }
+            Entry::Zip { jar } => match jar.nested() {
+                Some((outer, nested)) => Some(format!(
+                    "jar:file:{}!/{}!/{}",
+                    file(outer),
+                    nested,
+                    name
+                )),
+                None => Some(format!("jar:file:{}!/{}", file(jar.path()), name)),
+            },
+            Entry::Jmod { jar } => Some(format!(
+                "jar:file:{}!/{}{}",
+                file(jar.path()),
+                JMOD_CLASSES,
+                name
+            )),
+            Entry::Jimage { image } => image.url(name),
+            Entry::Wildcard { .. } | Entry::Composite { .. } => None,
+        }
+    }
+
+    /// Where the file `name` of the directory or jar is, at `zip_index` in a jar or `.jmod`
+    /// file if it's known.
+    fn code_source(&self, name: &str, zip_index: Option<usize>) -> CodeSource {
+        let (path, entry) = match self {
+            Entry::Dir { path } | Entry::Exploded { path } => (path.clone(), None),
+            Entry::Zip { jar } | Entry::Jmod { jar } => {
+                let entry = match (zip_index, self) {
+                    (Some(zip_index), _) => jar.name_at(zip_index),
+                    (None, Entry::Jmod { .. }) => Some(JMOD_CLASSES.to_owned() + name),
+                    (None, _) => Some(name.to_owned()),
+                };
+                (jar.path().to_owned(), entry)
+            }
+            Entry::Jimage { image } => {
+                let url = image.url(name);
+                let entry = url.and_then(|x| Some(x.strip_prefix("jrt:/")?.to_owned()));
+                (image.path().to_owned(), entry)
+            }
+            Entry::Wildcard { .. } | Entry::Composite { .. } => (PathBuf::new(), None),
+        };
+        CodeSource { path, entry }
+    }
+
+    /// The directories and jars of the entry, in the order they're searched.
+    fn leaves<'a>(&'a self, leaves: &mut Vec<&'a Entry>) {
+        match self {
+            Entry::Dir { .. }
+            | Entry::Zip { .. }
+            | Entry::Jimage { .. }
+            | Entry::Jmod { .. }
+            | Entry::Exploded { .. } => leaves.push(self),
+            Entry::Wildcard { entries } | Entry::Composite { entries } => {
+                for entry in entries {
+                    entry.leaves(leaves);
+                }
+            }
+        }
+    }
+}
+
+/// The jars in the directory `dir` of the jar at `outer`, like `BOOT-INF/lib/`, as nested
+/// jars.
+fn nested_jars(outer: &Path, dir: &str) -> Vec<Entry> {
+    let mut names: Vec<String> = Jar::new(outer)
+        .names()
+        .into_iter()
+        .map(|(name, _)| name)
+        .filter(|x| {
+            let name = x.strip_prefix(dir).unwrap_or("");
+            name.ends_with(".jar") && !name.contains('/')
+        })
+        .collect();
+    names.sort();
+    names
+        .iter()
+        .map(|x| {
+            let path = format!("{}{}{}", outer.display(), NESTED_SEPARATOR, x);
+            Entry::Zip {
+                jar: Jar::new(Path::new(&path)),
             }
+        })
+        .collect()
+}
+
+/// The names of the files under `dir`, like `pkg/A.class`, each after `prefix`.
+fn class_files(dir: &Path, prefix: &str, names: &mut Vec<String>) {
+    let Ok(entries) = read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|x| x.ok()) {
+        let name = prefix.to_owned() + &entry.file_name().to_string_lossy();
+        if entry.path().is_dir() {
+            class_files(&entry.path(), &(name + "/"), names);
+        } else {
+            names.push(name);
+        }
+    }
+}
+
+/// A module on the module path, before it's resolved.
+#[derive(Debug)]
+pub struct ModuleLocation {
+    pub path: PathBuf,
+    /// `module-info.class`, which an automatic module doesn't have.
+    pub module_info: Option<Vec<u8>>,
+    /// The packages with classes, like `java/lang`.
+    pub packages: Vec<String>,
+}
+
+/// Where a class file was read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeSource {
+    /// The directory, jar, `.jmod` file or runtime image.
+    pub path: PathBuf,
+    /// The name of the class file in a jar, `.jmod` file or runtime image, like
+    /// `META-INF/versions/11/pkg/A.class` in a multi-release jar, `None` in a directory.
+    pub entry: Option<String>,
+}
+
+/// Like `lib/a.jar!/pkg/A.class`, or the directory alone.
+impl fmt::Display for CodeSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.entry {
+            Some(entry) => write!(f, "{}!/{}", self.path.display(), entry),
+            None => write!(f, "{}", self.path.display()),
         }
     }
 }
 
+/// A class more than one directory or jar has, the first one shadowing the others.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Duplicate {
+    /// Like `pkg/A`.
+    pub name: String,
+    /// Where the class is, in the order they're searched, the first being the one loaded.
+    pub sources: Vec<CodeSource>,
+    /// Whether the class files aren't all the same.
+    pub differs: bool,
+}
+
+/// Which part of the class path a class was found in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassOrigin {
+    Boot,
+    /// The extension class path, `jre/lib/ext`.
+    Ext,
+    /// The module path, `--module-path`.
+    Module,
+    User,
+}
+
 #[derive(Debug)]
 pub struct ClassPath {
     boot: Entry,
+    ext: Entry,
+    modules: Entry,
     user: Entry,
+    /// The file names in every jar and `.jmod` file, each with the index of the first
+    /// directory or jar among the `leaves` that has it and its index in that jar, built
+    /// on the first lookup so that a jar is only searched through once.
+    index: OnceCell<HashMap<String, (usize, usize)>>,
+    /// The feature version of the JDK the VM emulates, which chooses the files multi-release
+    /// jars serve.
+    release: u32,
 }
 
 impl ClassPath {
+    /// Reads multi-release jars as a JDK of the feature version `release` would, like
+    /// `-Djdk.util.jar.version` does.
+    pub fn set_release(&mut self, release: u32) {
+        self.release = release;
+        self.index = OnceCell::new();
+    }
+
     pub fn read_class(&self, name: &str) -> Result<Vec<u8>, io::Error> {
+        self.find_class(name).map(|(data, _)| data)
+    }
+
+    /// Reads a class like `read_class`, telling where it was found as well.
+    ///
+    /// The index tells which jar or `.jmod` file has the class, if one does, so only the
+    /// directories and runtime images before it are looked in.
+    pub fn find_class(&self, name: &str) -> Result<(Vec<u8>, ClassOrigin), io::Error> {
+        self.find_class_source(name).map(|(data, origin, _)| (data, origin))
+    }
+
+    /// Reads a class like `find_class`, telling which directory or jar it was in as well.
+    pub fn find_class_source(
+        &self,
+        name: &str,
+    ) -> Result<(Vec<u8>, ClassOrigin, CodeSource), io::Error> {
         let class_file_name = name.to_owned() + ".class";
+        let leaves = self.leaves();
+        let not_found = || Error::new(ErrorKind::NotFound, "Class not found");
+        let (leaf, zip_index) = self.locate(&leaves, &class_file_name).ok_or_else(not_found)?;
+        let (entry, origin) = leaves[leaf];
+        let data = read_located(entry, zip_index, &class_file_name)?;
+        let source = entry.code_source(&class_file_name, zip_index);
+        Ok((data, origin, source))
+    }
+
+    /// The classes more than one directory or jar has, by name. Runtime images aren't
+    /// listed, but the classes the others have are looked for in them.
+    pub fn duplicates(&self) -> Vec<Duplicate> {
+        let leaves = self.leaves();
+        let mut found: HashMap<String, Vec<(usize, Option<usize>)>> = HashMap::new();
+        for (leaf, (entry, _)) in leaves.iter().enumerate() {
+            let names: Vec<(String, Option<usize>)> = match entry {
+                Entry::Dir { path } | Entry::Exploded { path } => {
+                    let mut names = Vec::new();
+                    class_files(path, "", &mut names);
+                    names.into_iter().map(|x| (x, None)).collect()
+                }
+                Entry::Zip { jar } => jar
+                    .names_for_release(self.release)
+                    .into_iter()
+                    .map(|(x, zip_index)| (x, Some(zip_index)))
+                    .collect(),
+                Entry::Jmod { jar } => jar
+                    .names()
+                    .into_iter()
+                    .filter_map(|(x, zip_index)| {
+                        Some((x.strip_prefix(JMOD_CLASSES)?.to_owned(), Some(zip_index)))
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            for (name, zip_index) in names {
+                let class = name.ends_with(".class")
+                    && name != "module-info.class"
+                    && !name.starts_with("META-INF/");
+                if class {
+                    found.entry(name).or_default().push((leaf, zip_index));
+                }
+            }
+        }
+        let mut duplicates: Vec<Duplicate> = found
+            .into_iter()
+            .filter_map(|(name, mut places)| {
+                for (leaf, (entry, _)) in leaves.iter().enumerate() {
+                    if let Entry::Jimage { .. } = entry {
+                        if entry.has(&name, self.release) {
+                            places.push((leaf, None));
+                        }
+                    }
+                }
+                if places.len() < 2 {
+                    return None;
+                }
+                places.sort();
+                let data: Vec<Option<Vec<u8>>> = places
+                    .iter()
+                    .map(|(leaf, zip_index)| read_located(leaves[*leaf].0, *zip_index, &name).ok())
+                    .collect();
+                let sources = places
+                    .iter()
+                    .map(|(leaf, zip_index)| leaves[*leaf].0.code_source(&name, *zip_index))
+                    .collect();
+                Some(Duplicate {
+                    name: name.strip_suffix(".class").unwrap().to_owned(),
+                    sources,
+                    differs: data.iter().any(|x| *x != data[0]),
+                })
+            })
+            .collect();
+        duplicates.sort_by(|x, y| x.name.cmp(&y.name));
+        duplicates
+    }
+
+    /// The directory or jar among `leaves` that has the file `name` first, with the
+    /// index of the file if it's a jar or `.jmod` file.
+    ///
+    /// The index tells which jar or `.jmod` file has the file, if one does, so only the
+    /// directories and runtime images before it are looked in.
+    fn locate(
+        &self,
+        leaves: &[(&Entry, ClassOrigin)],
+        name: &str,
+    ) -> Option<(usize, Option<usize>)> {
+        let index = self.index.get_or_init(|| index(leaves, self.release));
+        let found = index.get(name).copied();
+        let end = found.map_or(leaves.len(), |(leaf, _)| leaf);
+        for (leaf, (entry, _)) in leaves[..end].iter().enumerate() {
+            let searched = matches!(
+                entry,
+                Entry::Dir { .. } | Entry::Jimage { .. } | Entry::Exploded { .. }
+            );
+            if searched && entry.has(name, self.release) {
+                return Some((leaf, None));
+            }
+        }
+        found.map(|(leaf, zip_index)| (leaf, Some(zip_index)))
+    }
+
+    /// The URL of the resource `name`, like `pkg/config.properties`, in the first directory
+    /// or jar that has it, like `ClassLoader.getResource`.
+    pub fn resource(&self, name: &str) -> Option<String> {
+        let leaves = self.leaves();
+        let (leaf, _) = self.locate(&leaves, name)?;
+        leaves[leaf].0.url(name)
+    }
 
-        self.boot
-            .read_class(&class_file_name)
-            .or_else(|x| self.user.read_class(&class_file_name))
-        //            .or_else(|| self.ext.read_class())
+    /// The URLs of the resource `name` in every directory and jar that has it, in the order
+    /// they're searched, like `ClassLoader.getResources`.
+    pub fn resources(&self, name: &str) -> Vec<String> {
+        self.leaves()
+            .iter()
+            .filter(|(entry, _)| entry.has(name, self.release))
+            .filter_map(|(entry, _)| entry.url(name))
+            .collect()
     }
+
+    /// Reads the resource `name` in every directory and jar that has it, in the order of
+    /// `resources`.
+    pub fn read_resources(&self, name: &str) -> Vec<Vec<u8>> {
+        self.leaves()
+            .iter()
+            .filter(|(entry, _)| entry.has(name, self.release))
+            .filter_map(|(entry, _)| match entry {
+                Entry::Zip { jar } => jar.read(&jar.find(name, self.release)?).ok(),
+                entry => entry.read_class(name).ok(),
+            })
+            .collect()
+    }
+
+    /// Reads the resource `resource` finds, like `ClassLoader.getResourceAsStream`.
+    pub fn read_resource(&self, name: &str) -> Result<Vec<u8>, io::Error> {
+        let leaves = self.leaves();
+        let not_found = || Error::new(ErrorKind::NotFound, "Resource not found");
+        let (leaf, zip_index) = self.locate(&leaves, name).ok_or_else(not_found)?;
+        read_located(leaves[leaf].0, zip_index, name)
+    }
+
+    /// The `module-info.class` of the module `name` of the runtime image, if the boot
+    /// class path is one.
+    pub fn system_module_info(&self, name: &str) -> Option<Vec<u8>> {
+        match &self.boot {
+            Entry::Jimage { image } => image.read(&format!("/{}/module-info.class", name)).ok(),
+            _ => None,
+        }
+    }
+
+    /// The packages of the boot class path, which are the ones of java.base when it isn't
+    /// a runtime image.
+    pub fn boot_packages(&self) -> Vec<String> {
+        let mut leaves = Vec::new();
+        self.boot.leaves(&mut leaves);
+        let mut packages: Vec<String> = leaves
+            .iter()
+            .filter_map(|x| x.module())
+            .flat_map(|x| x.packages)
+            .collect();
+        packages.sort();
+        packages.dedup();
+        packages
+    }
+
+    /// The modules of `--module-path`, in order.
+    pub fn module_path(&self) -> Vec<ModuleLocation> {
+        let mut leaves = Vec::new();
+        self.modules.leaves(&mut leaves);
+        leaves.iter().filter_map(|x| x.module()).collect()
+    }
+
+    /// Every directory and jar, boot ones first, then extension, module and user ones.
+    fn leaves(&self) -> Vec<(&Entry, ClassOrigin)> {
+        [
+            (&self.boot, ClassOrigin::Boot),
+            (&self.ext, ClassOrigin::Ext),
+            (&self.modules, ClassOrigin::Module),
+            (&self.user, ClassOrigin::User),
+        ]
+        .into_iter()
+        .flat_map(|(entry, origin)| {
+            let mut leaves = Vec::new();
+            entry.leaves(&mut leaves);
+            leaves.into_iter().map(move |x| (x, origin))
+        })
+        .collect()
+    }
+}
+
+/// Reads the file `name` of the directory or jar `entry`, at `zip_index` in a jar or
+/// `.jmod` file.
+fn read_located(entry: &Entry, zip_index: Option<usize>, name: &str) -> io::Result<Vec<u8>> {
+    match (entry, zip_index) {
+        (Entry::Zip { jar } | Entry::Jmod { jar }, Some(zip_index)) => jar.read_index(zip_index),
+        (entry, _) => entry.read_class(name),
+    }
+}
+
+/// Maps the file names in the jars and `.jmod` files among `leaves` to the one that has
+/// them first and their index in it. The names in a `.jmod` file are the ones under
+/// `classes/`, without it, and the ones in a multi-release jar the ones for `release`.
+fn index(leaves: &[(&Entry, ClassOrigin)], release: u32) -> HashMap<String, (usize, usize)> {
+    let mut index = HashMap::new();
+    for (leaf, (entry, _)) in leaves.iter().enumerate() {
+        match entry {
+            Entry::Zip { jar } => {
+                for (name, zip_index) in jar.names_for_release(release) {
+                    index.entry(name).or_insert((leaf, zip_index));
+                }
+            }
+            Entry::Jmod { jar } => {
+                for (name, zip_index) in jar.names() {
+                    if let Some(name) = name.strip_prefix(JMOD_CLASSES) {
+                        index.entry(name.to_owned()).or_insert((leaf, zip_index));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    index
+}
+
+/// The runtime image of a JDK 9 or later home, which has no `rt.jar`.
+fn modules_path(java_home: &Path) -> PathBuf {
+    java_home.join("lib").join("modules")
 }
 
+/// The classes of a JDK 9 or later home are in its runtime image, the ones of a JDK 8
+/// `jre` in the jars of its `lib`.
 fn parse_boot_classpath(jre: &str) -> Entry {
+    let modules = modules_path(Path::new(jre));
+    if modules.is_file() {
+        return match Jimage::open(&modules) {
+            Ok(image) => Entry::Jimage { image },
+            Err(e) => panic!("Can not read runtime image {}: {}", modules.display(), e),
+        };
+    }
     let jre_lib_path = Path::new(jre)
         .join("lib")
         .join("*")
@@ -110,9 +652,83 @@ fn parse_boot_classpath(jre: &str) -> Entry {
     Entry::new(&jre_lib_path)
 }
 
+fn parse_ext_classpath(jre: &str) -> Entry {
+    let jre_ext_path = Path::new(jre)
+        .join("lib")
+        .join("ext")
+        .join("*")
+        .to_str()
+        .unwrap()
+        .to_owned();
+    Entry::new(&jre_ext_path)
+}
+
+/// The modules of `--module-path`, each of its entries a module or a directory of them.
+fn parse_module_path(module_path_opt: Option<String>) -> Entry {
+    let entries = module_path_opt
+        .iter()
+        .flat_map(|x| x.split(PATH_SEPARATOR))
+        .filter(|x| !x.is_empty())
+        .map(Entry::new_module)
+        .collect();
+    Entry::Composite { entries }
+}
+
 fn parse_user_classpath(cp_opt: Option<String>) -> Entry {
     let cp = cp_opt.unwrap_or(".".to_owned());
-    Entry::new(&cp)
+    with_manifest_class_path(Entry::new(&cp), &mut HashSet::new())
+}
+
+/// Puts after each jar of `entry` the directories and jars of the `Class-Path` of its
+/// manifest, relative to the jar, and theirs in turn. A jar is only put once, the first
+/// time it's found.
+fn with_manifest_class_path(entry: Entry, seen: &mut HashSet<PathBuf>) -> Entry {
+    let map = |entries: Vec<Entry>, seen: &mut HashSet<PathBuf>| -> Vec<Entry> {
+        entries
+            .into_iter()
+            .map(|x| with_manifest_class_path(x, seen))
+            .collect()
+    };
+    match entry {
+        Entry::Zip { jar } => {
+            seen.insert(canonical(jar.path()));
+            let base = jar.path().parent().unwrap_or(Path::new("")).to_owned();
+            let mut entries = Vec::new();
+            for url in jar.manifest().map(|x| x.class_path()).unwrap_or_default() {
+                let path = base.join(url.strip_prefix("file:").unwrap_or(&url));
+                if url.ends_with('/') {
+                    entries.push(Entry::Dir { path });
+                } else if !seen.contains(&canonical(&path)) {
+                    let entry = Entry::Zip {
+                        jar: Jar::new(&path),
+                    };
+                    entries.push(with_manifest_class_path(entry, seen));
+                }
+            }
+            if entries.is_empty() {
+                return Entry::Zip { jar };
+            }
+            entries.insert(0, Entry::Zip { jar });
+            Entry::Composite { entries }
+        }
+        Entry::Wildcard { entries } => Entry::Wildcard {
+            entries: map(entries, seen),
+        },
+        Entry::Composite { entries } => Entry::Composite {
+            entries: map(entries, seen),
+        },
+        entry => entry,
+    }
+}
+
+/// The path without `.` and `..` and links, to tell whether two paths are the same jar.
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_owned())
+}
+
+/// The main class of the manifest of `jar`, which `-jar` runs, like `pkg/Main`.
+pub fn jar_main_class(jar: &str) -> Option<String> {
+    Jar::new(Path::new(jar)).manifest()?.main_class()
 }
 
 fn exists(path: &str) -> bool {
@@ -127,6 +743,10 @@ fn get_jre(jre_opt: Option<String>) -> String {
                 "./jre".to_string()
             } else {
                 match env::var_os("JAVA_HOME") {
+                    // A JDK 9 or later home is its own runtime, a JDK 8 one has a jre
+                    Some(java_home) if modules_path(Path::new(&java_home)).is_file() => {
+                        java_home.to_str().unwrap().to_string()
+                    }
                     Some(java_home) => Path::new(&java_home)
                         .join("jre")
                         .to_str()
@@ -140,8 +760,442 @@ fn get_jre(jre_opt: Option<String>) -> String {
 }
 
 pub fn parse(jre_opt: Option<String>, cp_opt: Option<String>) -> ClassPath {
+    parse_with_module_path(jre_opt, cp_opt, None)
+}
+
+/// Parses the class path like `parse`, with the modules of `--module-path` searched
+/// after the extension class path and before the user class path.
+pub fn parse_with_module_path(
+    jre_opt: Option<String>,
+    cp_opt: Option<String>,
+    module_path_opt: Option<String>,
+) -> ClassPath {
     let jre = get_jre(jre_opt);
     let boot = parse_boot_classpath(&jre);
+    let ext = parse_ext_classpath(&jre);
+    let modules = parse_module_path(module_path_opt);
     let user = parse_user_classpath(cp_opt);
-    ClassPath { user, boot }
+    ClassPath {
+        user,
+        ext,
+        modules,
+        boot,
+        index: OnceCell::new(),
+        release: DEFAULT_RELEASE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    use zip::write::FileOptions;
+    use zip::CompressionMethod;
+
+    use crate::classpath::classpath::*;
+    use crate::classpath::jimage::tests::{write_image, Stored};
+
+    /// A directory of its own for `test` under the temporary directory, emptied.
+    fn temp_dir(test: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("jvm-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_class(dir: &Path, name: &str, data: &[u8]) {
+        let path = dir.join(name.to_owned() + ".class");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, data).unwrap();
+    }
+
+    fn write_jar(path: &Path, name: &str, data: &[u8]) {
+        write_zip(path, &[(&(name.to_owned() + ".class"), data)]);
+    }
+
+    fn write_zip(path: &Path, files: &[(&str, &[u8])]) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, zip_bytes(files, CompressionMethod::Deflated)).unwrap();
+    }
+
+    fn zip_bytes(files: &[(&str, &[u8])], compression: CompressionMethod) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = FileOptions::default().compression_method(compression);
+        for (name, data) in files {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn composite() {
+        let dir = temp_dir("composite");
+        write_class(&dir.join("first"), "pkg/Hello", b"first");
+        write_class(&dir.join("second"), "pkg/Hello", b"second");
+        write_class(&dir.join("second"), "pkg/Other", b"other");
+        write_jar(&dir.join("lib").join("a.jar"), "pkg/Jarred", b"jarred");
+        let path = |x: &str| dir.join(x).to_str().unwrap().to_owned();
+        let cp = [
+            path("missing"),
+            path("first"),
+            path("second"),
+            path("lib/*"),
+        ]
+        .join(&PATH_SEPARATOR.to_string());
+        let entry = Entry::new(&cp);
+        let read = |x: &str| entry.read_class(&(x.to_owned() + ".class")).ok();
+        assert_eq!(read("pkg/Hello"), Some(b"first".to_vec()));
+        assert_eq!(read("pkg/Other"), Some(b"other".to_vec()));
+        assert_eq!(read("pkg/Jarred"), Some(b"jarred".to_vec()));
+        assert_eq!(read("pkg/Missing"), None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    // Jars are opened on the first lookup and kept open, even once their files are gone
+    #[test]
+    fn indexed_jars() {
+        let dir = temp_dir("indexed");
+        write_class(&dir.join("before"), "pkg/Dir", b"before");
+        write_jar(&dir.join("a.jar"), "pkg/Dir", b"jar");
+        write_jar(&dir.join("b.jar"), "pkg/Jarred", b"b");
+        write_class(&dir.join("after"), "pkg/Jarred", b"after");
+        write_class(&dir.join("after"), "pkg/Late", b"late");
+        let path = |x: &str| dir.join(x).to_str().unwrap().to_owned();
+        let cp = [path("before"), path("a.jar"), path("b.jar"), path("after")]
+            .join(&PATH_SEPARATOR.to_string());
+        let class_path = parse(Some("src/test_data/jre".to_string()), Some(cp));
+        let read = |x: &str| class_path.read_class(x).ok();
+        assert_eq!(read("pkg/Dir"), Some(b"before".to_vec()));
+        fs::remove_file(dir.join("b.jar")).unwrap();
+        assert_eq!(read("pkg/Jarred"), Some(b"b".to_vec()));
+        assert_eq!(read("pkg/Late"), Some(b"late".to_vec()));
+        assert_eq!(read("pkg/Missing"), None);
+        assert!(read("java/lang/Object").is_some());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn ext_between_boot_and_user() {
+        let dir = temp_dir("ext");
+        let jre = dir.join("jre");
+        write_jar(&jre.join("lib").join("rt.jar"), "java/lang/Object", b"boot");
+        write_jar(&jre.join("lib/ext").join("ext.jar"), "pkg/Hello", b"ext");
+        write_class(&dir.join("user"), "java/lang/Object", b"user");
+        write_class(&dir.join("user"), "pkg/Hello", b"user");
+        write_class(&dir.join("user"), "pkg/Main", b"main");
+        let to_string = |x: PathBuf| Some(x.to_str().unwrap().to_owned());
+        let class_path = parse(to_string(jre), to_string(dir.join("user")));
+        let find = |x: &str| class_path.find_class(x).unwrap();
+        assert_eq!(
+            find("java/lang/Object"),
+            (b"boot".to_vec(), ClassOrigin::Boot)
+        );
+        assert_eq!(find("pkg/Hello"), (b"ext".to_vec(), ClassOrigin::Ext));
+        assert_eq!(find("pkg/Main"), (b"main".to_vec(), ClassOrigin::User));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    // A home with a runtime image is a JDK 9 or later one, its jars aren't searched
+    #[test]
+    fn runtime_image() {
+        let dir = temp_dir("image");
+        let home = dir.join("jdk");
+        write_image(
+            &home.join("lib").join("modules"),
+            &[],
+            vec![
+                ("/java.base/java/lang/Object.class", b"object", Stored::Plain),
+                ("/java.desktop/java/awt/Frame.class", b"frame", Stored::Zip),
+            ],
+        );
+        write_jar(&home.join("lib").join("jrt-fs.jar"), "pkg/Jarred", b"jar");
+        write_class(&dir.join("user"), "java/awt/Frame", b"user");
+        write_class(&dir.join("user"), "pkg/Main", b"main");
+        let to_string = |x: PathBuf| Some(x.to_str().unwrap().to_owned());
+        let class_path = parse(to_string(home), to_string(dir.join("user")));
+        let find = |x: &str| class_path.find_class(x).ok();
+        assert_eq!(
+            find("java/lang/Object"),
+            Some((b"object".to_vec(), ClassOrigin::Boot))
+        );
+        assert_eq!(
+            find("java/awt/Frame"),
+            Some((b"frame".to_vec(), ClassOrigin::Boot))
+        );
+        assert_eq!(find("pkg/Jarred"), None);
+        assert_eq!(find("pkg/Main"), Some((b"main".to_vec(), ClassOrigin::User)));
+        assert_eq!(
+            class_path.resources("java/awt/Frame.class")[0],
+            "jrt:/java.desktop/java/awt/Frame.class"
+        );
+        assert_eq!(class_path.resource("java/awt/Window.class"), None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// A `.jmod` file of one class, its zip after the header like `jmod` writes it.
+    fn write_jmod(path: &Path, name: &str, data: &[u8]) {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        zip.start_file("classes/module-info.class", Default::default())
+            .unwrap();
+        zip.start_file(format!("classes/{}.class", name), Default::default())
+            .unwrap();
+        zip.write_all(data).unwrap();
+        zip.start_file(format!("lib/{}.so", name), Default::default())
+            .unwrap();
+        let mut jmod = b"JM\x01\x00".to_vec();
+        jmod.extend(zip.finish().unwrap().into_inner());
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, jmod).unwrap();
+    }
+
+    #[test]
+    fn module_path() {
+        let dir = temp_dir("modules");
+        let mods = dir.join("mods");
+        write_jmod(&mods.join("a.jmod"), "a/A", b"jmod");
+        write_class(&mods.join("b"), "module-info", b"b");
+        write_class(&mods.join("b"), "b/B", b"exploded");
+        write_class(&mods.join("b"), "a/A", b"shadowed");
+        write_jar(&mods.join("c.jar"), "c/C", b"jar");
+        write_class(&mods.join("not-a-module"), "d/D", b"ignored");
+        write_class(&dir.join("e"), "module-info", b"e");
+        write_class(&dir.join("e"), "e/E", b"single");
+        write_class(&dir.join("user"), "b/B", b"user");
+        write_class(&dir.join("user"), "d/D", b"user");
+        let path = |x: &str| dir.join(x).to_str().unwrap().to_owned();
+        let module_path = [path("mods"), path("e")].join(&PATH_SEPARATOR.to_string());
+        let class_path = parse_with_module_path(
+            Some("src/test_data/jre".to_string()),
+            Some(path("user")),
+            Some(module_path),
+        );
+        let find = |x: &str| class_path.find_class(x).ok();
+        let module = |x: &[u8]| Some((x.to_vec(), ClassOrigin::Module));
+        assert_eq!(find("a/A"), module(b"jmod"));
+        assert_eq!(find("b/B"), module(b"exploded"));
+        assert_eq!(find("c/C"), module(b"jar"));
+        assert_eq!(find("e/E"), module(b"single"));
+        assert_eq!(find("d/D"), Some((b"user".to_vec(), ClassOrigin::User)));
+        assert_eq!(find("lib/a/A.so"), None);
+        let entry = Entry::new_module(&path("mods/a.jmod"));
+        assert_eq!(entry.read_class("a/A.class").ok(), Some(b"jmod".to_vec()));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn multi_release_jars() {
+        let dir = temp_dir("multi-release");
+        let files: [(&str, &[u8]); 5] = [
+            (
+                "META-INF/MANIFEST.MF",
+                b"Manifest-Version: 1.0\r\nMulti-Release: true\r\n\r\n",
+            ),
+            ("pkg/A.class", b"base"),
+            ("META-INF/versions/9/pkg/A.class", b"nine"),
+            ("META-INF/versions/11/pkg/A.class", b"eleven"),
+            ("META-INF/versions/11/pkg/B.class", b"only eleven"),
+        ];
+        write_zip(&dir.join("multi.jar"), &files);
+        // Without the attribute the versions are just files
+        write_zip(
+            &dir.join("plain.jar"),
+            &[("META-INF/MANIFEST.MF", b"Manifest-Version: 1.0\n\n"), files[4]],
+        );
+        let read = |jar: &str, release: u32, name: &str| {
+            let mut class_path = parse(
+                Some("src/test_data/jre".to_string()),
+                Some(dir.join(jar).to_str().unwrap().to_owned()),
+            );
+            class_path.set_release(release);
+            class_path.read_class(name).ok()
+        };
+        let base = Some(b"base".to_vec());
+        assert_eq!(read("multi.jar", DEFAULT_RELEASE, "pkg/A"), base);
+        assert_eq!(read("multi.jar", DEFAULT_RELEASE, "pkg/B"), None);
+        assert_eq!(read("multi.jar", 10, "pkg/A"), Some(b"nine".to_vec()));
+        assert_eq!(read("multi.jar", 17, "pkg/A"), Some(b"eleven".to_vec()));
+        assert_eq!(read("multi.jar", 17, "pkg/B"), Some(b"only eleven".to_vec()));
+        assert_eq!(read("plain.jar", 17, "pkg/B"), None);
+        assert_eq!(
+            read("plain.jar", 17, "META-INF/versions/11/pkg/B"),
+            Some(b"only eleven".to_vec())
+        );
+        // The manifest is read once for every name looked up
+        let jar = Jar::new(&dir.join("multi.jar"));
+        assert!(std::ptr::eq(jar.manifest().unwrap(), jar.manifest().unwrap()));
+        let eleven = Some("META-INF/versions/11/pkg/A.class".to_owned());
+        assert_eq!(jar.find("pkg/A.class", 17), eleven);
+        assert_eq!(jar.find("pkg/A.class", 8), Some("pkg/A.class".to_owned()));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn manifest_class_path() {
+        let dir = temp_dir("manifest");
+        let manifest = |x: &str| format!("Manifest-Version: 1.0\r\n{}\r\n\r\n", x);
+        let a = manifest(
+            "Main-Class: app.Main\r\nClass-Path: lib/b.jar classes/ missing.jar a.jar",
+        );
+        write_zip(
+            &dir.join("a.jar"),
+            &[("META-INF/MANIFEST.MF", a.as_bytes()), ("pkg/A.class", b"a")],
+        );
+        let b = manifest("Class-Path: c%20d.jar ../a.jar");
+        write_zip(
+            &dir.join("lib/b.jar"),
+            &[("META-INF/MANIFEST.MF", b.as_bytes()), ("pkg/B.class", b"b")],
+        );
+        write_zip(&dir.join("lib/c d.jar"), &[("pkg/C.class", b"c"), ("pkg/Dir.class", b"c")]);
+        write_class(&dir.join("classes"), "pkg/Dir", b"dir");
+        write_class(&dir.join("after"), "pkg/C", b"after");
+        let path = |x: &str| dir.join(x).to_str().unwrap().to_owned();
+        let cp = [path("a.jar"), path("after")].join(&PATH_SEPARATOR.to_string());
+        let class_path = parse(Some("src/test_data/jre".to_string()), Some(cp));
+        let mut leaves = Vec::new();
+        class_path.user.leaves(&mut leaves);
+        // a.jar, b.jar, c d.jar, classes/, missing.jar and after/
+        assert_eq!(leaves.len(), 6);
+        let read = |x: &str| class_path.read_class(x).ok();
+        assert_eq!(read("pkg/B"), Some(b"b".to_vec()));
+        assert_eq!(read("pkg/C"), Some(b"c".to_vec()));
+        assert_eq!(read("pkg/Dir"), Some(b"c".to_vec()));
+        assert_eq!(jar_main_class(&path("a.jar")), Some("app/Main".to_owned()));
+        assert_eq!(jar_main_class(&path("lib/b.jar")), None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn resources() {
+        let dir = temp_dir("resources");
+        fs::create_dir_all(dir.join("classes/res")).unwrap();
+        fs::write(dir.join("classes/res/a.txt"), b"dir").unwrap();
+        write_zip(
+            &dir.join("lib.jar"),
+            &[
+                ("META-INF/MANIFEST.MF", b"Multi-Release: true\r\n"),
+                ("res/a.txt", b"jar"),
+                ("res/b.txt", b"8"),
+                ("META-INF/versions/11/res/b.txt", b"11"),
+            ],
+        );
+        let path = |x: &str| dir.join(x).to_str().unwrap().to_owned();
+        let cp = [path("classes"), path("lib.jar")].join(&PATH_SEPARATOR.to_string());
+        let mut class_path = parse(Some("src/test_data/jre".to_string()), Some(cp));
+        let canonical = |x: &str| dir.canonicalize().unwrap().join(x).display().to_string();
+        let in_dir = format!("file:{}", canonical("classes/res/a.txt"));
+        let in_jar = format!("jar:file:{}!/res/a.txt", canonical("lib.jar"));
+        assert_eq!(class_path.resource("res/a.txt"), Some(in_dir.clone()));
+        assert_eq!(class_path.resources("res/a.txt"), vec![in_dir, in_jar]);
+        assert_eq!(class_path.read_resource("res/a.txt").unwrap(), b"dir");
+        assert_eq!(class_path.read_resources("res/a.txt"), vec![b"dir", b"jar"]);
+        assert_eq!(class_path.read_resource("res/b.txt").unwrap(), b"8");
+        class_path.set_release(11);
+        assert_eq!(class_path.read_resource("res/b.txt").unwrap(), b"11");
+        assert_eq!(class_path.read_resources("res/b.txt"), vec![b"11"]);
+        assert!(class_path.resources("java/lang/Object.class")[0].starts_with("jar:file:"));
+        assert_eq!(class_path.resource("res/c.txt"), None);
+        assert!(class_path.read_resource("res/c.txt").is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn nested_jars() {
+        let dir = temp_dir("nested");
+        let stored = zip_bytes(&[("pkg/A.class", b"stored")], CompressionMethod::Stored);
+        let deflated = zip_bytes(
+            &[("pkg/A.class", b"deflated"), ("pkg/B.class", b"b")],
+            CompressionMethod::Deflated,
+        );
+        let outer = zip_bytes(
+            &[
+                ("BOOT-INF/lib/a-stored.jar", &stored),
+                ("BOOT-INF/lib/b-deflated.jar", &deflated),
+                ("BOOT-INF/lib/notes.txt", b"not a jar"),
+                ("BOOT-INF/lib/old/c.jar", &stored),
+            ],
+            CompressionMethod::Stored,
+        );
+        fs::write(dir.join("app.jar"), outer).unwrap();
+        let app = dir.join("app.jar").to_str().unwrap().to_owned();
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some(app.clone() + "!/BOOT-INF/lib/*"),
+        );
+        let mut leaves = Vec::new();
+        class_path.user.leaves(&mut leaves);
+        assert_eq!(leaves.len(), 2);
+        assert_eq!(class_path.read_class("pkg/A").unwrap(), b"stored");
+        assert_eq!(class_path.read_class("pkg/B").unwrap(), b"b");
+        let url = format!(
+            "jar:file:{}!/BOOT-INF/lib/b-deflated.jar!/pkg/B.class",
+            dir.canonicalize().unwrap().join("app.jar").display()
+        );
+        assert_eq!(class_path.resource("pkg/B.class"), Some(url));
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some(app + "!/BOOT-INF/lib/b-deflated.jar"),
+        );
+        assert_eq!(class_path.read_class("pkg/A").unwrap(), b"deflated");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn code_sources_and_duplicates() {
+        let dir = temp_dir("duplicates");
+        write_class(&dir.join("first"), "pkg/A", b"a");
+        write_zip(
+            &dir.join("a.jar"),
+            &[("pkg/A.class", b"a"), ("pkg/B.class", b"b1")],
+        );
+        write_zip(
+            &dir.join("b.jar"),
+            &[("pkg/B.class", b"b2"), ("java/lang/Object.class", b"mine")],
+        );
+        let path = |x: &str| dir.join(x);
+        let cp = [path("first"), path("a.jar"), path("b.jar")].map(|x| x.display().to_string());
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some(cp.join(&PATH_SEPARATOR.to_string())),
+        );
+        let source = |x: &str| class_path.find_class_source(x).unwrap().2;
+        let in_jar = |x: &str, name: &str| CodeSource {
+            path: path(x),
+            entry: Some(name.to_owned()),
+        };
+        let first = CodeSource {
+            path: path("first"),
+            entry: None,
+        };
+        assert_eq!(source("pkg/A"), first);
+        assert_eq!(source("pkg/B"), in_jar("a.jar", "pkg/B.class"));
+        assert_eq!(
+            source("pkg/B").to_string(),
+            format!("{}!/pkg/B.class", path("a.jar").display())
+        );
+        let rt = CodeSource {
+            path: PathBuf::from("src/test_data/jre/lib/rt.jar"),
+            entry: Some("java/lang/Object.class".to_owned()),
+        };
+        assert_eq!(source("java/lang/Object"), rt);
+        let duplicates = class_path.duplicates();
+        let expected = [
+            ("java/lang/Object", vec![rt, in_jar("b.jar", "java/lang/Object.class")], true),
+            ("pkg/A", vec![first, in_jar("a.jar", "pkg/A.class")], false),
+            (
+                "pkg/B",
+                vec![in_jar("a.jar", "pkg/B.class"), in_jar("b.jar", "pkg/B.class")],
+                true,
+            ),
+        ];
+        assert_eq!(duplicates.len(), expected.len());
+        for (duplicate, (name, sources, differs)) in duplicates.iter().zip(expected) {
+            assert_eq!(duplicate.name, name);
+            assert_eq!(duplicate.sources, sources);
+            assert_eq!(duplicate.differs, differs);
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
 }
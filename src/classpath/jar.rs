@@ -0,0 +1,269 @@
+use std::cell::{OnceCell, RefCell};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+#[cfg(not(feature = "mmap"))]
+use std::io::BufReader;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use zip::{CompressionMethod, ZipArchive};
+
+use crate::classpath::manifest::Manifest;
+#[cfg(feature = "mmap")]
+use crate::classpath::mmap::Mmap;
+
+const MANIFEST: &str = "META-INF/MANIFEST.MF";
+
+/// Where a multi-release jar keeps the files of each feature version, like
+/// `META-INF/versions/9/pkg/A.class`.
+const VERSIONS: &str = "META-INF/versions/";
+
+/// Separates the path of a jar from the name of a jar nested in it, like
+/// `app.jar!/BOOT-INF/lib/x.jar`.
+pub const NESTED_SEPARATOR: &str = "!/";
+
+/// What a jar file is read from, memory-mapped with the `mmap` feature.
+#[cfg(feature = "mmap")]
+type FileSource = Cursor<Mmap>;
+#[cfg(not(feature = "mmap"))]
+type FileSource = BufReader<File>;
+
+#[derive(Debug)]
+enum Source {
+    File(FileSource),
+    /// A jar stored in another without compression, read where it is in the outer one.
+    Stored(Window<FileSource>),
+    /// A compressed jar in another, inflated into memory.
+    Inflated(Cursor<Vec<u8>>),
+}
+
+impl Read for Source {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Source::File(x) => x.read(buf),
+            Source::Stored(x) => x.read(buf),
+            Source::Inflated(x) => x.read(buf),
+        }
+    }
+}
+
+impl Seek for Source {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            Source::File(x) => x.seek(pos),
+            Source::Stored(x) => x.seek(pos),
+            Source::Inflated(x) => x.seek(pos),
+        }
+    }
+}
+
+/// The bytes `start..start + len` of `inner`, read and sought as if they were all of it.
+#[derive(Debug)]
+struct Window<R> {
+    inner: R,
+    start: u64,
+    len: u64,
+    pos: u64,
+}
+
+impl<R: Read + Seek> Read for Window<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.len.saturating_sub(self.pos) as usize);
+        if len == 0 {
+            return Ok(0);
+        }
+        self.inner.seek(SeekFrom::Start(self.start + self.pos))?;
+        let read = self.inner.read(&mut buf[..len])?;
+        self.pos += read as u64;
+        Ok(read)
+    }
+}
+
+impl<R> Seek for Window<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(x) => Some(x),
+            SeekFrom::End(x) => self.len.checked_add_signed(x),
+            SeekFrom::Current(x) => self.pos.checked_add_signed(x),
+        };
+        self.pos = pos
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Seek before the start"))?;
+        Ok(self.pos)
+    }
+}
+
+/// A jar on the class path, or nested in one. It's opened and its central directory parsed
+/// on first use, then kept open.
+#[derive(Debug)]
+pub struct Jar {
+    path: PathBuf,
+    /// `None` once opening the jar failed, it then has no files.
+    archive: OnceCell<Option<RefCell<ZipArchive<Source>>>>,
+    /// Read on first use, `find` needing it for every name looked up.
+    manifest: OnceCell<Option<Manifest>>,
+}
+
+fn open_file(path: &Path) -> io::Result<FileSource> {
+    let file = File::open(path)?;
+    #[cfg(feature = "mmap")]
+    let source = Cursor::new(Mmap::new(&file)?);
+    #[cfg(not(feature = "mmap"))]
+    let source = BufReader::new(file);
+    Ok(source)
+}
+
+fn open(path: &Path) -> io::Result<ZipArchive<Source>> {
+    Ok(ZipArchive::new(Source::File(open_file(path)?))?)
+}
+
+/// Opens the jar `name` in the jar at `outer`, reading the outer one where the nested one
+/// is if it's stored, else inflating it.
+fn open_nested(outer: &Path, name: &str) -> io::Result<ZipArchive<Source>> {
+    let mut archive = ZipArchive::new(open_file(outer)?)?;
+    let file = archive.by_name(name)?;
+    let source = if file.compression() == CompressionMethod::Stored {
+        let (start, len) = (file.data_start(), file.size());
+        drop(file);
+        Source::Stored(Window {
+            inner: archive.into_inner(),
+            start,
+            len,
+            pos: 0,
+        })
+    } else {
+        Source::Inflated(Cursor::new(read_to_end(file)?))
+    };
+    Ok(ZipArchive::new(source)?)
+}
+
+fn read_to_end(mut file: zip::read::ZipFile) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::<u8>::with_capacity(file.size() as usize);
+    file.read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+impl Jar {
+    pub fn new(path: &Path) -> Jar {
+        Jar {
+            path: path.to_owned(),
+            archive: OnceCell::new(),
+            manifest: OnceCell::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The path of the outer jar and the name in it of a nested jar.
+    pub fn nested(&self) -> Option<(&Path, &str)> {
+        let (outer, name) = self.path.to_str()?.split_once(NESTED_SEPARATOR)?;
+        Some((Path::new(outer), name))
+    }
+
+    fn archive(&self) -> io::Result<&RefCell<ZipArchive<Source>>> {
+        let open = || match self.nested() {
+            Some((outer, name)) => open_nested(outer, name),
+            None => open(&self.path),
+        };
+        self.archive
+            .get_or_init(|| open().ok().map(RefCell::new))
+            .as_ref()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Jar can't be opened"))
+    }
+
+    /// The name of every file in the jar with its index in the zip.
+    pub fn names(&self) -> Vec<(String, usize)> {
+        let Ok(archive) = self.archive() else {
+            return Vec::new();
+        };
+        let mut archive = archive.borrow_mut();
+        (0..archive.len())
+            .filter_map(|i| Some((archive.by_index_raw(i).ok()?.name().to_owned(), i)))
+            .collect()
+    }
+
+    /// The manifest, `META-INF/MANIFEST.MF`, if the jar has one.
+    pub fn manifest(&self) -> Option<&Manifest> {
+        self.manifest
+            .get_or_init(|| self.read(MANIFEST).ok().map(|x| Manifest::parse(&x)))
+            .as_ref()
+    }
+
+    fn is_multi_release(&self) -> bool {
+        self.manifest().is_some_and(|x| x.is_multi_release())
+    }
+
+    /// The files of the jar like `names`, but if it's a multi-release jar with the ones of
+    /// the highest version up to `release` instead of the ones at the root, as a JDK of
+    /// that feature version sees them.
+    pub fn names_for_release(&self, release: u32) -> Vec<(String, usize)> {
+        let names = self.names();
+        if !self.is_multi_release() {
+            return names;
+        }
+        let mut versioned: HashMap<String, (u32, usize)> = HashMap::new();
+        let mut root = Vec::new();
+        for (name, index) in names {
+            let Some((version, name)) = name
+                .strip_prefix(VERSIONS)
+                .and_then(|x| x.split_once('/'))
+                .and_then(|(version, name)| Some((version.parse::<u32>().ok()?, name)))
+            else {
+                root.push((name, index));
+                continue;
+            };
+            if (9..=release).contains(&version) && !name.is_empty() {
+                let entry = versioned.entry(name.to_owned()).or_insert((version, index));
+                if version > entry.0 {
+                    *entry = (version, index);
+                }
+            }
+        }
+        let mut names: Vec<(String, usize)> = root
+            .into_iter()
+            .map(|(name, index)| match versioned.remove(&name) {
+                Some((_, index)) => (name, index),
+                None => (name, index),
+            })
+            .collect();
+        let mut only_versioned: Vec<(String, usize)> =
+            versioned.into_iter().map(|(x, (_, y))| (x, y)).collect();
+        only_versioned.sort();
+        names.extend(only_versioned);
+        names
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.archive()
+            .is_ok_and(|x| x.borrow_mut().by_name(name).is_ok())
+    }
+
+    /// The name the file `name` has in the jar, if it has it: the one of the highest version
+    /// up to `release` if it's a multi-release jar that has one, `name` otherwise.
+    pub fn find(&self, name: &str, release: u32) -> Option<String> {
+        let last = if self.is_multi_release() { release } else { 0 };
+        (9..=last)
+            .rev()
+            .map(|x| format!("{}{}/{}", VERSIONS, x, name))
+            .chain(std::iter::once(name.to_owned()))
+            .find(|x| self.contains(x))
+    }
+
+    pub fn read(&self, name: &str) -> io::Result<Vec<u8>> {
+        read_to_end(self.archive()?.borrow_mut().by_name(name)?)
+    }
+
+    /// The name of the file at `index` in the zip, as `names` gave it.
+    pub fn name_at(&self, index: usize) -> Option<String> {
+        let mut archive = self.archive().ok()?.borrow_mut();
+        let name = archive.by_index_raw(index).ok()?.name().to_owned();
+        Some(name)
+    }
+
+    /// Reads the file at `index` in the zip, as `names` gave it.
+    pub fn read_index(&self, index: usize) -> io::Result<Vec<u8>> {
+        read_to_end(self.archive()?.borrow_mut().by_index(index)?)
+    }
+}
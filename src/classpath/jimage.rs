@@ -0,0 +1,674 @@
+//! The reader of `lib/modules`, the jimage file JDK 9 and later keep their classes in.
+//!
+//! The file starts with a header and an index: a perfect hash table of resource names,
+//! made of a redirect table and an offset table, then the attributes of each resource
+//! location and the strings they refer to. The resources follow, some of them compressed.
+//! Every number is in the byte order of the platform that wrote the image, which the
+//! magic number tells.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::io::{Error, ErrorKind, Read};
+#[cfg(not(feature = "mmap"))]
+use std::io::{Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use flate2::read::ZlibDecoder;
+
+#[cfg(feature = "mmap")]
+use crate::classpath::mmap::Mmap;
+
+const MAGIC: u32 = 0xCAFE_DADA;
+const MAJOR_VERSION: u32 = 1;
+const HEADER_SIZE: usize = 7 * 4;
+/// The multiplier and the first seed of the hash of resource names.
+const HASH_MULTIPLIER: u32 = 0x0100_0193;
+
+const ATTRIBUTE_END: u8 = 0;
+const ATTRIBUTE_MODULE: u8 = 1;
+const ATTRIBUTE_PARENT: u8 = 2;
+const ATTRIBUTE_BASE: u8 = 3;
+const ATTRIBUTE_EXTENSION: u8 = 4;
+const ATTRIBUTE_OFFSET: u8 = 5;
+const ATTRIBUTE_COMPRESSED: u8 = 6;
+const ATTRIBUTE_UNCOMPRESSED: u8 = 7;
+const ATTRIBUTE_COUNT: usize = 8;
+
+const COMPRESSED_MAGIC: u32 = 0xCAFE_FAFA;
+const COMPRESSED_HEADER_SIZE: usize = 4 + 8 + 8 + 4 + 4 + 1;
+
+/// What the resources are read from, memory-mapped with the `mmap` feature.
+#[cfg(feature = "mmap")]
+type Source = Mmap;
+#[cfg(not(feature = "mmap"))]
+type Source = RefCell<File>;
+
+#[derive(Debug)]
+pub struct Jimage {
+    path: PathBuf,
+    source: Source,
+    big_endian: bool,
+    /// The header, the tables, the locations and the strings.
+    index: Vec<u8>,
+    table_length: usize,
+    locations_start: usize,
+    strings_start: usize,
+    /// Where the resources start, their offsets are from there.
+    resources_start: u64,
+    /// The module of each package looked up, by package name.
+    modules: RefCell<HashMap<String, Option<String>>>,
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("Invalid jimage: {}", message),
+    )
+}
+
+/// The hash of a resource name, `seed` being `HASH_MULTIPLIER` first and then the value
+/// the redirect table gives when names collide.
+fn hash(name: &[u8], seed: u32) -> u32 {
+    name.iter().fold(seed, |hash, byte| {
+        hash.wrapping_mul(HASH_MULTIPLIER) ^ *byte as u32
+    }) & 0x7FFF_FFFF
+}
+
+/// Reads what a `readInt` of `CompressIndexes` reads: an int in 1 to 3 bytes whose
+/// first one has the high bit set and its length in the next two bits, else in 4 bytes.
+fn compressed_int(data: &[u8], at: &mut usize) -> io::Result<u32> {
+    let header = *data.get(*at).ok_or_else(|| invalid("truncated index"))?;
+    let (length, mut value) = if header & 0x80 != 0 {
+        ((header as usize >> 5) & 0x3, header as u32 & 0x1F)
+    } else {
+        (4, header as u32)
+    };
+    for i in 1..length {
+        let byte = *data
+            .get(*at + i)
+            .ok_or_else(|| invalid("truncated index"))?;
+        value = value << 8 | byte as u32;
+    }
+    *at += length.max(1);
+    Ok(value)
+}
+
+impl Jimage {
+    pub fn open(path: &Path) -> io::Result<Jimage> {
+        let mut file = File::open(path)?;
+        let mut header = [0; HEADER_SIZE];
+        file.read_exact(&mut header)?;
+        let big_endian = match u32::from_le_bytes(header[..4].try_into().unwrap()) {
+            MAGIC => false,
+            x if x.swap_bytes() == MAGIC => true,
+            _ => return Err(invalid("bad magic number")),
+        };
+        let u4 = |at: usize| {
+            let bytes = header[at..at + 4].try_into().unwrap();
+            if big_endian {
+                u32::from_be_bytes(bytes)
+            } else {
+                u32::from_le_bytes(bytes)
+            }
+        };
+        if u4(4) >> 16 != MAJOR_VERSION {
+            return Err(invalid("unsupported version"));
+        }
+        let table_length = u4(16) as usize;
+        let locations_start = HEADER_SIZE + table_length * 8;
+        let strings_start = locations_start + u4(20) as usize;
+        let index_size = strings_start + u4(24) as usize;
+        let mut index = header.to_vec();
+        index.resize(index_size, 0);
+        file.read_exact(&mut index[HEADER_SIZE..])?;
+        #[cfg(feature = "mmap")]
+        let source = Mmap::new(&file)?;
+        #[cfg(not(feature = "mmap"))]
+        let source = RefCell::new(file);
+        Ok(Jimage {
+            path: path.to_owned(),
+            source,
+            big_endian,
+            index,
+            table_length,
+            locations_start,
+            strings_start,
+            resources_start: index_size as u64,
+            modules: RefCell::new(HashMap::new()),
+        })
+    }
+
+    fn u4(&self, at: usize) -> u32 {
+        self.read_u4(&self.index, at)
+    }
+
+    /// The NUL terminated string at `offset` in the string table.
+    fn string(&self, offset: u64) -> io::Result<&str> {
+        let start = self.strings_start + offset as usize;
+        let strings = self
+            .index
+            .get(start..)
+            .ok_or_else(|| invalid("bad string"))?;
+        let end = strings
+            .iter()
+            .position(|x| *x == 0)
+            .unwrap_or(strings.len());
+        std::str::from_utf8(&strings[..end]).map_err(|_| invalid("bad string"))
+    }
+
+    /// The attributes of the location at `offset` in the location table.
+    fn attributes(&self, offset: usize) -> io::Result<[u64; ATTRIBUTE_COUNT]> {
+        let mut attributes = [0; ATTRIBUTE_COUNT];
+        let mut at = self.locations_start + offset;
+        loop {
+            let byte = *self.index.get(at).ok_or_else(|| invalid("bad location"))?;
+            let kind = byte >> 3;
+            if kind == ATTRIBUTE_END {
+                return Ok(attributes);
+            }
+            let length = (byte & 0x7) as usize + 1;
+            let bytes = self
+                .index
+                .get(at + 1..at + 1 + length)
+                .ok_or_else(|| invalid("bad location"))?;
+            let value = bytes.iter().fold(0, |value, x| value << 8 | *x as u64);
+            *attributes
+                .get_mut(kind as usize)
+                .ok_or_else(|| invalid("bad attribute"))? = value;
+            at += 1 + length;
+        }
+    }
+
+    /// The name a location stands for, `/module/parent/base.extension` with the parts
+    /// it has.
+    fn location_name(&self, attributes: &[u64; ATTRIBUTE_COUNT]) -> io::Result<String> {
+        let mut name = String::new();
+        let module = self.string(attributes[ATTRIBUTE_MODULE as usize])?;
+        if !module.is_empty() {
+            name += &format!("/{}/", module);
+        }
+        let parent = self.string(attributes[ATTRIBUTE_PARENT as usize])?;
+        if !parent.is_empty() {
+            name += &format!("{}/", parent);
+        }
+        name += self.string(attributes[ATTRIBUTE_BASE as usize])?;
+        let extension = self.string(attributes[ATTRIBUTE_EXTENSION as usize])?;
+        if !extension.is_empty() {
+            name += &format!(".{}", extension);
+        }
+        Ok(name)
+    }
+
+    /// The attributes of the resource `name`, like `/java.base/java/lang/Object.class`.
+    fn find(&self, name: &str) -> io::Result<Option<[u64; ATTRIBUTE_COUNT]>> {
+        if self.table_length == 0 {
+            return Ok(None);
+        }
+        let bytes = name.as_bytes();
+        let length = self.table_length as u32;
+        let index = hash(bytes, HASH_MULTIPLIER) % length;
+        let redirect = self.u4(HEADER_SIZE + index as usize * 4) as i32;
+        let index = match redirect {
+            0 => return Ok(None),
+            x if x < 0 => (-1 - x) as u32,
+            x => hash(bytes, x as u32) % length,
+        };
+        if index >= length {
+            return Err(invalid("bad redirect"));
+        }
+        let offset = self.u4(HEADER_SIZE + (self.table_length + index as usize) * 4);
+        let attributes = self.attributes(offset as usize)?;
+        // The table only tells where the name would be, another one may be there
+        if self.location_name(&attributes)? != name {
+            return Ok(None);
+        }
+        Ok(Some(attributes))
+    }
+
+    fn read_at(&self, offset: u64, length: usize) -> io::Result<Vec<u8>> {
+        let start = self.resources_start + offset;
+        #[cfg(feature = "mmap")]
+        {
+            let data = self.source.as_ref();
+            data.get(start as usize..start as usize + length)
+                .map(|x| x.to_vec())
+                .ok_or_else(|| invalid("resource out of the file"))
+        }
+        #[cfg(not(feature = "mmap"))]
+        {
+            let mut file = self.source.borrow_mut();
+            file.seek(SeekFrom::Start(start))?;
+            let mut data = vec![0; length];
+            file.read_exact(&mut data)?;
+            Ok(data)
+        }
+    }
+
+    /// The content of the resource `name`, decompressed.
+    pub fn read(&self, name: &str) -> io::Result<Vec<u8>> {
+        let attributes = self
+            .find(name)?
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "Resource not found"))?;
+        let offset = attributes[ATTRIBUTE_OFFSET as usize];
+        let compressed = attributes[ATTRIBUTE_COMPRESSED as usize] as usize;
+        let uncompressed = attributes[ATTRIBUTE_UNCOMPRESSED as usize] as usize;
+        if compressed == 0 {
+            return self.read_at(offset, uncompressed);
+        }
+        let mut data = self.read_at(offset, compressed)?;
+        // Each compression wraps the resource in a header of its own
+        while data.len() >= COMPRESSED_HEADER_SIZE && self.read_u4(&data, 0) == COMPRESSED_MAGIC {
+            let size = self.read_u8(&data, 12) as usize;
+            let decompressor = self.string(self.read_u4(&data, 20) as u64)?;
+            let content = &data[COMPRESSED_HEADER_SIZE..];
+            data = match decompressor {
+                "zip" => {
+                    let mut decompressed = Vec::with_capacity(size);
+                    ZlibDecoder::new(content).read_to_end(&mut decompressed)?;
+                    decompressed
+                }
+                "compact-cp" => self.expand_strings(content)?,
+                _ => return Err(invalid(&format!("unknown decompressor {}", decompressor))),
+            };
+        }
+        Ok(data)
+    }
+
+    fn read_u4(&self, data: &[u8], at: usize) -> u32 {
+        let bytes = data[at..at + 4].try_into().unwrap();
+        if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        }
+    }
+
+    fn read_u8(&self, data: &[u8], at: usize) -> u64 {
+        let bytes = data[at..at + 8].try_into().unwrap();
+        if self.big_endian {
+            u64::from_be_bytes(bytes)
+        } else {
+            u64::from_le_bytes(bytes)
+        }
+    }
+
+    /// Undoes the `compact-cp` compression of a class file, which moves the UTF-8
+    /// constants to the string table of the image, descriptors split into the package
+    /// and the simple name of each class they refer to.
+    fn expand_strings(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        const EXTERNALIZED_STRING: u8 = 23;
+        const EXTERNALIZED_STRING_DESCRIPTOR: u8 = 25;
+        let truncated = || invalid("truncated class");
+        let get = |range: std::ops::Range<usize>| data.get(range).ok_or_else(truncated);
+        let mut out = get(0..10)?.to_vec();
+        let count = u16::from_be_bytes([data[8], data[9]]);
+        let mut at = 10;
+        let put_utf8 = |out: &mut Vec<u8>, bytes: &[u8]| {
+            out.push(1);
+            out.extend((bytes.len() as u16).to_be_bytes());
+            out.extend(bytes);
+        };
+        let mut i = 1;
+        while i < count {
+            let tag = *data.get(at).ok_or_else(truncated)?;
+            at += 1;
+            match tag {
+                EXTERNALIZED_STRING => {
+                    let index = compressed_int(data, &mut at)?;
+                    put_utf8(&mut out, self.string(index as u64)?.as_bytes());
+                }
+                EXTERNALIZED_STRING_DESCRIPTOR => {
+                    let descriptor = self.string(compressed_int(data, &mut at)? as u64)?;
+                    let length = compressed_int(data, &mut at)? as usize;
+                    let indexes = get(at..at + length)?;
+                    at += length;
+                    let mut indexes_at = 0;
+                    let mut next = || compressed_int(indexes, &mut indexes_at);
+                    let mut expanded = Vec::new();
+                    for byte in descriptor.bytes() {
+                        expanded.push(byte);
+                        if byte == b'L' {
+                            let package = self.string(next()? as u64)?;
+                            if !package.is_empty() {
+                                expanded.extend(package.bytes());
+                                expanded.push(b'/');
+                            }
+                            expanded.extend(self.string(next()? as u64)?.bytes());
+                        }
+                    }
+                    put_utf8(&mut out, &expanded);
+                }
+                1 => {
+                    let length = u16::from_be_bytes(get(at..at + 2)?.try_into().unwrap());
+                    let end = at + 2 + length as usize;
+                    out.push(tag);
+                    out.extend(get(at..end)?);
+                    at = end;
+                }
+                _ => {
+                    let size = match tag {
+                        3 | 4 | 9 | 10 | 11 | 12 | 17 | 18 => 4,
+                        5 | 6 => 8,
+                        7 | 8 | 16 | 19 | 20 => 2,
+                        15 => 3,
+                        _ => return Err(invalid("bad constant tag")),
+                    };
+                    out.push(tag);
+                    out.extend(get(at..at + size)?);
+                    at += size;
+                    // Longs and doubles take two entries
+                    if tag == 5 || tag == 6 {
+                        i += 1;
+                    }
+                }
+            }
+            i += 1;
+        }
+        out.extend(get(at..data.len())?);
+        Ok(out)
+    }
+
+    /// The module the package `package`, like `java/lang`, is in.
+    fn module(&self, package: &str) -> io::Result<Option<String>> {
+        if let Some(module) = self.modules.borrow().get(package) {
+            return Ok(module.clone());
+        }
+        // The content is pairs of a flag telling the module has no classes of the
+        // package and the offset of its name, the first one with some is the one
+        let name = format!("/packages/{}", package.replace('/', "."));
+        let module = match self.read(&name) {
+            Ok(data) => data
+                .chunks_exact(8)
+                .find(|x| self.read_u4(x, 0) == 0)
+                .map(|x| self.string(self.read_u4(x, 4) as u64).map(|x| x.to_owned()))
+                .transpose()?,
+            Err(e) if e.kind() == ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        self.modules
+            .borrow_mut()
+            .insert(package.to_owned(), module.clone());
+        Ok(module)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The `jrt:` URL of the resource `name`, like `jrt:/java.base/java/lang/Object.class`,
+    /// if the module its package is in has it.
+    pub fn url(&self, name: &str) -> Option<String> {
+        let (package, _) = name.rsplit_once('/')?;
+        let path = format!("/{}/{}", self.module(package).ok()??, name);
+        self.find(&path).ok()??;
+        Some(format!("jrt:{}", path))
+    }
+
+    /// Reads the class file `class_file_name`, like `java/lang/Object.class`, from the
+    /// module its package is in.
+    pub fn read_class(&self, class_file_name: &str) -> io::Result<Vec<u8>> {
+        let not_found = || Error::new(ErrorKind::NotFound, "Class not found");
+        let (package, _) = class_file_name.rsplit_once('/').ok_or_else(not_found)?;
+        let module = self.module(package)?.ok_or_else(not_found)?;
+        self.read(&format!("/{}/{}", module, class_file_name))
+    }
+}
+#[cfg(test)]
+pub mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::io::Write;
+
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+
+    use crate::classpath::jimage::*;
+
+    /// How a resource is stored in the image.
+    pub enum Stored {
+        Plain,
+        Zip,
+        /// Compressed by `compact-cp` to the bytes given, which refer to the strings
+        /// `write_image` puts first in the string table.
+        CompactCp(Vec<u8>),
+    }
+
+    /// The strings of an image, the empty one first.
+    #[derive(Default)]
+    struct Strings {
+        data: Vec<u8>,
+        offsets: HashMap<String, u32>,
+    }
+
+    impl Strings {
+        fn add(&mut self, string: &str) -> u32 {
+            if let Some(offset) = self.offsets.get(string) {
+                return *offset;
+            }
+            let offset = self.data.len() as u32;
+            self.data.extend(string.bytes());
+            self.data.push(0);
+            self.offsets.insert(string.to_owned(), offset);
+            offset
+        }
+    }
+
+    fn attribute(locations: &mut Vec<u8>, kind: u8, value: u64) {
+        if value == 0 {
+            return;
+        }
+        let bytes = value.to_be_bytes();
+        let skip = bytes.iter().take_while(|x| **x == 0).count();
+        locations.push(kind << 3 | (7 - skip) as u8);
+        locations.extend(&bytes[skip..]);
+    }
+
+    /// An int as `CompressIndexes` writes it, in 2 bytes.
+    pub fn compressed_index(value: u32) -> [u8; 2] {
+        [0xC0 | (value >> 8) as u8, value as u8]
+    }
+
+    /// Writes an image of the resources, named like `/module/pkg/Class.class`, and of
+    /// a `/packages/pkg` resource for each package, in the native byte order. The string
+    /// table starts with the empty string and then `shared`.
+    pub fn write_image(path: &Path, shared: &[&str], resources: Vec<(&str, &[u8], Stored)>) {
+        let mut strings = Strings::default();
+        strings.add("");
+        for string in shared {
+            strings.add(string);
+        }
+        let mut names = Vec::new();
+        let mut contents = Vec::new();
+        let mut packages: Vec<(String, String)> = Vec::new();
+        for (name, content, stored) in resources {
+            let (module, file) = name[1..].split_once('/').unwrap();
+            let (package, _) = file.rsplit_once('/').unwrap();
+            let package = package.replace('/', ".");
+            if !packages.iter().any(|(x, _)| *x == package) {
+                packages.push((package, module.to_owned()));
+            }
+            names.push(name.to_owned());
+            contents.push((content.to_vec(), stored));
+        }
+        for (package, module) in &packages {
+            let mut content = 0u32.to_ne_bytes().to_vec();
+            content.extend(strings.add(module).to_ne_bytes());
+            names.push(format!("/packages/{}", package));
+            contents.push((content, Stored::Plain));
+        }
+        let mut locations = vec![0];
+        let mut location_offsets = Vec::new();
+        let mut resources = Vec::new();
+        for (name, (content, stored)) in names.iter().zip(contents) {
+            let (module, file) = name[1..].split_once('/').unwrap();
+            let (parent, file) = file.rsplit_once('/').unwrap_or(("", file));
+            let (base, extension) = file.rsplit_once('.').unwrap_or((file, ""));
+            let stored = match stored {
+                Stored::Plain => content.clone(),
+                Stored::Zip => {
+                    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+                    encoder.write_all(&content).unwrap();
+                    header(
+                        &mut strings,
+                        "zip",
+                        encoder.finish().unwrap(),
+                        content.len(),
+                    )
+                }
+                Stored::CompactCp(compressed) => {
+                    header(&mut strings, "compact-cp", compressed, content.len())
+                }
+            };
+            location_offsets.push(locations.len() as u32);
+            attribute(&mut locations, ATTRIBUTE_MODULE, strings.add(module) as u64);
+            attribute(&mut locations, ATTRIBUTE_PARENT, strings.add(parent) as u64);
+            attribute(&mut locations, ATTRIBUTE_BASE, strings.add(base) as u64);
+            attribute(
+                &mut locations,
+                ATTRIBUTE_EXTENSION,
+                strings.add(extension) as u64,
+            );
+            attribute(&mut locations, ATTRIBUTE_OFFSET, resources.len() as u64);
+            if stored != content {
+                attribute(&mut locations, ATTRIBUTE_COMPRESSED, stored.len() as u64);
+            }
+            attribute(&mut locations, ATTRIBUTE_UNCOMPRESSED, content.len() as u64);
+            locations.push(0);
+            resources.extend(stored);
+        }
+        // A table of a prime length, as names a power of two apart may never spread.
+        // Names colliding in a bucket get a seed that spreads them to free slots, then
+        // the others get the free slots left
+        let length = (names.len()..)
+            .find(|x| *x > 2 && (2..*x).all(|y| x % y != 0))
+            .unwrap();
+        let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); length];
+        for (i, name) in names.iter().enumerate() {
+            buckets[(hash(name.as_bytes(), HASH_MULTIPLIER) % length as u32) as usize].push(i);
+        }
+        let mut redirect = vec![0i32; length];
+        let mut offsets: Vec<Option<u32>> = vec![None; length];
+        let mut order: Vec<usize> = (0..length).collect();
+        order.sort_by_key(|x| std::cmp::Reverse(buckets[*x].len()));
+        for bucket in order {
+            match buckets[bucket].len() {
+                0 => {}
+                1 => {
+                    let slot = offsets.iter().position(|x| x.is_none()).unwrap();
+                    offsets[slot] = Some(location_offsets[buckets[bucket][0]]);
+                    redirect[bucket] = -1 - slot as i32;
+                }
+                _ => {
+                    let slot = |seed, i: &usize| {
+                        (hash(names[*i].as_bytes(), seed) % length as u32) as usize
+                    };
+                    let seed = (1..)
+                        .find(|seed| {
+                            let mut slots: Vec<usize> =
+                                buckets[bucket].iter().map(|x| slot(*seed, x)).collect();
+                            slots.sort();
+                            slots.dedup();
+                            slots.len() == buckets[bucket].len()
+                                && slots.iter().all(|x| offsets[*x].is_none())
+                        })
+                        .unwrap();
+                    for i in &buckets[bucket] {
+                        offsets[slot(seed, i)] = Some(location_offsets[*i]);
+                    }
+                    redirect[bucket] = seed as i32;
+                }
+            }
+        }
+        let mut image = Vec::new();
+        let header = [
+            MAGIC,
+            MAJOR_VERSION << 16,
+            0,
+            names.len() as u32,
+            length as u32,
+            locations.len() as u32,
+            strings.data.len() as u32,
+        ];
+        header.iter().for_each(|x| image.extend(x.to_ne_bytes()));
+        redirect.iter().for_each(|x| image.extend(x.to_ne_bytes()));
+        offsets
+            .iter()
+            .for_each(|x| image.extend(x.unwrap_or(0).to_ne_bytes()));
+        image.extend(locations);
+        image.extend(&strings.data);
+        image.extend(resources);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, image).unwrap();
+    }
+
+    fn header(strings: &mut Strings, decompressor: &str, content: Vec<u8>, size: usize) -> Vec<u8> {
+        let mut data = COMPRESSED_MAGIC.to_ne_bytes().to_vec();
+        data.extend((content.len() as u64).to_ne_bytes());
+        data.extend((size as u64).to_ne_bytes());
+        data.extend(strings.add(decompressor).to_ne_bytes());
+        data.extend(0u32.to_ne_bytes());
+        data.push(1);
+        data.extend(content);
+        data
+    }
+
+    #[test]
+    fn resources() {
+        let path = std::env::temp_dir().join(format!("jvm-jimage-{}", std::process::id()));
+        let names: Vec<String> = (0..20)
+            .map(|x| format!("/mod.a/pkg/a/C{}.class", x))
+            .collect();
+        let mut resources: Vec<(&str, &[u8], Stored)> = names
+            .iter()
+            .map(|x| (x.as_str(), x.as_bytes(), Stored::Plain))
+            .collect();
+        let zipped = b"zipped".repeat(50);
+        resources.push(("/mod.b/pkg/b/Zipped.class", &zipped, Stored::Zip));
+        // A constant pool of a shared name and a descriptor sharing the names of its
+        // classes, then a constant of its own and the rest of the class
+        let shared = ["pkg/b/Shared", "(L;IL;)V", "java/lang", "String", "Top"];
+        let offset = |x: &str| {
+            if x.is_empty() {
+                return 0;
+            }
+            let i = shared.iter().position(|y| *y == x).unwrap();
+            shared[..i].iter().map(|x| x.len() as u32 + 1).sum::<u32>() + 1
+        };
+        let mut compressed = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 52, 0, 5];
+        compressed.push(23);
+        compressed.extend(compressed_index(offset("pkg/b/Shared")));
+        compressed.push(25);
+        compressed.extend(compressed_index(offset("(L;IL;)V")));
+        compressed.extend(compressed_index(8));
+        for string in ["java/lang", "String", "", "Top"] {
+            compressed.extend(compressed_index(offset(string)));
+        }
+        compressed.extend([5, 0, 0, 0, 0, 0, 0, 0, 7, 0xAB]);
+        let mut class = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 52, 0, 5];
+        class.extend([1, 0, 12]);
+        class.extend(b"pkg/b/Shared");
+        class.extend([1, 0, 27]);
+        class.extend(b"(Ljava/lang/String;ILTop;)V");
+        class.extend([5, 0, 0, 0, 0, 0, 0, 0, 7, 0xAB]);
+        resources.push((
+            "/mod.b/pkg/b/Shared.class",
+            &class,
+            Stored::CompactCp(compressed),
+        ));
+        write_image(&path, &shared, resources);
+        let image = Jimage::open(&path).unwrap();
+        for name in &names {
+            let class_file_name = name.strip_prefix("/mod.a/").unwrap();
+            assert_eq!(image.read_class(class_file_name).unwrap(), name.as_bytes());
+        }
+        assert_eq!(image.read("/mod.b/pkg/b/Zipped.class").unwrap(), zipped);
+        assert_eq!(image.read_class("pkg/b/Shared.class").unwrap(), class);
+        assert_eq!(image.module("pkg/b").unwrap(), Some("mod.b".to_owned()));
+        assert!(image.read_class("pkg/a/Missing.class").is_err());
+        assert!(image.read_class("pkg/c/C0.class").is_err());
+        assert!(image.read("/mod.b/pkg/a/C0.class").is_err());
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,192 @@
+//! The manifest of a jar, `META-INF/MANIFEST.MF`: a main section of `Name: value`
+//! attributes, then a section per entry that starts with its `Name` attribute, sections
+//! separated by blank lines. Lines are at most 72 bytes, a longer value going on in
+//! lines that start with a space.
+
+/// Attributes in the order they're written, their names compared ignoring case.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Attributes(Vec<(String, String)>);
+
+impl Attributes {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(x, _)| x.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    pub main: Attributes,
+    /// The sections of entries, by the value of their `Name` attribute.
+    pub sections: Vec<(String, Attributes)>,
+}
+
+impl Manifest {
+    /// Parses a manifest, skipping the lines that aren't attributes.
+    pub fn parse(data: &[u8]) -> Manifest {
+        // Joins the continuation lines first, on bytes since a line may end in the middle
+        // of a character, an empty line ending a section
+        let mut lines: Vec<Vec<u8>> = Vec::new();
+        let mut continued = false;
+        for line in split_lines(data) {
+            match line.strip_prefix(b" ") {
+                Some(rest) if continued => lines.last_mut().unwrap().extend_from_slice(rest),
+                _ => {
+                    continued = !line.is_empty();
+                    lines.push(line.to_vec());
+                }
+            }
+        }
+        let mut manifest = Manifest::default();
+        let mut section: Option<Attributes> = None;
+        for line in lines {
+            let line = String::from_utf8_lossy(&line);
+            if line.is_empty() {
+                if let Some(attributes) = section.take() {
+                    manifest.add(attributes);
+                }
+                continue;
+            }
+            let Some((name, value)) = line.split_once(": ") else {
+                continue;
+            };
+            section
+                .get_or_insert_with(Attributes::default)
+                .0
+                .push((name.to_owned(), value.to_owned()));
+        }
+        if let Some(attributes) = section {
+            manifest.add(attributes);
+        }
+        manifest
+    }
+
+    /// Adds a section, the main one if it's the first.
+    fn add(&mut self, attributes: Attributes) {
+        if self.main.0.is_empty() && self.sections.is_empty() {
+            self.main = attributes;
+        } else if let Some(name) = attributes.get("Name") {
+            self.sections.push((name.to_owned(), attributes));
+        }
+    }
+
+    /// The attributes of the section of the entry `name`.
+    pub fn section(&self, name: &str) -> Option<&Attributes> {
+        self.sections
+            .iter()
+            .find(|(x, _)| x == name)
+            .map(|(_, attributes)| attributes)
+    }
+
+    /// The relative URLs of `Class-Path`, separated by spaces, percent-decoded.
+    pub fn class_path(&self) -> Vec<String> {
+        self.main
+            .get("Class-Path")
+            .unwrap_or("")
+            .split_whitespace()
+            .map(percent_decode)
+            .collect()
+    }
+
+    /// The class of `Main-Class` in internal form, like `pkg/Main`.
+    pub fn main_class(&self) -> Option<String> {
+        self.main
+            .get("Main-Class")
+            .map(|x| x.trim().replace('.', "/"))
+    }
+
+    pub fn is_multi_release(&self) -> bool {
+        self.main
+            .get("Multi-Release")
+            .is_some_and(|x| x.trim().eq_ignore_ascii_case("true"))
+    }
+}
+
+/// The lines of `data`, ended by `\r\n`, `\r` or `\n`.
+fn split_lines(data: &[u8]) -> Vec<&[u8]> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < data.len() {
+        match data[i] {
+            b'\r' | b'\n' => {
+                lines.push(&data[start..i]);
+                if data[i] == b'\r' && data.get(i + 1) == Some(&b'\n') {
+                    i += 1;
+                }
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    lines.push(&data[start..]);
+    lines
+}
+
+/// `a%20b` -> `a b`, bytes that aren't escapes left as they are.
+fn percent_decode(url: &str) -> String {
+    let bytes = url.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .and_then(|x| std::str::from_utf8(x).ok())
+            .and_then(|x| u8::from_str_radix(x, 16).ok());
+        match escaped {
+            Some(byte) if bytes[i] == b'%' => {
+                decoded.push(byte);
+                i += 3;
+            }
+            _ => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::classpath::manifest::*;
+
+    #[test]
+    fn parse() {
+        let manifest = Manifest::parse(
+            b"Manifest-Version: 1.0\r\n\
+              Main-Class: com.example.Main\r\n\
+              class-path: lib/a.jar lib/with%20space.jar \r\n \
+              lib/continued\r\n \
+              .jar classes/\r\n\
+              Multi-Release: TRUE\r\n\
+              \r\n\
+              Name: com/example/\r\n\
+              Sealed: true\r\n\
+              \r\n\
+              Name: com/example/Main.class\n\
+              Created-By: hand \xC3\r\n \xA9\n",
+        );
+        assert_eq!(manifest.main.get("MANIFEST-VERSION"), Some("1.0"));
+        assert_eq!(manifest.main_class(), Some("com/example/Main".to_owned()));
+        assert_eq!(
+            manifest.class_path(),
+            vec![
+                "lib/a.jar",
+                "lib/with space.jar",
+                "lib/continued.jar",
+                "classes/"
+            ]
+        );
+        assert!(manifest.is_multi_release());
+        assert_eq!(manifest.sections.len(), 2);
+        let section = manifest.section("com/example/").unwrap();
+        assert_eq!(section.get("Sealed"), Some("true"));
+        let section = manifest.section("com/example/Main.class").unwrap();
+        assert_eq!(section.get("Created-By"), Some("hand \u{e9}"));
+        assert_eq!(Manifest::parse(b""), Manifest::default());
+    }
+}
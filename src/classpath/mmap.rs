@@ -0,0 +1,76 @@
+//! Memory maps of jars, through the raw `mmap` and `munmap` of libc.
+//!
+//! Only built with the `mmap` feature, on Unix.
+
+#[cfg(not(unix))]
+compile_error!("The mmap feature needs Unix");
+
+use std::ffi::c_void;
+use std::fs::File;
+use std::io;
+#[cfg(unix)]
+use std::os::unix::io::AsRawFd;
+use std::ptr;
+use std::slice;
+
+const PROT_READ: i32 = 1;
+const MAP_PRIVATE: i32 = 0x02;
+const MAP_FAILED: *mut c_void = !0 as *mut c_void;
+
+#[cfg(unix)]
+extern "C" {
+    fn mmap(
+        addr: *mut c_void,
+        len: usize,
+        prot: i32,
+        flags: i32,
+        fd: i32,
+        offset: i64,
+    ) -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> i32;
+}
+
+/// A file mapped read-only to memory, so that reading it is copying from its pages.
+#[derive(Debug)]
+pub struct Mmap {
+    address: *mut c_void,
+    len: usize,
+}
+
+impl Mmap {
+    pub fn new(file: &File) -> io::Result<Mmap> {
+        let len = file.metadata()?.len() as usize;
+        if len == 0 {
+            // mmap refuses an empty mapping, and an empty file is no jar anyway
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Empty file"));
+        }
+        let address = unsafe {
+            mmap(
+                ptr::null_mut(),
+                len,
+                PROT_READ,
+                MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if address == MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Mmap { address, len })
+    }
+}
+
+impl AsRef<[u8]> for Mmap {
+    fn as_ref(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.address as *const u8, self.len) }
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        unsafe {
+            munmap(self.address, self.len);
+        }
+    }
+}
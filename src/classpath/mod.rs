@@ -1 +1,6 @@
 pub mod classpath;
+mod jar;
+mod jimage;
+pub mod manifest;
+#[cfg(feature = "mmap")]
+mod mmap;
@@ -0,0 +1,98 @@
+use std::rc::Rc;
+
+use crate::instruction::reference::invoke::invoke_method;
+use crate::rtda::heap::class::Class;
+use crate::rtda::heap::object::ObjectRef;
+use crate::rtda::slot::Slot;
+use crate::rtda::thread::Thread;
+
+const FINALIZE: (&str, &str) = ("finalize", "()V");
+
+/// Whether instances of `class` need `finalize()` to run before they're freed, that is
+/// whether it overrides `Object.finalize()` with a non-empty method.
+pub fn has_finalizer(class: &Class) -> bool {
+    let (name, descriptor) = FINALIZE;
+    match class.lookup_method(name, descriptor) {
+        Some(method) => {
+            method.class().name != "java/lang/Object" && method.code.as_slice() != [0xB1]
+        }
+        None => false,
+    }
+}
+
+/// Calls `finalize()` on `object`, taken from the finalization queue, on `thread`, the
+/// Finalizer thread whose stack is empty. Returns the pc to start at.
+pub fn run_finalizer(thread: &mut Thread, object: ObjectRef) -> usize {
+    let class = Rc::clone(&thread.heap.borrow().get(object).class);
+    let (name, descriptor) = FINALIZE;
+    let method = class
+        .lookup_method(name, descriptor)
+        .expect("Object.finalize() not found");
+    invoke_method(thread, method, vec![Slot::Ref(Some(object))])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::gc::finalizer::*;
+    use crate::gc::heap::{Collector, Heap};
+    use crate::instruction::instruction::execute;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::heap::object::Object;
+
+    #[test]
+    fn finalizer() {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("gc/Finalizable");
+        assert!(has_finalizer(&class));
+        assert!(!has_finalizer(&class_loader.load("java/lang/Object")));
+        assert!(!has_finalizer(&class_loader.load("gc/Node")));
+
+        let mut thread = Thread::new();
+        let object = Object::new(Rc::clone(&class));
+        let object = thread.heap.borrow_mut().allocate(object).unwrap();
+        assert_eq!(thread.heap.borrow_mut().take_finalizable(), None);
+
+        // unreachable, so queued for finalization instead of being freed
+        thread.heap.borrow_mut().collect(vec![]);
+        assert!(thread.heap.borrow().contains(object));
+
+        let object = thread.heap.borrow_mut().take_finalizable().unwrap();
+        let mut pc = run_finalizer(&mut thread, object);
+        assert_eq!(pc, 0);
+        assert_eq!(thread.current_frame().method.name(), "finalize");
+        for _ in 0..5 {
+            pc = execute(pc, &mut thread);
+        }
+        assert!(thread.is_stack_empty());
+        assert_eq!(class.static_vars.borrow().get_int(0), 1);
+
+        // finalize() runs once, the object is freed by the next collection
+        thread.heap.borrow_mut().collect(vec![]);
+        assert!(!thread.heap.borrow().contains(object));
+    }
+
+    #[test]
+    fn minor_collection_finalizer() {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let mut heap = Heap::with_collector(4096, Collector::Generational);
+        let object = Object::new(class_loader.load("gc/Finalizable"));
+        let object = heap.allocate(object).unwrap();
+
+        heap.collect(vec![]);
+        assert!(heap.is_young(object));
+        assert_eq!(heap.take_finalizable(), Some(object));
+        heap.collect(vec![]);
+        assert!(!heap.contains(object));
+    }
+}
@@ -0,0 +1,140 @@
+use crate::gc::heap::{Entry, Heap, YoungObject, CARD_SIZE};
+use crate::gc::mark_sweep;
+use crate::rtda::heap::object::ObjectRef;
+
+/// Minor collections an object survives in the young generation before it's promoted.
+pub const TENURING_THRESHOLD: u8 = 2;
+
+/// Copies the young objects reachable from `roots`, or from old objects on dirty cards,
+/// into a fresh semispace and promotes the ones old enough. The old generation isn't
+/// traced, so garbage there survives until the next full collection. References are
+/// traced like other fields, they are only processed by full collections.
+pub fn collect_minor(heap: &mut Heap, roots: Vec<ObjectRef>) {
+    let dirty_cards: Vec<usize> = (0..heap.cards.len()).filter(|x| heap.cards[*x]).collect();
+    let mut gray = roots;
+    for card in dirty_cards.iter() {
+        for index in card_handles(heap, *card) {
+            if let Entry::Old(ref object) = heap.handles[index] {
+                gray.extend(object.references());
+            }
+        }
+    }
+
+    let mut from_space = std::mem::take(&mut heap.young);
+    heap.young_used = 0;
+    let mut evacuated = vec![false; heap.handles.len()];
+    let mut promoted = Vec::new();
+    evacuate(heap, &mut from_space, &mut evacuated, &mut promoted, gray);
+
+    // Unreachable young objects with a finalizer are resurrected and queued
+    let (finalizable, unreachable): (Vec<ObjectRef>, Vec<ObjectRef>) = heap
+        .finalizable
+        .iter()
+        .partition(|x| !heap.is_young(**x) || evacuated[x.0]);
+    heap.finalizable = finalizable;
+    heap.finalization_queue.extend(unreachable.iter().cloned());
+    evacuate(
+        heap,
+        &mut from_space,
+        &mut evacuated,
+        &mut promoted,
+        unreachable,
+    );
+    free_young(heap, from_space);
+
+    let cards = dirty_cards
+        .into_iter()
+        .chain(promoted.into_iter().map(|x| x.0 / CARD_SIZE));
+    refresh_cards(heap, cards);
+}
+
+/// Copies the young objects reachable from `gray` that aren't evacuated yet.
+fn evacuate(
+    heap: &mut Heap,
+    from_space: &mut [Option<YoungObject>],
+    evacuated: &mut [bool],
+    promoted: &mut Vec<ObjectRef>,
+    gray: Vec<ObjectRef>,
+) {
+    let mut gray = gray;
+    while let Some(reference) = gray.pop() {
+        let index = match heap.handles[reference.0] {
+            Entry::Young(index) if !evacuated[reference.0] => index,
+            _ => continue,
+        };
+        evacuated[reference.0] = true;
+        let young_object = from_space[index].take().expect("Young object moved twice");
+        gray.extend(young_object.object.references());
+        if copy(heap, young_object) {
+            promoted.push(reference);
+        }
+    }
+}
+
+/// Marks both generations, sweeps the old one and copies the live young objects.
+pub fn collect_full(heap: &mut Heap, roots: Vec<ObjectRef>, clear_soft_references: bool) {
+    let marked = mark_sweep::mark(heap, roots, clear_soft_references);
+    mark_sweep::sweep_old(heap, &marked);
+
+    let mut from_space = std::mem::take(&mut heap.young);
+    heap.young_used = 0;
+    for young_object in from_space.iter_mut() {
+        let is_live = matches!(young_object, Some(x) if marked[x.reference.0]);
+        if is_live {
+            copy(heap, young_object.take().unwrap());
+        }
+    }
+    free_young(heap, from_space);
+
+    refresh_cards(heap, 0..heap.cards.len());
+}
+
+/// Moves a surviving young object to the new semispace, or to the old generation once it
+/// reaches the tenuring threshold and fits there. Returns whether it was promoted.
+fn copy(heap: &mut Heap, young_object: YoungObject) -> bool {
+    let YoungObject {
+        reference,
+        age,
+        object,
+    } = young_object;
+    let age = age + 1;
+    let size = object.size();
+    if age >= TENURING_THRESHOLD && heap.old_used + size <= heap.old_size {
+        heap.old_used += size;
+        heap.stats.objects_promoted += 1;
+        heap.stats.bytes_promoted += size as u64;
+        heap.handles[reference.0] = Entry::Old(object);
+        true
+    } else {
+        heap.young_used += size;
+        heap.young.push(Some(YoungObject {
+            reference,
+            age,
+            object,
+        }));
+        heap.handles[reference.0] = Entry::Young(heap.young.len() - 1);
+        false
+    }
+}
+
+/// Frees the objects left in the old semispace.
+fn free_young(heap: &mut Heap, from_space: Vec<Option<YoungObject>>) {
+    for young_object in from_space.into_iter().flatten() {
+        heap.free(young_object.reference, young_object.object.size());
+    }
+}
+
+fn card_handles(heap: &Heap, card: usize) -> std::ops::Range<usize> {
+    card * CARD_SIZE..((card + 1) * CARD_SIZE).min(heap.handles.len())
+}
+
+/// Recomputes the dirty bit of `cards` from the old objects they cover.
+fn refresh_cards(heap: &mut Heap, cards: impl Iterator<Item = usize>) {
+    for card in cards {
+        let dirty = card_handles(heap, card).any(|index| match heap.handles[index] {
+            Entry::Old(ref object) => object.references().into_iter().any(|x| heap.is_young(x)),
+            _ => false,
+        });
+        heap.cards[card] = dirty;
+    }
+}
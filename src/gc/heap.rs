@@ -0,0 +1,734 @@
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::gc::{finalizer, generational, mark_sweep};
+use crate::rtda::heap::class::Class;
+use crate::rtda::heap::object::{ArrayData, Object, ObjectRef};
+use crate::rtda::slot::Slot;
+
+/// Heap size used when no `-Xmx` is given.
+pub const DEFAULT_MAX_HEAP_SIZE: usize = 64 * 1024 * 1024;
+
+/// The garbage collector a heap runs, chosen at startup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Collector {
+    /// A single space collected by mark-sweep.
+    MarkSweep,
+    /// A young semispace collected by copying, whose survivors are promoted to an old
+    /// generation collected by mark-sweep.
+    Generational,
+}
+
+impl FromStr for Collector {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "mark-sweep" => Ok(Collector::MarkSweep),
+            "generational" => Ok(Collector::Generational),
+            _ => Err(format!("Unknown collector: {}", name)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum CollectionKind {
+    Minor,
+    Full,
+    /// A full collection that also clears softly reachable referents, the last resort
+    /// before `OutOfMemoryError`.
+    FullClearingSoftReferences,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct GcStats {
+    pub collections: u64,
+    pub minor_collections: u64,
+    pub total_pause: Duration,
+    pub last_pause: Duration,
+    pub objects_allocated: u64,
+    pub bytes_allocated: u64,
+    pub objects_freed: u64,
+    pub bytes_freed: u64,
+    pub objects_promoted: u64,
+    pub bytes_promoted: u64,
+}
+
+/// Where the object of a handle lives.
+#[derive(Debug)]
+pub(super) enum Entry {
+    Free,
+    /// Outside the young generation, which under mark-sweep holds every object.
+    Old(Object),
+    /// Index into the young semispace.
+    Young(usize),
+}
+
+#[derive(Debug)]
+pub(super) struct YoungObject {
+    pub(super) reference: ObjectRef,
+    /// Minor collections survived.
+    pub(super) age: u8,
+    pub(super) object: Object,
+}
+
+/// The garbage-collected heap. Objects are reached through `ObjectRef` handles into a
+/// handle table, so collectors can move them, handles of collected objects are reused
+/// for new allocations.
+pub struct Heap {
+    pub(super) collector: Collector,
+    pub(super) handles: Vec<Entry>,
+    pub(super) free_handles: Vec<usize>,
+    /// The young semispace objects are allocated in, a minor collection copies its
+    /// survivors into a fresh one. Slots are only empty while a collection runs.
+    pub(super) young: Vec<Option<YoungObject>>,
+    pub(super) young_used: usize,
+    pub(super) young_size: usize,
+    pub(super) old_used: usize,
+    pub(super) old_size: usize,
+    /// One dirty bit per `CARD_SIZE` handles, set when an old object in the card may
+    /// point into the young generation.
+    pub(super) cards: Vec<bool>,
+    pub(super) stats: GcStats,
+    /// Live objects whose class overrides `finalize()`, registered when allocated.
+    pub(super) finalizable: Vec<ObjectRef>,
+    /// Unreachable finalizable objects waiting for their `finalize()` to run, kept alive
+    /// until then.
+    pub(super) finalization_queue: VecDeque<ObjectRef>,
+    interned_strings: HashMap<String, ObjectRef>,
+    pub(super) gc_requested: Option<CollectionKind>,
+    /// The young generation was full at the last failed allocation, else the old one.
+    young_exhausted: bool,
+    /// The strongest collection that already ran for the allocation being retried.
+    retried_after: Option<CollectionKind>,
+}
+
+/// Handles covered by one card of the card table.
+pub(super) const CARD_SIZE: usize = 64;
+
+impl Default for Heap {
+    fn default() -> Self {
+        Heap::new(DEFAULT_MAX_HEAP_SIZE)
+    }
+}
+
+impl Heap {
+    pub fn new(max_size: usize) -> Heap {
+        Heap::with_collector(max_size, Collector::MarkSweep)
+    }
+
+    /// A heap of at most `max_size` bytes. Under the generational collector a quarter of
+    /// it is the young generation.
+    pub fn with_collector(max_size: usize, collector: Collector) -> Heap {
+        let young_size = match collector {
+            Collector::MarkSweep => 0,
+            Collector::Generational => max_size / 4,
+        };
+        Heap {
+            collector,
+            handles: Vec::new(),
+            free_handles: Vec::new(),
+            young: Vec::new(),
+            young_used: 0,
+            young_size,
+            old_used: 0,
+            old_size: max_size - young_size,
+            cards: Vec::new(),
+            stats: GcStats::default(),
+            finalizable: Vec::new(),
+            finalization_queue: VecDeque::new(),
+            interned_strings: HashMap::new(),
+            gc_requested: None,
+            young_exhausted: false,
+            retried_after: None,
+        }
+    }
+
+    pub fn collector(&self) -> Collector {
+        self.collector
+    }
+
+    /// Stores `object` in the heap, or returns `None` if it doesn't fit in what's left.
+    /// Objects larger than the young generation go straight to the old one.
+    pub fn allocate(&mut self, object: Object) -> Option<ObjectRef> {
+        let size = object.size();
+        if !self.has_room(&[size]) {
+            return None;
+        }
+        let in_young = size <= self.young_size;
+        let has_finalizer = finalizer::has_finalizer(&object.class);
+        self.stats.objects_allocated += 1;
+        self.stats.bytes_allocated += size as u64;
+        self.retried_after = None;
+        let reference = self.new_handle();
+        if in_young {
+            self.young_used += size;
+            self.young.push(Some(YoungObject {
+                reference,
+                age: 0,
+                object,
+            }));
+            self.handles[reference.0] = Entry::Young(self.young.len() - 1);
+        } else {
+            self.old_used += size;
+            self.handles[reference.0] = Entry::Old(object);
+        }
+        if has_finalizer {
+            self.finalizable.push(reference);
+        }
+        Some(reference)
+    }
+
+    /// Whether objects of `sizes` can all be allocated, remembering which generation
+    /// is exhausted if not.
+    fn has_room(&mut self, sizes: &[usize]) -> bool {
+        let (mut young_used, mut old_used) = (self.young_used, self.old_used);
+        for size in sizes {
+            if *size <= self.young_size {
+                young_used += size;
+            } else {
+                old_used += size;
+            }
+        }
+        self.young_exhausted = young_used > self.young_size;
+        !self.young_exhausted && old_used <= self.old_size
+    }
+
+    fn new_handle(&mut self) -> ObjectRef {
+        match self.free_handles.pop() {
+            Some(index) => ObjectRef(index),
+            None => {
+                self.handles.push(Entry::Free);
+                if self.cards.len() * CARD_SIZE < self.handles.len() {
+                    self.cards.push(false);
+                }
+                ObjectRef(self.handles.len() - 1)
+            }
+        }
+    }
+
+    /// Called after a failed allocation, which is retried once the requested collection
+    /// has run. Each failure asks for a stronger collection: minor, full, then full
+    /// clearing soft references. A failure after that throws `OutOfMemoryError`.
+    pub fn request_gc_for_allocation(&mut self) {
+        let kind = match self.retried_after {
+            Some(CollectionKind::FullClearingSoftReferences) => {
+                self.retried_after = None;
+                panic!("java.lang.OutOfMemoryError: Java heap space")
+            }
+            Some(CollectionKind::Full) => CollectionKind::FullClearingSoftReferences,
+            Some(CollectionKind::Minor) => CollectionKind::Full,
+            None if self.young_exhausted => CollectionKind::Minor,
+            None => CollectionKind::Full,
+        };
+        self.gc_requested = Some(kind);
+    }
+
+    pub fn is_gc_requested(&self) -> bool {
+        self.gc_requested.is_some()
+    }
+
+    /// Frees every object not reachable from `roots`, the intern table or the
+    /// finalization queue. When no collection was requested, the generational collector
+    /// runs a minor one.
+    pub fn collect(&mut self, roots: impl IntoIterator<Item = ObjectRef>) {
+        let roots: Vec<ObjectRef> = roots
+            .into_iter()
+            .chain(self.interned_strings.values().cloned())
+            .chain(self.finalization_queue.iter().cloned())
+            .collect();
+        let start = Instant::now();
+        let kind = match (self.collector, self.gc_requested) {
+            (Collector::Generational, None | Some(CollectionKind::Minor)) => CollectionKind::Minor,
+            (_, Some(CollectionKind::FullClearingSoftReferences)) => {
+                CollectionKind::FullClearingSoftReferences
+            }
+            _ => CollectionKind::Full,
+        };
+        let clear_soft_references = kind == CollectionKind::FullClearingSoftReferences;
+        match (self.collector, kind) {
+            (Collector::Generational, CollectionKind::Minor) => {
+                generational::collect_minor(self, roots);
+                self.stats.minor_collections += 1;
+            }
+            (Collector::Generational, _) => {
+                generational::collect_full(self, roots, clear_soft_references)
+            }
+            (Collector::MarkSweep, _) => mark_sweep::collect(self, roots, clear_soft_references),
+        }
+        if self.gc_requested.take().is_some() {
+            self.retried_after = Some(kind);
+        }
+        let pause = start.elapsed();
+        self.stats.collections += 1;
+        self.stats.last_pause = pause;
+        self.stats.total_pause += pause;
+    }
+
+    /// Records a reference store into `target`. Under the generational collector, an old
+    /// object pointing to a young one dirties its card, so minor collections find it.
+    pub fn write_barrier(&mut self, target: ObjectRef, val: Slot) {
+        if let Slot::Ref(Some(val)) = val {
+            if self.is_old(target) && self.is_young(val) {
+                self.cards[target.0 / CARD_SIZE] = true;
+            }
+        }
+    }
+
+    pub fn get(&self, reference: ObjectRef) -> &Object {
+        match self.handles[reference.0] {
+            Entry::Old(ref object) => object,
+            Entry::Young(index) => {
+                &self.young[index]
+                    .as_ref()
+                    .expect("Young object being moved")
+                    .object
+            }
+            Entry::Free => panic!("Dangling reference"),
+        }
+    }
+
+    pub fn get_mut(&mut self, reference: ObjectRef) -> &mut Object {
+        match self.handles[reference.0] {
+            Entry::Old(ref mut object) => object,
+            Entry::Young(index) => {
+                &mut self.young[index]
+                    .as_mut()
+                    .expect("Young object being moved")
+                    .object
+            }
+            Entry::Free => panic!("Dangling reference"),
+        }
+    }
+
+    pub fn contains(&self, reference: ObjectRef) -> bool {
+        !matches!(self.handles.get(reference.0), None | Some(Entry::Free))
+    }
+
+    pub fn is_young(&self, reference: ObjectRef) -> bool {
+        matches!(self.handles[reference.0], Entry::Young(_))
+    }
+
+    pub fn is_old(&self, reference: ObjectRef) -> bool {
+        matches!(self.handles[reference.0], Entry::Old(_))
+    }
+
+    /// The next object whose `finalize()` should run, if any.
+    pub fn take_finalizable(&mut self) -> Option<ObjectRef> {
+        self.finalization_queue.pop_front()
+    }
+
+    /// Whether `reference` is on a dirty card, i.e. in the remembered set.
+    pub fn is_remembered(&self, reference: ObjectRef) -> bool {
+        self.cards[reference.0 / CARD_SIZE]
+    }
+
+    pub fn used(&self) -> usize {
+        self.young_used + self.old_used
+    }
+
+    pub fn max_size(&self) -> usize {
+        self.young_size + self.old_size
+    }
+
+    pub fn live_objects(&self) -> usize {
+        self.handles.len() - self.free_handles.len()
+    }
+
+    pub fn stats(&self) -> &GcStats {
+        &self.stats
+    }
+
+    /// Returns the handle of a collected object to the free list.
+    pub(super) fn free(&mut self, reference: ObjectRef, size: usize) {
+        self.handles[reference.0] = Entry::Free;
+        self.free_handles.push(reference.0);
+        self.stats.objects_freed += 1;
+        self.stats.bytes_freed += size as u64;
+    }
+
+    /// The interned `java.lang.String` for `val`, created on first use.
+    /// Returns `None` if the heap has no room left for it.
+    pub fn intern(
+        &mut self,
+        val: &str,
+        string_class: Rc<Class>,
+        char_array_class: Rc<Class>,
+    ) -> Option<ObjectRef> {
+        if let Some(string) = self.interned_strings.get(val) {
+            return Some(*string);
+        }
+        let chars: Vec<u16> = val.encode_utf16().collect();
+        let mut array = Object::new_array(char_array_class, chars.len());
+        *array.array_mut() = ArrayData::Chars(chars);
+        let value_slot_id = string_class
+            .lookup_field("value", "[C")
+            .expect("java.lang.NoSuchFieldError: value")
+            .slot_id;
+        let mut string = Object::new(string_class);
+        if !self.has_room(&[array.size(), string.size()]) {
+            return None;
+        }
+        let array = self.allocate(array)?;
+        string.fields_mut()[value_slot_id] = Slot::Ref(Some(array));
+        let string = self.allocate(string)?;
+        self.write_barrier(string, Slot::Ref(Some(array)));
+        self.interned_strings.insert(val.to_owned(), string);
+        Some(string)
+    }
+
+    /// The interned string equal to the `java.lang.String` object `string`, which is
+    /// interned itself if none is yet.
+    pub fn intern_string(&mut self, string: ObjectRef) -> ObjectRef {
+        let val = self.java_string(string);
+        *self.interned_strings.entry(val).or_insert(string)
+    }
+
+    /// The Rust string of a `java.lang.String` object.
+    pub fn java_string(&self, string: ObjectRef) -> String {
+        let string = self.get(string);
+        let value_slot_id = string
+            .class
+            .lookup_field("value", "[C")
+            .expect("java.lang.NoSuchFieldError: value")
+            .slot_id;
+        match string.fields()[value_slot_id] {
+            Slot::Ref(Some(array)) => match self.get(array).array() {
+                ArrayData::Chars(chars) => String::from_utf16_lossy(chars),
+                _ => panic!("Not char[]"),
+            },
+            _ => panic!("java.lang.NullPointerException"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::gc::heap::{CollectionKind, Collector, Heap};
+    use crate::rtda::heap::class::Class;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::heap::object::{Object, ObjectRef};
+    use crate::rtda::slot::Slot;
+
+    fn class_loader() -> Rc<ClassLoader> {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        ClassLoader::new(class_path)
+    }
+
+    fn node(heap: &mut Heap, class: &Rc<Class>, next: Option<ObjectRef>) -> ObjectRef {
+        let next_slot_id = class.lookup_field("next", "Lgc/Node;").unwrap().slot_id;
+        let mut object = Object::new(Rc::clone(class));
+        object.fields_mut()[next_slot_id] = Slot::Ref(next);
+        heap.allocate(object).unwrap()
+    }
+
+    #[test]
+    fn collect_unreachable() {
+        let class = class_loader().load("gc/Node");
+        let mut heap = Heap::default();
+        let tail = node(&mut heap, &class, None);
+        let head = node(&mut heap, &class, Some(tail));
+        let garbage = node(&mut heap, &class, None);
+        let used = heap.used();
+
+        heap.collect(vec![head]);
+        assert!(heap.contains(head));
+        assert!(heap.contains(tail));
+        assert!(!heap.contains(garbage));
+        assert_eq!(heap.live_objects(), 2);
+        assert_eq!(heap.used(), used - heap.get(head).size());
+
+        heap.collect(vec![]);
+        assert_eq!(heap.live_objects(), 0);
+        assert_eq!(heap.used(), 0);
+    }
+
+    #[test]
+    fn collect_cycle() {
+        let class = class_loader().load("gc/Node");
+        let next_slot_id = class.lookup_field("next", "Lgc/Node;").unwrap().slot_id;
+        let mut heap = Heap::default();
+        let a = node(&mut heap, &class, None);
+        let b = node(&mut heap, &class, Some(a));
+        heap.get_mut(a).fields_mut()[next_slot_id] = Slot::Ref(Some(b));
+
+        heap.collect(vec![b]);
+        assert_eq!(heap.live_objects(), 2);
+        heap.collect(vec![]);
+        assert_eq!(heap.live_objects(), 0);
+    }
+
+    #[test]
+    fn reuse_freed_slot() {
+        let class = class_loader().load("gc/Node");
+        let mut heap = Heap::default();
+        let garbage = node(&mut heap, &class, None);
+        heap.collect(vec![]);
+        let reference = node(&mut heap, &class, None);
+        assert_eq!(reference, garbage);
+        assert_eq!(heap.handles.len(), 1);
+    }
+
+    #[test]
+    fn static_roots() {
+        let class_loader = class_loader();
+        let class = class_loader.load("gc/Node");
+        let mut heap = Heap::default();
+        let head = node(&mut heap, &class, None);
+        node(&mut heap, &class, None);
+        let head_slot_id = class.lookup_field("head", "Lgc/Node;").unwrap().slot_id;
+        class
+            .static_vars
+            .borrow_mut()
+            .set_ref(head_slot_id, Some(head));
+
+        heap.collect(class_loader.static_roots());
+        assert_eq!(heap.live_objects(), 1);
+        assert!(heap.contains(head));
+    }
+
+    #[test]
+    fn interned_strings() {
+        let class_loader = class_loader();
+        let string_class = class_loader.load("java/lang/String");
+        let char_array_class = class_loader.load("[C");
+        let mut heap = Heap::default();
+        let hello = heap
+            .intern(
+                "hello",
+                Rc::clone(&string_class),
+                Rc::clone(&char_array_class),
+            )
+            .unwrap();
+        let again = heap
+            .intern(
+                "hello",
+                Rc::clone(&string_class),
+                Rc::clone(&char_array_class),
+            )
+            .unwrap();
+        assert_eq!(hello, again);
+
+        heap.collect(vec![]);
+        assert_eq!(heap.live_objects(), 2);
+        assert_eq!(heap.java_string(hello), "hello");
+    }
+
+    #[test]
+    fn allocation_limit() {
+        let class = class_loader().load("gc/Node");
+        let size = Object::new(Rc::clone(&class)).size();
+        let mut heap = Heap::new(size * 2);
+        node(&mut heap, &class, None);
+        node(&mut heap, &class, None);
+        assert_eq!(heap.allocate(Object::new(Rc::clone(&class))), None);
+
+        heap.request_gc_for_allocation();
+        assert!(heap.is_gc_requested());
+        heap.collect(vec![]);
+        assert!(!heap.is_gc_requested());
+        assert!(heap.allocate(Object::new(class)).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "java.lang.OutOfMemoryError: Java heap space")]
+    fn out_of_memory() {
+        let class = class_loader().load("gc/Node");
+        let size = Object::new(Rc::clone(&class)).size();
+        let mut heap = Heap::new(size);
+        let live = node(&mut heap, &class, None);
+        heap.request_gc_for_allocation();
+        heap.collect(vec![live]);
+        assert_eq!(heap.allocate(Object::new(Rc::clone(&class))), None);
+        heap.request_gc_for_allocation();
+        assert_eq!(
+            heap.gc_requested,
+            Some(CollectionKind::FullClearingSoftReferences)
+        );
+        heap.collect(vec![live]);
+        assert_eq!(heap.allocate(Object::new(class)), None);
+        heap.request_gc_for_allocation();
+    }
+
+    #[test]
+    fn stats() {
+        let class = class_loader().load("gc/Node");
+        let mut heap = Heap::default();
+        let live = node(&mut heap, &class, None);
+        node(&mut heap, &class, None);
+        node(&mut heap, &class, None);
+        heap.collect(vec![live]);
+        heap.collect(vec![live]);
+
+        let size = heap.get(live).size() as u64;
+        let stats = heap.stats();
+        assert_eq!(stats.collections, 2);
+        assert_eq!(stats.objects_allocated, 3);
+        assert_eq!(stats.bytes_allocated, 3 * size);
+        assert_eq!(stats.objects_freed, 2);
+        assert_eq!(stats.bytes_freed, 2 * size);
+        assert!(stats.total_pause >= stats.last_pause);
+    }
+
+    fn generational_heap() -> Heap {
+        // 1 KB of young generation, 3 KB of old generation
+        Heap::with_collector(4096, Collector::Generational)
+    }
+
+    #[test]
+    fn collector_from_str() {
+        assert_eq!("mark-sweep".parse(), Ok(Collector::MarkSweep));
+        assert_eq!("generational".parse(), Ok(Collector::Generational));
+        assert!("g1".parse::<Collector>().is_err());
+    }
+
+    #[test]
+    fn minor_collection() {
+        let class = class_loader().load("gc/Node");
+        let mut heap = generational_heap();
+        let tail = node(&mut heap, &class, None);
+        let head = node(&mut heap, &class, Some(tail));
+        let garbage = node(&mut heap, &class, None);
+        assert!(heap.is_young(head));
+
+        heap.collect(vec![head]);
+        assert!(heap.is_young(head));
+        assert!(heap.is_young(tail));
+        assert!(!heap.contains(garbage));
+        assert_eq!(heap.young.len(), 2);
+
+        heap.collect(vec![head]);
+        assert!(heap.is_old(head));
+        assert!(heap.is_old(tail));
+        assert_eq!(heap.young_used, 0);
+        assert_eq!(heap.old_used, 2 * heap.get(head).size());
+
+        let stats = heap.stats();
+        assert_eq!(stats.minor_collections, 2);
+        assert_eq!(stats.objects_promoted, 2);
+        assert_eq!(stats.objects_freed, 1);
+    }
+
+    #[test]
+    fn minor_collection_skips_old_garbage() {
+        let class = class_loader().load("gc/Node");
+        let mut heap = generational_heap();
+        let old = node(&mut heap, &class, None);
+        heap.collect(vec![old]);
+        heap.collect(vec![old]);
+        assert!(heap.is_old(old));
+
+        heap.collect(vec![]);
+        assert!(heap.contains(old));
+        heap.gc_requested = Some(CollectionKind::Full);
+        heap.collect(vec![]);
+        assert!(!heap.contains(old));
+        assert_eq!(heap.used(), 0);
+    }
+
+    #[test]
+    fn remembered_set() {
+        let class = class_loader().load("gc/Node");
+        let next_slot_id = class.lookup_field("next", "Lgc/Node;").unwrap().slot_id;
+        let mut heap = generational_heap();
+        let old = node(&mut heap, &class, None);
+        heap.collect(vec![old]);
+        heap.collect(vec![old]);
+        let young = node(&mut heap, &class, None);
+        assert!(!heap.is_remembered(old));
+
+        heap.get_mut(old).fields_mut()[next_slot_id] = Slot::Ref(Some(young));
+        heap.write_barrier(old, Slot::Ref(Some(young)));
+        assert!(heap.is_remembered(old));
+
+        // only the dirty card keeps the young object alive
+        heap.collect(vec![]);
+        assert!(heap.is_young(young));
+        assert!(heap.is_remembered(old));
+
+        heap.collect(vec![]);
+        assert!(heap.is_old(young));
+        assert!(!heap.is_remembered(old));
+    }
+
+    #[test]
+    fn promoted_object_pointing_to_young() {
+        let class = class_loader().load("gc/Node");
+        let mut heap = generational_heap();
+        let survivor = node(&mut heap, &class, None);
+        heap.collect(vec![survivor]);
+        let young = node(&mut heap, &class, None);
+        let next_slot_id = class.lookup_field("next", "Lgc/Node;").unwrap().slot_id;
+        heap.get_mut(survivor).fields_mut()[next_slot_id] = Slot::Ref(Some(young));
+
+        // survivor is promoted while young stays in the young generation
+        heap.collect(vec![survivor]);
+        assert!(heap.is_old(survivor));
+        assert!(heap.is_young(young));
+        assert!(heap.is_remembered(survivor));
+        heap.collect(vec![]);
+        assert!(heap.contains(young));
+    }
+
+    #[test]
+    fn generational_allocation_failure() {
+        let class = class_loader().load("gc/Node");
+        let size = Object::new(Rc::clone(&class)).size();
+        let mut heap = generational_heap();
+        let mut live = Vec::new();
+        while let Some(reference) = heap.allocate(Object::new(Rc::clone(&class))) {
+            live.push(reference);
+        }
+        assert_eq!(live.len(), 1024 / size);
+
+        heap.request_gc_for_allocation();
+        assert_eq!(heap.gc_requested, Some(CollectionKind::Minor));
+        heap.collect(live.clone());
+        assert_eq!(heap.allocate(Object::new(Rc::clone(&class))), None);
+
+        // the young generation is still full of survivors, so a full collection follows
+        heap.request_gc_for_allocation();
+        assert_eq!(heap.gc_requested, Some(CollectionKind::Full));
+        heap.collect(live);
+        assert!(heap.allocate(Object::new(class)).is_some());
+        assert_eq!(heap.stats().collections, 2);
+        assert_eq!(heap.stats().minor_collections, 1);
+    }
+
+    #[test]
+    fn large_object_in_old_generation() {
+        let class_loader = class_loader();
+        let mut heap = generational_heap();
+        let small = Object::new_array(class_loader.load("[I"), 16);
+        let large = Object::new_array(class_loader.load("[I"), 512);
+        let small = heap.allocate(small).unwrap();
+        let large = heap.allocate(large).unwrap();
+        assert!(heap.is_young(small));
+        assert!(heap.is_old(large));
+    }
+
+    #[test]
+    #[should_panic(expected = "java.lang.OutOfMemoryError: Java heap space")]
+    fn generational_out_of_memory() {
+        let class_loader = class_loader();
+        let mut heap = generational_heap();
+        let large = Object::new_array(class_loader.load("[I"), 512);
+        let large = heap.allocate(large).unwrap();
+        let again = Object::new_array(class_loader.load("[I"), 512);
+        assert_eq!(heap.allocate(again), None);
+        heap.request_gc_for_allocation();
+        assert_eq!(heap.gc_requested, Some(CollectionKind::Full));
+        heap.collect(vec![large]);
+        heap.request_gc_for_allocation();
+        heap.collect(vec![large]);
+        heap.request_gc_for_allocation();
+    }
+}
@@ -0,0 +1,71 @@
+use crate::gc::heap::{Entry, Heap};
+use crate::gc::reference::{self, ReferenceKind};
+use crate::rtda::heap::object::ObjectRef;
+
+/// Marks everything reachable from `roots`, then sweeps the unmarked objects into the
+/// free list.
+pub fn collect(heap: &mut Heap, roots: Vec<ObjectRef>, clear_soft_references: bool) {
+    let marked = mark(heap, roots, clear_soft_references);
+    sweep_old(heap, &marked);
+}
+
+/// Whether each handle is reachable from `roots`, through objects of both generations.
+/// References are processed along the way: weakly reachable referents are cleared,
+/// finalizable objects are resurrected and queued.
+pub fn mark(heap: &mut Heap, roots: Vec<ObjectRef>, clear_soft_references: bool) -> Vec<bool> {
+    let mut marker = Marker {
+        marked: vec![false; heap.handles.len()],
+        discover: true,
+        discovered: Vec::new(),
+    };
+    marker.mark_from(heap, roots);
+    reference::process(heap, &mut marker, clear_soft_references);
+    marker.marked
+}
+
+pub struct Marker {
+    pub marked: Vec<bool>,
+    /// Whether to stop at the referent of active references and record them instead.
+    pub discover: bool,
+    pub discovered: Vec<(ObjectRef, ReferenceKind)>,
+}
+
+impl Marker {
+    pub fn mark_from(&mut self, heap: &Heap, roots: Vec<ObjectRef>) {
+        let mut gray = roots;
+        while let Some(reference) = gray.pop() {
+            if self.marked[reference.0] {
+                continue;
+            }
+            self.marked[reference.0] = true;
+            let object = heap.get(reference);
+            let kind = if self.discover {
+                reference::discoverable(object)
+            } else {
+                None
+            };
+            match kind {
+                Some(kind) => {
+                    self.discovered.push((reference, kind));
+                    gray.extend(reference::references_but_referent(object));
+                }
+                None => gray.extend(object.references()),
+            }
+        }
+    }
+}
+
+/// Frees the unmarked objects outside the young generation.
+pub fn sweep_old(heap: &mut Heap, marked: &[bool]) {
+    for (index, is_marked) in marked.iter().enumerate() {
+        if *is_marked {
+            continue;
+        }
+        let size = match heap.handles[index] {
+            Entry::Old(ref object) => object.size(),
+            _ => continue,
+        };
+        heap.old_used -= size;
+        heap.free(ObjectRef(index), size);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod finalizer;
+mod generational;
+pub mod heap;
+mod mark_sweep;
+pub mod reference;
@@ -0,0 +1,368 @@
+use std::rc::Rc;
+
+use crate::gc::heap::Heap;
+use crate::gc::mark_sweep::Marker;
+use crate::rtda::heap::class::Class;
+use crate::rtda::heap::object::{Object, ObjectRef};
+use crate::rtda::slot::Slot;
+
+const REFERENCE: &str = "java/lang/ref/Reference";
+const REFERENT: (&str, &str) = ("referent", "Ljava/lang/Object;");
+const NEXT: (&str, &str) = ("next", "Ljava/lang/ref/Reference;");
+const DISCOVERED: (&str, &str) = ("discovered", "Ljava/lang/ref/Reference;");
+const PENDING: (&str, &str) = ("pending", "Ljava/lang/ref/Reference;");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    Soft,
+    Weak,
+    Phantom,
+}
+
+/// The kind of `java.lang.ref.Reference` instances of `class` are, if any. Other
+/// subclasses of `Reference`, like `FinalReference`, are treated as strong.
+pub fn reference_kind(class: &Class) -> Option<ReferenceKind> {
+    let mut c = Some(class);
+    while let Some(x) = c {
+        match x.name.as_str() {
+            "java/lang/ref/SoftReference" => return Some(ReferenceKind::Soft),
+            "java/lang/ref/WeakReference" => return Some(ReferenceKind::Weak),
+            "java/lang/ref/PhantomReference" => return Some(ReferenceKind::Phantom),
+            REFERENCE => return None,
+            _ => c = x.super_class.as_deref(),
+        }
+    }
+    None
+}
+
+fn slot_id(class: &Class, (name, descriptor): (&str, &str)) -> usize {
+    class
+        .lookup_field(name, descriptor)
+        .unwrap_or_else(|| panic!("java.lang.NoSuchFieldError: {}", name))
+        .slot_id
+}
+
+fn get_ref(object: &Object, field: (&str, &str)) -> Option<ObjectRef> {
+    match object.fields()[slot_id(&object.class, field)] {
+        Slot::Ref(val) => val,
+        Slot::Num(_) => panic!("Not Ref"),
+    }
+}
+
+fn set_ref(heap: &mut Heap, reference: ObjectRef, field: (&str, &str), val: Option<ObjectRef>) {
+    let object = heap.get_mut(reference);
+    let slot_id = slot_id(&object.class, field);
+    object.fields_mut()[slot_id] = Slot::Ref(val);
+}
+
+/// The kind of `object` if it's an active reference with a referent, whose referent
+/// the collector should not trace.
+pub fn discoverable(object: &Object) -> Option<ReferenceKind> {
+    let kind = reference_kind(&object.class)?;
+    let is_active = get_ref(object, NEXT).is_none();
+    if is_active && get_ref(object, REFERENT).is_some() {
+        Some(kind)
+    } else {
+        None
+    }
+}
+
+/// What a discovered reference points to besides its referent.
+pub fn references_but_referent(object: &Object) -> Vec<ObjectRef> {
+    let referent_slot_id = slot_id(&object.class, REFERENT);
+    object
+        .fields()
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != referent_slot_id)
+        .filter_map(|(_, x)| match x {
+            Slot::Ref(val) => *val,
+            Slot::Num(_) => None,
+        })
+        .collect()
+}
+
+/// Processes the references discovered while marking from the roots, in order of
+/// strength: softly reachable referents are kept unless memory is short, weakly reachable
+/// ones are cleared, unreachable finalizable objects are resurrected and queued, then
+/// phantom references to unreachable referents are enqueued. Cleared and enqueued
+/// references are put on the pending list.
+pub fn process(heap: &mut Heap, marker: &mut Marker, clear_soft_references: bool) {
+    if !clear_soft_references {
+        let mut processed = 0;
+        while processed < marker.discovered.len() {
+            let soft: Vec<ObjectRef> = marker.discovered[processed..]
+                .iter()
+                .filter(|(_, kind)| *kind == ReferenceKind::Soft)
+                .filter_map(|(x, _)| get_ref(heap.get(*x), REFERENT))
+                .collect();
+            processed = marker.discovered.len();
+            marker.mark_from(heap, soft);
+        }
+    }
+    marker.discover = false;
+
+    let mut pending = Vec::new();
+    for (reference, kind) in marker.discovered.iter() {
+        if *kind == ReferenceKind::Phantom {
+            continue;
+        }
+        let referent =
+            get_ref(heap.get(*reference), REFERENT).expect("Discovered without referent");
+        if !marker.marked[referent.0] {
+            set_ref(heap, *reference, REFERENT, None);
+            pending.push(*reference);
+        }
+    }
+
+    let (finalizable, unreachable): (Vec<ObjectRef>, Vec<ObjectRef>) =
+        heap.finalizable.iter().partition(|x| marker.marked[x.0]);
+    heap.finalizable = finalizable;
+    heap.finalization_queue.extend(unreachable.iter().cloned());
+    marker.mark_from(heap, unreachable);
+
+    // Like JDK 8, phantom referents stay alive until the reference is cleared
+    for (reference, kind) in marker.discovered.clone() {
+        if kind != ReferenceKind::Phantom {
+            continue;
+        }
+        let referent = get_ref(heap.get(reference), REFERENT).expect("Discovered without referent");
+        if !marker.marked[referent.0] {
+            marker.mark_from(heap, vec![referent]);
+            pending.push(reference);
+        }
+    }
+
+    enqueue_pending(heap, pending);
+}
+
+/// Prepends `references` to `Reference.pending`, linked through their `discovered`
+/// field, for the Reference Handler thread to move to their queues. Their `next` field
+/// points to themselves, which makes them inactive.
+fn enqueue_pending(heap: &mut Heap, references: Vec<ObjectRef>) {
+    let reference_class = match references.first() {
+        Some(reference) => reference_class(&heap.get(*reference).class),
+        None => return,
+    };
+    let pending_slot_id = slot_id(&reference_class, PENDING);
+    let mut head = reference_class
+        .static_vars
+        .borrow()
+        .get_ref(pending_slot_id);
+    for reference in references.into_iter().rev() {
+        set_ref(heap, reference, NEXT, Some(reference));
+        set_ref(heap, reference, DISCOVERED, head);
+        head = Some(reference);
+    }
+    reference_class
+        .static_vars
+        .borrow_mut()
+        .set_ref(pending_slot_id, head);
+}
+
+fn reference_class(class: &Rc<Class>) -> Rc<Class> {
+    let mut c = Rc::clone(class);
+    while c.name != REFERENCE {
+        c = c
+            .super_class
+            .clone()
+            .expect("Not a java.lang.ref.Reference");
+    }
+    c
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::gc::heap::{CollectionKind, Collector, Heap};
+    use crate::gc::reference::*;
+    use crate::rtda::heap::class_loader::ClassLoader;
+
+    fn class_loader() -> Rc<ClassLoader> {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        ClassLoader::new(class_path)
+    }
+
+    fn allocate(heap: &mut Heap, class_loader: &Rc<ClassLoader>, name: &str) -> ObjectRef {
+        heap.allocate(Object::new(class_loader.load(name))).unwrap()
+    }
+
+    fn reference(
+        heap: &mut Heap,
+        class_loader: &Rc<ClassLoader>,
+        name: &str,
+        referent: ObjectRef,
+    ) -> ObjectRef {
+        let reference = allocate(heap, class_loader, name);
+        set_ref(heap, reference, REFERENT, Some(referent));
+        reference
+    }
+
+    fn pending(class_loader: &Rc<ClassLoader>, heap: &Heap) -> Vec<ObjectRef> {
+        let class = class_loader.load(REFERENCE);
+        let mut head = class.static_vars.borrow().get_ref(slot_id(&class, PENDING));
+        let mut pending = Vec::new();
+        while let Some(reference) = head {
+            pending.push(reference);
+            head = get_ref(heap.get(reference), DISCOVERED);
+        }
+        pending
+    }
+
+    #[test]
+    fn kind() {
+        let class_loader = class_loader();
+        let kind = |x| reference_kind(&class_loader.load(x));
+        assert_eq!(
+            kind("java/lang/ref/SoftReference"),
+            Some(ReferenceKind::Soft)
+        );
+        assert_eq!(
+            kind("java/lang/ref/WeakReference"),
+            Some(ReferenceKind::Weak)
+        );
+        assert_eq!(
+            kind("java/lang/ref/PhantomReference"),
+            Some(ReferenceKind::Phantom)
+        );
+        assert_eq!(kind("gc/Node"), None);
+    }
+
+    #[test]
+    fn weak_reference() {
+        let class_loader = class_loader();
+        let mut heap = Heap::default();
+        let strong = allocate(&mut heap, &class_loader, "gc/Node");
+        let weak = allocate(&mut heap, &class_loader, "gc/Node");
+        let a = reference(
+            &mut heap,
+            &class_loader,
+            "java/lang/ref/WeakReference",
+            strong,
+        );
+        let b = reference(
+            &mut heap,
+            &class_loader,
+            "java/lang/ref/WeakReference",
+            weak,
+        );
+
+        heap.collect(vec![a, b, strong]);
+        assert!(!heap.contains(weak));
+        assert_eq!(get_ref(heap.get(a), REFERENT), Some(strong));
+        assert_eq!(get_ref(heap.get(b), REFERENT), None);
+        assert_eq!(get_ref(heap.get(b), NEXT), Some(b));
+        assert_eq!(pending(&class_loader, &heap), vec![b]);
+
+        // the pending list is rooted by a static field and grows at its head
+        let weak = allocate(&mut heap, &class_loader, "gc/Node");
+        let c = reference(
+            &mut heap,
+            &class_loader,
+            "java/lang/ref/WeakReference",
+            weak,
+        );
+        heap.collect(vec![c].into_iter().chain(class_loader.static_roots()));
+        assert_eq!(pending(&class_loader, &heap), vec![c, b]);
+    }
+
+    #[test]
+    fn soft_reference() {
+        let class_loader = class_loader();
+        let mut heap = Heap::default();
+        let soft = allocate(&mut heap, &class_loader, "gc/Node");
+        let reference = reference(
+            &mut heap,
+            &class_loader,
+            "java/lang/ref/SoftReference",
+            soft,
+        );
+
+        heap.collect(vec![reference]);
+        assert!(heap.contains(soft));
+        assert_eq!(get_ref(heap.get(reference), REFERENT), Some(soft));
+
+        heap.gc_requested = Some(CollectionKind::FullClearingSoftReferences);
+        heap.collect(vec![reference]);
+        assert!(!heap.contains(soft));
+        assert_eq!(get_ref(heap.get(reference), REFERENT), None);
+        assert_eq!(pending(&class_loader, &heap), vec![reference]);
+    }
+
+    #[test]
+    fn phantom_reference() {
+        let class_loader = class_loader();
+        let mut heap = Heap::default();
+        let phantom = allocate(&mut heap, &class_loader, "gc/Node");
+        let reference = reference(
+            &mut heap,
+            &class_loader,
+            "java/lang/ref/PhantomReference",
+            phantom,
+        );
+
+        heap.collect(vec![reference]);
+        assert!(heap.contains(phantom));
+        assert_eq!(get_ref(heap.get(reference), REFERENT), Some(phantom));
+        assert_eq!(pending(&class_loader, &heap), vec![reference]);
+
+        // enqueued references are inactive, so they aren't discovered again
+        heap.collect(
+            vec![reference]
+                .into_iter()
+                .chain(class_loader.static_roots()),
+        );
+        assert_eq!(pending(&class_loader, &heap), vec![reference]);
+    }
+
+    #[test]
+    fn finalizable_referent() {
+        let class_loader = class_loader();
+        let mut heap = Heap::default();
+        let finalizable = allocate(&mut heap, &class_loader, "gc/Finalizable");
+        let weak = reference(
+            &mut heap,
+            &class_loader,
+            "java/lang/ref/WeakReference",
+            finalizable,
+        );
+        let phantom = reference(
+            &mut heap,
+            &class_loader,
+            "java/lang/ref/PhantomReference",
+            finalizable,
+        );
+
+        // weak references are cleared before finalization, phantom ones only after
+        heap.collect(vec![weak, phantom]);
+        assert!(heap.contains(finalizable));
+        assert_eq!(get_ref(heap.get(weak), REFERENT), None);
+        assert_eq!(get_ref(heap.get(phantom), REFERENT), Some(finalizable));
+        assert_eq!(pending(&class_loader, &heap), vec![weak]);
+    }
+
+    #[test]
+    fn minor_collection_keeps_referents() {
+        let class_loader = class_loader();
+        let mut heap = Heap::with_collector(4096, Collector::Generational);
+        let weak = allocate(&mut heap, &class_loader, "gc/Node");
+        let reference = reference(
+            &mut heap,
+            &class_loader,
+            "java/lang/ref/WeakReference",
+            weak,
+        );
+
+        heap.collect(vec![reference]);
+        assert!(heap.contains(weak));
+        heap.gc_requested = Some(CollectionKind::Full);
+        heap.collect(vec![reference]);
+        assert!(!heap.contains(weak));
+        assert_eq!(pending(&class_loader, &heap), vec![reference]);
+    }
+}
@@ -1,122 +1,85 @@
-use crate::instruction::instruction::ExecuteResult;
 use crate::rtda::frame::Frame;
-use crate::rtda::thread::Thread;
-use crate::util::code_reader::CodeReader;
 
 #[allow(non_snake_case)]
-fn _dcmp(frame: Frame, flag: bool) -> (f64, f64, Frame) {
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
+fn _dcmp(frame: &mut Frame, flag: bool) {
+    let val2 = frame.operand_stack.pop_double();
+    let val1 = frame.operand_stack.pop_double();
 
-    let (val2, operand_stack) = operand_stack.pop_double();
-    let (val1, operand_stack) = operand_stack.pop_double();
-
-    let operand_stack = if val1 > val2 {
-        operand_stack.push_int(1)
+    let result = if val1 > val2 {
+        1
     } else if val1 < val2 {
-        operand_stack.push_int(-1)
+        -1
     } else if val1 == val2 {
-        operand_stack.push_int(0)
+        0
     } else if flag {
-        operand_stack.push_int(1)
+        1
     } else {
-        operand_stack.push_int(-1)
-    };
-
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
+        -1
     };
-    (val1, val2, frame)
+    frame.operand_stack.push_int(result);
 }
 
 #[allow(non_snake_case)]
-pub fn DCMPG(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("DCMPG");
+pub fn DCMPG(frame: &mut Frame) {
+    trace!("DCMPG");
 
-    let (frame, thread) = thread.pop_frame();
-    let (_, _, frame) = _dcmp(frame, true);
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+    _dcmp(frame, true);
 }
 
 #[allow(non_snake_case)]
-pub fn DCMPL(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("DCMPG");
+pub fn DCMPL(frame: &mut Frame) {
+    trace!("DCMPG");
 
-    let (frame, thread) = thread.pop_frame();
-    let (_, _, frame) = _dcmp(frame, false);
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+    _dcmp(frame, false);
 }
 
 #[cfg(test)]
 mod tests {
-    use std::rc::Rc;
+    use std::cell::RefCell;
+    use std::rc::{Rc, Weak};
 
     use vec_map::VecMap;
 
     use crate::classfile::constant_pool::ConstantPool;
     use crate::classfile::member_info::MemberInfo;
-    use crate::instruction::comparison::dcmp::*;
     use crate::instruction::comparison::dcmp::{DCMPG, DCMPL};
-    use crate::instruction::instruction::ExecuteResult;
     use crate::rtda::frame::Frame;
     use crate::rtda::heap::class::Class;
     use crate::rtda::heap::method::Method;
     use crate::rtda::operand_stack::OperandStack;
-    use crate::rtda::thread::Thread;
     use crate::rtda::vars::Vars;
-    use crate::util::code_reader::CodeReader;
 
     #[test]
     #[allow(non_snake_case)]
     fn test_DCMPL() {
-        let frame = create_frame(1.48, 1.49);
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            DCMPL(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_int();
+        let mut frame = create_frame(1.48, 1.49);
+        DCMPL(&mut frame);
+        let val = frame.operand_stack.pop_int();
         assert_eq!(val, -1);
     }
 
     #[test]
     #[allow(non_snake_case)]
     fn test_DCMPG() {
-        let frame = create_frame(1.49, 1.48);
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            DCMPG(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_int();
+        let mut frame = create_frame(1.49, 1.48);
+        DCMPG(&mut frame);
+        let val = frame.operand_stack.pop_int();
         assert_eq!(val, 1);
     }
 
     #[test]
     #[allow(non_snake_case)]
     fn test_DCMPG_equal() {
-        let frame = create_frame(1.49, 1.49);
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            DCMPG(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_int();
+        let mut frame = create_frame(1.49, 1.49);
+        DCMPG(&mut frame);
+        let val = frame.operand_stack.pop_int();
         assert_eq!(val, 0);
     }
 
     fn create_frame(op1: f64, op2: f64) -> Frame {
-        let operand_stack = OperandStack::new(10);
-        let operand_stack = operand_stack.push_double(op1);
-        let operand_stack = operand_stack.push_double(op2);
+        let mut operand_stack = OperandStack::new(10);
+        operand_stack.push_double(op1);
+        operand_stack.push_double(op2);
         let method = Rc::new(Method::new(MemberInfo {
             access_flags: 0u16,
             name: "".to_string(),
@@ -133,16 +96,23 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
         Frame {
             class,
             local_vars: Vars::new(10),
             operand_stack: operand_stack,
             method,
+            next_pc: 0,
         }
     }
 }
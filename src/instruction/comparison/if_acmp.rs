@@ -0,0 +1,33 @@
+use crate::rtda::frame::Frame;
+use crate::rtda::heap::object::ObjectRef;
+
+fn _acmp(frame: &mut Frame) -> (Option<ObjectRef>, Option<ObjectRef>) {
+    let ref2 = frame.operand_stack.pop_ref();
+    let ref1 = frame.operand_stack.pop_ref();
+
+    (ref1, ref2)
+}
+
+#[allow(non_snake_case)]
+pub fn IF_ACMPEQ(target: usize, frame: &mut Frame) -> Option<usize> {
+    trace!("IF_ACMPEQ");
+
+    let (ref1, ref2) = _acmp(frame);
+    if ref1 == ref2 {
+        Some(target)
+    } else {
+        None
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn IF_ACMPNE(target: usize, frame: &mut Frame) -> Option<usize> {
+    trace!("IF_ACMPNE");
+
+    let (ref1, ref2) = _acmp(frame);
+    if ref1 != ref2 {
+        Some(target)
+    } else {
+        None
+    }
+}
@@ -1,129 +1,99 @@
-use crate::instruction::instruction::ExecuteResult;
 use crate::rtda::frame::Frame;
-use crate::rtda::thread::Thread;
-use crate::util::code_reader::CodeReader;
 
 #[allow(non_snake_case)]
-fn _icmpPop(frame: Frame) -> (i32, i32, Frame) {
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-
-    let (val2, operand_stack) = operand_stack.pop_int();
-    let (val1, operand_stack) = operand_stack.pop_int();
-
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    (val1, val2, frame)
+fn _icmpPop(frame: &mut Frame) -> (i32, i32) {
+    let val2 = frame.operand_stack.pop_int();
+    let val1 = frame.operand_stack.pop_int();
+
+    (val1, val2)
 }
 
 #[allow(non_snake_case)]
-pub fn IF_ICMPGT(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("IF_ICMPGT");
-    let (offset, code_reader) = code_reader.read_i16();
-
-    let (frame, thread) = thread.pop_frame();
-
-    let (val1, val2, frame) = _icmpPop(frame);
-    let offset = if val1 > val2 { offset as isize } else { 0 };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset };
-    (execute_result, code_reader)
+pub fn IF_ICMPGT(target: usize, frame: &mut Frame) -> Option<usize> {
+    trace!("IF_ICMPGT");
+
+    let (val1, val2) = _icmpPop(frame);
+    if val1 > val2 {
+        Some(target)
+    } else {
+        None
+    }
 }
 
 #[allow(non_snake_case)]
-pub fn IF_ICMPGE(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("IF_ICMPGE");
-    let (offset, code_reader) = code_reader.read_i16();
-
-    let (frame, thread) = thread.pop_frame();
-
-    let (val1, val2, frame) = _icmpPop(frame);
-    let offset = if val1 >= val2 { offset as isize } else { 0 };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset };
-    (execute_result, code_reader)
+pub fn IF_ICMPGE(target: usize, frame: &mut Frame) -> Option<usize> {
+    trace!("IF_ICMPGE");
+
+    let (val1, val2) = _icmpPop(frame);
+    if val1 >= val2 {
+        Some(target)
+    } else {
+        None
+    }
 }
 
 #[allow(non_snake_case)]
-pub fn IF_ICMPEQ(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("IF_ICMPEQ");
-    let (offset, code_reader) = code_reader.read_i16();
-
-    let (frame, thread) = thread.pop_frame();
-
-    let (val1, val2, frame) = _icmpPop(frame);
-    let offset = if val1 == val2 { offset as isize } else { 0 };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset };
-    (execute_result, code_reader)
+pub fn IF_ICMPEQ(target: usize, frame: &mut Frame) -> Option<usize> {
+    trace!("IF_ICMPEQ");
+
+    let (val1, val2) = _icmpPop(frame);
+    if val1 == val2 {
+        Some(target)
+    } else {
+        None
+    }
 }
 
 #[allow(non_snake_case)]
-pub fn IF_ICMPNE(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("IF_ICMPNE");
-    let (offset, code_reader) = code_reader.read_i16();
-
-    let (frame, thread) = thread.pop_frame();
-
-    let (val1, val2, frame) = _icmpPop(frame);
-    let offset = if val1 != val2 { offset as isize } else { 0 };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset };
-    (execute_result, code_reader)
+pub fn IF_ICMPNE(target: usize, frame: &mut Frame) -> Option<usize> {
+    trace!("IF_ICMPNE");
+
+    let (val1, val2) = _icmpPop(frame);
+    if val1 != val2 {
+        Some(target)
+    } else {
+        None
+    }
 }
 
 #[allow(non_snake_case)]
-pub fn IF_ICMPLT(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("IF_ICMPLT");
-    let (offset, code_reader) = code_reader.read_i16();
-
-    let (frame, thread) = thread.pop_frame();
-
-    let (val1, val2, frame) = _icmpPop(frame);
-    let offset = if val1 < val2 { offset as isize } else { 0 };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset };
-    (execute_result, code_reader)
+pub fn IF_ICMPLT(target: usize, frame: &mut Frame) -> Option<usize> {
+    trace!("IF_ICMPLT");
+
+    let (val1, val2) = _icmpPop(frame);
+    if val1 < val2 {
+        Some(target)
+    } else {
+        None
+    }
 }
 
 #[allow(non_snake_case)]
-pub fn IF_ICMPLE(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("IF_ICMPLE");
-    let (offset, code_reader) = code_reader.read_i16();
-
-    let (frame, thread) = thread.pop_frame();
-
-    let (val1, val2, frame) = _icmpPop(frame);
-    let offset = if val1 <= val2 { offset as isize } else { 0 };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset };
-    (execute_result, code_reader)
+pub fn IF_ICMPLE(target: usize, frame: &mut Frame) -> Option<usize> {
+    trace!("IF_ICMPLE");
+
+    let (val1, val2) = _icmpPop(frame);
+    if val1 <= val2 {
+        Some(target)
+    } else {
+        None
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::rc::Rc;
+    use std::cell::RefCell;
+    use std::rc::{Rc, Weak};
 
     use vec_map::VecMap;
 
     use crate::classfile::constant_pool::ConstantPool;
     use crate::classfile::member_info::MemberInfo;
     use crate::instruction::comparison::if_icmp::*;
-    use crate::instruction::instruction::ExecuteResult;
     use crate::rtda::frame::Frame;
     use crate::rtda::heap::class::Class;
     use crate::rtda::heap::method::Method;
-    use crate::rtda::thread::Thread;
     use crate::rtda::vars::Vars;
-    use crate::util::code_reader::CodeReader;
 
     #[test]
     #[allow(non_snake_case)]
@@ -145,31 +115,23 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(1);
-        let operand_stack = operand_stack.push_int(0);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IF_ICMPGT(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 257);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(1);
+        frame.operand_stack.push_int(0);
+        let jump = IF_ICMPGT(257, &mut frame);
+        assert_eq!(jump, Some(257));
     }
 
     #[test]
@@ -192,32 +154,24 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
 
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(1);
-        let operand_stack = operand_stack.push_int(2);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IF_ICMPGT(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 0);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(1);
+        frame.operand_stack.push_int(2);
+        let jump = IF_ICMPGT(257, &mut frame);
+        assert_eq!(jump, None);
     }
 
     #[test]
@@ -240,31 +194,23 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(1);
-        let operand_stack = operand_stack.push_int(1);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IF_ICMPGE(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 257);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(1);
+        frame.operand_stack.push_int(1);
+        let jump = IF_ICMPGE(257, &mut frame);
+        assert_eq!(jump, Some(257));
     }
 
     #[test]
@@ -287,32 +233,24 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
 
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(0);
-        let operand_stack = operand_stack.push_int(2);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IF_ICMPGE(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 0);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(0);
+        frame.operand_stack.push_int(2);
+        let jump = IF_ICMPGE(257, &mut frame);
+        assert_eq!(jump, None);
     }
 
     #[test]
@@ -334,31 +272,23 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(-1);
-        let operand_stack = operand_stack.push_int(-1);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IF_ICMPEQ(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 257);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(-1);
+        frame.operand_stack.push_int(-1);
+        let jump = IF_ICMPEQ(257, &mut frame);
+        assert_eq!(jump, Some(257));
     }
 
     #[test]
@@ -380,31 +310,23 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(0);
-        let operand_stack = operand_stack.push_int(1);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IF_ICMPEQ(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 0);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(0);
+        frame.operand_stack.push_int(1);
+        let jump = IF_ICMPEQ(257, &mut frame);
+        assert_eq!(jump, None);
     }
 
     #[test]
@@ -426,31 +348,23 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(0);
-        let operand_stack = operand_stack.push_int(1);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IF_ICMPNE(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 257);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(0);
+        frame.operand_stack.push_int(1);
+        let jump = IF_ICMPNE(257, &mut frame);
+        assert_eq!(jump, Some(257));
     }
 
     #[test]
@@ -472,31 +386,23 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(-1);
-        let operand_stack = operand_stack.push_int(-1);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IF_ICMPNE(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 0);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(-1);
+        frame.operand_stack.push_int(-1);
+        let jump = IF_ICMPNE(257, &mut frame);
+        assert_eq!(jump, None);
     }
 
     #[test]
@@ -518,31 +424,23 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(1);
-        let operand_stack = operand_stack.push_int(2);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IF_ICMPLT(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 257);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(1);
+        frame.operand_stack.push_int(2);
+        let jump = IF_ICMPLT(257, &mut frame);
+        assert_eq!(jump, Some(257));
     }
 
     #[test]
@@ -564,31 +462,23 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(0);
-        let operand_stack = operand_stack.push_int(0);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IF_ICMPLT(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 0);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(0);
+        frame.operand_stack.push_int(0);
+        let jump = IF_ICMPLT(257, &mut frame);
+        assert_eq!(jump, None);
     }
 
     #[test]
@@ -610,31 +500,23 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(0);
-        let operand_stack = operand_stack.push_int(0);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IF_ICMPLE(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 257);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(0);
+        frame.operand_stack.push_int(0);
+        let jump = IF_ICMPLE(257, &mut frame);
+        assert_eq!(jump, Some(257));
     }
 
     #[test]
@@ -656,30 +538,22 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(2);
-        let operand_stack = operand_stack.push_int(1);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IF_ICMPLE(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 0);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(2);
+        frame.operand_stack.push_int(1);
+        let jump = IF_ICMPLE(257, &mut frame);
+        assert_eq!(jump, None);
     }
 }
@@ -1,126 +1,96 @@
-use crate::instruction::instruction::ExecuteResult;
 use crate::rtda::frame::Frame;
-use crate::rtda::thread::Thread;
-use crate::util::code_reader::CodeReader;
-
-fn _ifcond(frame: Frame) -> (i32, Frame) {
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-
-    let (val, operand_stack) = operand_stack.pop_int();
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    (val, frame)
+
+fn _ifcond(frame: &mut Frame) -> i32 {
+    frame.operand_stack.pop_int()
 }
 
 #[allow(non_snake_case)]
-pub fn IFEQ(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("IFEQ");
-    let (offset, code_reader) = code_reader.read_i16();
-    let (frame, thread) = thread.pop_frame();
-
-    let (val, frame) = _ifcond(frame);
-    let offset = if val == 0 { offset as isize } else { 0 };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset };
-    (execute_result, code_reader)
+pub fn IFEQ(target: usize, frame: &mut Frame) -> Option<usize> {
+    trace!("IFEQ");
+
+    let val = _ifcond(frame);
+    if val == 0 {
+        Some(target)
+    } else {
+        None
+    }
 }
 
 #[allow(non_snake_case)]
-pub fn IFNE(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("IFNE");
-    let (offset, code_reader) = code_reader.read_i16();
-
-    let (frame, thread) = thread.pop_frame();
-
-    let (val, frame) = _ifcond(frame);
-    let offset = if val != 0 { offset as isize } else { 0 };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset };
-    (execute_result, code_reader)
+pub fn IFNE(target: usize, frame: &mut Frame) -> Option<usize> {
+    trace!("IFNE");
+
+    let val = _ifcond(frame);
+    if val != 0 {
+        Some(target)
+    } else {
+        None
+    }
 }
 
 #[allow(non_snake_case)]
-pub fn IFLT(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("IFLT");
-    let (offset, code_reader) = code_reader.read_i16();
-
-    let (frame, thread) = thread.pop_frame();
-
-    let (val, frame) = _ifcond(frame);
-    let offset = if val < 0 { offset as isize } else { 0 };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset };
-    (execute_result, code_reader)
+pub fn IFLT(target: usize, frame: &mut Frame) -> Option<usize> {
+    trace!("IFLT");
+
+    let val = _ifcond(frame);
+    if val < 0 {
+        Some(target)
+    } else {
+        None
+    }
 }
 
 #[allow(non_snake_case)]
-pub fn IFGE(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("IFGE");
-    let (offset, code_reader) = code_reader.read_i16();
-
-    let (frame, thread) = thread.pop_frame();
-
-    let (val, frame) = _ifcond(frame);
-    let offset = if val >= 0 { offset as isize } else { 0 };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset };
-    (execute_result, code_reader)
+pub fn IFGE(target: usize, frame: &mut Frame) -> Option<usize> {
+    trace!("IFGE");
+
+    let val = _ifcond(frame);
+    if val >= 0 {
+        Some(target)
+    } else {
+        None
+    }
 }
 
 #[allow(non_snake_case)]
-pub fn IFGT(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("IFGT");
-    let (offset, code_reader) = code_reader.read_i16();
-
-    let (frame, thread) = thread.pop_frame();
-
-    let (val, frame) = _ifcond(frame);
-    let offset = if val > 0 { offset as isize } else { 0 };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset };
-    (execute_result, code_reader)
+pub fn IFGT(target: usize, frame: &mut Frame) -> Option<usize> {
+    trace!("IFGT");
+
+    let val = _ifcond(frame);
+    if val > 0 {
+        Some(target)
+    } else {
+        None
+    }
 }
 
 #[allow(non_snake_case)]
-pub fn IFLE(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("IFLE");
-    let (offset, code_reader) = code_reader.read_i16();
-
-    let (frame, thread) = thread.pop_frame();
-
-    let (val, frame) = _ifcond(frame);
-    let offset = if val <= 0 { offset as isize } else { 0 };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset };
-    (execute_result, code_reader)
+pub fn IFLE(target: usize, frame: &mut Frame) -> Option<usize> {
+    trace!("IFLE");
+
+    let val = _ifcond(frame);
+    if val <= 0 {
+        Some(target)
+    } else {
+        None
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::rc::Rc;
+    use std::cell::RefCell;
+    use std::rc::{Rc, Weak};
 
     use vec_map::VecMap;
 
     use crate::classfile::constant_pool::ConstantPool;
     use crate::classfile::member_info::MemberInfo;
-    use crate::instruction::comparison::ifcond::*;
     use crate::instruction::comparison::ifcond::IFLT;
-    use crate::instruction::instruction::ExecuteResult;
+    use crate::instruction::comparison::ifcond::*;
     use crate::rtda::frame::Frame;
     use crate::rtda::heap::class::Class;
     use crate::rtda::heap::method::Method;
-    use crate::rtda::thread::Thread;
     use crate::rtda::vars::Vars;
-    use crate::util::code_reader::CodeReader;
 
     #[test]
     #[allow(non_snake_case)]
@@ -141,30 +111,22 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(0);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IFEQ(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 257);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(0);
+        let jump = IFEQ(257, &mut frame);
+        assert_eq!(jump, Some(257));
     }
 
     #[allow(non_snake_case)]
@@ -185,30 +147,22 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(0);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IFEQ(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 0);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(0);
+        let jump = IFEQ(257, &mut frame);
+        assert_eq!(jump, None);
     }
 
     #[test]
@@ -230,30 +184,22 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(1);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IFNE(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 257);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(1);
+        let jump = IFNE(257, &mut frame);
+        assert_eq!(jump, Some(257));
     }
 
     #[allow(non_snake_case)]
@@ -274,30 +220,22 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(0);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IFNE(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 0);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(0);
+        let jump = IFNE(257, &mut frame);
+        assert_eq!(jump, None);
     }
 
     #[test]
@@ -319,30 +257,22 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(-1);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IFLT(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 257);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(-1);
+        let jump = IFLT(257, &mut frame);
+        assert_eq!(jump, Some(257));
     }
 
     #[allow(non_snake_case)]
@@ -363,30 +293,22 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(0);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IFLT(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 0);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(0);
+        let jump = IFLT(257, &mut frame);
+        assert_eq!(jump, None);
     }
 
     #[test]
@@ -408,30 +330,22 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(0);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IFGE(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 257);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(0);
+        let jump = IFGE(257, &mut frame);
+        assert_eq!(jump, Some(257));
     }
 
     #[allow(non_snake_case)]
@@ -452,30 +366,22 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(-1);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IFGE(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 0);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(-1);
+        let jump = IFGE(257, &mut frame);
+        assert_eq!(jump, None);
     }
 
     #[test]
@@ -497,30 +403,22 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(1);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IFGT(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 257);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(1);
+        let jump = IFGT(257, &mut frame);
+        assert_eq!(jump, Some(257));
     }
 
     #[allow(non_snake_case)]
@@ -541,30 +439,22 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(0);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IFGT(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 0);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(0);
+        let jump = IFGT(257, &mut frame);
+        assert_eq!(jump, None);
     }
 
     #[test]
@@ -586,30 +476,22 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(0);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IFLE(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 257);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(0);
+        let jump = IFLE(257, &mut frame);
+        assert_eq!(jump, Some(257));
     }
 
     #[allow(non_snake_case)]
@@ -630,29 +512,21 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
-
-        let operand_stack = operand_stack.push_int(1);
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread: _, offset }, _) =
-            IFLE(CodeReader::new(Rc::new(vec![1, 1])), thread);
-        assert_eq!(offset, 0);
+        let mut frame = Frame::new(class, method);
+
+        frame.operand_stack.push_int(1);
+        let jump = IFLE(257, &mut frame);
+        assert_eq!(jump, None);
     }
 }
@@ -0,0 +1,30 @@
+use crate::rtda::frame::Frame;
+use crate::rtda::heap::object::ObjectRef;
+
+fn _pop_ref(frame: &mut Frame) -> Option<ObjectRef> {
+    frame.operand_stack.pop_ref()
+}
+
+#[allow(non_snake_case)]
+pub fn IFNULL(target: usize, frame: &mut Frame) -> Option<usize> {
+    trace!("IFNULL");
+
+    let val = _pop_ref(frame);
+    if val.is_none() {
+        Some(target)
+    } else {
+        None
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn IFNONNULL(target: usize, frame: &mut Frame) -> Option<usize> {
+    trace!("IFNONNULL");
+
+    let val = _pop_ref(frame);
+    if val.is_some() {
+        Some(target)
+    } else {
+        None
+    }
+}
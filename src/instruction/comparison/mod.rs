@@ -1,5 +1,7 @@
 pub mod dcmp;
 pub mod fcmp;
+pub mod if_acmp;
 pub mod if_icmp;
 pub mod ifcond;
+pub mod ifnull;
 pub mod lcmp;
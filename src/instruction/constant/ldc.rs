@@ -1,63 +1,122 @@
 use crate::classfile::constant_info::ConstantInfo;
-use crate::instruction::instruction::ExecuteResult;
 use crate::rtda::frame::Frame;
 use crate::rtda::thread::Thread;
-use crate::util::code_reader::CodeReader;
 
 #[allow(non_snake_case)]
-pub fn LDC(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("LDC");
-    let (index, code_reader) = code_reader.read_u8();
-    let (frame, thread) = thread.pop_frame();
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-    let class_copy = class.clone();
-    let constant_info = class_copy.constant_pool.get(index as usize);
-    let operand_stack = match constant_info {
-        ConstantInfo::Integer(val) => operand_stack.push_int(*val),
-        ConstantInfo::Float(val) => operand_stack.push_float(*val),
+pub fn LDC(index: usize, pc: usize, thread: &mut Thread) -> Option<usize> {
+    trace!("LDC");
+    let (frame, heap) = thread.current_frame_and_heap();
+    let class = &frame.class;
+    let constant_info = class.constant_pool.get(index);
+    let val = match constant_info {
+        ConstantInfo::Integer(val) => {
+            frame.operand_stack.push_int(*val);
+            return None;
+        }
+        ConstantInfo::Float(val) => {
+            frame.operand_stack.push_float(*val);
+            return None;
+        }
+        ConstantInfo::String(string_index) => {
+            let val = class.constant_pool.get_utf8(*string_index as usize);
+            let loader = class.loader.upgrade().expect("ClassLoader is dropped");
+            let string_class = loader.load("java/lang/String");
+            let char_array_class = loader.load("[C");
+            heap.borrow_mut()
+                .intern(val, string_class, char_array_class)
+        }
+        ConstantInfo::Class { name_index } => {
+            let name = class.constant_pool.get_utf8(*name_index as usize);
+            let loader = class.loader.upgrade().expect("ClassLoader is dropped");
+            let class = loader.load(name);
+            loader.mirror(&class, &mut heap.borrow_mut())
+        }
         _ => panic!("TODO: LDC"),
     };
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+    match val {
+        Some(val) => {
+            frame.operand_stack.push_ref(Some(val));
+            None
+        }
+        None => {
+            heap.borrow_mut().request_gc_for_allocation();
+            Some(pc)
+        }
+    }
 }
 
 #[allow(non_snake_case)]
-pub fn LDC2_W(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("LDC2_W");
-    let (index, code_reader) = code_reader.read_u16();
-    let (frame, thread) = thread.pop_frame();
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-    let class_copy = class.clone();
-    let constant_info = class_copy.constant_pool.get(index as usize);
-    let operand_stack = match constant_info {
-        ConstantInfo::Long(val) => operand_stack.push_long(*val),
-        ConstantInfo::Double(val) => operand_stack.push_double(*val),
+pub fn LDC2_W(index: usize, frame: &mut Frame) {
+    trace!("LDC2_W");
+    match frame.class.constant_pool.get(index) {
+        ConstantInfo::Long(val) => frame.operand_stack.push_long(*val),
+        ConstantInfo::Double(val) => frame.operand_stack.push_double(*val),
         _ => panic!("java.lang.ClassFormatError"),
-    };
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::instruction::constant::ldc::*;
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::thread::Thread;
+
+    // Node's constant pool: #7 = String hello
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_LDC_string() {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("gc/Node");
+        let method = class
+            .lookup_method("greet", "()Ljava/lang/String;")
+            .unwrap();
+        let frame = Frame::new(Rc::clone(&class), method);
+        let mut thread = Thread::new();
+        thread.push_frame(frame);
+        LDC(7, 0, &mut thread);
+        LDC(7, 0, &mut thread);
+        let mut frame = thread.pop_frame();
+        let a = frame.operand_stack.pop_ref();
+        let b = frame.operand_stack.pop_ref();
+        assert_eq!(a, b);
+        assert_eq!(thread.heap.borrow().java_string(a.unwrap()), "hello");
+    }
+
+    // Node's constant pool: #9 = Class gc/Node
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_LDC_class() {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("gc/Node");
+        let method = class.lookup_method("sum", "()I").unwrap();
+        let frame = Frame::new(Rc::clone(&class), method);
+        let mut thread = Thread::new();
+        thread.push_frame(frame);
+        LDC(9, 0, &mut thread);
+        LDC(9, 0, &mut thread);
+        let mut frame = thread.pop_frame();
+        let a = frame.operand_stack.pop_ref();
+        let b = frame.operand_stack.pop_ref();
+        assert_eq!(a, b);
+        let mirror = a.unwrap();
+        assert_eq!(
+            thread.heap.borrow().get(mirror).class.name,
+            "java/lang/Class"
+        );
+        let mirrored = class_loader.class_of_mirror(mirror).unwrap();
+        assert!(Rc::ptr_eq(&mirrored, &class));
+        assert!(class_loader.static_roots().contains(&mirror));
+    }
 }
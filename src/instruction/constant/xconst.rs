@@ -1,362 +1,124 @@
-use crate::instruction::instruction::ExecuteResult;
 use crate::rtda::frame::Frame;
-use crate::rtda::thread::Thread;
-use crate::util::code_reader::CodeReader;
 
 #[allow(non_snake_case)]
-pub fn DCONST_0(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("DCONST_0");
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-    let operand_stack = operand_stack.push_double(0f64);
-    let local_vars = local_vars;
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn ACONST_NULL(frame: &mut Frame) {
+    trace!("ACONST_NULL");
+
+    frame.operand_stack.push_ref(None);
 }
 
 #[allow(non_snake_case)]
-pub fn DCONST_1(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("DCONST_1");
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-    let operand_stack = operand_stack.push_double(1f64);
-    let local_vars = local_vars;
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn DCONST_0(frame: &mut Frame) {
+    trace!("DCONST_0");
+
+    frame.operand_stack.push_double(0f64);
 }
 
 #[allow(non_snake_case)]
-pub fn FCONST_0(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("FCONST_0");
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-    let operand_stack = operand_stack.push_float(0f32);
-    let local_vars = local_vars;
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn DCONST_1(frame: &mut Frame) {
+    trace!("DCONST_1");
+
+    frame.operand_stack.push_double(1f64);
 }
 
 #[allow(non_snake_case)]
-pub fn FCONST_1(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("FCONST_1");
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-    let operand_stack = operand_stack.push_float(1f32);
-    let local_vars = local_vars;
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn FCONST_0(frame: &mut Frame) {
+    trace!("FCONST_0");
+
+    frame.operand_stack.push_float(0f32);
 }
 
 #[allow(non_snake_case)]
-pub fn FCONST_2(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("FCONST_2");
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-    let operand_stack = operand_stack.push_float(2f32);
-    let local_vars = local_vars;
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn FCONST_1(frame: &mut Frame) {
+    trace!("FCONST_1");
+
+    frame.operand_stack.push_float(1f32);
 }
 
 #[allow(non_snake_case)]
-pub fn ICONST_M1(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("ICONST_M1");
-
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-    let operand_stack = operand_stack.push_int(-1);
-    let local_vars = local_vars;
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn FCONST_2(frame: &mut Frame) {
+    trace!("FCONST_2");
+
+    frame.operand_stack.push_float(2f32);
 }
 
 #[allow(non_snake_case)]
-pub fn ICONST_0(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("ICONST_0");
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-    let operand_stack = operand_stack.push_int(0);
-    let local_vars = local_vars;
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn ICONST_M1(frame: &mut Frame) {
+    trace!("ICONST_M1");
+
+    frame.operand_stack.push_int(-1);
 }
 
 #[allow(non_snake_case)]
-pub fn ICONST_1(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("ICONST_1");
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-    let operand_stack = operand_stack.push_int(1);
-    let local_vars = local_vars;
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn ICONST_0(frame: &mut Frame) {
+    trace!("ICONST_0");
+
+    frame.operand_stack.push_int(0);
 }
 
 #[allow(non_snake_case)]
-pub fn ICONST_2(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("ICONST_2");
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-    let operand_stack = operand_stack.push_int(2);
-    let local_vars = local_vars;
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn ICONST_1(frame: &mut Frame) {
+    trace!("ICONST_1");
+
+    frame.operand_stack.push_int(1);
 }
 
 #[allow(non_snake_case)]
-pub fn ICONST_3(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("ICONST_3");
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-    let operand_stack = operand_stack.push_int(3);
-    let local_vars = local_vars;
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn ICONST_2(frame: &mut Frame) {
+    trace!("ICONST_2");
+
+    frame.operand_stack.push_int(2);
 }
 
 #[allow(non_snake_case)]
-pub fn ICONST_4(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("ICONST_4");
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-    let operand_stack = operand_stack.push_int(4);
-    let local_vars = local_vars;
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn ICONST_3(frame: &mut Frame) {
+    trace!("ICONST_3");
+
+    frame.operand_stack.push_int(3);
 }
 
 #[allow(non_snake_case)]
-pub fn ICONST_5(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("ICONST_5");
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-    let operand_stack = operand_stack.push_int(5);
-    let local_vars = local_vars;
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn ICONST_4(frame: &mut Frame) {
+    trace!("ICONST_4");
+
+    frame.operand_stack.push_int(4);
 }
 
 #[allow(non_snake_case)]
-pub fn LCONST_0(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("LCONST_0");
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-    let operand_stack = operand_stack.push_long(0i64);
-    let local_vars = local_vars;
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn ICONST_5(frame: &mut Frame) {
+    trace!("ICONST_5");
+
+    frame.operand_stack.push_int(5);
 }
 
 #[allow(non_snake_case)]
-pub fn LCONST_1(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("LCONST_1");
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-    let operand_stack = operand_stack.push_long(1i64);
-    let local_vars = local_vars;
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn LCONST_0(frame: &mut Frame) {
+    trace!("LCONST_0");
+
+    frame.operand_stack.push_long(0i64);
+}
+
+#[allow(non_snake_case)]
+pub fn LCONST_1(frame: &mut Frame) {
+    trace!("LCONST_1");
+
+    frame.operand_stack.push_long(1i64);
 }
 
 #[cfg(test)]
 mod tests {
-    use std::rc::Rc;
+    use std::cell::RefCell;
+    use std::rc::{Rc, Weak};
 
     use vec_map::VecMap;
 
     use crate::classfile::constant_pool::ConstantPool;
     use crate::classfile::member_info::MemberInfo;
     use crate::instruction::constant::xconst::*;
-    use crate::instruction::constant::xconst::*;
-    use crate::instruction::instruction::ExecuteResult;
     use crate::rtda::frame::Frame;
     use crate::rtda::heap::class::Class;
     use crate::rtda::heap::method::Method;
-    use crate::rtda::thread::Thread;
     use crate::rtda::vars::Vars;
-    use crate::util::code_reader::CodeReader;
 
     #[test]
     #[allow(non_snake_case)]
@@ -377,17 +139,20 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            DCONST_0(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_double();
+        let mut frame = Frame::new(class, method);
+        DCONST_0(&mut frame);
+        let val = frame.operand_stack.pop_double();
         assert_eq!(val, 0f64);
     }
 
@@ -410,17 +175,20 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            DCONST_1(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_double();
+        let mut frame = Frame::new(class, method);
+        DCONST_1(&mut frame);
+        let val = frame.operand_stack.pop_double();
         assert_eq!(val, 1f64);
     }
 
@@ -443,17 +211,20 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            FCONST_0(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_float();
+        let mut frame = Frame::new(class, method);
+        FCONST_0(&mut frame);
+        let val = frame.operand_stack.pop_float();
         assert_eq!(val, 0f32);
     }
 
@@ -476,17 +247,20 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            FCONST_1(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_float();
+        let mut frame = Frame::new(class, method);
+        FCONST_1(&mut frame);
+        let val = frame.operand_stack.pop_float();
         assert_eq!(val, 1f32);
     }
 
@@ -509,17 +283,20 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            FCONST_2(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_float();
+        let mut frame = Frame::new(class, method);
+        FCONST_2(&mut frame);
+        let val = frame.operand_stack.pop_float();
         assert_eq!(val, 2f32);
     }
 
@@ -542,17 +319,20 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            ICONST_M1(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_int();
+        let mut frame = Frame::new(class, method);
+        ICONST_M1(&mut frame);
+        let val = frame.operand_stack.pop_int();
         assert_eq!(val, -1);
     }
 
@@ -575,17 +355,20 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            ICONST_0(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_int();
+        let mut frame = Frame::new(class, method);
+        ICONST_0(&mut frame);
+        let val = frame.operand_stack.pop_int();
         assert_eq!(val, 0);
     }
 
@@ -608,17 +391,20 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            ICONST_1(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_int();
+        let mut frame = Frame::new(class, method);
+        ICONST_1(&mut frame);
+        let val = frame.operand_stack.pop_int();
         assert_eq!(val, 1);
     }
 
@@ -641,17 +427,20 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            ICONST_2(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_int();
+        let mut frame = Frame::new(class, method);
+        ICONST_2(&mut frame);
+        let val = frame.operand_stack.pop_int();
         assert_eq!(val, 2);
     }
 
@@ -674,17 +463,20 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            ICONST_3(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_int();
+        let mut frame = Frame::new(class, method);
+        ICONST_3(&mut frame);
+        let val = frame.operand_stack.pop_int();
         assert_eq!(val, 3);
     }
 
@@ -707,17 +499,20 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            ICONST_4(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_int();
+        let mut frame = Frame::new(class, method);
+        ICONST_4(&mut frame);
+        let val = frame.operand_stack.pop_int();
         assert_eq!(val, 4);
     }
 
@@ -740,17 +535,20 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            ICONST_5(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_int();
+        let mut frame = Frame::new(class, method);
+        ICONST_5(&mut frame);
+        let val = frame.operand_stack.pop_int();
         assert_eq!(val, 5);
     }
 
@@ -773,17 +571,20 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            LCONST_0(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_long();
+        let mut frame = Frame::new(class, method);
+        LCONST_0(&mut frame);
+        let val = frame.operand_stack.pop_long();
         assert_eq!(val, 0i64);
     }
 
@@ -806,17 +607,20 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            LCONST_1(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_long();
+        let mut frame = Frame::new(class, method);
+        LCONST_1(&mut frame);
+        let val = frame.operand_stack.pop_long();
         assert_eq!(val, 1i64);
     }
 }
@@ -1,14 +1,6 @@
-use crate::instruction::instruction::ExecuteResult;
-use crate::rtda::thread::Thread;
-use crate::util::code_reader::CodeReader;
-
 #[allow(non_snake_case)]
-pub fn GOTO(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("GOTO");
-
-    let (offset, code_reader) = code_reader.read_i16();
-    let offset = offset as isize;
+pub fn GOTO(target: usize) -> Option<usize> {
+    trace!("GOTO");
 
-    let execute_result = ExecuteResult { thread, offset };
-    (execute_result, code_reader)
+    Some(target)
 }
@@ -1 +1,2 @@
 pub mod goto;
+pub mod xreturn;
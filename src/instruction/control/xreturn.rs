@@ -0,0 +1,117 @@
+use crate::rtda::thread::Thread;
+
+fn _return(thread: &mut Thread, slot_count: usize) -> Option<usize> {
+    let mut frame = thread.pop_frame();
+    let val = frame.operand_stack.pop_slots(slot_count);
+    if frame.method.is_synchronized() {
+        thread.exit_synchronized();
+    }
+    if thread.is_stack_empty() {
+        return None;
+    }
+
+    let frame = thread.current_frame_mut();
+    for x in val {
+        frame.operand_stack.push_slot(x);
+    }
+    Some(frame.next_pc)
+}
+
+#[allow(non_snake_case)]
+pub fn RETURN(thread: &mut Thread) -> Option<usize> {
+    trace!("RETURN");
+    _return(thread, 0)
+}
+
+#[allow(non_snake_case)]
+pub fn IRETURN(thread: &mut Thread) -> Option<usize> {
+    trace!("IRETURN");
+    _return(thread, 1)
+}
+
+#[allow(non_snake_case)]
+pub fn FRETURN(thread: &mut Thread) -> Option<usize> {
+    trace!("FRETURN");
+    _return(thread, 1)
+}
+
+#[allow(non_snake_case)]
+pub fn ARETURN(thread: &mut Thread) -> Option<usize> {
+    trace!("ARETURN");
+    _return(thread, 1)
+}
+
+#[allow(non_snake_case)]
+pub fn LRETURN(thread: &mut Thread) -> Option<usize> {
+    trace!("LRETURN");
+    _return(thread, 2)
+}
+
+#[allow(non_snake_case)]
+pub fn DRETURN(thread: &mut Thread) -> Option<usize> {
+    trace!("DRETURN");
+    _return(thread, 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::instruction::control::xreturn::*;
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::thread::Thread;
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_IRETURN() {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("vtable/a/A");
+        let method = |name: &str| {
+            class
+                .methods
+                .iter()
+                .find(|x| x.name() == name)
+                .cloned()
+                .unwrap()
+        };
+        let caller = Frame::new(Rc::clone(&class), method("callPkg"));
+        let caller = Frame {
+            next_pc: 4,
+            ..caller
+        };
+        let mut callee = Frame::new(Rc::clone(&class), method("pkg"));
+        callee.operand_stack.push_int(42);
+        let mut thread = Thread::new();
+        thread.push_frame(caller);
+        thread.push_frame(callee);
+        let jump = IRETURN(&mut thread);
+        assert_eq!(jump, Some(4));
+        let mut frame = thread.pop_frame();
+        assert_eq!(frame.method.name(), "callPkg");
+        let val = frame.operand_stack.pop_int();
+        assert_eq!(val, 42);
+        assert!(thread.is_stack_empty());
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_RETURN_last_frame() {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("MyObject");
+        let frame = Frame::new(Rc::clone(&class), class.main_method());
+        let mut thread = Thread::new();
+        thread.push_frame(frame);
+        RETURN(&mut thread);
+        assert!(thread.is_stack_empty());
+    }
+}
@@ -0,0 +1,442 @@
+use crate::instruction::inline_cache::{CallCache, FieldCache, InlineCache};
+use crate::verifier::instruction_length;
+
+/// An instruction with its operands decoded: local variable and constant pool indexes
+/// are widened and branch offsets are resolved to the pcs they jump to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Instruction {
+    Nop,
+    AconstNull,
+    IconstM1,
+    Iconst0,
+    Iconst1,
+    Iconst2,
+    Iconst3,
+    Iconst4,
+    Iconst5,
+    Lconst0,
+    Lconst1,
+    Fconst0,
+    Fconst1,
+    Fconst2,
+    Dconst0,
+    Dconst1,
+    Bipush(i32),
+    Sipush(i32),
+    Ldc(usize),
+    Ldc2W(usize),
+    Iload(usize),
+    Lload(usize),
+    Aload(usize),
+    Iload0,
+    Iload1,
+    Iload2,
+    Iload3,
+    Lload0,
+    Lload1,
+    Lload2,
+    Lload3,
+    Aload0,
+    Aload1,
+    Aload2,
+    Aload3,
+    Aaload,
+    Baload,
+    Istore(usize),
+    Lstore(usize),
+    Astore(usize),
+    Istore0,
+    Istore1,
+    Istore2,
+    Istore3,
+    Lstore0,
+    Lstore1,
+    Lstore2,
+    Lstore3,
+    Astore0,
+    Astore1,
+    Astore2,
+    Astore3,
+    Aastore,
+    Pop,
+    Pop2,
+    Dup,
+    Iadd,
+    Imul,
+    Lmul,
+    Fmul,
+    Dmul,
+    Ineg,
+    Lneg,
+    Fneg,
+    Dneg,
+    Iand,
+    Land,
+    Iinc(usize, i32),
+    Lcmp,
+    Fcmpl,
+    Fcmpg,
+    Dcmpl,
+    Dcmpg,
+    Ifeq(usize),
+    Ifne(usize),
+    Iflt(usize),
+    Ifge(usize),
+    Ifgt(usize),
+    Ifle(usize),
+    IfIcmpeq(usize),
+    IfIcmpne(usize),
+    IfIcmplt(usize),
+    IfIcmpge(usize),
+    IfIcmpgt(usize),
+    IfIcmple(usize),
+    IfAcmpeq(usize),
+    IfAcmpne(usize),
+    Goto(usize),
+    Ireturn,
+    Lreturn,
+    Freturn,
+    Dreturn,
+    Areturn,
+    Return,
+    Getstatic(usize),
+    Putstatic(usize),
+    Getfield(usize),
+    Putfield(usize),
+    Invokevirtual(usize),
+    Invokespecial(usize),
+    Invokestatic(usize),
+    Invokeinterface(usize),
+    New(usize),
+    Arraylength,
+    Athrow,
+    Checkcast(usize),
+    InstanceOf(usize),
+    Monitorenter,
+    Monitorexit,
+    Ifnull(usize),
+    Ifnonnull(usize),
+    /// An opcode the interpreter doesn't implement, which fails when it's executed.
+    Unsupported(u8),
+}
+
+impl Instruction {
+    /// The pc a branch jumps to when it's taken, `None` for other instructions.
+    pub fn branch_target(&self) -> Option<usize> {
+        match *self {
+            Instruction::Ifeq(target)
+            | Instruction::Ifne(target)
+            | Instruction::Iflt(target)
+            | Instruction::Ifge(target)
+            | Instruction::Ifgt(target)
+            | Instruction::Ifle(target)
+            | Instruction::IfIcmpeq(target)
+            | Instruction::IfIcmpne(target)
+            | Instruction::IfIcmplt(target)
+            | Instruction::IfIcmpge(target)
+            | Instruction::IfIcmpgt(target)
+            | Instruction::IfIcmple(target)
+            | Instruction::IfAcmpeq(target)
+            | Instruction::IfAcmpne(target)
+            | Instruction::Goto(target)
+            | Instruction::Ifnull(target)
+            | Instruction::Ifnonnull(target) => Some(target),
+            _ => None,
+        }
+    }
+}
+
+/// The code of a method decoded once, so that running an instruction doesn't read its
+/// bytes again.
+#[derive(Debug)]
+pub struct DecodedCode {
+    /// The instructions in order, each with the pc of the one after it.
+    instructions: Vec<(Instruction, usize)>,
+    /// The index in `instructions` of the instruction starting at each pc, `u32::MAX`
+    /// for the pcs of operands.
+    indexes: Vec<u32>,
+    /// The inline cache of each call site and field access, by pc.
+    caches: Vec<(usize, InlineCache)>,
+}
+
+impl DecodedCode {
+    pub fn decode(code: &[u8]) -> DecodedCode {
+        let mut instructions = Vec::new();
+        let mut indexes = vec![u32::MAX; code.len()];
+        let mut caches = Vec::new();
+        let mut pc = 0;
+        while pc < code.len() {
+            let length = instruction_length(code, pc)
+                .unwrap_or_else(|e| panic!("java.lang.VerifyError: {} at pc {}", e, pc));
+            let instruction = decode(code, pc);
+            match instruction {
+                Instruction::Invokevirtual(_) | Instruction::Invokeinterface(_) => {
+                    caches.push((pc, InlineCache::Call(CallCache::default())))
+                }
+                Instruction::Getfield(_) | Instruction::Putfield(_) => {
+                    caches.push((pc, InlineCache::Field(FieldCache::default())))
+                }
+                _ => {}
+            }
+            indexes[pc] = instructions.len() as u32;
+            instructions.push((instruction, pc + length));
+            pc += length;
+        }
+        DecodedCode {
+            instructions,
+            indexes,
+            caches,
+        }
+    }
+
+    /// The inline cache of the instruction at `pc`, if it's a call site or a field access.
+    pub fn cache(&self, pc: usize) -> Option<&InlineCache> {
+        let index = self.caches.binary_search_by_key(&pc, |(x, _)| *x).ok()?;
+        Some(&self.caches[index].1)
+    }
+
+    /// The inline cache of the `invokevirtual` or `invokeinterface` at `pc`.
+    pub fn call_cache(&self, pc: usize) -> &CallCache {
+        match self.cache(pc) {
+            Some(InlineCache::Call(cache)) => cache,
+            _ => panic!("No call site at pc {}", pc),
+        }
+    }
+
+    /// The inline cache of the `getfield` or `putfield` at `pc`.
+    pub fn field_cache(&self, pc: usize) -> &FieldCache {
+        match self.cache(pc) {
+            Some(InlineCache::Field(cache)) => cache,
+            _ => panic!("No field access at pc {}", pc),
+        }
+    }
+
+    /// The instruction starting at `pc` and the pc of the one after it.
+    pub fn at(&self, pc: usize) -> (Instruction, usize) {
+        match self.indexes.get(pc) {
+            Some(&index) if index != u32::MAX => self.instructions[index as usize],
+            _ => panic!("No instruction starts at pc {}", pc),
+        }
+    }
+
+    /// Every instruction with its pc and the pc of the one after it, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, Instruction, usize)> + '_ {
+        let pcs = std::iter::once(0).chain(self.instructions.iter().map(|(_, x)| *x));
+        pcs.zip(self.instructions.iter())
+            .map(|(pc, (instruction, next_pc))| (pc, *instruction, *next_pc))
+    }
+
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+}
+
+fn u8_at(code: &[u8], at: usize) -> usize {
+    code[at] as usize
+}
+
+fn u16_at(code: &[u8], at: usize) -> usize {
+    (code[at] as usize) << 8 | code[at + 1] as usize
+}
+
+fn branch_target(code: &[u8], pc: usize, offset_size: usize) -> usize {
+    let offset = match offset_size {
+        2 => i16::from_be_bytes([code[pc + 1], code[pc + 2]]) as isize,
+        _ => i32::from_be_bytes([code[pc + 1], code[pc + 2], code[pc + 3], code[pc + 4]]) as isize,
+    };
+    (pc as isize + offset) as usize
+}
+
+/// Decodes the instruction at `pc`, which `instruction_length` has checked is complete.
+fn decode(code: &[u8], pc: usize) -> Instruction {
+    let index = || u8_at(code, pc + 1);
+    let cp_index = || u16_at(code, pc + 1);
+    let target = || branch_target(code, pc, 2);
+    match code[pc] {
+        0x00 => Instruction::Nop,
+        0x01 => Instruction::AconstNull,
+        0x02 => Instruction::IconstM1,
+        0x03 => Instruction::Iconst0,
+        0x04 => Instruction::Iconst1,
+        0x05 => Instruction::Iconst2,
+        0x06 => Instruction::Iconst3,
+        0x07 => Instruction::Iconst4,
+        0x08 => Instruction::Iconst5,
+        0x09 => Instruction::Lconst0,
+        0x0A => Instruction::Lconst1,
+        0x0B => Instruction::Fconst0,
+        0x0C => Instruction::Fconst1,
+        0x0D => Instruction::Fconst2,
+        0x0E => Instruction::Dconst0,
+        0x0F => Instruction::Dconst1,
+        0x10 => Instruction::Bipush(code[pc + 1] as i8 as i32),
+        0x11 => Instruction::Sipush(u16_at(code, pc + 1) as i16 as i32),
+        0x12 => Instruction::Ldc(index()),
+        0x14 => Instruction::Ldc2W(cp_index()),
+        0x15 => Instruction::Iload(index()),
+        0x16 => Instruction::Lload(index()),
+        0x19 => Instruction::Aload(index()),
+        0x1A => Instruction::Iload0,
+        0x1B => Instruction::Iload1,
+        0x1C => Instruction::Iload2,
+        0x1D => Instruction::Iload3,
+        0x1E => Instruction::Lload0,
+        0x1F => Instruction::Lload1,
+        0x20 => Instruction::Lload2,
+        0x21 => Instruction::Lload3,
+        0x2A => Instruction::Aload0,
+        0x2B => Instruction::Aload1,
+        0x2C => Instruction::Aload2,
+        0x2D => Instruction::Aload3,
+        0x32 => Instruction::Aaload,
+        0x33 => Instruction::Baload,
+        0x36 => Instruction::Istore(index()),
+        0x37 => Instruction::Lstore(index()),
+        0x3A => Instruction::Astore(index()),
+        0x3B => Instruction::Istore0,
+        0x3C => Instruction::Istore1,
+        0x3D => Instruction::Istore2,
+        0x3E => Instruction::Istore3,
+        0x3F => Instruction::Lstore0,
+        0x40 => Instruction::Lstore1,
+        0x41 => Instruction::Lstore2,
+        0x42 => Instruction::Lstore3,
+        0x4B => Instruction::Astore0,
+        0x4C => Instruction::Astore1,
+        0x4D => Instruction::Astore2,
+        0x4E => Instruction::Astore3,
+        0x53 => Instruction::Aastore,
+        0x57 => Instruction::Pop,
+        0x58 => Instruction::Pop2,
+        0x59 => Instruction::Dup,
+        0x60 => Instruction::Iadd,
+        0x68 => Instruction::Imul,
+        0x69 => Instruction::Lmul,
+        0x6A => Instruction::Fmul,
+        0x6B => Instruction::Dmul,
+        0x74 => Instruction::Ineg,
+        0x75 => Instruction::Lneg,
+        0x76 => Instruction::Fneg,
+        0x77 => Instruction::Dneg,
+        0x7E => Instruction::Iand,
+        0x7F => Instruction::Land,
+        0x84 => Instruction::Iinc(index(), code[pc + 2] as i8 as i32),
+        0x94 => Instruction::Lcmp,
+        0x95 => Instruction::Fcmpl,
+        0x96 => Instruction::Fcmpg,
+        0x97 => Instruction::Dcmpl,
+        0x98 => Instruction::Dcmpg,
+        0x99 => Instruction::Ifeq(target()),
+        0x9A => Instruction::Ifne(target()),
+        0x9B => Instruction::Iflt(target()),
+        0x9C => Instruction::Ifge(target()),
+        0x9D => Instruction::Ifgt(target()),
+        0x9E => Instruction::Ifle(target()),
+        0x9F => Instruction::IfIcmpeq(target()),
+        0xA0 => Instruction::IfIcmpne(target()),
+        0xA1 => Instruction::IfIcmplt(target()),
+        0xA2 => Instruction::IfIcmpge(target()),
+        0xA3 => Instruction::IfIcmpgt(target()),
+        0xA4 => Instruction::IfIcmple(target()),
+        0xA5 => Instruction::IfAcmpeq(target()),
+        0xA6 => Instruction::IfAcmpne(target()),
+        0xA7 => Instruction::Goto(target()),
+        0xAC => Instruction::Ireturn,
+        0xAD => Instruction::Lreturn,
+        0xAE => Instruction::Freturn,
+        0xAF => Instruction::Dreturn,
+        0xB0 => Instruction::Areturn,
+        0xB1 => Instruction::Return,
+        0xB2 => Instruction::Getstatic(cp_index()),
+        0xB3 => Instruction::Putstatic(cp_index()),
+        0xB4 => Instruction::Getfield(cp_index()),
+        0xB5 => Instruction::Putfield(cp_index()),
+        0xB6 => Instruction::Invokevirtual(cp_index()),
+        0xB7 => Instruction::Invokespecial(cp_index()),
+        0xB8 => Instruction::Invokestatic(cp_index()),
+        0xB9 => Instruction::Invokeinterface(cp_index()),
+        0xBB => Instruction::New(cp_index()),
+        0xBE => Instruction::Arraylength,
+        0xBF => Instruction::Athrow,
+        0xC0 => Instruction::Checkcast(cp_index()),
+        0xC1 => Instruction::InstanceOf(cp_index()),
+        0xC2 => Instruction::Monitorenter,
+        0xC3 => Instruction::Monitorexit,
+        0xC4 => decode_wide(code, pc),
+        0xC6 => Instruction::Ifnull(target()),
+        0xC7 => Instruction::Ifnonnull(target()),
+        0xC8 => Instruction::Goto(branch_target(code, pc, 4)),
+        opcode => Instruction::Unsupported(opcode),
+    }
+}
+
+/// Decodes a `wide` instruction into the one it widens.
+fn decode_wide(code: &[u8], pc: usize) -> Instruction {
+    let index = u16_at(code, pc + 2);
+    match code[pc + 1] {
+        0x15 => Instruction::Iload(index),
+        0x16 => Instruction::Lload(index),
+        0x19 => Instruction::Aload(index),
+        0x36 => Instruction::Istore(index),
+        0x37 => Instruction::Lstore(index),
+        0x3A => Instruction::Astore(index),
+        0x84 => Instruction::Iinc(
+            index,
+            i16::from_be_bytes([code[pc + 4], code[pc + 5]]) as i32,
+        ),
+        _ => Instruction::Unsupported(0xC4),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::instruction::decoder::*;
+
+    #[test]
+    fn decode() {
+        let code = [
+            0x10, 0xFE, // bipush -2
+            0x3C, // istore_1
+            0x84, 0x01, 0xFF, // iinc 1, -1
+            0xC4, 0x84, 0x01, 0x00, 0x01, 0x00, // wide iinc 256, 256
+            0x1B, // iload_1
+            0x9A, 0xFF, 0xF6, // ifne -10
+            0xC8, 0x00, 0x00, 0x00, 0x05, // goto_w +5
+            0xB1, // return
+            0x11, 0xFF, 0x00, // sipush -256
+        ];
+        let decoded = DecodedCode::decode(&code);
+        assert_eq!(decoded.len(), 9);
+        assert_eq!(decoded.at(0), (Instruction::Bipush(-2), 2));
+        assert_eq!(decoded.at(2), (Instruction::Istore1, 3));
+        assert_eq!(decoded.at(3), (Instruction::Iinc(1, -1), 6));
+        assert_eq!(decoded.at(6), (Instruction::Iinc(256, 256), 12));
+        assert_eq!(decoded.at(13), (Instruction::Ifne(3), 16));
+        assert_eq!(decoded.at(16), (Instruction::Goto(21), 21));
+        assert_eq!(decoded.at(21), (Instruction::Return, 22));
+        assert_eq!(decoded.at(22), (Instruction::Sipush(-256), 25));
+        let pcs: Vec<usize> = decoded.iter().map(|(pc, _, _)| pc).collect();
+        assert_eq!(pcs, vec![0, 2, 3, 6, 12, 13, 16, 21, 22]);
+        assert_eq!(decoded.at(13).0.branch_target(), Some(3));
+        assert_eq!(decoded.at(12).0.branch_target(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "No instruction starts at pc 1")]
+    fn operand_pc() {
+        DecodedCode::decode(&[0x10, 0x01, 0xAC]).at(1);
+    }
+
+    #[test]
+    fn unsupported() {
+        // tableswitch with padding, a default, low and high of 0 and a single offset
+        let mut code = [0; 20];
+        code[0] = 0xAA;
+        let decoded = DecodedCode::decode(&code);
+        assert_eq!(decoded.at(0), (Instruction::Unsupported(0xAA), 20));
+    }
+}
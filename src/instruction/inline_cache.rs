@@ -0,0 +1,334 @@
+//! The caches the decoded code keeps at each call site and field access. A call site
+//! remembers the methods it dispatched to by class of receiver, first one and then up to
+//! `POLYMORPHIC_LIMIT`, after which it's megamorphic and always dispatches through the
+//! vtable or the interface lookup. A field access remembers the slots of the field once
+//! it's been resolved and checked.
+
+use std::cell::{Cell, OnceCell, RefCell};
+use std::fmt::Write;
+use std::rc::{Rc, Weak};
+
+use crate::instruction::decoder::Instruction;
+use crate::rtda::heap::class::Class;
+use crate::rtda::heap::class_loader::ClassLoader;
+use crate::rtda::heap::field::Field;
+use crate::rtda::heap::method::Method;
+
+/// How many classes of receiver a call site caches before it turns megamorphic.
+pub const POLYMORPHIC_LIMIT: usize = 4;
+
+#[derive(Debug)]
+pub enum InlineCache {
+    Call(CallCache),
+    Field(FieldCache),
+}
+
+/// The methods an `invokevirtual` or `invokeinterface` dispatched to.
+#[derive(Debug, Default)]
+pub struct CallCache {
+    state: RefCell<CallState>,
+    hits: Cell<u64>,
+    misses: Cell<u64>,
+}
+
+#[derive(Debug, Default)]
+enum CallState {
+    #[default]
+    Empty,
+    Monomorphic(Target),
+    Polymorphic(Vec<Target>),
+    Megamorphic,
+}
+
+/// A class of receiver and the method it dispatches to.
+#[derive(Debug)]
+struct Target {
+    receiver: Weak<Class>,
+    method: Rc<Method>,
+}
+
+impl Target {
+    fn is(&self, receiver: &Rc<Class>) -> bool {
+        std::ptr::eq(self.receiver.as_ptr(), Rc::as_ptr(receiver))
+    }
+}
+
+impl CallCache {
+    /// The method a receiver of class `receiver` dispatched to before, or `None` if the
+    /// call site has to dispatch, in which case it should `update` the cache after.
+    pub fn lookup(&self, receiver: &Rc<Class>) -> Option<Rc<Method>> {
+        let method = match &*self.state.borrow() {
+            CallState::Monomorphic(target) if target.is(receiver) => {
+                Some(Rc::clone(&target.method))
+            }
+            CallState::Polymorphic(targets) => targets
+                .iter()
+                .find(|x| x.is(receiver))
+                .map(|x| Rc::clone(&x.method)),
+            _ => None,
+        };
+        let counter = if method.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.set(counter.get() + 1);
+        method
+    }
+
+    /// Caches that receivers of class `receiver` dispatch to `method`.
+    pub fn update(&self, receiver: &Rc<Class>, method: &Rc<Method>) {
+        let target = Target {
+            receiver: Rc::downgrade(receiver),
+            method: Rc::clone(method),
+        };
+        let mut state = self.state.borrow_mut();
+        *state = match std::mem::take(&mut *state) {
+            CallState::Empty => CallState::Monomorphic(target),
+            CallState::Monomorphic(first) => CallState::Polymorphic(vec![first, target]),
+            CallState::Polymorphic(targets) if targets.len() >= POLYMORPHIC_LIMIT => {
+                CallState::Megamorphic
+            }
+            CallState::Polymorphic(mut targets) => {
+                targets.push(target);
+                CallState::Polymorphic(targets)
+            }
+            CallState::Megamorphic => CallState::Megamorphic,
+        };
+    }
+
+    /// The class of the only receiver the call site saw, if it's monomorphic.
+    pub fn monomorphic(&self) -> Option<Rc<Class>> {
+        match &*self.state.borrow() {
+            CallState::Monomorphic(target) => target.receiver.upgrade(),
+            _ => None,
+        }
+    }
+
+    /// The classes of the receivers the call site caches.
+    pub fn receivers(&self) -> Vec<Rc<Class>> {
+        match &*self.state.borrow() {
+            CallState::Monomorphic(target) => target.receiver.upgrade().into_iter().collect(),
+            CallState::Polymorphic(targets) => targets
+                .iter()
+                .filter_map(|x| x.receiver.upgrade())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn state_name(&self) -> &'static str {
+        match &*self.state.borrow() {
+            CallState::Empty => "empty",
+            CallState::Monomorphic(_) => "monomorphic",
+            CallState::Polymorphic(_) => "polymorphic",
+            CallState::Megamorphic => "megamorphic",
+        }
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits.get()
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.get()
+    }
+}
+
+/// Where a `getfield` or `putfield` finds its field in the object.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldSlots {
+    pub slot_id: usize,
+    pub slot_count: usize,
+    pub is_ref: bool,
+}
+
+impl FieldSlots {
+    pub fn new(field: &Field) -> FieldSlots {
+        FieldSlots {
+            slot_id: field.slot_id,
+            slot_count: field.slot_count(),
+            is_ref: field.is_ref(),
+        }
+    }
+}
+
+/// The slots of the field a `getfield` or `putfield` accesses, set once the field is
+/// resolved and the access is checked.
+#[derive(Debug, Default)]
+pub struct FieldCache {
+    slots: OnceCell<FieldSlots>,
+    hits: Cell<u64>,
+    misses: Cell<u64>,
+}
+
+impl FieldCache {
+    /// The slots of the field, or `None` if the access has to resolve it and `set` them.
+    pub fn lookup(&self) -> Option<FieldSlots> {
+        let slots = self.slots.get().copied();
+        let counter = if slots.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.set(counter.get() + 1);
+        slots
+    }
+
+    pub fn set(&self, slots: FieldSlots) {
+        let _ = self.slots.set(slots);
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits.get()
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.get()
+    }
+}
+
+fn mnemonic(instruction: Instruction) -> &'static str {
+    match instruction {
+        Instruction::Invokevirtual(_) => "invokevirtual",
+        Instruction::Invokeinterface(_) => "invokeinterface",
+        Instruction::Getfield(_) => "getfield",
+        Instruction::Putfield(_) => "putfield",
+        _ => unreachable!("{:?} has no inline cache", instruction),
+    }
+}
+
+/// The counters of the inline caches of every method that ran, with how many call sites
+/// are in each state first and then a line per site, like
+/// `pkg/A.run(I)V@12 invokevirtual polymorphic [pkg/B, pkg/C] hits 98 misses 2`.
+pub fn dump(class_loader: &ClassLoader) -> String {
+    let mut sites = String::new();
+    let mut states = [
+        ("empty", 0),
+        ("monomorphic", 0),
+        ("polymorphic", 0),
+        ("megamorphic", 0),
+    ];
+    for class in class_loader.classes() {
+        for method in &class.methods {
+            let Some(decoded_code) = method.decoded() else {
+                continue;
+            };
+            for (pc, instruction, _) in decoded_code.iter() {
+                let Some(cache) = decoded_code.cache(pc) else {
+                    continue;
+                };
+                let site = format!(
+                    "{}.{}{}@{} {}",
+                    class.name,
+                    method.name(),
+                    method.descriptor(),
+                    pc,
+                    mnemonic(instruction)
+                );
+                match cache {
+                    InlineCache::Call(cache) => {
+                        let state = cache.state_name();
+                        if let Some(count) = states.iter_mut().find(|(x, _)| *x == state) {
+                            count.1 += 1;
+                        }
+                        let receivers: Vec<String> =
+                            cache.receivers().iter().map(|x| x.name.clone()).collect();
+                        let _ = writeln!(
+                            sites,
+                            "{} {} [{}] hits {} misses {}",
+                            site,
+                            state,
+                            receivers.join(", "),
+                            cache.hits(),
+                            cache.misses()
+                        );
+                    }
+                    InlineCache::Field(cache) => {
+                        let _ = writeln!(
+                            sites,
+                            "{} hits {} misses {}",
+                            site,
+                            cache.hits(),
+                            cache.misses()
+                        );
+                    }
+                }
+            }
+        }
+    }
+    let summary: Vec<String> = states
+        .iter()
+        .map(|(state, count)| format!("{} {}", state, count))
+        .collect();
+    format!("call sites: {}\n{}", summary.join(", "), sites)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::instruction::inline_cache::*;
+    use crate::interpreter::{interpret, Schedule};
+    use crate::rtda::frame::Frame;
+    use crate::rtda::thread::Thread;
+
+    fn class_loader() -> Rc<ClassLoader> {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        ClassLoader::new(class_path)
+    }
+
+    #[test]
+    fn call_cache_states() {
+        let class_loader = class_loader();
+        let classes: Vec<Rc<Class>> = ["vtable/a/A", "vtable/a/B", "vtable/b/C", "gc/Node"]
+            .iter()
+            .chain(["jit/Shapes", "jit/Wide"].iter())
+            .map(|x| class_loader.load(x))
+            .collect();
+        let method = class_loader.load("jit/Shapes").main_method();
+        let cache = CallCache::default();
+        assert!(cache.lookup(&classes[0]).is_none());
+        cache.update(&classes[0], &method);
+        assert_eq!(cache.state_name(), "monomorphic");
+        assert!(Rc::ptr_eq(&cache.lookup(&classes[0]).unwrap(), &method));
+        assert!(Rc::ptr_eq(&cache.monomorphic().unwrap(), &classes[0]));
+        for class in &classes[1..POLYMORPHIC_LIMIT] {
+            assert!(cache.lookup(class).is_none());
+            cache.update(class, &method);
+        }
+        assert_eq!(cache.state_name(), "polymorphic");
+        assert_eq!(cache.receivers().len(), POLYMORPHIC_LIMIT);
+        assert!(cache.monomorphic().is_none());
+        assert!(cache.lookup(&classes[POLYMORPHIC_LIMIT - 1]).is_some());
+        cache.update(&classes[POLYMORPHIC_LIMIT], &method);
+        assert_eq!(cache.state_name(), "megamorphic");
+        assert!(cache.lookup(&classes[0]).is_none());
+        assert_eq!(
+            (cache.hits(), cache.misses()),
+            (2, POLYMORPHIC_LIMIT as u64 + 1)
+        );
+    }
+
+    // totalArea calls area on Shapes 2000 times and then on Wide 10 times, which has its
+    // own getfield
+    #[test]
+    fn dump_counters() {
+        let class_loader = class_loader();
+        let class = class_loader.load("jit/Shapes");
+        let mut thread = Thread::new();
+        thread.push_frame(Frame::new(Rc::clone(&class), class.main_method()));
+        interpret(&class_loader, thread, Schedule::default());
+        let dump = dump(&class_loader);
+        assert!(dump.starts_with("call sites: empty 0, monomorphic 0, polymorphic 1,"));
+        assert!(dump.contains(
+            "jit/Shapes.totalArea(Ljit/Shapes;I)I@11 invokevirtual polymorphic \
+             [jit/Shapes, jit/Wide] hits 2008 misses 2"
+        ));
+        assert!(dump.contains("jit/Shapes.area()I@1 getfield hits 1999 misses 1"));
+    }
+}
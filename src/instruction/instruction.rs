@@ -1,93 +1,213 @@
+use std::rc::Rc;
+
 use crate::instruction::comparison::dcmp::*;
 use crate::instruction::comparison::fcmp::*;
+use crate::instruction::comparison::if_acmp::*;
 use crate::instruction::comparison::if_icmp::*;
 use crate::instruction::comparison::ifcond::*;
+use crate::instruction::comparison::ifnull::*;
 use crate::instruction::comparison::lcmp::*;
 use crate::instruction::constant::ldc::*;
 use crate::instruction::constant::nop::NOP;
 use crate::instruction::constant::xconst::*;
 use crate::instruction::constant::xipush::*;
 use crate::instruction::control::goto::*;
+use crate::instruction::control::xreturn::*;
+use crate::instruction::decoder::Instruction;
+use crate::instruction::load::aload::*;
 use crate::instruction::load::iload::*;
+use crate::instruction::load::lload::*;
+use crate::instruction::load::xaload::*;
 use crate::instruction::math::add::*;
 use crate::instruction::math::and::*;
 use crate::instruction::math::inc::*;
 use crate::instruction::math::mul::*;
 use crate::instruction::math::neg::*;
+use crate::instruction::reference::arraylength::*;
+use crate::instruction::reference::athrow::*;
+use crate::instruction::reference::checkcast::*;
+use crate::instruction::reference::getfield::*;
+use crate::instruction::reference::getstatic::*;
+use crate::instruction::reference::instanceof::*;
+use crate::instruction::reference::invokeinterface::*;
+use crate::instruction::reference::invokespecial::*;
+use crate::instruction::reference::invokestatic::*;
+use crate::instruction::reference::invokevirtual::*;
+use crate::instruction::reference::monitor::*;
+use crate::instruction::reference::new::*;
+use crate::instruction::reference::putfield::*;
+use crate::instruction::reference::putstatic::*;
+use crate::instruction::stack::dup::*;
+use crate::instruction::stack::pop::*;
+use crate::instruction::store::astore::*;
 use crate::instruction::store::istore::*;
+use crate::instruction::store::lstore::*;
+use crate::instruction::store::xastore::*;
+use crate::rtda::frame::Frame;
 use crate::rtda::thread::Thread;
-use crate::util::code_reader::CodeReader;
 
-pub struct ExecuteResult {
-    pub thread: Thread,
-    pub offset: isize,
+/// Runs the instruction at `pc` of the current frame in place and returns the pc to
+/// continue at.
+pub fn execute(pc: usize, thread: &mut Thread) -> usize {
+    #[cfg(feature = "jit")]
+    if let Some(next_pc) = crate::jit::run_compiled(pc, thread) {
+        return next_pc;
+    }
+    if let Some(next_pc) = crate::instruction::register::run_registers(pc, thread) {
+        return next_pc;
+    }
+    let method = Rc::clone(&thread.current_frame().method);
+    let decoded_code = method.decoded_code();
+    let (instruction, next_pc) = decoded_code.at(pc);
+
+    let jump = match instruction {
+        Instruction::Ldc(index) => LDC(index, pc, thread),
+        Instruction::Ifeq(target) => IFEQ(target, thread.current_frame_mut()),
+        Instruction::Ifne(target) => IFNE(target, thread.current_frame_mut()),
+        Instruction::Iflt(target) => IFLT(target, thread.current_frame_mut()),
+        Instruction::Ifge(target) => IFGE(target, thread.current_frame_mut()),
+        Instruction::Ifgt(target) => IFGT(target, thread.current_frame_mut()),
+        Instruction::Ifle(target) => IFLE(target, thread.current_frame_mut()),
+        Instruction::IfIcmpeq(target) => IF_ICMPEQ(target, thread.current_frame_mut()),
+        Instruction::IfIcmpne(target) => IF_ICMPNE(target, thread.current_frame_mut()),
+        Instruction::IfIcmplt(target) => IF_ICMPLT(target, thread.current_frame_mut()),
+        Instruction::IfIcmpge(target) => IF_ICMPGE(target, thread.current_frame_mut()),
+        Instruction::IfIcmpgt(target) => IF_ICMPGT(target, thread.current_frame_mut()),
+        Instruction::IfIcmple(target) => IF_ICMPLE(target, thread.current_frame_mut()),
+        Instruction::IfAcmpeq(target) => IF_ACMPEQ(target, thread.current_frame_mut()),
+        Instruction::IfAcmpne(target) => IF_ACMPNE(target, thread.current_frame_mut()),
+        Instruction::Goto(target) => GOTO(target),
+        Instruction::Ireturn => IRETURN(thread),
+        Instruction::Lreturn => LRETURN(thread),
+        Instruction::Freturn => FRETURN(thread),
+        Instruction::Dreturn => DRETURN(thread),
+        Instruction::Areturn => ARETURN(thread),
+        Instruction::Return => RETURN(thread),
+        Instruction::Invokevirtual(index) => {
+            INVOKEVIRTUAL(index, decoded_code.call_cache(pc), next_pc, thread)
+        }
+        Instruction::Invokespecial(index) => INVOKESPECIAL(index, next_pc, thread),
+        Instruction::Invokestatic(index) => INVOKESTATIC(index, next_pc, thread),
+        Instruction::Invokeinterface(index) => {
+            INVOKEINTERFACE(index, decoded_code.call_cache(pc), next_pc, thread)
+        }
+        Instruction::Getfield(index) => {
+            GETFIELD(index, decoded_code.field_cache(pc), thread);
+            None
+        }
+        Instruction::Putfield(index) => {
+            PUTFIELD(index, decoded_code.field_cache(pc), thread);
+            None
+        }
+        Instruction::New(index) => NEW(index, pc, thread),
+        Instruction::Ifnull(target) => IFNULL(target, thread.current_frame_mut()),
+        Instruction::Ifnonnull(target) => IFNONNULL(target, thread.current_frame_mut()),
+        instruction => {
+            step(instruction, thread);
+            None
+        }
+    };
+    #[cfg(feature = "jit")]
+    if instruction.branch_target().is_some() && jump.is_some_and(|x| x <= pc) {
+        crate::jit::count_backedge(thread);
+    }
+    jump.unwrap_or(next_pc)
 }
 
-pub fn execute(pc: usize, thread: Thread) -> (ExecuteResult, CodeReader) {
-    let (frame, thread) = thread.pop_frame();
-    let code = frame.method.clone().code.clone();
-    let code_reader = CodeReader::new(code).set_pc(pc);
-    let (opcode, after_opcode) = code_reader.read_u8();
+/// Runs an instruction that always continues at the next one.
+fn step(instruction: Instruction, thread: &mut Thread) {
+    match instruction {
+        Instruction::Aaload => AALOAD(thread),
+        Instruction::Baload => BALOAD(thread),
+        Instruction::Aastore => AASTORE(thread),
+        Instruction::Arraylength => ARRAYLENGTH(thread),
+        Instruction::Athrow => ATHROW(thread),
+        Instruction::Checkcast(index) => CHECKCAST(index, thread),
+        Instruction::InstanceOf(index) => INSTANCE_OF(index, thread),
+        Instruction::Monitorenter => MONITORENTER(thread),
+        Instruction::Monitorexit => MONITOREXIT(thread),
+        instruction => step_in_frame(instruction, thread.current_frame_mut()),
+    }
+}
 
-    let instruction: fn(CodeReader, Thread) -> (ExecuteResult, CodeReader) = match opcode {
-        0x00 => NOP,
-        0x02 => ICONST_M1,
-        0x03 => ICONST_0,
-        0x04 => ICONST_1,
-        0x05 => ICONST_2,
-        0x06 => ICONST_3,
-        0x07 => ICONST_4,
-        0x08 => ICONST_5,
-        0x09 => LCONST_0,
-        0x0A => LCONST_1,
-        0x0B => FCONST_0,
-        0x0C => FCONST_1,
-        0x0D => FCONST_2,
-        0x0E => DCONST_0,
-        0x0F => DCONST_1,
-        0x10 => BIPUSH,
-        0x12 => LDC,
-        0x14 => LDC2_W,
-        0x1B => ILOAD_1,
-        0x1C => ILOAD_2,
-        0x3C => ISTORE_1,
-        0x3D => ISTORE_2,
-        0x60 => IADD,
-        0x68 => IMUL,
-        0x69 => LMUL,
-        0x6A => FMUL,
-        0x6B => DMUL,
-        0x74 => INEG,
-        0x75 => LNEG,
-        0x76 => FNEG,
-        0x77 => DNEG,
-        0x7E => IAND,
-        0x7F => LAND,
-        0x84 => IINC,
-        0x94 => LCMP,
-        0x95 => FCMPL,
-        0x96 => FCMPG,
-        0x97 => DCMPL,
-        0x98 => DCMPG,
-        0x99 => IFEQ,
-        0x9A => IFNE,
-        0x9B => IFLT,
-        0x9C => IFGE,
-        0x9D => IFGT,
-        0x9E => IFLE,
-        0x9F => IF_ICMPEQ,
-        0xA0 => IF_ICMPNE,
-        0xA1 => IF_ICMPLT,
-        0xA2 => IF_ICMPGE,
-        0xA3 => IF_ICMPGT,
-        0xA4 => IF_ICMPLE,
-        0xA7 => GOTO,
-        _ => {
+/// Runs an instruction that only touches the current frame.
+fn step_in_frame(instruction: Instruction, frame: &mut Frame) {
+    match instruction {
+        Instruction::Nop => NOP(frame),
+        Instruction::AconstNull => ACONST_NULL(frame),
+        Instruction::IconstM1 => ICONST_M1(frame),
+        Instruction::Iconst0 => ICONST_0(frame),
+        Instruction::Iconst1 => ICONST_1(frame),
+        Instruction::Iconst2 => ICONST_2(frame),
+        Instruction::Iconst3 => ICONST_3(frame),
+        Instruction::Iconst4 => ICONST_4(frame),
+        Instruction::Iconst5 => ICONST_5(frame),
+        Instruction::Lconst0 => LCONST_0(frame),
+        Instruction::Lconst1 => LCONST_1(frame),
+        Instruction::Fconst0 => FCONST_0(frame),
+        Instruction::Fconst1 => FCONST_1(frame),
+        Instruction::Fconst2 => FCONST_2(frame),
+        Instruction::Dconst0 => DCONST_0(frame),
+        Instruction::Dconst1 => DCONST_1(frame),
+        Instruction::Bipush(val) => BIPUSH(val, frame),
+        Instruction::Sipush(val) => SIPUSH(val, frame),
+        Instruction::Ldc2W(index) => LDC2_W(index, frame),
+        Instruction::Iload(index) => ILOAD(index, frame),
+        Instruction::Lload(index) => LLOAD(index, frame),
+        Instruction::Aload(index) => ALOAD(index, frame),
+        Instruction::Iload0 => ILOAD_0(frame),
+        Instruction::Iload1 => ILOAD_1(frame),
+        Instruction::Iload2 => ILOAD_2(frame),
+        Instruction::Iload3 => ILOAD_3(frame),
+        Instruction::Lload0 => LLOAD_0(frame),
+        Instruction::Lload1 => LLOAD_1(frame),
+        Instruction::Lload2 => LLOAD_2(frame),
+        Instruction::Lload3 => LLOAD_3(frame),
+        Instruction::Aload0 => ALOAD_0(frame),
+        Instruction::Aload1 => ALOAD_1(frame),
+        Instruction::Aload2 => ALOAD_2(frame),
+        Instruction::Aload3 => ALOAD_3(frame),
+        Instruction::Istore(index) => ISTORE(index, frame),
+        Instruction::Lstore(index) => LSTORE(index, frame),
+        Instruction::Astore(index) => ASTORE(index, frame),
+        Instruction::Istore0 => ISTORE_0(frame),
+        Instruction::Istore1 => ISTORE_1(frame),
+        Instruction::Istore2 => ISTORE_2(frame),
+        Instruction::Istore3 => ISTORE_3(frame),
+        Instruction::Lstore0 => LSTORE_0(frame),
+        Instruction::Lstore1 => LSTORE_1(frame),
+        Instruction::Lstore2 => LSTORE_2(frame),
+        Instruction::Lstore3 => LSTORE_3(frame),
+        Instruction::Astore0 => ASTORE_0(frame),
+        Instruction::Astore1 => ASTORE_1(frame),
+        Instruction::Astore2 => ASTORE_2(frame),
+        Instruction::Astore3 => ASTORE_3(frame),
+        Instruction::Pop => POP(frame),
+        Instruction::Pop2 => POP2(frame),
+        Instruction::Dup => DUP(frame),
+        Instruction::Iadd => IADD(frame),
+        Instruction::Imul => IMUL(frame),
+        Instruction::Lmul => LMUL(frame),
+        Instruction::Fmul => FMUL(frame),
+        Instruction::Dmul => DMUL(frame),
+        Instruction::Ineg => INEG(frame),
+        Instruction::Lneg => LNEG(frame),
+        Instruction::Fneg => FNEG(frame),
+        Instruction::Dneg => DNEG(frame),
+        Instruction::Iand => IAND(frame),
+        Instruction::Land => LAND(frame),
+        Instruction::Iinc(index, delta) => IINC(index, delta, frame),
+        Instruction::Lcmp => LCMP(frame),
+        Instruction::Fcmpl => FCMPL(frame),
+        Instruction::Fcmpg => FCMPG(frame),
+        Instruction::Dcmpl => DCMPL(frame),
+        Instruction::Dcmpg => DCMPG(frame),
+        Instruction::Getstatic(index) => GETSTATIC(index, frame),
+        Instruction::Putstatic(index) => PUTSTATIC(index, frame),
+        Instruction::Unsupported(opcode) => {
             println!("{:?}", frame);
             panic!("Unsupported opcode : {:X}", opcode)
         }
-    };
-
-    let thread = thread.push_frame(frame);
-    instruction(after_opcode, thread)
+        instruction => unreachable!("{:?} isn't run in frame", instruction),
+    }
 }
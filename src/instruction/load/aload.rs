@@ -0,0 +1,41 @@
+use crate::rtda::frame::Frame;
+
+fn _aload(frame: &mut Frame, index: usize) {
+    let val = frame.local_vars.get_ref(index);
+    frame.operand_stack.push_ref(val);
+}
+
+#[allow(non_snake_case)]
+pub fn ALOAD(index: usize, frame: &mut Frame) {
+    trace!("ALOAD");
+
+    _aload(frame, index);
+}
+
+#[allow(non_snake_case)]
+pub fn ALOAD_0(frame: &mut Frame) {
+    trace!("ALOAD_0");
+
+    _aload(frame, 0);
+}
+
+#[allow(non_snake_case)]
+pub fn ALOAD_1(frame: &mut Frame) {
+    trace!("ALOAD_1");
+
+    _aload(frame, 1);
+}
+
+#[allow(non_snake_case)]
+pub fn ALOAD_2(frame: &mut Frame) {
+    trace!("ALOAD_2");
+
+    _aload(frame, 2);
+}
+
+#[allow(non_snake_case)]
+pub fn ALOAD_3(frame: &mut Frame) {
+    trace!("ALOAD_3");
+
+    _aload(frame, 3);
+}
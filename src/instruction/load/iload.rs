@@ -1,54 +1,41 @@
-use crate::instruction::instruction::ExecuteResult;
 use crate::rtda::frame::Frame;
-use crate::rtda::thread::Thread;
-use crate::util::code_reader::CodeReader;
-
-fn _iload(frame: Frame, index: usize) -> Frame {
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-    let val = local_vars.get_int(index);
-    let operand_stack = operand_stack.push_int(val);
-    Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    }
+
+fn _iload(frame: &mut Frame, index: usize) {
+    let val = frame.local_vars.get_int(index);
+    frame.operand_stack.push_int(val);
+}
+
+#[allow(non_snake_case)]
+pub fn ILOAD(index: usize, frame: &mut Frame) {
+    trace!("ILOAD");
+
+    _iload(frame, index);
+}
+
+#[allow(non_snake_case)]
+pub fn ILOAD_0(frame: &mut Frame) {
+    trace!("ILOAD_0");
+
+    _iload(frame, 0);
 }
 
 #[allow(non_snake_case)]
-pub fn ILOAD_0(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("ILOAD_0");
-    let (frame, thread) = thread.pop_frame();
-
-    let frame = _iload(frame, 0);
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn ILOAD_1(frame: &mut Frame) {
+    trace!("ILOAD_1");
+
+    _iload(frame, 1);
 }
 
 #[allow(non_snake_case)]
-pub fn ILOAD_1(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("ILOAD_1");
-    let (frame, thread) = thread.pop_frame();
-
-    let frame = _iload(frame, 1);
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn ILOAD_2(frame: &mut Frame) {
+    trace!("ILOAD_2");
+
+    _iload(frame, 2);
 }
 
 #[allow(non_snake_case)]
-pub fn ILOAD_2(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("ILOAD_2");
-    let (frame, thread) = thread.pop_frame();
-
-    let frame = _iload(frame, 2);
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn ILOAD_3(frame: &mut Frame) {
+    trace!("ILOAD_3");
+
+    _iload(frame, 3);
 }
@@ -0,0 +1,41 @@
+use crate::rtda::frame::Frame;
+
+fn _lload(frame: &mut Frame, index: usize) {
+    let val = frame.local_vars.get_long(index);
+    frame.operand_stack.push_long(val);
+}
+
+#[allow(non_snake_case)]
+pub fn LLOAD(index: usize, frame: &mut Frame) {
+    trace!("LLOAD");
+
+    _lload(frame, index);
+}
+
+#[allow(non_snake_case)]
+pub fn LLOAD_0(frame: &mut Frame) {
+    trace!("LLOAD_0");
+
+    _lload(frame, 0);
+}
+
+#[allow(non_snake_case)]
+pub fn LLOAD_1(frame: &mut Frame) {
+    trace!("LLOAD_1");
+
+    _lload(frame, 1);
+}
+
+#[allow(non_snake_case)]
+pub fn LLOAD_2(frame: &mut Frame) {
+    trace!("LLOAD_2");
+
+    _lload(frame, 2);
+}
+
+#[allow(non_snake_case)]
+pub fn LLOAD_3(frame: &mut Frame) {
+    trace!("LLOAD_3");
+
+    _lload(frame, 3);
+}
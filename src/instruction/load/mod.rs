@@ -1 +1,4 @@
+pub mod aload;
 pub mod iload;
+pub mod lload;
+pub mod xaload;
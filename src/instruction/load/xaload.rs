@@ -0,0 +1,118 @@
+use crate::rtda::heap::object::ArrayData;
+use crate::rtda::thread::Thread;
+
+#[allow(non_snake_case)]
+pub fn AALOAD(thread: &mut Thread) {
+    trace!("AALOAD");
+    let (frame, heap) = thread.current_frame_and_heap();
+
+    let index = frame.operand_stack.pop_int();
+    let array_ref = frame.operand_stack.pop_ref();
+    let array_ref = array_ref.expect("java.lang.NullPointerException");
+    let heap = heap.borrow();
+    let refs = match heap.get(array_ref).array() {
+        ArrayData::Refs(refs) => refs,
+        _ => panic!("Not Refs"),
+    };
+    if index < 0 || index as usize >= refs.len() {
+        panic!("java.lang.ArrayIndexOutOfBoundsException: {}", index)
+    }
+    frame.operand_stack.push_ref(refs[index as usize]);
+}
+
+/// Loads a byte or boolean element, sign-extended to an int.
+#[allow(non_snake_case)]
+pub fn BALOAD(thread: &mut Thread) {
+    trace!("BALOAD");
+    let (frame, heap) = thread.current_frame_and_heap();
+
+    let index = frame.operand_stack.pop_int();
+    let array_ref = frame.operand_stack.pop_ref();
+    let array_ref = array_ref.expect("java.lang.NullPointerException");
+    let heap = heap.borrow();
+    let bytes = match heap.get(array_ref).array() {
+        ArrayData::Bytes(bytes) => bytes,
+        _ => panic!("Not Bytes"),
+    };
+    if index < 0 || index as usize >= bytes.len() {
+        panic!("java.lang.ArrayIndexOutOfBoundsException: {}", index)
+    }
+    frame.operand_stack.push_int(bytes[index as usize] as i32);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::instruction::load::xaload::*;
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::heap::object::Object;
+    use crate::rtda::thread::Thread;
+
+    // Loads element `index` of a MyObject[2] whose element 1 is set
+    fn aaload(index: i32) -> bool {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("MyObject");
+        let method = class
+            .lookup_method("main", "([Ljava/lang/String;)V")
+            .unwrap();
+        let mut thread = Thread::new();
+        let (array, object) = {
+            let mut heap = thread.heap.borrow_mut();
+            let object = heap.allocate(Object::new(Rc::clone(&class))).unwrap();
+            let mut array = Object::new_array(class_loader.load("[LMyObject;"), 2);
+            *array.array_mut() = ArrayData::Refs(vec![None, Some(object)]);
+            (heap.allocate(array).unwrap(), object)
+        };
+        let mut frame = Frame::new(class, method);
+        frame.operand_stack.push_ref(Some(array));
+        frame.operand_stack.push_int(index);
+        thread.push_frame(frame);
+        AALOAD(&mut thread);
+        thread.current_frame_mut().operand_stack.pop_ref() == Some(object)
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_AALOAD() {
+        assert!(!aaload(0));
+        assert!(aaload(1));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    #[should_panic(expected = "java.lang.ArrayIndexOutOfBoundsException: 2")]
+    fn test_AALOAD_out_of_bounds() {
+        aaload(2);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_BALOAD() {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("MyObject");
+        let method = class
+            .lookup_method("main", "([Ljava/lang/String;)V")
+            .unwrap();
+        let mut thread = Thread::new();
+        let mut array = Object::new_array(class_loader.load("[B"), 2);
+        *array.array_mut() = ArrayData::Bytes(vec![1, -1]);
+        let array = thread.heap.borrow_mut().allocate(array).unwrap();
+        let mut frame = Frame::new(class, method);
+        frame.operand_stack.push_ref(Some(array));
+        frame.operand_stack.push_int(1);
+        thread.push_frame(frame);
+        BALOAD(&mut thread);
+        assert_eq!(thread.current_frame_mut().operand_stack.pop_int(), -1);
+    }
+}
@@ -1,137 +1,59 @@
-use crate::instruction::instruction::ExecuteResult;
 use crate::rtda::frame::Frame;
-use crate::rtda::thread::Thread;
-use crate::util::code_reader::CodeReader;
 
 #[allow(non_snake_case)]
-pub fn IADD(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("IADD");
-    let (frame, thread) = thread.pop_frame();
+pub fn IADD(frame: &mut Frame) {
+    trace!("IADD");
 
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-
-    let (v2, operand_stack) = operand_stack.pop_int();
-    let (v1, operand_stack) = operand_stack.pop_int();
+    let v2 = frame.operand_stack.pop_int();
+    let v1 = frame.operand_stack.pop_int();
     let result = v1 + v2;
-    let operand_stack = operand_stack.push_int(result);
-
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+    frame.operand_stack.push_int(result);
 }
 
 #[allow(non_snake_case)]
-pub fn DADD(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("DADD");
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
+pub fn DADD(frame: &mut Frame) {
+    trace!("DADD");
 
-    let (v2, operand_stack) = operand_stack.pop_double();
-    let (v1, operand_stack) = operand_stack.pop_double();
+    let v2 = frame.operand_stack.pop_double();
+    let v1 = frame.operand_stack.pop_double();
     let result = v1 + v2;
-    let operand_stack = operand_stack.push_double(result);
-
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+    frame.operand_stack.push_double(result);
 }
 
 #[allow(non_snake_case)]
-pub fn LADD(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("LADD");
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
+pub fn LADD(frame: &mut Frame) {
+    trace!("LADD");
 
-    let (v2, operand_stack) = operand_stack.pop_long();
-    let (v1, operand_stack) = operand_stack.pop_long();
+    let v2 = frame.operand_stack.pop_long();
+    let v1 = frame.operand_stack.pop_long();
     let result = v1 + v2;
-    let operand_stack = operand_stack.push_long(result);
-
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+    frame.operand_stack.push_long(result);
 }
 
 #[allow(non_snake_case)]
-pub fn FADD(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("FADD");
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
+pub fn FADD(frame: &mut Frame) {
+    trace!("FADD");
 
-    let (v2, operand_stack) = operand_stack.pop_float();
-    let (v1, operand_stack) = operand_stack.pop_float();
+    let v2 = frame.operand_stack.pop_float();
+    let v1 = frame.operand_stack.pop_float();
     let result = v1 + v2;
-    let operand_stack = operand_stack.push_float(result);
-
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+    frame.operand_stack.push_float(result);
 }
 
 #[cfg(test)]
 mod tests {
-    use std::rc::Rc;
+    use std::cell::RefCell;
+    use std::rc::{Rc, Weak};
 
     use vec_map::VecMap;
 
     use crate::classfile::constant_pool::ConstantPool;
     use crate::classfile::member_info::MemberInfo;
-    use crate::instruction::instruction::ExecuteResult;
-    use crate::instruction::math::add::*;
     use crate::instruction::math::add::*;
     use crate::rtda::frame::Frame;
     use crate::rtda::heap::class::Class;
     use crate::rtda::heap::method::Method;
-    use crate::rtda::thread::Thread;
     use crate::rtda::vars::Vars;
-    use crate::util::code_reader::CodeReader;
 
     #[test]
     #[allow(non_snake_case)]
@@ -152,33 +74,24 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
+        let mut frame = Frame::new(class, method);
 
-        let operand_stack = operand_stack.push_int(2);
-        let operand_stack = operand_stack.push_int(3);
+        frame.operand_stack.push_int(2);
+        frame.operand_stack.push_int(3);
 
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            IADD(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_int();
+        IADD(&mut frame);
+        let val = frame.operand_stack.pop_int();
         assert_eq!(val, 5);
     }
 
@@ -201,33 +114,24 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
+        let mut frame = Frame::new(class, method);
 
-        let operand_stack = operand_stack.push_double(2.71828182845f64);
-        let operand_stack = operand_stack.push_double(3.1415926535897926f64);
+        frame.operand_stack.push_double(2.71828182845f64);
+        frame.operand_stack.push_double(3.1415926535897926f64);
 
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            DADD(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_double();
+        DADD(&mut frame);
+        let val = frame.operand_stack.pop_double();
         assert_eq!(val, 5.8598744820397926);
     }
 
@@ -250,33 +154,24 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
+        let mut frame = Frame::new(class, method);
 
-        let operand_stack = operand_stack.push_float(3.1415926);
-        let operand_stack = operand_stack.push_float(3.1415926);
+        frame.operand_stack.push_float(3.1415926);
+        frame.operand_stack.push_float(3.1415926);
 
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            FADD(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_float();
+        FADD(&mut frame);
+        let val = frame.operand_stack.pop_float();
         assert_eq!(val, 6.2831852);
     }
 
@@ -299,33 +194,24 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
+        let mut frame = Frame::new(class, method);
 
-        let operand_stack = operand_stack.push_long(12345678969);
-        let operand_stack = operand_stack.push_long(2997924580);
+        frame.operand_stack.push_long(12345678969);
+        frame.operand_stack.push_long(2997924580);
 
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            DADD(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_long();
+        DADD(&mut frame);
+        let val = frame.operand_stack.pop_long();
         assert_eq!(val, 15343603549);
     }
 }
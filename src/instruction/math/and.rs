@@ -1,79 +1,35 @@
-use crate::instruction::instruction::ExecuteResult;
 use crate::rtda::frame::Frame;
-use crate::rtda::thread::Thread;
-use crate::util::code_reader::CodeReader;
 
 #[allow(non_snake_case)]
-pub fn IAND(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-
-    let (v2, operand_stack) = operand_stack.pop_int();
-    let (v1, operand_stack) = operand_stack.pop_int();
+pub fn IAND(frame: &mut Frame) {
+    let v2 = frame.operand_stack.pop_int();
+    let v1 = frame.operand_stack.pop_int();
     let result = v1 & v2;
-    let operand_stack = operand_stack.push_int(result);
-
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+    frame.operand_stack.push_int(result);
 }
 
 #[allow(non_snake_case)]
-pub fn LAND(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-
-    let (v2, operand_stack) = operand_stack.pop_long();
-    let (v1, operand_stack) = operand_stack.pop_long();
+pub fn LAND(frame: &mut Frame) {
+    let v2 = frame.operand_stack.pop_long();
+    let v1 = frame.operand_stack.pop_long();
     let result = v1 & v2;
-    let operand_stack = operand_stack.push_long(result);
-
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+    frame.operand_stack.push_long(result);
 }
 
 #[cfg(test)]
 mod tests {
-    use std::rc::Rc;
+    use std::cell::RefCell;
+    use std::rc::{Rc, Weak};
 
     use vec_map::VecMap;
 
     use crate::classfile::constant_pool::ConstantPool;
     use crate::classfile::member_info::MemberInfo;
-    use crate::instruction::instruction::ExecuteResult;
-    use crate::instruction::math::and::*;
     use crate::instruction::math::and::*;
     use crate::rtda::frame::Frame;
     use crate::rtda::heap::class::Class;
     use crate::rtda::heap::method::Method;
-    use crate::rtda::thread::Thread;
     use crate::rtda::vars::Vars;
-    use crate::util::code_reader::CodeReader;
 
     #[test]
     #[allow(non_snake_case)]
@@ -94,33 +50,24 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
+        let mut frame = Frame::new(class, method);
 
-        let operand_stack = operand_stack.push_int(350);
-        let operand_stack = operand_stack.push_int(678);
+        frame.operand_stack.push_int(350);
+        frame.operand_stack.push_int(678);
 
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            IAND(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_int();
+        IAND(&mut frame);
+        let val = frame.operand_stack.pop_int();
         assert_eq!(val, 6);
     }
 
@@ -143,33 +90,24 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
+        let mut frame = Frame::new(class, method);
 
-        let operand_stack = operand_stack.push_long(12345678969);
-        let operand_stack = operand_stack.push_long(2997924580);
+        frame.operand_stack.push_long(12345678969);
+        frame.operand_stack.push_long(2997924580);
 
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            LAND(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_long();
+        LAND(&mut frame);
+        let val = frame.operand_stack.pop_long();
         assert_eq!(val, 2458914912);
     }
 }
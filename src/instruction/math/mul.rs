@@ -1,138 +1,59 @@
-use crate::instruction::instruction::ExecuteResult;
 use crate::rtda::frame::Frame;
-use crate::rtda::thread::Thread;
-use crate::util::code_reader::CodeReader;
 
 #[allow(non_snake_case)]
-pub fn DMUL(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("DMUL");
+pub fn DMUL(frame: &mut Frame) {
+    trace!("DMUL");
 
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-
-    let (v2, operand_stack) = operand_stack.pop_double();
-    let (v1, operand_stack) = operand_stack.pop_double();
+    let v2 = frame.operand_stack.pop_double();
+    let v1 = frame.operand_stack.pop_double();
     let result = v1 * v2;
-    let operand_stack = operand_stack.push_double(result);
-
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+    frame.operand_stack.push_double(result);
 }
 
 #[allow(non_snake_case)]
-pub fn FMUL(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("FMUL");
-    let (frame, thread) = thread.pop_frame();
+pub fn FMUL(frame: &mut Frame) {
+    trace!("FMUL");
 
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-
-    let (v2, operand_stack) = operand_stack.pop_float();
-    let (v1, operand_stack) = operand_stack.pop_float();
+    let v2 = frame.operand_stack.pop_float();
+    let v1 = frame.operand_stack.pop_float();
     let result = v1 * v2;
-    let operand_stack = operand_stack.push_float(result);
-
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+    frame.operand_stack.push_float(result);
 }
 
 #[allow(non_snake_case)]
-pub fn IMUL(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("IMUL");
-    let (frame, thread) = thread.pop_frame();
+pub fn IMUL(frame: &mut Frame) {
+    trace!("IMUL");
 
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-
-    let (v2, operand_stack) = operand_stack.pop_int();
-    let (v1, operand_stack) = operand_stack.pop_int();
+    let v2 = frame.operand_stack.pop_int();
+    let v1 = frame.operand_stack.pop_int();
     let result = v1 * v2;
-    let operand_stack = operand_stack.push_int(result);
-
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+    frame.operand_stack.push_int(result);
 }
 
 #[allow(non_snake_case)]
-pub fn LMUL(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("LMUL");
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
+pub fn LMUL(frame: &mut Frame) {
+    trace!("LMUL");
 
-    let (v2, operand_stack) = operand_stack.pop_long();
-    let (v1, operand_stack) = operand_stack.pop_long();
+    let v2 = frame.operand_stack.pop_long();
+    let v1 = frame.operand_stack.pop_long();
     let result = v1 * v2;
-    let operand_stack = operand_stack.push_long(result);
-
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+    frame.operand_stack.push_long(result);
 }
 
 #[cfg(test)]
 mod test {
-    use std::rc::Rc;
+    use std::cell::RefCell;
+    use std::rc::{Rc, Weak};
 
     use vec_map::VecMap;
 
     use crate::classfile::constant_pool::ConstantPool;
     use crate::classfile::member_info::MemberInfo;
-    use crate::instruction::instruction::ExecuteResult;
-    use crate::instruction::math::mul::*;
     use crate::instruction::math::mul::*;
     use crate::rtda::frame::Frame;
     use crate::rtda::heap::class::Class;
     use crate::rtda::heap::method::Method;
-    use crate::rtda::thread::Thread;
     use crate::rtda::vars::Vars;
-    use crate::util::code_reader::CodeReader;
 
     #[test]
     #[allow(non_snake_case)]
@@ -153,33 +74,24 @@ mod test {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
+        let mut frame = Frame::new(class, method);
 
-        let operand_stack = operand_stack.push_double(2.71828182845f64);
-        let operand_stack = operand_stack.push_double(3.1415926535897926f64);
+        frame.operand_stack.push_double(2.71828182845f64);
+        frame.operand_stack.push_double(3.1415926535897926f64);
 
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            DMUL(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_double();
+        DMUL(&mut frame);
+        let val = frame.operand_stack.pop_double();
         assert_eq!(val, 8.53973422264514888498427947f64);
     }
 
@@ -202,33 +114,24 @@ mod test {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
+        let mut frame = Frame::new(class, method);
 
-        let operand_stack = operand_stack.push_float(2.71828182845f32);
-        let operand_stack = operand_stack.push_float(3.1415926535897926f32);
+        frame.operand_stack.push_float(2.71828182845f32);
+        frame.operand_stack.push_float(3.1415926535897926f32);
 
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            FMUL(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_float();
+        FMUL(&mut frame);
+        let val = frame.operand_stack.pop_float();
         assert_eq!(val, 8.53973422264514888498427947f32);
     }
 
@@ -251,33 +154,24 @@ mod test {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
+        let mut frame = Frame::new(class, method);
 
-        let operand_stack = operand_stack.push_int(2);
-        let operand_stack = operand_stack.push_int(3);
+        frame.operand_stack.push_int(2);
+        frame.operand_stack.push_int(3);
 
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            IMUL(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_int();
+        IMUL(&mut frame);
+        let val = frame.operand_stack.pop_int();
         assert_eq!(val, 6);
     }
 
@@ -300,33 +194,24 @@ mod test {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
+        let mut frame = Frame::new(class, method);
 
-        let operand_stack = operand_stack.push_long(1234567890);
-        let operand_stack = operand_stack.push_long(2997924580);
+        frame.operand_stack.push_long(1234567890);
+        frame.operand_stack.push_long(2997924580);
 
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            LMUL(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_long();
+        LMUL(&mut frame);
+        let val = frame.operand_stack.pop_long();
         assert_eq!(val, 3701141423109736200);
     }
 }
@@ -1,126 +1,48 @@
-use crate::instruction::instruction::ExecuteResult;
 use crate::rtda::frame::Frame;
-use crate::rtda::thread::Thread;
-use crate::util::code_reader::CodeReader;
 
 #[allow(non_snake_case)]
-pub fn DNEG(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-
-    let (v, operand_stack) = operand_stack.pop_double();
-    let operand_stack = operand_stack.push_double(-v);
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn DNEG(frame: &mut Frame) {
+    let v = frame.operand_stack.pop_double();
+    frame.operand_stack.push_double(-v);
 }
 
 #[allow(non_snake_case)]
-pub fn FNEG(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-
-    let (v, operand_stack) = operand_stack.pop_float();
-    let operand_stack = operand_stack.push_float(-v);
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn FNEG(frame: &mut Frame) {
+    let v = frame.operand_stack.pop_float();
+    frame.operand_stack.push_float(-v);
 }
 
 #[allow(non_snake_case)]
-pub fn INEG(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-
-    let (v, operand_stack) = operand_stack.pop_int();
-    let operand_stack = operand_stack.push_int(-v);
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn INEG(frame: &mut Frame) {
+    let v = frame.operand_stack.pop_int();
+    frame.operand_stack.push_int(-v);
 }
 
 #[allow(non_snake_case)]
-pub fn LNEG(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    let (frame, thread) = thread.pop_frame();
-
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-
-    let (v, operand_stack) = operand_stack.pop_long();
-    let operand_stack = operand_stack.push_long(-v);
-    let frame = Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    };
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn LNEG(frame: &mut Frame) {
+    let v = frame.operand_stack.pop_long();
+    frame.operand_stack.push_long(-v);
 }
 
 #[cfg(test)]
 mod tests {
+    use std::cell::RefCell;
     use std::f32;
     use std::f64;
-    use std::rc::Rc;
+    use std::rc::{Rc, Weak};
 
     use vec_map::VecMap;
 
     use crate::classfile::constant_pool::ConstantPool;
     use crate::classfile::member_info::MemberInfo;
-    use crate::instruction::instruction::ExecuteResult;
-    use crate::instruction::math::neg::*;
-    use crate::instruction::math::neg::*;
     use crate::instruction::math::neg::FNEG;
     use crate::instruction::math::neg::INEG;
     use crate::instruction::math::neg::LNEG;
+    use crate::instruction::math::neg::*;
     use crate::rtda::frame::Frame;
     use crate::rtda::heap::class::Class;
     use crate::rtda::heap::method::Method;
-    use crate::rtda::thread::Thread;
     use crate::rtda::vars::Vars;
-    use crate::util::code_reader::CodeReader;
 
     #[test]
     #[allow(non_snake_case)]
@@ -141,32 +63,23 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
+        let mut frame = Frame::new(class, method);
 
-        let operand_stack = operand_stack.push_double(2f64);
+        frame.operand_stack.push_double(2f64);
 
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            DNEG(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_double();
+        DNEG(&mut frame);
+        let val = frame.operand_stack.pop_double();
         assert_eq!(val, -2f64);
     }
 
@@ -189,32 +102,23 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
+        let mut frame = Frame::new(class, method);
 
-        let operand_stack = operand_stack.push_double(-0f64);
+        frame.operand_stack.push_double(-0f64);
 
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            DNEG(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_double();
+        DNEG(&mut frame);
+        let val = frame.operand_stack.pop_double();
         assert_eq!(val, 0f64);
     }
 
@@ -237,32 +141,23 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
+        let mut frame = Frame::new(class, method);
 
-        let operand_stack = operand_stack.push_double(f64::INFINITY);
+        frame.operand_stack.push_double(f64::INFINITY);
 
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            DNEG(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_double();
+        DNEG(&mut frame);
+        let val = frame.operand_stack.pop_double();
         assert_eq!(val, f64::NEG_INFINITY);
     }
 
@@ -285,32 +180,23 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
+        let mut frame = Frame::new(class, method);
 
-        let operand_stack = operand_stack.push_float(-100.7678f32);
+        frame.operand_stack.push_float(-100.7678f32);
 
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            FNEG(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_float();
+        FNEG(&mut frame);
+        let val = frame.operand_stack.pop_float();
         assert_eq!(val, 100.7678f32);
     }
 
@@ -333,32 +219,23 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
+        let mut frame = Frame::new(class, method);
 
-        let operand_stack = operand_stack.push_float(f32::MAX);
+        frame.operand_stack.push_float(f32::MAX);
 
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            FNEG(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_float();
+        FNEG(&mut frame);
+        let val = frame.operand_stack.pop_float();
         assert_eq!(val, f32::MIN);
     }
     #[test]
@@ -380,32 +257,23 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
+        let mut frame = Frame::new(class, method);
 
-        let operand_stack = operand_stack.push_int(234556);
+        frame.operand_stack.push_int(234556);
 
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            INEG(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_int();
+        INEG(&mut frame);
+        let val = frame.operand_stack.pop_int();
         assert_eq!(val, -234556);
     }
 
@@ -428,32 +296,23 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        let Frame {
-            operand_stack,
-            local_vars,
-            method,
-            class,
-        } = frame;
+        let mut frame = Frame::new(class, method);
 
-        let operand_stack = operand_stack.push_long(-54875845748435i64);
+        frame.operand_stack.push_long(-54875845748435i64);
 
-        let frame = Frame {
-            class,
-            operand_stack,
-            local_vars,
-            method,
-        };
-        let thread = Thread::new().push_frame(frame);
-        let (ExecuteResult { thread, offset: _ }, _) =
-            LNEG(CodeReader::new(Rc::new(vec![])), thread);
-        let (frame, _) = thread.pop_frame();
-        let (val, _) = frame.operand_stack.pop_long();
+        LNEG(&mut frame);
+        let val = frame.operand_stack.pop_long();
         assert_eq!(val, 54875845748435i64);
     }
 }
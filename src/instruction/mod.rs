@@ -1,7 +1,12 @@
 mod comparison;
 mod constant;
 mod control;
+pub mod decoder;
+pub mod inline_cache;
 pub mod instruction;
 mod load;
 mod math;
+pub(crate) mod reference;
+pub mod register;
+mod stack;
 mod store;
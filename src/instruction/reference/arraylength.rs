@@ -0,0 +1,22 @@
+use crate::rtda::heap::object::ArrayData;
+use crate::rtda::thread::Thread;
+
+#[allow(non_snake_case)]
+pub fn ARRAYLENGTH(thread: &mut Thread) {
+    trace!("ARRAYLENGTH");
+    let (frame, heap) = thread.current_frame_and_heap();
+
+    let array_ref = frame.operand_stack.pop_ref();
+    let array_ref = array_ref.expect("java.lang.NullPointerException");
+    let len = match heap.borrow().get(array_ref).array() {
+        ArrayData::Bytes(vec) => vec.len(),
+        ArrayData::Shorts(vec) => vec.len(),
+        ArrayData::Chars(vec) => vec.len(),
+        ArrayData::Ints(vec) => vec.len(),
+        ArrayData::Longs(vec) => vec.len(),
+        ArrayData::Floats(vec) => vec.len(),
+        ArrayData::Doubles(vec) => vec.len(),
+        ArrayData::Refs(vec) => vec.len(),
+    };
+    frame.operand_stack.push_int(len as i32);
+}
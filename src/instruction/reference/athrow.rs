@@ -0,0 +1,91 @@
+use std::panic;
+use std::rc::Rc;
+
+use crate::rtda::heap::object::ObjectRef;
+use crate::rtda::thread::Thread;
+
+/// What `athrow` panics with, caught by the interpreter which looks for a handler of the
+/// exception like it does for the exceptions the VM raises.
+pub struct Thrown(pub ObjectRef);
+
+#[allow(non_snake_case)]
+pub fn ATHROW(thread: &mut Thread) {
+    trace!("ATHROW");
+    let exception = thread
+        .current_frame_mut()
+        .operand_stack
+        .pop_ref()
+        .expect("java.lang.NullPointerException");
+    panic::panic_any(Thrown(exception))
+}
+
+/// Unwinds the stack of `thread` down to the handler of `exception`, thrown at `pc` of the
+/// current frame, and returns the pc of the handler, whose frame then only has the
+/// exception on its operand stack. Without a handler the stack is left empty.
+pub fn throw(thread: &mut Thread, pc: usize, exception: ObjectRef) -> Option<usize> {
+    let class = Rc::clone(&thread.heap.borrow().get(exception).class);
+    let mut pc = pc;
+    loop {
+        let frame = thread.current_frame_mut();
+        if let Some(handler) = frame.method.find_exception_handler(&class, pc) {
+            frame.operand_stack.clear();
+            frame.operand_stack.push_ref(Some(exception));
+            return Some(handler);
+        }
+        let frame = thread.pop_frame();
+        if frame.method.is_synchronized() {
+            thread.exit_synchronized();
+        }
+        if thread.is_stack_empty() {
+            return None;
+        }
+        // The caller resumes right after the invoke instruction it's in
+        pc = thread.current_frame().next_pc - 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::instruction::reference::athrow::*;
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::heap::object::Object;
+
+    // thrown() calls call() at pc 0, which calls fail() at pc 0, both caught at pc 6
+    #[test]
+    fn test_throw() {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("exceptions/Handlers");
+        let mut thread = Thread::new();
+        for name in ["thrown", "call"] {
+            let method = class.lookup_method(name, "()V").unwrap();
+            let mut frame = Frame::new(Rc::clone(&class), method);
+            frame.next_pc = 3;
+            frame.operand_stack.push_int(1);
+            thread.push_frame(frame);
+        }
+        let method = class.lookup_method("fail", "()V").unwrap();
+        thread.push_frame(Frame::new(Rc::clone(&class), method));
+
+        let caught = Object::new(class_loader.load("java/lang/RuntimeException"));
+        let caught = thread.heap.borrow_mut().allocate(caught).unwrap();
+        assert_eq!(throw(&mut thread, 7, caught), Some(6));
+        let frame = thread.current_frame_mut();
+        assert_eq!(frame.method.name(), "thrown");
+        assert_eq!(frame.operand_stack.pop_ref(), Some(caught));
+        assert_eq!(frame.operand_stack.refs().count(), 0);
+
+        // Exception isn't a RuntimeException
+        let uncaught = Object::new(class_loader.load("java/lang/Exception"));
+        let uncaught = thread.heap.borrow_mut().allocate(uncaught).unwrap();
+        assert_eq!(throw(&mut thread, 0, uncaught), None);
+        assert!(thread.is_stack_empty());
+    }
+}
@@ -0,0 +1,94 @@
+use crate::rtda::heap::class_ref::ClassRef;
+use crate::rtda::thread::Thread;
+
+#[allow(non_snake_case)]
+pub fn CHECKCAST(index: usize, thread: &mut Thread) {
+    trace!("CHECKCAST");
+    let (frame, heap) = thread.current_frame_and_heap();
+
+    let reference = frame.operand_stack.pop_ref();
+    if let Some(object) = reference {
+        let target = ClassRef::new(&frame.class.constant_pool, index).resolved_class(&frame.class);
+        let heap = heap.borrow();
+        let object = heap.get(object);
+        if !object.is_instance_of(&target) {
+            panic!(
+                "java.lang.ClassCastException: {} cannot be cast to {}",
+                object.class.java_name(),
+                target.java_name()
+            )
+        }
+    }
+    frame.operand_stack.push_ref(reference);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::instruction::reference::checkcast::*;
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::heap::object::Object;
+    use crate::rtda::thread::Thread;
+
+    // MyObject's constant pool: #3 = Class MyObject, #9 = Class java/lang/Object
+    fn checkcast(object_class_name: Option<&str>, index: usize) {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("MyObject");
+        let mut thread = Thread::new();
+        let object = object_class_name.map(|x| {
+            let object_class = class_loader.load(x);
+            let object = if object_class.is_array() {
+                Object::new_array(object_class, 0)
+            } else {
+                Object::new(object_class)
+            };
+            thread.heap.borrow_mut().allocate(object).unwrap()
+        });
+        let mut frame = Frame::new(Rc::clone(&class), class.main_method());
+        frame.operand_stack.push_ref(object);
+        thread.push_frame(frame);
+        CHECKCAST(index, &mut thread);
+        let mut frame = thread.pop_frame();
+        let val = frame.operand_stack.pop_ref();
+        assert_eq!(val, object);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_CHECKCAST_success() {
+        checkcast(Some("MyObject"), 3);
+        checkcast(Some("MyObject"), 9);
+        checkcast(Some("[[I"), 9);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_CHECKCAST_null() {
+        checkcast(None, 3);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    #[should_panic(
+        expected = "java.lang.ClassCastException: java.lang.Object cannot be cast to MyObject"
+    )]
+    fn test_CHECKCAST_fail() {
+        checkcast(Some("java/lang/Object"), 3);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    #[should_panic(
+        expected = "java.lang.ClassCastException: [LMyObject; cannot be cast to MyObject"
+    )]
+    fn test_CHECKCAST_array_fail() {
+        checkcast(Some("[LMyObject;"), 3);
+    }
+}
@@ -0,0 +1,27 @@
+use crate::instruction::inline_cache::{FieldCache, FieldSlots};
+use crate::rtda::thread::Thread;
+
+/// The field is resolved on the first execution, later ones take its slots from the
+/// inline cache.
+#[allow(non_snake_case)]
+pub fn GETFIELD(index: usize, cache: &FieldCache, thread: &mut Thread) {
+    trace!("GETFIELD");
+    let (frame, heap) = thread.current_frame_and_heap();
+
+    let field = cache.lookup().unwrap_or_else(|| {
+        let field = frame.class.resolved_field_ref(index);
+        if field.is_static() {
+            panic!("java.lang.IncompatibleClassChangeError")
+        }
+        let field = FieldSlots::new(&field);
+        cache.set(field);
+        field
+    });
+    let reference = frame.operand_stack.pop_ref();
+    let reference = reference.expect("java.lang.NullPointerException");
+    let heap = heap.borrow();
+    let slots = &heap.get(reference).fields()[field.slot_id..field.slot_id + field.slot_count];
+    for val in slots {
+        frame.operand_stack.push_slot(*val);
+    }
+}
@@ -0,0 +1,16 @@
+use crate::rtda::frame::Frame;
+
+#[allow(non_snake_case)]
+pub fn GETSTATIC(index: usize, frame: &mut Frame) {
+    trace!("GETSTATIC");
+
+    let field = frame.class.resolved_field_ref(index);
+    if !field.is_static() {
+        panic!("java.lang.IncompatibleClassChangeError")
+    }
+    let field_class = field.class();
+    let static_vars = field_class.static_vars.borrow();
+    for index in field.slot_id..field.slot_id + field.slot_count() {
+        frame.operand_stack.push_slot(static_vars.get_slot(index));
+    }
+}
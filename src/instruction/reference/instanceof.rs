@@ -0,0 +1,90 @@
+use std::rc::Rc;
+
+use crate::rtda::heap::class_ref::ClassRef;
+use crate::rtda::thread::Thread;
+
+#[allow(non_snake_case)]
+pub fn INSTANCE_OF(index: usize, thread: &mut Thread) {
+    trace!("INSTANCE_OF");
+    let (frame, heap) = thread.current_frame_and_heap();
+
+    let reference = frame.operand_stack.pop_ref();
+    let val = match reference {
+        Some(object) => {
+            let target =
+                ClassRef::new(&frame.class.constant_pool, index).resolved_class(&frame.class);
+            let object_class = Rc::clone(&heap.borrow().get(object).class);
+            if target.is_assignable_from(&object_class) {
+                1
+            } else {
+                0
+            }
+        }
+        None => 0,
+    };
+    frame.operand_stack.push_int(val);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::instruction::reference::instanceof::*;
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::heap::object::Object;
+    use crate::rtda::thread::Thread;
+
+    // MyObject's constant pool: #3 = Class MyObject, #9 = Class java/lang/Object
+    fn instance_of(object_class_name: Option<&str>, index: usize) -> i32 {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("MyObject");
+        let mut thread = Thread::new();
+        let object = object_class_name.map(|x| {
+            let object_class = class_loader.load(x);
+            let object = if object_class.is_array() {
+                Object::new_array(object_class, 0)
+            } else {
+                Object::new(object_class)
+            };
+            thread.heap.borrow_mut().allocate(object).unwrap()
+        });
+        let mut frame = Frame::new(Rc::clone(&class), class.main_method());
+        frame.operand_stack.push_ref(object);
+        thread.push_frame(frame);
+        INSTANCE_OF(index, &mut thread);
+        thread.pop_frame().operand_stack.pop_int()
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_INSTANCE_OF_same_class() {
+        assert_eq!(instance_of(Some("MyObject"), 3), 1);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_INSTANCE_OF_super_class() {
+        assert_eq!(instance_of(Some("MyObject"), 9), 1);
+        assert_eq!(instance_of(Some("java/lang/Object"), 3), 0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_INSTANCE_OF_array() {
+        assert_eq!(instance_of(Some("[LMyObject;"), 9), 1);
+        assert_eq!(instance_of(Some("[I"), 9), 1);
+        assert_eq!(instance_of(Some("[LMyObject;"), 3), 0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_INSTANCE_OF_null() {
+        assert_eq!(instance_of(None, 3), 0);
+    }
+}
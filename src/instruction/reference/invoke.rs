@@ -0,0 +1,60 @@
+use std::rc::Rc;
+
+use crate::native::find_native_method;
+use crate::rtda::frame::Frame;
+use crate::rtda::heap::method::Method;
+use crate::rtda::monitor::Lock;
+use crate::rtda::slot::Slot;
+use crate::rtda::thread::Thread;
+
+/// Pushes a new frame for `method` with `args` as its first local variables,
+/// and returns the pc to continue at, the start of its code.
+///
+/// Native methods run right away, their result is pushed on the caller's operand stack
+/// and the returned pc is the caller's `next_pc`.
+pub fn invoke_method(thread: &mut Thread, method: Rc<Method>, args: Vec<Slot>) -> usize {
+    if method.is_native() {
+        return invoke_native_method(thread, method, args);
+    }
+    let lock = if !method.is_synchronized() {
+        None
+    } else if method.is_static() {
+        Some(Lock::Class(method.class().name.clone()))
+    } else {
+        match args[0] {
+            Slot::Ref(Some(object)) => Some(Lock::Object(object)),
+            _ => panic!("java.lang.NullPointerException"),
+        }
+    };
+    #[cfg(feature = "jit")]
+    method.profile.count_invocation(&method, &thread.vm.jit);
+    let mut frame = Frame::new(method.class(), method);
+    for (index, val) in args.into_iter().enumerate() {
+        frame.local_vars.set_slot(index, val);
+    }
+    thread.push_frame(frame);
+    if let Some(lock) = lock {
+        thread.enter_synchronized(lock);
+    }
+    0
+}
+
+fn invoke_native_method(thread: &mut Thread, method: Rc<Method>, args: Vec<Slot>) -> usize {
+    let class = method.class();
+    let native_method = find_native_method(&class.name, method.name(), method.descriptor())
+        .unwrap_or_else(|| {
+            panic!(
+                "java.lang.UnsatisfiedLinkError: {}.{}{}",
+                class.java_name(),
+                method.name(),
+                method.descriptor()
+            )
+        });
+    let val = native_method(thread, args);
+
+    let frame = thread.current_frame_mut();
+    for x in val {
+        frame.operand_stack.push_slot(x);
+    }
+    frame.next_pc
+}
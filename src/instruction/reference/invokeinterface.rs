@@ -0,0 +1,51 @@
+use std::rc::Rc;
+
+use crate::instruction::inline_cache::CallCache;
+use crate::instruction::reference::invoke::invoke_method;
+use crate::rtda::slot::Slot;
+use crate::rtda::thread::Thread;
+
+/// The checks of the receiver and the lookup of the method it implements run once per
+/// class of receiver, whose method the inline cache of the call site keeps after.
+#[allow(non_snake_case)]
+pub fn INVOKEINTERFACE(
+    index: usize,
+    cache: &CallCache,
+    next_pc: usize,
+    thread: &mut Thread,
+) -> Option<usize> {
+    trace!("INVOKEINTERFACE");
+    let frame = thread.current_frame_mut();
+
+    let resolved_method = frame.class.resolved_interface_method_ref(index);
+    if resolved_method.is_static() || resolved_method.is_private() {
+        panic!("java.lang.IncompatibleClassChangeError")
+    }
+    let args = frame
+        .operand_stack
+        .pop_slots(resolved_method.arg_slot_count);
+    frame.next_pc = next_pc;
+    let receiver = match args[0] {
+        Slot::Ref(Some(object)) => Rc::clone(&thread.heap.borrow().get(object).class),
+        Slot::Ref(None) => panic!("java.lang.NullPointerException"),
+        _ => panic!("Not Ref"),
+    };
+    if let Some(method) = cache.lookup(&receiver) {
+        return Some(invoke_method(thread, method, args));
+    }
+    if !receiver.is_implements(&resolved_method.class()) {
+        panic!("java.lang.IncompatibleClassChangeError")
+    }
+    let method_to_be_invoked = receiver
+        .lookup_method(resolved_method.name(), resolved_method.descriptor())
+        .expect("java.lang.AbstractMethodError");
+    if method_to_be_invoked.is_abstract() {
+        panic!("java.lang.AbstractMethodError")
+    }
+    if !method_to_be_invoked.is_public() {
+        panic!("java.lang.IllegalAccessError")
+    }
+    cache.update(&receiver, &method_to_be_invoked);
+
+    Some(invoke_method(thread, method_to_be_invoked, args))
+}
@@ -0,0 +1,44 @@
+use std::rc::Rc;
+
+use crate::instruction::reference::invoke::invoke_method;
+use crate::rtda::slot::Slot;
+use crate::rtda::thread::Thread;
+
+/// Instance initializers, private methods and `super.m()` calls, none of which go through
+/// the vtable.
+#[allow(non_snake_case)]
+pub fn INVOKESPECIAL(index: usize, next_pc: usize, thread: &mut Thread) -> Option<usize> {
+    trace!("INVOKESPECIAL");
+    let frame = thread.current_frame_mut();
+
+    let class = &frame.class;
+    let resolved_method = class.resolved_method_ref(index);
+    if resolved_method.is_static() {
+        panic!("java.lang.IncompatibleClassChangeError")
+    }
+    let args = frame
+        .operand_stack
+        .pop_slots(resolved_method.arg_slot_count);
+    if args[0] == Slot::Ref(None) {
+        panic!("java.lang.NullPointerException")
+    }
+    let resolved_class = resolved_method.class();
+    let method_to_be_invoked = if class.is_super()
+        && !resolved_method.is_initializer()
+        && class.is_sub_class_of(&resolved_class)
+    {
+        class
+            .super_class
+            .as_ref()
+            .and_then(|x| x.lookup_method(resolved_method.name(), resolved_method.descriptor()))
+            .expect("java.lang.AbstractMethodError")
+    } else {
+        Rc::clone(&resolved_method)
+    };
+    if method_to_be_invoked.is_abstract() {
+        panic!("java.lang.AbstractMethodError")
+    }
+    frame.next_pc = next_pc;
+
+    Some(invoke_method(thread, method_to_be_invoked, args))
+}
@@ -0,0 +1,19 @@
+use crate::instruction::reference::invoke::invoke_method;
+use crate::rtda::thread::Thread;
+
+#[allow(non_snake_case)]
+pub fn INVOKESTATIC(index: usize, next_pc: usize, thread: &mut Thread) -> Option<usize> {
+    trace!("INVOKESTATIC");
+    let frame = thread.current_frame_mut();
+
+    let resolved_method = frame.class.resolved_method_ref(index);
+    if !resolved_method.is_static() {
+        panic!("java.lang.IncompatibleClassChangeError")
+    }
+    let args = frame
+        .operand_stack
+        .pop_slots(resolved_method.arg_slot_count);
+    frame.next_pc = next_pc;
+
+    Some(invoke_method(thread, resolved_method, args))
+}
@@ -0,0 +1,148 @@
+use std::rc::Rc;
+
+use crate::instruction::inline_cache::CallCache;
+use crate::instruction::reference::invoke::invoke_method;
+use crate::rtda::slot::Slot;
+use crate::rtda::thread::Thread;
+
+/// The method reference is resolved once per call site and cached by the calling class,
+/// later executions look the receiver's class up in the inline cache of the call site and
+/// only index the receiver's vtable on a miss.
+#[allow(non_snake_case)]
+pub fn INVOKEVIRTUAL(
+    index: usize,
+    cache: &CallCache,
+    next_pc: usize,
+    thread: &mut Thread,
+) -> Option<usize> {
+    trace!("INVOKEVIRTUAL");
+    let frame = thread.current_frame_mut();
+
+    let resolved_method = frame.class.resolved_method_ref(index);
+    if resolved_method.is_static() {
+        panic!("java.lang.IncompatibleClassChangeError")
+    }
+    let args = frame
+        .operand_stack
+        .pop_slots(resolved_method.arg_slot_count);
+    frame.next_pc = next_pc;
+    let receiver = match args[0] {
+        Slot::Ref(Some(object)) => Rc::clone(&thread.heap.borrow().get(object).class),
+        Slot::Ref(None) => panic!("java.lang.NullPointerException"),
+        _ => panic!("Not Ref"),
+    };
+    if let Some(method) = cache.lookup(&receiver) {
+        return Some(invoke_method(thread, method, args));
+    }
+    let method_to_be_invoked = match resolved_method.vtable_index {
+        Some(vtable_index) => Rc::clone(&receiver.vtable[vtable_index]),
+        None if resolved_method.is_private() => resolved_method,
+        None => receiver
+            .vtable
+            .iter()
+            .find(|x| {
+                x.name() == resolved_method.name() && x.descriptor() == resolved_method.descriptor()
+            })
+            .cloned()
+            .expect("java.lang.AbstractMethodError"),
+    };
+    if method_to_be_invoked.is_abstract() {
+        panic!("java.lang.AbstractMethodError")
+    }
+    cache.update(&receiver, &method_to_be_invoked);
+
+    Some(invoke_method(thread, method_to_be_invoked, args))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::instruction::decoder::Instruction;
+    use crate::instruction::reference::invokevirtual::*;
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class::Class;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::heap::object::Object;
+    use crate::rtda::thread::Thread;
+
+    // A.callPub and A.callPkg are `aload_0; invokevirtual #index; ireturn`
+    fn invoke(caller_name: &str, receiver_class_name: Option<&str>) -> (Rc<Class>, Frame, Frame) {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("vtable/a/A");
+        let method = class
+            .methods
+            .iter()
+            .find(|x| x.name() == caller_name)
+            .cloned()
+            .unwrap();
+        let mut thread = Thread::new();
+        let receiver = receiver_class_name.map(|x| {
+            let object = Object::new(class_loader.load(x));
+            thread.heap.borrow_mut().allocate(object).unwrap()
+        });
+        let mut frame = Frame::new(Rc::clone(&class), Rc::clone(&method));
+        frame.operand_stack.push_ref(receiver);
+        thread.push_frame(frame);
+        let (instruction, next_pc) = method.decoded_code().at(1);
+        let index = match instruction {
+            Instruction::Invokevirtual(index) => index,
+            _ => panic!("Not invokevirtual"),
+        };
+        let cache = method.decoded_code().call_cache(1);
+        let jump = INVOKEVIRTUAL(index, cache, next_pc, &mut thread);
+        assert_eq!(jump, Some(0));
+        let callee = thread.pop_frame();
+        let caller = thread.pop_frame();
+        assert_eq!(callee.local_vars.get_ref(0), receiver);
+        (class, caller, callee)
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_INVOKEVIRTUAL() {
+        let (_, caller, callee) = invoke("callPkg", Some("vtable/a/A"));
+        assert_eq!(caller.next_pc, 4);
+        assert_eq!(callee.class.name, "vtable/a/A");
+        assert_eq!(callee.method.name(), "pkg");
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_INVOKEVIRTUAL_override() {
+        let (_, _, callee) = invoke("callPkg", Some("vtable/a/B"));
+        assert_eq!(callee.class.name, "vtable/a/B");
+        let (_, _, callee) = invoke("callPub", Some("vtable/b/C"));
+        assert_eq!(callee.class.name, "vtable/b/C");
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_INVOKEVIRTUAL_package_private() {
+        let (_, _, callee) = invoke("callPkg", Some("vtable/b/C"));
+        assert_eq!(callee.class.name, "vtable/a/B");
+        assert_eq!(callee.method.name(), "pkg");
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_INVOKEVIRTUAL_resolve_once() {
+        let (class, _, callee) = invoke("callPkg", Some("vtable/a/A"));
+        let index = class.resolved_method_refs.borrow().keys().next().unwrap();
+        let resolved_method = class.resolved_method_ref(index);
+        assert!(Rc::ptr_eq(&resolved_method, &callee.method));
+        assert_eq!(class.resolved_method_refs.borrow().len(), 1);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    #[should_panic(expected = "java.lang.NullPointerException")]
+    fn test_INVOKEVIRTUAL_null() {
+        invoke("callPkg", None);
+    }
+}
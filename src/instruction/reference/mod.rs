@@ -0,0 +1,15 @@
+pub mod arraylength;
+pub mod athrow;
+pub mod checkcast;
+pub mod getfield;
+pub mod getstatic;
+pub mod instanceof;
+pub(crate) mod invoke;
+pub mod invokeinterface;
+pub mod invokespecial;
+pub mod invokestatic;
+pub mod invokevirtual;
+pub mod monitor;
+pub mod new;
+pub mod putfield;
+pub mod putstatic;
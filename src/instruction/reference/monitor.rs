@@ -0,0 +1,98 @@
+use crate::rtda::frame::Frame;
+use crate::rtda::monitor::Lock;
+use crate::rtda::thread::Thread;
+
+fn _pop_lock(frame: &mut Frame) -> Lock {
+    match frame.operand_stack.pop_ref() {
+        Some(object) => Lock::Object(object),
+        None => panic!("java.lang.NullPointerException"),
+    }
+}
+
+/// Blocks the thread when another one owns the monitor, the scheduler enters it before
+/// the next instruction runs.
+#[allow(non_snake_case)]
+pub fn MONITORENTER(thread: &mut Thread) {
+    trace!("MONITORENTER");
+
+    let lock = _pop_lock(thread.current_frame_mut());
+    thread.enter_monitor(lock);
+}
+
+#[allow(non_snake_case)]
+pub fn MONITOREXIT(thread: &mut Thread) {
+    trace!("MONITOREXIT");
+
+    let lock = _pop_lock(thread.current_frame_mut());
+    thread.exit_monitor(&lock);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::instruction::reference::monitor::*;
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::heap::object::Object;
+    use crate::rtda::thread::ThreadState;
+
+    fn thread_with_ref(null: bool) -> (Rc<ClassLoader>, Thread, Lock) {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("threads/Counter");
+        let method = class.lookup_method("add", "(I)V").unwrap();
+        let mut thread = Thread::new();
+        let object = Object::new(Rc::clone(&class));
+        let object = thread.heap.borrow_mut().allocate(object).unwrap();
+        let mut frame = Frame::new(class, method);
+        let val = if null { None } else { Some(object) };
+        frame.operand_stack.push_ref(val);
+        frame.operand_stack.push_ref(val);
+        thread.push_frame(frame);
+        (class_loader, thread, Lock::Object(object))
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_MONITORENTER() {
+        let (_loader, mut thread, lock) = thread_with_ref(false);
+        let mut other = thread.spawn();
+        MONITORENTER(&mut thread);
+        assert_eq!(thread.state, ThreadState::Runnable);
+        assert!(thread.vm.monitors.borrow().is_owner(&lock, thread.id));
+
+        other.push_frame(thread.pop_frame());
+        MONITORENTER(&mut other);
+        assert_eq!(other.state, ThreadState::Blocked { lock, count: 1 });
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_MONITOREXIT() {
+        let (_loader, mut thread, lock) = thread_with_ref(false);
+        MONITORENTER(&mut thread);
+        MONITOREXIT(&mut thread);
+        assert!(!thread.vm.monitors.borrow().is_owner(&lock, thread.id));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    #[should_panic(expected = "java.lang.IllegalMonitorStateException")]
+    fn test_MONITOREXIT_not_owner() {
+        let (_loader, mut thread, _) = thread_with_ref(false);
+        MONITOREXIT(&mut thread);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    #[should_panic(expected = "java.lang.NullPointerException")]
+    fn test_MONITORENTER_null() {
+        let (_loader, mut thread, _) = thread_with_ref(true);
+        MONITORENTER(&mut thread);
+    }
+}
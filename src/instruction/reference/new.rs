@@ -0,0 +1,78 @@
+use crate::rtda::heap::class_ref::ClassRef;
+use crate::rtda::heap::object::Object;
+use crate::rtda::thread::Thread;
+
+/// When the heap is full, a collection is requested and the thread jumps back to this
+/// instruction at `pc`, so it runs again after the collection.
+#[allow(non_snake_case)]
+pub fn NEW(index: usize, pc: usize, thread: &mut Thread) -> Option<usize> {
+    trace!("NEW");
+    let (frame, heap) = thread.current_frame_and_heap();
+
+    let target = ClassRef::new(&frame.class.constant_pool, index).resolved_class(&frame.class);
+    if target.is_interface() || target.is_abstract() {
+        panic!("java.lang.InstantiationError: {}", target.java_name())
+    }
+    let reference = heap.borrow_mut().allocate(Object::new(target));
+    match reference {
+        Some(reference) => {
+            frame.operand_stack.push_ref(Some(reference));
+            None
+        }
+        None => {
+            heap.borrow_mut().request_gc_for_allocation();
+            Some(pc)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::gc::heap::Heap;
+    use crate::instruction::reference::new::*;
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::thread::Thread;
+
+    // Node's constant pool: #9 = Class gc/Node
+    fn new(heap: Heap) -> (Thread, Option<usize>) {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("gc/Node");
+        let method = class.lookup_method("push", "(I)V").unwrap();
+        let mut thread = Thread::with_heap(Rc::new(RefCell::new(heap)));
+        thread.push_frame(Frame::new(Rc::clone(&class), method));
+        let jump = NEW(9, 5, &mut thread);
+        (thread, jump)
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_NEW() {
+        let (mut thread, jump) = new(Heap::default());
+        assert_eq!(jump, None);
+        let mut frame = thread.pop_frame();
+        let reference = frame.operand_stack.pop_ref();
+        let heap = thread.heap.borrow();
+        let object = heap.get(reference.unwrap());
+        assert_eq!(object.class.name, "gc/Node");
+        assert_eq!(object.fields().len(), 4);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_NEW_heap_full() {
+        let (mut thread, jump) = new(Heap::new(0));
+        assert_eq!(jump, Some(5));
+        assert!(thread.heap.borrow().is_gc_requested());
+        let frame = thread.pop_frame();
+        assert_eq!(frame.operand_stack.refs().count(), 0);
+    }
+}
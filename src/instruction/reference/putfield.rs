@@ -0,0 +1,143 @@
+use std::rc::Rc;
+
+use crate::instruction::inline_cache::{FieldCache, FieldSlots};
+use crate::rtda::thread::Thread;
+
+/// The field is resolved and the access checked on the first execution, later ones take
+/// its slots from the inline cache.
+#[allow(non_snake_case)]
+pub fn PUTFIELD(index: usize, cache: &FieldCache, thread: &mut Thread) {
+    trace!("PUTFIELD");
+    let (frame, heap) = thread.current_frame_and_heap();
+
+    let field = cache.lookup().unwrap_or_else(|| {
+        let field = frame.class.resolved_field_ref(index);
+        if field.is_static() {
+            panic!("java.lang.IncompatibleClassChangeError")
+        }
+        if field.is_final()
+            && (!Rc::ptr_eq(&field.class(), &frame.class) || frame.method.name() != "<init>")
+        {
+            panic!("java.lang.IllegalAccessError")
+        }
+        let field = FieldSlots::new(&field);
+        cache.set(field);
+        field
+    });
+    let slots = frame.operand_stack.pop_slots(field.slot_count);
+    let reference = frame.operand_stack.pop_ref();
+    let reference = reference.expect("java.lang.NullPointerException");
+    let mut heap = heap.borrow_mut();
+    let fields = heap.get_mut(reference).fields_mut();
+    fields[field.slot_id..field.slot_id + slots.len()].copy_from_slice(&slots);
+    if field.is_ref {
+        heap.write_barrier(reference, slots[0]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::instruction::inline_cache::FieldCache;
+    use crate::instruction::reference::getfield::GETFIELD;
+    use crate::instruction::reference::putfield::*;
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::heap::object::{Object, ObjectRef};
+    use crate::rtda::thread::Thread;
+
+    fn thread(object: bool) -> (Rc<ClassLoader>, Thread, Option<ObjectRef>) {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("gc/Node");
+        let method = class.lookup_method("push", "(I)V").unwrap();
+        let mut thread = Thread::new();
+        let reference = if object {
+            let object = Object::new(Rc::clone(&class));
+            thread.heap.borrow_mut().allocate(object)
+        } else {
+            None
+        };
+        thread.push_frame(Frame::new(class, method));
+        (class_loader, thread, reference)
+    }
+
+    // Node's constant pool: #12 = Fieldref value:I, #16 = Fieldref weight:J
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_PUTFIELD_GETFIELD() {
+        let (_class_loader, mut thread, reference) = thread(true);
+        let frame = thread.current_frame_mut();
+        frame.operand_stack.push_ref(reference);
+        frame.operand_stack.push_int(-7);
+        frame.operand_stack.push_ref(reference);
+        frame.operand_stack.push_long(2997924580);
+        PUTFIELD(16, &FieldCache::default(), &mut thread);
+        PUTFIELD(12, &FieldCache::default(), &mut thread);
+
+        let frame = thread.current_frame_mut();
+        frame.operand_stack.push_ref(reference);
+        frame.operand_stack.push_ref(reference);
+        GETFIELD(16, &FieldCache::default(), &mut thread);
+        let weight = thread.current_frame_mut().operand_stack.pop_long();
+        GETFIELD(12, &FieldCache::default(), &mut thread);
+        let value = thread.current_frame_mut().operand_stack.pop_int();
+        assert_eq!(weight, 2997924580);
+        assert_eq!(value, -7);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_PUTFIELD_cached() {
+        let (_class_loader, mut thread, reference) = thread(true);
+        let cache = FieldCache::default();
+        for val in [3, 4] {
+            let frame = thread.current_frame_mut();
+            frame.operand_stack.push_ref(reference);
+            frame.operand_stack.push_int(val);
+            PUTFIELD(12, &cache, &mut thread);
+        }
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+        thread.current_frame_mut().operand_stack.push_ref(reference);
+        GETFIELD(12, &FieldCache::default(), &mut thread);
+        assert_eq!(thread.current_frame_mut().operand_stack.pop_int(), 4);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    #[should_panic(expected = "java.lang.NullPointerException")]
+    fn test_PUTFIELD_null() {
+        let (_class_loader, mut thread, _) = thread(false);
+        let frame = thread.current_frame_mut();
+        frame.operand_stack.push_ref(None);
+        frame.operand_stack.push_int(1);
+        PUTFIELD(12, &FieldCache::default(), &mut thread);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    #[should_panic(expected = "java.lang.NullPointerException")]
+    fn test_GETFIELD_null() {
+        let (_class_loader, mut thread, _) = thread(false);
+        let frame = thread.current_frame_mut();
+        frame.operand_stack.push_ref(None);
+        GETFIELD(12, &FieldCache::default(), &mut thread);
+    }
+
+    // #20 = Fieldref head:Lgc/Node;
+    #[test]
+    #[allow(non_snake_case)]
+    #[should_panic(expected = "java.lang.IncompatibleClassChangeError")]
+    fn test_PUTFIELD_static() {
+        let (_class_loader, mut thread, reference) = thread(true);
+        let frame = thread.current_frame_mut();
+        frame.operand_stack.push_ref(reference);
+        frame.operand_stack.push_ref(None);
+        PUTFIELD(20, &FieldCache::default(), &mut thread);
+    }
+}
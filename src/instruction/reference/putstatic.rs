@@ -0,0 +1,68 @@
+use std::rc::Rc;
+
+use crate::rtda::frame::Frame;
+
+#[allow(non_snake_case)]
+pub fn PUTSTATIC(index: usize, frame: &mut Frame) {
+    trace!("PUTSTATIC");
+
+    let field = frame.class.resolved_field_ref(index);
+    if !field.is_static() {
+        panic!("java.lang.IncompatibleClassChangeError")
+    }
+    let field_class = field.class();
+    if field.is_final()
+        && (!Rc::ptr_eq(&field_class, &frame.class) || frame.method.name() != "<clinit>")
+    {
+        panic!("java.lang.IllegalAccessError")
+    }
+    let slots = frame.operand_stack.pop_slots(field.slot_count());
+    let mut static_vars = field_class.static_vars.borrow_mut();
+    for (i, val) in slots.into_iter().enumerate() {
+        static_vars.set_slot(field.slot_id + i, val);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::instruction::reference::getstatic::GETSTATIC;
+    use crate::instruction::reference::putstatic::*;
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::heap::object::Object;
+    use crate::rtda::thread::Thread;
+
+    // Node's constant pool: #20 = Fieldref head:Lgc/Node;, #27 = Fieldref count:I
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_PUTSTATIC_GETSTATIC() {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("gc/Node");
+        let method = class.lookup_method("push", "(I)V").unwrap();
+        let thread = Thread::new();
+        let head = thread
+            .heap
+            .borrow_mut()
+            .allocate(Object::new(Rc::clone(&class)));
+        let mut frame = Frame::new(Rc::clone(&class), method);
+        frame.operand_stack.push_int(3);
+        frame.operand_stack.push_ref(head);
+        PUTSTATIC(20, &mut frame);
+        PUTSTATIC(27, &mut frame);
+        assert_eq!(class_loader.static_roots(), vec![head.unwrap()]);
+
+        GETSTATIC(27, &mut frame);
+        GETSTATIC(20, &mut frame);
+        let val = frame.operand_stack.pop_ref();
+        assert_eq!(val, head);
+        let val = frame.operand_stack.pop_int();
+        assert_eq!(val, 3);
+    }
+}
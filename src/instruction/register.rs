@@ -0,0 +1,523 @@
+//! The register interpreter, for methods that only compute with ints and longs. Their
+//! stack bytecode is translated once into instructions on registers: the local variables
+//! and the operand stack slots of a frame map to fixed registers of a flat `[i64]`, the
+//! locals first, each register holding the 32-bit value of its slot like the frame does.
+//! A long takes two registers, the low half first. Pushes and pops become moves between
+//! registers, so an instruction runs without touching the operand stack.
+//!
+//! A method qualifies when every instruction is one of those below, leaving the local
+//! variables it doesn't use, like the arguments of `main`, alone. Returning goes back to
+//! the stack interpreter, as does every `FUEL` backward branches so that other threads
+//! get to run.
+
+use std::rc::Rc;
+
+use crate::classfile::constant_info::ConstantInfo;
+use crate::instruction::decoder::Instruction;
+use crate::rtda::heap::method::Method;
+use crate::rtda::slot::Slot;
+use crate::rtda::thread::Thread;
+use crate::util::converter;
+
+/// Backward branches the register interpreter takes before it returns to the stack
+/// interpreter.
+pub const FUEL: u64 = 10_000;
+
+/// How an `If` or `IfCmp` compares.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Condition {
+    Eq,
+    Ne,
+    Lt,
+    Ge,
+    Gt,
+    Le,
+}
+
+impl Condition {
+    fn holds(self, a: i64, b: i64) -> bool {
+        match self {
+            Condition::Eq => a == b,
+            Condition::Ne => a != b,
+            Condition::Lt => a < b,
+            Condition::Ge => a >= b,
+            Condition::Gt => a > b,
+            Condition::Le => a <= b,
+        }
+    }
+}
+
+/// An instruction on registers. A long operand is the pair of registers from the one
+/// given, branches jump to the index of an op.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Const(usize, i32),
+    Move(usize, usize),
+    Iadd(usize, usize, usize),
+    Imul(usize, usize, usize),
+    Iand(usize, usize, usize),
+    Ineg(usize, usize),
+    Lmul(usize, usize, usize),
+    Land(usize, usize, usize),
+    Lneg(usize, usize),
+    Lcmp(usize, usize, usize),
+    Iinc(usize, i32),
+    If(Condition, usize, usize),
+    IfCmp(Condition, usize, usize, usize),
+    Goto(usize),
+    /// Goes back to the stack interpreter at the pc of the op.
+    Exit,
+}
+
+/// Where the register interpreter stopped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exit {
+    pub pc: usize,
+    /// The operand stack slots in use, whose values are in the registers after the locals.
+    pub depth: usize,
+    pub backedges: u64,
+}
+
+/// A method translated to register instructions.
+#[derive(Debug)]
+pub struct RegisterCode {
+    ops: Vec<Op>,
+    /// The pc of the bytecode instruction each op comes from.
+    pcs: Vec<usize>,
+    /// The index of the first op of the instruction starting at each pc.
+    entries: Vec<Option<usize>>,
+    /// The operand stack depth before the instruction at each pc.
+    depths: Vec<usize>,
+    max_locals: usize,
+    /// The local variables the code stores to, which go back to the frame.
+    stores: Vec<usize>,
+}
+
+/// The operand stack depth and the ops of the instruction `instruction`, with the
+/// operand stack at registers from `stack` and `depth` of them in use, if it's one the
+/// register interpreter runs. Branches are to pcs until `translate` maps them to ops.
+fn ops(
+    method: &Method,
+    instruction: Instruction,
+    stack: usize,
+    depth: usize,
+) -> Option<(usize, Vec<Op>)> {
+    let top = stack + depth;
+    let int = |val| Some((depth + 1, vec![Op::Const(top, val)]));
+    let long = |val: i64| {
+        let [low, high] = converter::i64_to_i32seq(val);
+        Some((
+            depth + 2,
+            vec![Op::Const(top, low), Op::Const(top + 1, high)],
+        ))
+    };
+    let load = |index: usize| Some((depth + 1, vec![Op::Move(top, index)]));
+    let load_long = |index: usize| {
+        Some((
+            depth + 2,
+            vec![Op::Move(top, index), Op::Move(top + 1, index + 1)],
+        ))
+    };
+    let store = |index: usize| Some((depth.checked_sub(1)?, vec![Op::Move(index, top - 1)]));
+    let store_long = |index: usize| {
+        Some((
+            depth.checked_sub(2)?,
+            vec![Op::Move(index, top - 2), Op::Move(index + 1, top - 1)],
+        ))
+    };
+    let binary = |pop: usize, op: fn(usize, usize, usize) -> Op| {
+        let depth = depth.checked_sub(pop)?;
+        let a = stack + depth;
+        Some((depth + pop / 2, vec![op(a, a, a + pop / 2)]))
+    };
+    let unary = |size: usize, op: fn(usize, usize) -> Op| {
+        depth.checked_sub(size)?;
+        Some((depth, vec![op(top - size, top - size)]))
+    };
+    let branch = |condition, target| {
+        let depth = depth.checked_sub(1)?;
+        Some((depth, vec![Op::If(condition, stack + depth, target)]))
+    };
+    let compare = |condition, target| {
+        let depth = depth.checked_sub(2)?;
+        let a = stack + depth;
+        Some((depth, vec![Op::IfCmp(condition, a, a + 1, target)]))
+    };
+    match instruction {
+        Instruction::Nop => Some((depth, Vec::new())),
+        Instruction::IconstM1 => int(-1),
+        Instruction::Iconst0 => int(0),
+        Instruction::Iconst1 => int(1),
+        Instruction::Iconst2 => int(2),
+        Instruction::Iconst3 => int(3),
+        Instruction::Iconst4 => int(4),
+        Instruction::Iconst5 => int(5),
+        Instruction::Lconst0 => long(0),
+        Instruction::Lconst1 => long(1),
+        Instruction::Bipush(val) => int(val),
+        Instruction::Ldc(index) => match method.class().constant_pool.get(index) {
+            ConstantInfo::Integer(val) => int(*val),
+            _ => None,
+        },
+        Instruction::Ldc2W(index) => match method.class().constant_pool.get(index) {
+            ConstantInfo::Long(val) => long(*val),
+            _ => None,
+        },
+        Instruction::Iload(index) => load(index),
+        Instruction::Iload0 => load(0),
+        Instruction::Iload1 => load(1),
+        Instruction::Iload2 => load(2),
+        Instruction::Iload3 => load(3),
+        Instruction::Lload(index) => load_long(index),
+        Instruction::Lload0 => load_long(0),
+        Instruction::Lload1 => load_long(1),
+        Instruction::Lload2 => load_long(2),
+        Instruction::Lload3 => load_long(3),
+        Instruction::Istore(index) => store(index),
+        Instruction::Istore0 => store(0),
+        Instruction::Istore1 => store(1),
+        Instruction::Istore2 => store(2),
+        Instruction::Istore3 => store(3),
+        Instruction::Lstore(index) => store_long(index),
+        Instruction::Lstore0 => store_long(0),
+        Instruction::Lstore1 => store_long(1),
+        Instruction::Lstore2 => store_long(2),
+        Instruction::Lstore3 => store_long(3),
+        Instruction::Pop => Some((depth.checked_sub(1)?, Vec::new())),
+        Instruction::Pop2 => Some((depth.checked_sub(2)?, Vec::new())),
+        Instruction::Dup => {
+            depth.checked_sub(1)?;
+            Some((depth + 1, vec![Op::Move(top, top - 1)]))
+        }
+        Instruction::Iadd => binary(2, Op::Iadd),
+        Instruction::Imul => binary(2, Op::Imul),
+        Instruction::Iand => binary(2, Op::Iand),
+        Instruction::Lmul => binary(4, Op::Lmul),
+        Instruction::Land => binary(4, Op::Land),
+        Instruction::Ineg => unary(1, Op::Ineg),
+        Instruction::Lneg => unary(2, Op::Lneg),
+        Instruction::Lcmp => {
+            let depth = depth.checked_sub(4)?;
+            let a = stack + depth;
+            Some((depth + 1, vec![Op::Lcmp(a, a, a + 2)]))
+        }
+        Instruction::Iinc(index, delta) => Some((depth, vec![Op::Iinc(index, delta)])),
+        Instruction::Ifeq(target) => branch(Condition::Eq, target),
+        Instruction::Ifne(target) => branch(Condition::Ne, target),
+        Instruction::Iflt(target) => branch(Condition::Lt, target),
+        Instruction::Ifge(target) => branch(Condition::Ge, target),
+        Instruction::Ifgt(target) => branch(Condition::Gt, target),
+        Instruction::Ifle(target) => branch(Condition::Le, target),
+        Instruction::IfIcmpeq(target) => compare(Condition::Eq, target),
+        Instruction::IfIcmpne(target) => compare(Condition::Ne, target),
+        Instruction::IfIcmplt(target) => compare(Condition::Lt, target),
+        Instruction::IfIcmpge(target) => compare(Condition::Ge, target),
+        Instruction::IfIcmpgt(target) => compare(Condition::Gt, target),
+        Instruction::IfIcmple(target) => compare(Condition::Le, target),
+        Instruction::Goto(target) => Some((depth, vec![Op::Goto(target)])),
+        Instruction::Ireturn | Instruction::Lreturn | Instruction::Return => {
+            Some((depth, vec![Op::Exit]))
+        }
+        _ => None,
+    }
+}
+
+/// Translates `method` to register instructions, if it only computes with ints and longs
+/// and its operand stack has the same depth at every pc whichever way it's reached.
+pub fn translate(method: &Method) -> Option<RegisterCode> {
+    if method.is_native()
+        || method.is_abstract()
+        || method.is_synchronized()
+        || method.code.is_empty()
+    {
+        return None;
+    }
+    let code = method.decoded_code();
+    let stack = method.max_locals;
+    let mut depths = vec![None; method.code.len()];
+    depths[0] = Some(0);
+    let mut pending = vec![0];
+    while let Some(pc) = pending.pop() {
+        let (instruction, next_pc) = code.at(pc);
+        let depth = depths[pc]?;
+        let (next_depth, instruction_ops) = ops(method, instruction, stack, depth)?;
+        if next_depth > method.max_stack {
+            return None;
+        }
+        let mut successors = Vec::new();
+        if let Some(target) = instruction.branch_target() {
+            successors.push(target);
+        }
+        if !matches!(instruction, Instruction::Goto(_)) && !instruction_ops.contains(&Op::Exit) {
+            successors.push(next_pc);
+        }
+        for successor in successors {
+            match depths.get(successor)? {
+                Some(x) if *x != next_depth => return None,
+                Some(_) => {}
+                None => {
+                    depths[successor] = Some(next_depth);
+                    pending.push(successor);
+                }
+            }
+        }
+    }
+
+    let mut entries = vec![None; method.code.len()];
+    let mut ops = Vec::new();
+    let mut pcs = Vec::new();
+    let mut stores = Vec::new();
+    for (pc, instruction, _) in code.iter() {
+        // Dead code is left out
+        let Some(depth) = depths[pc] else {
+            continue;
+        };
+        let (_, instruction_ops) = self::ops(method, instruction, stack, depth)?;
+        for op in &instruction_ops {
+            match *op {
+                Op::Move(dst, _) if dst < stack => stores.push(dst),
+                Op::Iinc(index, _) => stores.push(index),
+                _ => {}
+            }
+        }
+        entries[pc] = Some(ops.len());
+        pcs.extend(std::iter::repeat_n(pc, instruction_ops.len()));
+        ops.extend(instruction_ops);
+    }
+    for op in ops.iter_mut() {
+        if let Op::If(_, _, target) | Op::IfCmp(_, _, _, target) | Op::Goto(target) = op {
+            *target = entries[*target]?;
+        }
+    }
+    stores.sort_unstable();
+    stores.dedup();
+    Some(RegisterCode {
+        ops,
+        pcs,
+        entries,
+        depths: depths.into_iter().map(|x| x.unwrap_or(0)).collect(),
+        max_locals: method.max_locals,
+        stores,
+    })
+}
+
+impl RegisterCode {
+    /// The op to start at for the instruction at `pc`, unless it goes back to the stack
+    /// interpreter right away.
+    pub fn entry(&self, pc: usize) -> Option<usize> {
+        let entry = (*self.entries.get(pc)?)?;
+        match self.ops.get(entry) {
+            Some(Op::Exit) => None,
+            Some(_) => Some(entry),
+            None => None,
+        }
+    }
+
+    pub fn depth(&self, pc: usize) -> usize {
+        self.depths[pc]
+    }
+
+    pub fn ops(&self) -> &[Op] {
+        &self.ops
+    }
+
+    /// Runs the code from the instruction at `pc`, which `entry` returned an op for.
+    pub fn run(&self, pc: usize, registers: &mut [i64], fuel: u64) -> Exit {
+        let long = |registers: &[i64], at: usize| {
+            converter::i32seq_to_i64([registers[at] as i32, registers[at + 1] as i32])
+        };
+        let set_long = |registers: &mut [i64], at: usize, val: i64| {
+            let [low, high] = converter::i64_to_i32seq(val);
+            registers[at] = low as i64;
+            registers[at + 1] = high as i64;
+        };
+        let mut index = self.entries[pc].expect("No register op at pc");
+        let mut backedges = 0;
+        loop {
+            let mut next = index + 1;
+            match self.ops[index] {
+                Op::Const(dst, val) => registers[dst] = val as i64,
+                Op::Move(dst, src) => registers[dst] = registers[src],
+                Op::Iadd(dst, a, b) => {
+                    registers[dst] = (registers[a] as i32).wrapping_add(registers[b] as i32) as i64
+                }
+                Op::Imul(dst, a, b) => {
+                    registers[dst] = (registers[a] as i32).wrapping_mul(registers[b] as i32) as i64
+                }
+                Op::Iand(dst, a, b) => registers[dst] = registers[a] & registers[b],
+                Op::Ineg(dst, a) => registers[dst] = (registers[a] as i32).wrapping_neg() as i64,
+                Op::Lmul(dst, a, b) => {
+                    let val = long(registers, a).wrapping_mul(long(registers, b));
+                    set_long(registers, dst, val)
+                }
+                Op::Land(dst, a, b) => {
+                    let val = long(registers, a) & long(registers, b);
+                    set_long(registers, dst, val)
+                }
+                Op::Lneg(dst, a) => {
+                    let val = long(registers, a).wrapping_neg();
+                    set_long(registers, dst, val)
+                }
+                Op::Lcmp(dst, a, b) => {
+                    registers[dst] = long(registers, a).cmp(&long(registers, b)) as i64
+                }
+                Op::Iinc(index, delta) => {
+                    registers[index] = (registers[index] as i32).wrapping_add(delta) as i64
+                }
+                Op::If(condition, a, target) => {
+                    if condition.holds(registers[a], 0) {
+                        next = target;
+                    }
+                }
+                Op::IfCmp(condition, a, b, target) => {
+                    if condition.holds(registers[a], registers[b]) {
+                        next = target;
+                    }
+                }
+                Op::Goto(target) => next = target,
+                Op::Exit => {
+                    let pc = self.pcs[index];
+                    return Exit {
+                        pc,
+                        depth: self.depths[pc],
+                        backedges,
+                    };
+                }
+            }
+            if next <= index {
+                backedges += 1;
+                if backedges >= fuel {
+                    let pc = self.pcs[next];
+                    return Exit {
+                        pc,
+                        depth: self.depths[pc],
+                        backedges,
+                    };
+                }
+            }
+            index = next;
+        }
+    }
+}
+
+/// Runs the current method on registers if it has register code to enter at `pc`, and
+/// returns the pc the stack interpreter continues at.
+///
+/// The local variables and the operand stack are copied to the registers, then the stack
+/// the code leaves and the variables it stored to are copied back to the frame.
+pub fn run_registers(pc: usize, thread: &mut Thread) -> Option<usize> {
+    if thread.vm.stack_only.get() {
+        return None;
+    }
+    #[cfg(feature = "jit")]
+    let vm = Rc::clone(&thread.vm);
+    let frame = thread.current_frame_mut();
+    let method = Rc::clone(&frame.method);
+    let code = method.register_code()?;
+    code.entry(pc)?;
+    let depth = code.depth(pc);
+    if frame.operand_stack.len() != depth {
+        return None;
+    }
+    let mut registers = vec![0; code.max_locals + method.max_stack];
+    for (index, val) in registers[..code.max_locals].iter_mut().enumerate() {
+        if let Some(Slot::Num(slot)) = frame.local_vars.try_get_slot(index) {
+            *val = slot as i64;
+        }
+    }
+    let stack = &mut registers[code.max_locals..];
+    for (val, slot) in stack.iter_mut().zip(frame.operand_stack.pop_slots(depth)) {
+        *val = match slot {
+            Slot::Num(slot) => slot as i64,
+            Slot::Ref(_) => panic!("Not Num"),
+        };
+    }
+    let exit = code.run(pc, &mut registers, FUEL);
+    for index in &code.stores {
+        frame
+            .local_vars
+            .set_slot(*index, Slot::Num(registers[*index] as i32));
+    }
+    for val in &registers[code.max_locals..code.max_locals + exit.depth] {
+        frame.operand_stack.push_slot(Slot::Num(*val as i32));
+    }
+    #[cfg(feature = "jit")]
+    {
+        let backedges = exit.backedges.try_into().unwrap_or(u32::MAX);
+        method.profile.count_backedges(backedges, &method, &vm.jit);
+    }
+    Some(exit.pc)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::instruction::register::*;
+    use crate::interpreter::{interpret, Schedule};
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class::Class;
+    use crate::rtda::heap::class_loader::ClassLoader;
+
+    fn load(name: &str) -> (Rc<ClassLoader>, Rc<Class>) {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load(name);
+        (class_loader, class)
+    }
+
+    fn run_main(stack_only: bool) -> String {
+        let (class_loader, class) = load("register/Sums");
+        let mut thread = Thread::new();
+        thread.vm.stack_only.set(stack_only);
+        #[cfg(feature = "jit")]
+        thread.vm.jit.enabled.set(false);
+        thread.push_frame(Frame::new(Rc::clone(&class), class.main_method()));
+        interpret(&class_loader, thread, Schedule::default());
+        let static_vars = class.static_vars.borrow();
+        assert_eq!(static_vars.get_int(0), 5050);
+        assert_eq!(static_vars.get_long(1), 4052555153018976267);
+        assert_eq!(static_vars.get_int(3), 102334155);
+        format!("{:?}", static_vars)
+    }
+
+    #[test]
+    fn translate_primitive_only() {
+        let (_class_loader, class) = load("register/Sums");
+        assert!(translate(&class.main_method()).is_none());
+        let gauss = class.lookup_method("gauss", "(I)I").unwrap();
+        let code = translate(&gauss).unwrap();
+        // iconst_0, istore_1, iconst_1, istore_2, then the loop at 4 with an empty stack
+        assert_eq!(code.ops()[..2], [Op::Const(3, 0), Op::Move(1, 3)]);
+        assert_eq!(code.entry(4), Some(4));
+        assert_eq!(code.depth(4), 0);
+        assert_eq!(code.stores, vec![1, 2]);
+        // ireturn goes back to the stack interpreter
+        assert_eq!(code.ops().last(), Some(&Op::Exit));
+        assert_eq!(code.entry(gauss.code.len() - 1), None);
+    }
+
+    #[test]
+    fn out_of_fuel() {
+        let (_class_loader, class) = load("register/Sums");
+        let gauss = class.lookup_method("gauss", "(I)I").unwrap();
+        let code = gauss.register_code().unwrap();
+        let mut registers = vec![0; gauss.max_locals + gauss.max_stack];
+        registers[0] = 100;
+        let exit = code.run(0, &mut registers, 10);
+        assert_eq!((exit.pc, exit.depth, exit.backedges), (4, 0, 10));
+        assert_eq!(registers[1..3], [55, 11]);
+        let exit = code.run(exit.pc, &mut registers, FUEL);
+        assert_eq!((exit.depth, exit.backedges), (1, 90));
+        assert_eq!(registers[gauss.max_locals], 5050);
+    }
+
+    #[test]
+    fn registers_like_stack() {
+        assert_eq!(run_main(false), run_main(true));
+    }
+}
@@ -0,0 +1,43 @@
+use crate::rtda::frame::Frame;
+
+#[allow(non_snake_case)]
+pub fn DUP(frame: &mut Frame) {
+    trace!("DUP");
+
+    let val = frame.operand_stack.pop_slots(1);
+    frame.operand_stack.push_slot(val[0]);
+    frame.operand_stack.push_slot(val[0]);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::instruction::stack::dup::*;
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class_loader::ClassLoader;
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_DUP() {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("threads/Main");
+        let method = class
+            .methods
+            .iter()
+            .find(|x| x.name() == "counter")
+            .cloned()
+            .unwrap();
+        let mut frame = Frame::new(Rc::clone(&class), method);
+        frame.operand_stack.push_int(7);
+        DUP(&mut frame);
+        let val1 = frame.operand_stack.pop_int();
+        let val2 = frame.operand_stack.pop_int();
+        assert_eq!((val1, val2), (7, 7));
+    }
+}
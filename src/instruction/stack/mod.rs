@@ -0,0 +1,2 @@
+pub mod dup;
+pub mod pop;
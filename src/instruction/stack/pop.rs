@@ -0,0 +1,19 @@
+use crate::rtda::frame::Frame;
+
+fn _pop(frame: &mut Frame, slot_count: usize) {
+    frame.operand_stack.pop_slots(slot_count);
+}
+
+#[allow(non_snake_case)]
+pub fn POP(frame: &mut Frame) {
+    trace!("POP");
+
+    _pop(frame, 1);
+}
+
+#[allow(non_snake_case)]
+pub fn POP2(frame: &mut Frame) {
+    trace!("POP2");
+
+    _pop(frame, 2);
+}
@@ -0,0 +1,41 @@
+use crate::rtda::frame::Frame;
+
+fn _astore(frame: &mut Frame, index: usize) {
+    let val = frame.operand_stack.pop_ref();
+    frame.local_vars.set_ref(index, val);
+}
+
+#[allow(non_snake_case)]
+pub fn ASTORE(index: usize, frame: &mut Frame) {
+    trace!("ASTORE");
+
+    _astore(frame, index);
+}
+
+#[allow(non_snake_case)]
+pub fn ASTORE_0(frame: &mut Frame) {
+    trace!("ASTORE_0");
+
+    _astore(frame, 0);
+}
+
+#[allow(non_snake_case)]
+pub fn ASTORE_1(frame: &mut Frame) {
+    trace!("ASTORE_1");
+
+    _astore(frame, 1);
+}
+
+#[allow(non_snake_case)]
+pub fn ASTORE_2(frame: &mut Frame) {
+    trace!("ASTORE_2");
+
+    _astore(frame, 2);
+}
+
+#[allow(non_snake_case)]
+pub fn ASTORE_3(frame: &mut Frame) {
+    trace!("ASTORE_3");
+
+    _astore(frame, 3);
+}
@@ -1,43 +1,41 @@
-use crate::instruction::instruction::ExecuteResult;
 use crate::rtda::frame::Frame;
-use crate::rtda::thread::Thread;
-use crate::util::code_reader::CodeReader;
-
-fn _istore(frame: Frame, index: usize) -> Frame {
-    let Frame {
-        operand_stack,
-        local_vars,
-        method,
-        class,
-    } = frame;
-    let (val, operand_stack) = operand_stack.pop_int();
-    let local_vars = local_vars.set_int(index, val);
-    Frame {
-        class,
-        operand_stack,
-        local_vars,
-        method,
-    }
+
+fn _istore(frame: &mut Frame, index: usize) {
+    let val = frame.operand_stack.pop_int();
+    frame.local_vars.set_int(index, val);
+}
+
+#[allow(non_snake_case)]
+pub fn ISTORE(index: usize, frame: &mut Frame) {
+    trace!("ISTORE");
+
+    _istore(frame, index);
 }
 
 #[allow(non_snake_case)]
-pub fn ISTORE_1(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("ISTORE_1");
-    let (frame, thread) = thread.pop_frame();
-
-    let frame = _istore(frame, 1);
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn ISTORE_0(frame: &mut Frame) {
+    trace!("ISTORE_0");
+
+    _istore(frame, 0);
 }
 
 #[allow(non_snake_case)]
-pub fn ISTORE_2(code_reader: CodeReader, thread: Thread) -> (ExecuteResult, CodeReader) {
-    println!("ISTORE_2");
-    let (frame, thread) = thread.pop_frame();
-
-    let frame = _istore(frame, 2);
-    let thread = thread.push_frame(frame);
-    let execute_result = ExecuteResult { thread, offset: 0 };
-    (execute_result, code_reader)
+pub fn ISTORE_1(frame: &mut Frame) {
+    trace!("ISTORE_1");
+
+    _istore(frame, 1);
+}
+
+#[allow(non_snake_case)]
+pub fn ISTORE_2(frame: &mut Frame) {
+    trace!("ISTORE_2");
+
+    _istore(frame, 2);
+}
+
+#[allow(non_snake_case)]
+pub fn ISTORE_3(frame: &mut Frame) {
+    trace!("ISTORE_3");
+
+    _istore(frame, 3);
 }
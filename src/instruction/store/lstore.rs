@@ -0,0 +1,41 @@
+use crate::rtda::frame::Frame;
+
+fn _lstore(frame: &mut Frame, index: usize) {
+    let val = frame.operand_stack.pop_long();
+    frame.local_vars.set_long(index, val);
+}
+
+#[allow(non_snake_case)]
+pub fn LSTORE(index: usize, frame: &mut Frame) {
+    trace!("LSTORE");
+
+    _lstore(frame, index);
+}
+
+#[allow(non_snake_case)]
+pub fn LSTORE_0(frame: &mut Frame) {
+    trace!("LSTORE_0");
+
+    _lstore(frame, 0);
+}
+
+#[allow(non_snake_case)]
+pub fn LSTORE_1(frame: &mut Frame) {
+    trace!("LSTORE_1");
+
+    _lstore(frame, 1);
+}
+
+#[allow(non_snake_case)]
+pub fn LSTORE_2(frame: &mut Frame) {
+    trace!("LSTORE_2");
+
+    _lstore(frame, 2);
+}
+
+#[allow(non_snake_case)]
+pub fn LSTORE_3(frame: &mut Frame) {
+    trace!("LSTORE_3");
+
+    _lstore(frame, 3);
+}
@@ -1 +1,4 @@
+pub mod astore;
 pub mod istore;
+pub mod lstore;
+pub mod xastore;
@@ -0,0 +1,118 @@
+use std::rc::Rc;
+
+use crate::rtda::heap::object::ArrayData;
+use crate::rtda::slot::Slot;
+use crate::rtda::thread::Thread;
+
+#[allow(non_snake_case)]
+pub fn AASTORE(thread: &mut Thread) {
+    trace!("AASTORE");
+    let (frame, heap) = thread.current_frame_and_heap();
+
+    let val = frame.operand_stack.pop_ref();
+    let index = frame.operand_stack.pop_int();
+    let array_ref = frame.operand_stack.pop_ref();
+    let array_ref = array_ref.expect("java.lang.NullPointerException");
+    let mut heap = heap.borrow_mut();
+    let length = match heap.get(array_ref).array() {
+        ArrayData::Refs(refs) => refs.len(),
+        _ => panic!("Not Refs"),
+    };
+    if index < 0 || index as usize >= length {
+        panic!("java.lang.ArrayIndexOutOfBoundsException: {}", index)
+    }
+    if let Some(val) = val {
+        let component_class = heap
+            .get(array_ref)
+            .class
+            .component_class
+            .clone()
+            .expect("Array without component");
+        let val_class = Rc::clone(&heap.get(val).class);
+        if !component_class.is_assignable_from(&val_class) {
+            panic!("java.lang.ArrayStoreException: {}", val_class.java_name())
+        }
+    }
+    if let ArrayData::Refs(refs) = heap.get_mut(array_ref).array_mut() {
+        refs[index as usize] = val;
+    }
+    heap.write_barrier(array_ref, Slot::Ref(val));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::gc::heap::{Collector, Heap};
+    use crate::instruction::store::xastore::*;
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::heap::object::{Object, ObjectRef};
+    use crate::rtda::thread::Thread;
+
+    fn aastore(array_class_name: &str, val_class_name: Option<&str>, index: i32) -> Thread {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("gc/Node");
+        let method = class.lookup_method("push", "(I)V").unwrap();
+        // Arrays larger than the young generation are allocated in the old one
+        let heap = Heap::with_collector(64 * 1024, Collector::Generational);
+        let mut thread = Thread::with_heap(Rc::new(RefCell::new(heap)));
+        let array = Object::new_array(class_loader.load(array_class_name), 8192);
+        let array = thread.heap.borrow_mut().allocate(array);
+        let val = val_class_name.map(|x| {
+            let object = Object::new(class_loader.load(x));
+            thread.heap.borrow_mut().allocate(object).unwrap()
+        });
+        let mut frame = Frame::new(class, method);
+        frame.operand_stack.push_ref(array);
+        frame.operand_stack.push_int(index);
+        frame.operand_stack.push_ref(val);
+        thread.push_frame(frame);
+        AASTORE(&mut thread);
+        let heap = thread.heap.borrow();
+        assert!(heap.is_old(array.unwrap()));
+        assert_eq!(
+            heap.get(array.unwrap()).references(),
+            val.into_iter().collect::<Vec<ObjectRef>>()
+        );
+        assert_eq!(heap.is_remembered(array.unwrap()), val.is_some());
+        drop(heap);
+        thread
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_AASTORE() {
+        aastore("[Lgc/Node;", Some("gc/Node"), 3);
+        aastore("[Ljava/lang/Object;", Some("gc/Node"), 0);
+        aastore("[Lgc/Node;", None, 8191);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    #[should_panic(expected = "java.lang.ArrayStoreException: java.lang.Object")]
+    fn test_AASTORE_incompatible() {
+        aastore("[Lgc/Node;", Some("java/lang/Object"), 0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    #[should_panic(expected = "java.lang.ArrayIndexOutOfBoundsException: 8192")]
+    fn test_AASTORE_out_of_bounds() {
+        aastore("[Lgc/Node;", Some("gc/Node"), 8192);
+    }
+
+    // The index is checked before the type of the value
+    #[test]
+    #[allow(non_snake_case)]
+    #[should_panic(expected = "java.lang.ArrayIndexOutOfBoundsException: -1")]
+    fn test_AASTORE_incompatible_out_of_bounds() {
+        aastore("[Lgc/Node;", Some("java/lang/Object"), -1);
+    }
+}
@@ -0,0 +1,710 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+use std::time::Duration;
+
+use crate::gc::finalizer::run_finalizer;
+use crate::gc::heap::Heap;
+use crate::instruction::instruction;
+use crate::instruction::reference::athrow::{throw, Thrown};
+use crate::rtda::heap::class_loader::ClassLoader;
+use crate::rtda::heap::object::{Object, ObjectRef};
+use crate::rtda::monitor::Lock;
+use crate::rtda::slot::Slot;
+use crate::rtda::thread::{Thread, ThreadState};
+use crate::rtda::vm::Vm;
+
+/// How many instructions a thread runs by default before the scheduler switches to the
+/// next one.
+pub const TIME_SLICE: usize = 100;
+
+/// Whether `trace!` prints, which it does unless a benchmark turns it off.
+static TRACE: AtomicBool = AtomicBool::new(true);
+
+/// Turns on or off the printing of each instruction run, its pc and each class load.
+pub fn set_trace(trace: bool) {
+    TRACE.store(trace, Ordering::Relaxed);
+}
+
+pub fn is_tracing() -> bool {
+    TRACE.load(Ordering::Relaxed)
+}
+
+/// How the scheduler interleaves threads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Schedule {
+    /// Instructions a thread runs before it's preempted.
+    pub time_slice: usize,
+    /// Without a seed threads take turns round-robin and sleep on the wall clock. With one
+    /// the next thread is drawn from the runnable ones by a generator seeded with it and
+    /// time is virtual, so a given seed always interleaves threads the same way.
+    pub seed: Option<u64>,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Schedule {
+            time_slice: TIME_SLICE,
+            seed: None,
+        }
+    }
+}
+
+/// SplitMix64, only used to draw threads reproducibly.
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let z = self.0;
+        let z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        let z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// The thread `finalize()` runs on, one object after another, started the first time an
+/// object is queued for finalization.
+enum Finalizer {
+    NotStarted,
+    /// Scheduled like any other thread while it runs the `finalize()` of an object.
+    Running(usize),
+    /// Waiting for the next object to finalize.
+    Idle(Thread),
+}
+
+impl Finalizer {
+    fn is(&self, id: usize) -> bool {
+        matches!(self, Finalizer::Running(x) if *x == id)
+    }
+}
+
+/// Runs `thread`, whose stack holds the frame of the entry method, and every thread it
+/// starts until they all terminate.
+///
+/// Java threads are green threads: they take turns on the current OS thread as `schedule`
+/// says, each running until its time slice is over or it blocks, waits or sleeps. An
+/// exception unwinds the stack of the thread it's thrown in down to its handler, and
+/// terminates the thread if there is none. The Finalizer thread is a daemon,
+/// the program ends without waiting for it.
+pub fn interpret(class_loader: &Rc<ClassLoader>, thread: Thread, schedule: Schedule) {
+    let thread = register_thread(class_loader, thread, "main");
+    let heap = Rc::clone(&thread.heap);
+    let vm = Rc::clone(&thread.vm);
+    let mut random = schedule.seed.map(Random);
+    if random.is_some() {
+        vm.clock.use_virtual_time();
+    }
+    let mut threads = VecDeque::from(vec![(thread, 0usize)]);
+    let mut finalizer = Finalizer::NotStarted;
+    loop {
+        threads.extend(vm.started.borrow_mut().drain(..).map(|x| (x, 0)));
+        let exiting = vm.exiting.take();
+        for id in exiting {
+            notify_joiners(&vm, id);
+        }
+        if threads.iter().all(|(x, _)| finalizer.is(x.id)) {
+            break;
+        }
+        if let Some(next) = finalize_next(class_loader, &mut finalizer, &threads[0].0) {
+            threads.push_back(next);
+        }
+        let next = match random {
+            Some(ref mut random) => next_random(&mut threads, random),
+            None => next_round_robin(&mut threads),
+        };
+        let (mut thread, pc) = match next {
+            Some(x) => x,
+            None => match earliest_deadline(&threads) {
+                Some(deadline) => {
+                    vm.clock.sleep_until(deadline);
+                    continue;
+                }
+                None => {
+                    eprintln!("Deadlock: every thread is blocked or waiting forever");
+                    break;
+                }
+            },
+        };
+        let id = thread.id;
+        match run(class_loader, &threads, &mut thread, pc, schedule.time_slice) {
+            Ok(_) if thread.is_stack_empty() && finalizer.is(id) => {
+                finalizer = Finalizer::Idle(thread);
+            }
+            // Like Java, the exceptions finalize() throws are ignored
+            Err(_) if finalizer.is(id) => {
+                thread.clear_stack();
+                vm.monitors.borrow_mut().release_all(id);
+                finalizer = Finalizer::Idle(thread);
+            }
+            Ok(_) if thread.is_stack_empty() => terminate(&heap, &vm, id, None),
+            Ok(pc) => threads.push_back((thread, pc)),
+            Err(exception) => terminate(&heap, &vm, id, Some(exception)),
+        }
+    }
+    match finalizer {
+        Finalizer::NotStarted => {}
+        Finalizer::Running(id) => terminate(&heap, &vm, id, None),
+        Finalizer::Idle(thread) => terminate(&heap, &vm, thread.id, None),
+    }
+}
+
+/// Has the Finalizer thread run the `finalize()` of the next object of the finalization
+/// queue unless it's busy with another one, spawning it from `current` the first time.
+/// Returns it along with its pc to be scheduled if so.
+fn finalize_next(
+    class_loader: &Rc<ClassLoader>,
+    finalizer: &mut Finalizer,
+    current: &Thread,
+) -> Option<(Thread, usize)> {
+    if let Finalizer::Running(_) = finalizer {
+        return None;
+    }
+    let object = current.heap.borrow_mut().take_finalizable()?;
+    let mut thread = match mem::replace(finalizer, Finalizer::NotStarted) {
+        Finalizer::Idle(thread) => thread,
+        _ => register_thread(class_loader, current.spawn(), "Finalizer"),
+    };
+    let pc = run_finalizer(&mut thread, object);
+    *finalizer = Finalizer::Running(thread.id);
+    Some((thread, pc))
+}
+
+/// Gives `thread`, not started from Java, its `java.lang.Thread` object named `name`.
+fn register_thread(class_loader: &Rc<ClassLoader>, thread: Thread, name: &str) -> Thread {
+    let class = class_loader.load("java/lang/Thread");
+    let name = thread.heap.borrow_mut().intern(
+        name,
+        class_loader.load("java/lang/String"),
+        class_loader.load("[C"),
+    );
+    let mut object = Object::new(Rc::clone(&class));
+    let name_field = class.lookup_field("name", "Ljava/lang/String;");
+    if let (Some(field), Some(name)) = (name_field, name) {
+        object.fields_mut()[field.slot_id] = Slot::Ref(Some(name));
+    }
+    let object = thread
+        .heap
+        .borrow_mut()
+        .allocate(object)
+        .expect("java.lang.OutOfMemoryError: Java heap space");
+    if let Some(name) = name {
+        thread
+            .heap
+            .borrow_mut()
+            .write_barrier(object, Slot::Ref(Some(name)));
+    }
+    thread
+        .vm
+        .java_threads
+        .borrow_mut()
+        .insert(thread.id, object);
+    thread
+}
+
+/// Takes the first thread of `threads` that can run, rotating the others to the back.
+fn next_round_robin(threads: &mut VecDeque<(Thread, usize)>) -> Option<(Thread, usize)> {
+    for _ in 0..threads.len() {
+        let (mut thread, pc) = threads.pop_front()?;
+        wake(&mut thread);
+        if thread.state == ThreadState::Runnable {
+            return Some((thread, pc));
+        }
+        threads.push_back((thread, pc));
+    }
+    None
+}
+
+/// Wakes up every thread of `threads` and takes one of those that can run at random.
+fn next_random(
+    threads: &mut VecDeque<(Thread, usize)>,
+    random: &mut Random,
+) -> Option<(Thread, usize)> {
+    for (thread, _) in threads.iter_mut() {
+        wake(thread);
+    }
+    let runnable: Vec<usize> = (0..threads.len())
+        .filter(|x| threads[*x].0.state == ThreadState::Runnable)
+        .collect();
+    if runnable.is_empty() {
+        return None;
+    }
+    let index = runnable[(random.next() % runnable.len() as u64) as usize];
+    threads.remove(index)
+}
+
+/// Makes `thread` runnable if what it's waiting for happened, entering its monitor on its
+/// behalf. If it was interrupted, it's woken up with an `InterruptedException` pending,
+/// thrown when it runs next.
+fn wake(thread: &mut Thread) {
+    let vm = Rc::clone(&thread.vm);
+    let state = match thread.state.clone() {
+        ThreadState::Yielding => ThreadState::Runnable,
+        ThreadState::Sleeping { deadline } => {
+            if thread.take_interrupt() {
+                let exception = "java.lang.InterruptedException: sleep interrupted".to_string();
+                thread.pending_exception = Some(exception);
+            } else if vm.clock.now() < deadline {
+                return;
+            }
+            ThreadState::Runnable
+        }
+        ThreadState::Waiting {
+            lock,
+            count,
+            deadline,
+        } => {
+            // Like a timed out or notified thread, an interrupted one enters the monitor
+            // again before it sees the exception.
+            if thread.take_interrupt() {
+                vm.monitors.borrow_mut().cancel_wait(&lock, thread.id);
+                thread.pending_exception = Some("java.lang.InterruptedException".to_string());
+            } else if deadline.is_some_and(|x| vm.clock.now() >= x) {
+                vm.monitors.borrow_mut().cancel_wait(&lock, thread.id);
+            } else if vm.monitors.borrow().is_waiting(&lock, thread.id) {
+                return;
+            }
+            ThreadState::Blocked { lock, count }
+        }
+        ThreadState::Parked { deadline } => {
+            // Unlike sleep and wait, park returns on interrupt and leaves the status set.
+            let interrupted = vm.interrupted.borrow().contains(&thread.id);
+            let unparked = vm.permits.borrow_mut().remove(&thread.id);
+            if !interrupted && !unparked && deadline.is_none_or(|x| vm.clock.now() < x) {
+                return;
+            }
+            ThreadState::Runnable
+        }
+        state => state,
+    };
+    let state = match state {
+        ThreadState::Blocked { lock, count } => {
+            if vm.monitors.borrow_mut().try_enter(&lock, thread.id, count) {
+                ThreadState::Runnable
+            } else {
+                ThreadState::Blocked { lock, count }
+            }
+        }
+        state => state,
+    };
+    thread.state = state;
+}
+
+fn earliest_deadline(threads: &VecDeque<(Thread, usize)>) -> Option<Duration> {
+    threads
+        .iter()
+        .filter_map(|(x, _)| match x.state {
+            ThreadState::Sleeping { deadline } => Some(deadline),
+            ThreadState::Waiting { deadline, .. } => deadline,
+            ThreadState::Parked { deadline } => deadline,
+            _ => None,
+        })
+        .min()
+}
+
+/// Runs `thread` for `time_slice` instructions, or until it blocks or its stack is empty,
+/// and returns the pc to resume it at. `others` are the threads not running, whose stacks
+/// are roots too. Fails with the message of the exception the thread died of.
+fn run(
+    class_loader: &Rc<ClassLoader>,
+    others: &VecDeque<(Thread, usize)>,
+    thread: &mut Thread,
+    pc: usize,
+    time_slice: usize,
+) -> Result<usize, String> {
+    let vm = Rc::clone(&thread.vm);
+    let mut pc = pc;
+    if let Some(exception) = thread.pending_exception.take() {
+        // Raised by the native the thread was in, which it resumes right after the invoke
+        // instruction of.
+        pc = catch(class_loader, thread, pc - 1, Exception::Raised(exception))?;
+    }
+    let mut left = time_slice;
+    loop {
+        // Only a thrown exception unwinds out of the slice, with `pc` still at the
+        // instruction that threw it
+        let result = catch_java(|| run_slice(class_loader, others, thread, &mut pc, &mut left));
+        match result {
+            Ok(()) => return Ok(pc),
+            Err(payload) => {
+                vm.clock.tick();
+                left -= 1;
+                pc = catch(class_loader, thread, pc, java_exception(payload))?;
+                if left == 0 || thread.is_stack_empty() || thread.state != ThreadState::Runnable {
+                    return Ok(pc);
+                }
+            }
+        }
+    }
+}
+
+/// Runs the instructions of `thread` from `pc` until `left` is used up or the thread stops
+/// running, `pc` then being the one to resume it at.
+fn run_slice(
+    class_loader: &Rc<ClassLoader>,
+    others: &VecDeque<(Thread, usize)>,
+    thread: &mut Thread,
+    pc: &mut usize,
+    left: &mut usize,
+) {
+    while *left > 0 {
+        let next_pc = instruction::execute(*pc, thread);
+        thread.vm.clock.tick();
+        *left -= 1;
+
+        // Safepoint: every thread is between two instructions, so all roots are visible.
+        if thread.heap.borrow().is_gc_requested() {
+            let roots = roots(class_loader, thread, others);
+            thread.heap.borrow_mut().collect(roots);
+        }
+        trace!("pc: {}", pc);
+        trace!("next_pc: {}", next_pc);
+        *pc = next_pc;
+        if thread.is_stack_empty() || thread.state != ThreadState::Runnable {
+            return;
+        }
+    }
+}
+
+fn roots(
+    class_loader: &ClassLoader,
+    current: &Thread,
+    others: &VecDeque<(Thread, usize)>,
+) -> Vec<ObjectRef> {
+    let vm = &current.vm;
+    let started = vm.started.borrow();
+    let java_threads = vm.java_threads.borrow();
+    let monitors = vm.monitors.borrow();
+    current
+        .roots()
+        .into_iter()
+        .chain(others.iter().flat_map(|(x, _)| x.roots()))
+        .chain(started.iter().flat_map(|x| x.roots()))
+        .chain(java_threads.values().copied())
+        .chain(monitors.roots())
+        .chain(class_loader.static_roots())
+        .collect()
+}
+
+/// A Java exception, either raised by the VM or thrown by `athrow`.
+enum Exception {
+    /// A panic whose message starts with the exception class name, followed by the detail
+    /// message if any.
+    Raised(String),
+    Thrown(ObjectRef),
+}
+
+thread_local! {
+    /// Whether the interpreter is catching the Java exceptions instructions panic with,
+    /// which the panic hook then keeps quiet about.
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f`, catching the panic it raises or throws an exception with. The panic hook
+/// doesn't print the exception, which is printed the Java way if no handler catches it.
+fn catch_java<T>(f: impl FnOnce() -> T) -> Result<T, Box<dyn Any + Send>> {
+    static QUIET_HOOK: Once = Once::new();
+    QUIET_HOOK.call_once(|| {
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !(CATCHING.get() && is_java_exception(info.payload())) {
+                default(info);
+            }
+        }));
+    });
+    let catching = CATCHING.replace(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.set(catching);
+    result
+}
+
+fn is_java_exception(payload: &(dyn Any + Send)) -> bool {
+    let message = match payload.downcast_ref::<String>() {
+        Some(message) => Some(message.as_str()),
+        None => payload.downcast_ref::<&str>().copied(),
+    };
+    payload.is::<Thrown>() || message.is_some_and(|x| x.starts_with("java."))
+}
+
+/// Any panic other than a Java exception is a bug in the VM itself and keeps unwinding.
+fn java_exception(payload: Box<dyn Any + Send>) -> Exception {
+    if let Some(Thrown(exception)) = payload.downcast_ref::<Thrown>() {
+        return Exception::Thrown(*exception);
+    }
+    let message = match payload.downcast_ref::<String>() {
+        Some(message) => Some(message.clone()),
+        None => payload.downcast_ref::<&str>().map(|x| x.to_string()),
+    };
+    match message {
+        Some(message) if message.starts_with("java.") => Exception::Raised(message),
+        _ => panic::resume_unwind(payload),
+    }
+}
+
+/// Hands `exception`, thrown at `pc` of the current frame of `thread`, to its handler and
+/// returns the pc of the handler. Fails with the message of the exception if there is no
+/// handler, or if the VM raised it but the exception class isn't on the class path.
+fn catch(
+    class_loader: &Rc<ClassLoader>,
+    thread: &mut Thread,
+    pc: usize,
+    exception: Exception,
+) -> Result<usize, String> {
+    let (object, message) = match exception {
+        Exception::Raised(message) => match new_exception(class_loader, thread, &message) {
+            Some(object) => (object, message),
+            None => return Err(message),
+        },
+        Exception::Thrown(object) => (object, describe(&thread.heap.borrow(), object)),
+    };
+    // Resolving the class of a catch clause can raise an exception too
+    match catch_java(|| throw(thread, pc, object)) {
+        Ok(Some(handler)) => Ok(handler),
+        Ok(None) => Err(message),
+        Err(payload) => match java_exception(payload) {
+            Exception::Raised(message) => Err(message),
+            Exception::Thrown(object) => Err(describe(&thread.heap.borrow(), object)),
+        },
+    }
+}
+
+/// The `java.lang.Throwable` for `message`, the message of an exception raised by the VM.
+fn new_exception(
+    class_loader: &Rc<ClassLoader>,
+    thread: &Thread,
+    message: &str,
+) -> Option<ObjectRef> {
+    let (name, detail) = match message.split_once(": ") {
+        Some((name, detail)) => (name, Some(detail)),
+        None => (message, None),
+    };
+    let name = name.replace('.', "/");
+    class_loader.class_path().find_class(&name).ok()?;
+    let class = class_loader.load(&name);
+    let field = class.lookup_field("detailMessage", "Ljava/lang/String;")?;
+    let string_class = class_loader.load("java/lang/String");
+    let char_array_class = class_loader.load("[C");
+    let mut heap = thread.heap.borrow_mut();
+    let detail = match detail {
+        Some(detail) => Some(heap.intern(detail, string_class, char_array_class)?),
+        None => None,
+    };
+    let mut object = Object::new(class);
+    object.fields_mut()[field.slot_id] = Slot::Ref(detail);
+    let object = heap.allocate(object)?;
+    heap.write_barrier(object, Slot::Ref(detail));
+    Some(object)
+}
+
+/// The class name of the `java.lang.Throwable` `object`, followed by its detail message
+/// if it has one, as printed when it's uncaught.
+fn describe(heap: &Heap, object: ObjectRef) -> String {
+    let object = heap.get(object);
+    let name = object.class.java_name();
+    let field = object
+        .class
+        .lookup_field("detailMessage", "Ljava/lang/String;");
+    match field.map(|x| &object.fields()[x.slot_id]) {
+        Some(Slot::Ref(Some(detail))) => format!("{}: {}", name, heap.java_string(*detail)),
+        _ => name,
+    }
+}
+
+/// Cleans up after the thread `id` ran to completion or died of `exception`: its monitors
+/// are released and the threads joining it are woken up, once nobody else owns the
+/// monitor of its `java.lang.Thread` object.
+fn terminate(heap: &RefCell<Heap>, vm: &Vm, id: usize, exception: Option<String>) {
+    if let Some(exception) = exception {
+        eprintln!(
+            "Exception in thread \"{}\" {}",
+            thread_name(heap, vm, id),
+            exception
+        );
+    }
+    vm.monitors.borrow_mut().release_all(id);
+    vm.interrupted.borrow_mut().remove(&id);
+    vm.permits.borrow_mut().remove(&id);
+    notify_joiners(vm, id);
+}
+
+/// Marks the thread `id` dead and wakes the threads waiting on its `java.lang.Thread`
+/// object, holding its monitor like `notifyAll` would. Otherwise a joiner that saw the
+/// thread alive but hasn't called `wait` yet would miss the wake-up. Retried by the
+/// scheduler while another thread owns the monitor.
+fn notify_joiners(vm: &Vm, id: usize) {
+    let java_thread = match vm.java_threads.borrow().get(&id) {
+        Some(java_thread) => *java_thread,
+        None => return,
+    };
+    let lock = Lock::Object(java_thread);
+    let mut monitors = vm.monitors.borrow_mut();
+    if !monitors.try_enter(&lock, id, 1) {
+        vm.exiting.borrow_mut().push(id);
+        return;
+    }
+    vm.java_threads.borrow_mut().remove(&id);
+    monitors.notify_all(&lock, id);
+    monitors.exit(&lock, id);
+}
+
+fn thread_name(heap: &RefCell<Heap>, vm: &Vm, id: usize) -> String {
+    let heap = heap.borrow();
+    let name = vm.java_threads.borrow().get(&id).and_then(|x| {
+        let object = heap.get(*x);
+        let field = object.class.lookup_field("name", "Ljava/lang/String;")?;
+        match object.fields()[field.slot_id] {
+            Slot::Ref(Some(name)) => Some(heap.java_string(name)),
+            _ => None,
+        }
+    });
+    name.unwrap_or_else(|| format!("Thread-{}", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use std::time::{Duration, Instant};
+
+    use crate::classpath::classpath::parse;
+    use crate::interpreter::*;
+    use crate::rtda::frame::Frame;
+
+    // threads/Main stores what each scenario computed in its only static field
+    fn run_main(name: &str, schedule: Schedule) -> (i32, Rc<Vm>) {
+        run_static("threads/Main", name, schedule)
+    }
+
+    fn run_static(class_name: &str, name: &str, schedule: Schedule) -> (i32, Rc<Vm>) {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load(class_name);
+        let method = class.lookup_method(name, "()V").unwrap();
+        let mut thread = Thread::new();
+        thread.push_frame(Frame::new(Rc::clone(&class), method));
+        let vm = Rc::clone(&thread.vm);
+        interpret(&class_loader, thread, schedule);
+        assert!(vm.java_threads.borrow().is_empty());
+        let result = class.static_vars.borrow().get_int(0);
+        (result, vm)
+    }
+
+    fn seeded(seed: u64, time_slice: usize) -> Schedule {
+        Schedule {
+            time_slice,
+            seed: Some(seed),
+        }
+    }
+
+    #[test]
+    fn synchronized_counter() {
+        assert_eq!(run_main("counter", Schedule::default()).0, 300);
+        for seed in 0..5 {
+            assert_eq!(run_main("counter", seeded(seed, 1)).0, 300);
+        }
+    }
+
+    #[test]
+    fn wait_and_notify() {
+        assert_eq!(run_main("producerConsumer", Schedule::default()).0, 55);
+        for seed in 0..5 {
+            assert_eq!(run_main("producerConsumer", seeded(seed, 3)).0, 55);
+        }
+    }
+
+    #[test]
+    fn sleep_and_join() {
+        let start = Instant::now();
+        assert_eq!(run_main("sleep", Schedule::default()).0, 1);
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn sleep_in_virtual_time() {
+        let (result, vm) = run_main("sleep", seeded(7, 10));
+        assert_eq!(result, 1);
+        assert!(vm.clock.is_virtual());
+        assert!(vm.clock.now() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn interrupt_sleeping_thread() {
+        let start = Instant::now();
+        assert_eq!(run_main("interrupt", Schedule::default()).0, 1);
+        assert!(start.elapsed() < Duration::from_secs(60));
+    }
+
+    // The thread catches the InterruptedException of each nap and keeps running
+    #[test]
+    fn interrupted_thread_keeps_running() {
+        let start = Instant::now();
+        assert_eq!(run_main("interruptTwice", Schedule::default()).0, 2);
+        assert!(start.elapsed() < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn exception_handlers() {
+        let run = |name| run_static("exceptions/Handlers", name, Schedule::default()).0;
+        assert_eq!(run("raised"), 1);
+        assert_eq!(run("thrown"), 1);
+        // the handler of the synchronized block exits the monitor before rethrowing
+        assert_eq!(run("unlocked"), 1);
+        assert_eq!(run("uncaught"), 1);
+    }
+
+    #[test]
+    fn uncaught_exception_terminates_only_its_thread() {
+        assert_eq!(run_main("uncaught", Schedule::default()).0, 1);
+    }
+
+    #[test]
+    fn current_thread() {
+        assert_eq!(run_main("current", Schedule::default()).0, 1);
+    }
+
+    // Two threads increment a shared field 20 times each without synchronization
+    #[test]
+    fn seeded_schedule_reproduces_race() {
+        let results: Vec<i32> = (0..8).map(|x| run_main("race", seeded(x, 2)).0).collect();
+        let again: Vec<i32> = (0..8).map(|x| run_main("race", seeded(x, 2)).0).collect();
+        assert_eq!(results, again);
+        assert!(results.iter().any(|x| *x < 40));
+        assert_eq!(run_main("race", seeded(0, 1000)).0, 40);
+    }
+
+    // finalize() throws holding the monitor main needs at the end
+    #[test]
+    fn finalizer_thread() {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("gc/Finalizers");
+        let method = class.lookup_method("run", "()V").unwrap();
+        let heap = Rc::new(RefCell::new(Heap::new(4096)));
+        let mut thread = Thread::with_heap(heap);
+        thread.push_frame(Frame::new(Rc::clone(&class), method));
+        let vm = Rc::clone(&thread.vm);
+        interpret(&class_loader, thread, Schedule::default());
+        assert!(vm.java_threads.borrow().is_empty());
+        let result = class.static_vars.borrow().get_int(0);
+        assert!(result > 0);
+    }
+
+    #[test]
+    fn catch_java_exceptions_quietly() {
+        let result = catch_java(|| panic!("java.lang.ArithmeticException: / by zero"));
+        assert!(is_java_exception(result.unwrap_err().as_ref()));
+        assert!(is_java_exception(&Thrown(ObjectRef(0))));
+        assert!(!is_java_exception(&"index out of bounds"));
+        assert!(!CATCHING.get());
+    }
+}
@@ -0,0 +1,314 @@
+//! Just enough of the x86-64 instruction encoding for the templates of the compiler.
+//! Memory operands are always a base register plus a 32-bit displacement.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reg {
+    Rax = 0,
+    Rcx = 1,
+    Rdx = 2,
+    Rbx = 3,
+    Rsi = 6,
+    Rdi = 7,
+    R12 = 12,
+    R13 = 13,
+    R14 = 14,
+}
+
+impl Reg {
+    fn low(self) -> u8 {
+        self as u8 & 7
+    }
+
+    fn high(self) -> u8 {
+        self as u8 >> 3
+    }
+}
+
+/// The condition codes of `jcc` and `setcc`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    Equal = 0x4,
+    NotEqual = 0x5,
+    Less = 0xC,
+    GreaterOrEqual = 0xD,
+    LessOrEqual = 0xE,
+    Greater = 0xF,
+}
+
+/// A position in the code, possibly jumped to before it's bound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Label(usize);
+
+#[derive(Debug, Default)]
+pub struct Assembler {
+    code: Vec<u8>,
+    /// Where each label is bound, once it is.
+    labels: Vec<Option<usize>>,
+    /// The offsets of the rel32 operands to patch with the label they jump to.
+    fixups: Vec<(usize, Label)>,
+}
+
+impl Assembler {
+    pub fn new() -> Assembler {
+        Assembler::default()
+    }
+
+    pub fn new_label(&mut self) -> Label {
+        self.labels.push(None);
+        Label(self.labels.len() - 1)
+    }
+
+    pub fn bind(&mut self, label: Label) {
+        self.labels[label.0] = Some(self.code.len());
+    }
+
+    /// The machine code with every jump patched, panics if a label jumped to isn't bound.
+    pub fn finish(mut self) -> Vec<u8> {
+        for (at, label) in self.fixups.drain(..) {
+            let target = self.labels[label.0].expect("Label isn't bound");
+            let rel = target as i64 - (at as i64 + 4);
+            self.code[at..at + 4].copy_from_slice(&(rel as i32).to_le_bytes());
+        }
+        self.code
+    }
+
+    fn rex(&mut self, wide: bool, reg: u8, base: Reg) {
+        let rex = 0x40 | (wide as u8) << 3 | (reg >> 3) << 2 | base.high();
+        if rex != 0x40 {
+            self.code.push(rex);
+        }
+    }
+
+    /// `opcode` with a register or opcode extension `reg` and the operand `[base + disp]`.
+    fn mem(&mut self, wide: bool, opcode: &[u8], reg: u8, base: Reg, disp: i32) {
+        self.rex(wide, reg, base);
+        self.code.extend_from_slice(opcode);
+        self.code.push(0b10 << 6 | (reg & 7) << 3 | base.low());
+        // rsp and r12 as a base need a SIB byte
+        if base.low() == 4 {
+            self.code.push(0x24);
+        }
+        self.code.extend_from_slice(&disp.to_le_bytes());
+    }
+
+    /// `opcode` with a register or opcode extension `reg` and the register operand `rm`.
+    fn direct(&mut self, wide: bool, opcode: &[u8], reg: u8, rm: Reg) {
+        self.rex(wide, reg, rm);
+        self.code.extend_from_slice(opcode);
+        self.code.push(0b11 << 6 | (reg & 7) << 3 | rm.low());
+    }
+
+    pub fn push(&mut self, reg: Reg) {
+        self.rex(false, 0, reg);
+        self.code.push(0x50 + reg.low());
+    }
+
+    pub fn pop(&mut self, reg: Reg) {
+        self.rex(false, 0, reg);
+        self.code.push(0x58 + reg.low());
+    }
+
+    pub fn ret(&mut self) {
+        self.code.push(0xC3);
+    }
+
+    pub fn ud2(&mut self) {
+        self.code.extend_from_slice(&[0x0F, 0x0B]);
+    }
+
+    /// `mov dst, src` on 64 bits.
+    pub fn mov(&mut self, dst: Reg, src: Reg) {
+        self.direct(true, &[0x89], src as u8, dst);
+    }
+
+    /// `mov dst, qword [base + disp]`.
+    pub fn load(&mut self, dst: Reg, base: Reg, disp: i32) {
+        self.mem(true, &[0x8B], dst as u8, base, disp);
+    }
+
+    /// `mov dst, dword [base + disp]`, zero-extended.
+    pub fn load32(&mut self, dst: Reg, base: Reg, disp: i32) {
+        self.mem(false, &[0x8B], dst as u8, base, disp);
+    }
+
+    /// `mov qword [base + disp], src`.
+    pub fn store(&mut self, base: Reg, disp: i32, src: Reg) {
+        self.mem(true, &[0x89], src as u8, base, disp);
+    }
+
+    /// `mov qword [base + disp], imm`, sign-extended.
+    pub fn store_imm(&mut self, base: Reg, disp: i32, imm: i32) {
+        self.mem(true, &[0xC7], 0, base, disp);
+        self.code.extend_from_slice(&imm.to_le_bytes());
+    }
+
+    /// `mov byte [base + disp], imm`.
+    pub fn store_byte(&mut self, base: Reg, disp: i32, imm: u8) {
+        self.mem(false, &[0xC6], 0, base, disp);
+        self.code.push(imm);
+    }
+
+    /// `mov dst, imm`, sign-extended to 64 bits.
+    pub fn mov_imm(&mut self, dst: Reg, imm: i32) {
+        self.direct(true, &[0xC7], 0, dst);
+        self.code.extend_from_slice(&imm.to_le_bytes());
+    }
+
+    /// `movsxd dst, src`: sign-extends the low 32 bits of `src`.
+    pub fn movsxd(&mut self, dst: Reg, src: Reg) {
+        self.direct(true, &[0x63], dst as u8, src);
+    }
+
+    /// `movzx dst, src` from the low byte of `src`, one of rax, rcx and rdx.
+    pub fn movzx8(&mut self, dst: Reg, src: Reg) {
+        self.direct(false, &[0x0F, 0xB6], dst as u8, src);
+    }
+
+    pub fn add32(&mut self, dst: Reg, src: Reg) {
+        self.direct(false, &[0x01], src as u8, dst);
+    }
+
+    pub fn add32_imm(&mut self, dst: Reg, imm: i32) {
+        self.direct(false, &[0x81], 0, dst);
+        self.code.extend_from_slice(&imm.to_le_bytes());
+    }
+
+    pub fn sub32(&mut self, dst: Reg, src: Reg) {
+        self.direct(false, &[0x29], src as u8, dst);
+    }
+
+    pub fn and32(&mut self, dst: Reg, src: Reg) {
+        self.direct(false, &[0x21], src as u8, dst);
+    }
+
+    pub fn and64(&mut self, dst: Reg, src: Reg) {
+        self.direct(true, &[0x21], src as u8, dst);
+    }
+
+    pub fn or64(&mut self, dst: Reg, src: Reg) {
+        self.direct(true, &[0x09], src as u8, dst);
+    }
+
+    pub fn imul32(&mut self, dst: Reg, src: Reg) {
+        self.direct(false, &[0x0F, 0xAF], dst as u8, src);
+    }
+
+    pub fn imul64(&mut self, dst: Reg, src: Reg) {
+        self.direct(true, &[0x0F, 0xAF], dst as u8, src);
+    }
+
+    pub fn neg32(&mut self, reg: Reg) {
+        self.direct(false, &[0xF7], 3, reg);
+    }
+
+    pub fn neg64(&mut self, reg: Reg) {
+        self.direct(true, &[0xF7], 3, reg);
+    }
+
+    pub fn shl64(&mut self, reg: Reg, count: u8) {
+        self.direct(true, &[0xC1], 4, reg);
+        self.code.push(count);
+    }
+
+    pub fn sar64(&mut self, reg: Reg, count: u8) {
+        self.direct(true, &[0xC1], 7, reg);
+        self.code.push(count);
+    }
+
+    /// `cmp a, b` on 32 bits, setting the flags of `a - b`.
+    pub fn cmp32(&mut self, a: Reg, b: Reg) {
+        self.direct(false, &[0x39], b as u8, a);
+    }
+
+    /// `cmp a, b` on 64 bits, setting the flags of `a - b`.
+    pub fn cmp64(&mut self, a: Reg, b: Reg) {
+        self.direct(true, &[0x39], b as u8, a);
+    }
+
+    pub fn cmp64_imm(&mut self, reg: Reg, imm: i32) {
+        self.direct(true, &[0x81], 7, reg);
+        self.code.extend_from_slice(&imm.to_le_bytes());
+    }
+
+    pub fn test32(&mut self, a: Reg, b: Reg) {
+        self.direct(false, &[0x85], b as u8, a);
+    }
+
+    /// `sub qword [base + disp], imm`.
+    pub fn sub_mem_imm8(&mut self, base: Reg, disp: i32, imm: i8) {
+        self.mem(true, &[0x83], 5, base, disp);
+        self.code.push(imm as u8);
+    }
+
+    /// `setcc` into the low byte of rax, rcx or rdx.
+    pub fn set(&mut self, condition: Condition, dst: Reg) {
+        self.direct(false, &[0x0F, 0x90 + condition as u8], 0, dst);
+    }
+
+    pub fn jmp(&mut self, label: Label) {
+        self.code.push(0xE9);
+        self.rel32(label);
+    }
+
+    pub fn jcc(&mut self, condition: Condition, label: Label) {
+        self.code.extend_from_slice(&[0x0F, 0x80 + condition as u8]);
+        self.rel32(label);
+    }
+
+    fn rel32(&mut self, label: Label) {
+        self.fixups.push((self.code.len(), label));
+        self.code.extend_from_slice(&[0; 4]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::jit::assembler::*;
+
+    // Expected bytes as given by an assembler
+    #[test]
+    fn encoding() {
+        let mut assembler = Assembler::new();
+        assembler.push(Reg::R12);
+        assembler.load(Reg::Rax, Reg::R12, 8);
+        assembler.store(Reg::R13, 16, Reg::Rcx);
+        assembler.store_byte(Reg::R14, 2, 1);
+        assembler.movsxd(Reg::Rax, Reg::Rax);
+        assembler.imul32(Reg::Rax, Reg::Rcx);
+        assembler.set(Condition::Less, Reg::Rcx);
+        assembler.sub_mem_imm8(Reg::Rbx, 24, 1);
+        assembler.ret();
+        assert_eq!(
+            assembler.finish(),
+            vec![
+                0x41, 0x54, // push r12
+                0x49, 0x8B, 0x84, 0x24, 0x08, 0x00, 0x00, 0x00, // mov rax, [r12 + 8]
+                0x49, 0x89, 0x8D, 0x10, 0x00, 0x00, 0x00, // mov [r13 + 16], rcx
+                0x41, 0xC6, 0x86, 0x02, 0x00, 0x00, 0x00, 0x01, // mov byte [r14 + 2], 1
+                0x48, 0x63, 0xC0, // movsxd rax, eax
+                0x0F, 0xAF, 0xC1, // imul eax, ecx
+                0x0F, 0x9C, 0xC1, // setl cl
+                0x48, 0x83, 0xAB, 0x18, 0x00, 0x00, 0x00, 0x01, // sub qword [rbx + 24], 1
+                0xC3, // ret
+            ]
+        );
+    }
+
+    #[test]
+    fn jumps() {
+        let mut assembler = Assembler::new();
+        let back = assembler.new_label();
+        let forward = assembler.new_label();
+        assembler.bind(back);
+        assembler.jcc(Condition::Equal, forward);
+        assembler.jmp(back);
+        assembler.bind(forward);
+        assert_eq!(
+            assembler.finish(),
+            vec![
+                0x0F, 0x84, 0x05, 0x00, 0x00, 0x00, // je +5
+                0xE9, 0xF5, 0xFF, 0xFF, 0xFF, // jmp -11
+            ]
+        );
+    }
+}
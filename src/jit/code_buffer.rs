@@ -0,0 +1,66 @@
+use std::ffi::c_void;
+use std::ptr;
+
+const PROT_READ: i32 = 1;
+const PROT_WRITE: i32 = 2;
+const PROT_EXEC: i32 = 4;
+const MAP_PRIVATE: i32 = 0x02;
+const MAP_ANONYMOUS: i32 = 0x20;
+const MAP_FAILED: *mut c_void = !0 as *mut c_void;
+
+extern "C" {
+    fn mmap(
+        addr: *mut c_void,
+        len: usize,
+        prot: i32,
+        flags: i32,
+        fd: i32,
+        offset: i64,
+    ) -> *mut c_void;
+    fn mprotect(addr: *mut c_void, len: usize, prot: i32) -> i32;
+    fn munmap(addr: *mut c_void, len: usize) -> i32;
+}
+
+/// Machine code copied to pages of its own, which are made executable and read-only
+/// once it's there.
+#[derive(Debug)]
+pub struct CodeBuffer {
+    address: *mut c_void,
+    len: usize,
+}
+
+impl CodeBuffer {
+    pub fn new(code: &[u8]) -> CodeBuffer {
+        let len = code.len().max(1);
+        unsafe {
+            let address = mmap(
+                ptr::null_mut(),
+                len,
+                PROT_READ | PROT_WRITE,
+                MAP_PRIVATE | MAP_ANONYMOUS,
+                -1,
+                0,
+            );
+            if address == MAP_FAILED {
+                panic!("java.lang.OutOfMemoryError: no memory for compiled code")
+            }
+            ptr::copy_nonoverlapping(code.as_ptr(), address as *mut u8, code.len());
+            if mprotect(address, len, PROT_READ | PROT_EXEC) != 0 {
+                panic!("Compiled code can't be made executable")
+            }
+            CodeBuffer { address, len }
+        }
+    }
+
+    pub fn as_ptr(&self) -> *const u8 {
+        self.address as *const u8
+    }
+}
+
+impl Drop for CodeBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            munmap(self.address, self.len);
+        }
+    }
+}
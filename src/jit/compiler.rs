@@ -0,0 +1,595 @@
+//! Compiles a method to x86-64 machine code with a fixed template per instruction.
+//!
+//! Compiled code works on copies of the local variables and the operand stack, one
+//! 64-bit word per slot holding the `i32` of a `Slot::Num` sign-extended, a long taking
+//! two slots like in the interpreter. It's entered at the start of the method or at the
+//! target of a backward branch, with an empty operand stack. Only the instructions on
+//! primitive ints and longs have templates. Any other one, a call or a return included,
+//! is an exit that deoptimizes: the code stops right before it and the interpreter
+//! carries on from its pc with the slots copied back.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::mem;
+
+use crate::instruction::decoder::{DecodedCode, Instruction};
+use crate::jit::assembler::{Assembler, Condition, Label, Reg};
+use crate::jit::code_buffer::CodeBuffer;
+use crate::jit::Kind;
+use crate::rtda::heap::method::Method;
+
+const CONTEXT: Reg = Reg::Rbx;
+const LOCALS: Reg = Reg::R12;
+const STACK: Reg = Reg::R13;
+const DIRTY: Reg = Reg::R14;
+
+// Offsets of the fields of `Context`
+const LOCALS_OFFSET: i32 = 0;
+const STACK_OFFSET: i32 = 8;
+const DIRTY_OFFSET: i32 = 16;
+const FUEL_OFFSET: i32 = 24;
+const DEPTH_OFFSET: i32 = 32;
+
+#[repr(C)]
+struct Context {
+    locals: *mut i64,
+    stack: *mut i64,
+    /// Set to `Kind::Num` for each local variable the code stores to.
+    dirty: *mut u8,
+    /// Backward branches the code may still take before it returns to the interpreter.
+    fuel: u64,
+    /// The operand stack depth when the code returned.
+    depth: u64,
+}
+
+/// Where compiled code stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Exit {
+    /// The pc the interpreter continues at.
+    pub pc: usize,
+    /// How many slots of the stack the code left.
+    pub depth: usize,
+    /// Backward branches the code took.
+    pub backedges: usize,
+}
+
+#[derive(Debug)]
+pub struct CompiledMethod {
+    code: CodeBuffer,
+    /// The pcs the code can be entered at.
+    entries: BTreeSet<usize>,
+    max_locals: usize,
+    max_stack: usize,
+}
+
+impl CompiledMethod {
+    pub fn is_entry(&self, pc: usize) -> bool {
+        self.entries.contains(&pc)
+    }
+
+    /// Runs the code from the entry `pc` until it exits or it took `fuel` backward
+    /// branches. `dirty` tells which of `locals` it stored to.
+    pub fn run(
+        &self,
+        pc: usize,
+        locals: &mut [i64],
+        stack: &mut [i64],
+        dirty: &mut [u8],
+        fuel: u64,
+    ) -> Exit {
+        assert!(
+            self.is_entry(pc),
+            "No compiled code is entered at pc {}",
+            pc
+        );
+        assert!(locals.len() >= self.max_locals && dirty.len() >= self.max_locals);
+        assert!(stack.len() >= self.max_stack);
+        let fuel = fuel.max(1);
+        let mut context = Context {
+            locals: locals.as_mut_ptr(),
+            stack: stack.as_mut_ptr(),
+            dirty: dirty.as_mut_ptr(),
+            fuel,
+            depth: 0,
+        };
+        // The code only touches the slots the method has room for, checked when it was
+        // compiled.
+        let pc = unsafe {
+            let code: extern "sysv64" fn(*mut Context, u64) -> u64 =
+                mem::transmute(self.code.as_ptr());
+            code(&mut context, pc as u64)
+        };
+        Exit {
+            pc: pc as usize,
+            depth: context.depth as usize,
+            backedges: (fuel - context.fuel) as usize,
+        }
+    }
+}
+
+/// Compiles `method`, `None` if no part of it can be compiled.
+pub fn compile(method: &Method) -> Option<CompiledMethod> {
+    if method.is_native() || method.is_abstract() || method.code.is_empty() {
+        return None;
+    }
+    let code = method.decoded_code();
+    let backward_targets: BTreeSet<usize> = code
+        .iter()
+        .filter_map(|(pc, instruction, _)| instruction.branch_target().filter(|x| *x <= pc))
+        .collect();
+    let depths = stack_depths(code, &backward_targets, method.max_stack)?;
+    // Entering right at an exit would get nowhere
+    let entries: BTreeSet<usize> = std::iter::once(0)
+        .chain(backward_targets)
+        .filter(|x| stack_effect(code.at(*x).0).is_some())
+        .collect();
+    if entries.is_empty() {
+        return None;
+    }
+    let machine_code = Templates::new(code, &depths, method.max_locals).emit(&entries)?;
+    Some(CompiledMethod {
+        code: CodeBuffer::new(&machine_code),
+        entries,
+        max_locals: method.max_locals,
+        max_stack: method.max_stack,
+    })
+}
+
+/// The slots an instruction pops and pushes, `None` for those without a template.
+fn stack_effect(instruction: Instruction) -> Option<(usize, usize)> {
+    let effect = match instruction {
+        Instruction::Nop | Instruction::Iinc(..) | Instruction::Goto(_) => (0, 0),
+        Instruction::IconstM1
+        | Instruction::Iconst0
+        | Instruction::Iconst1
+        | Instruction::Iconst2
+        | Instruction::Iconst3
+        | Instruction::Iconst4
+        | Instruction::Iconst5
+        | Instruction::Bipush(_)
+        | Instruction::Iload(_)
+        | Instruction::Iload0
+        | Instruction::Iload1
+        | Instruction::Iload2
+        | Instruction::Iload3 => (0, 1),
+        Instruction::Lconst0
+        | Instruction::Lconst1
+        | Instruction::Lload(_)
+        | Instruction::Lload0
+        | Instruction::Lload1
+        | Instruction::Lload2
+        | Instruction::Lload3 => (0, 2),
+        Instruction::Istore(_)
+        | Instruction::Istore0
+        | Instruction::Istore1
+        | Instruction::Istore2
+        | Instruction::Istore3
+        | Instruction::Pop => (1, 0),
+        Instruction::Lstore(_)
+        | Instruction::Lstore0
+        | Instruction::Lstore1
+        | Instruction::Lstore2
+        | Instruction::Lstore3
+        | Instruction::Pop2 => (2, 0),
+        Instruction::Dup => (1, 2),
+        Instruction::Iadd | Instruction::Imul | Instruction::Iand => (2, 1),
+        Instruction::Ineg => (1, 1),
+        Instruction::Lmul | Instruction::Land => (4, 2),
+        Instruction::Lneg => (2, 2),
+        Instruction::Lcmp => (4, 1),
+        Instruction::Ifeq(_)
+        | Instruction::Ifne(_)
+        | Instruction::Iflt(_)
+        | Instruction::Ifge(_)
+        | Instruction::Ifgt(_)
+        | Instruction::Ifle(_) => (1, 0),
+        Instruction::IfIcmpeq(_)
+        | Instruction::IfIcmpne(_)
+        | Instruction::IfIcmplt(_)
+        | Instruction::IfIcmpge(_)
+        | Instruction::IfIcmpgt(_)
+        | Instruction::IfIcmple(_) => (2, 0),
+        _ => return None,
+    };
+    Some(effect)
+}
+
+/// The operand stack depth before each instruction compiled code can reach from
+/// `entries` and the start of the method, all of them entered with an empty stack.
+/// `None` if the depths don't agree, which verified code never does.
+fn stack_depths(
+    code: &DecodedCode,
+    entries: &BTreeSet<usize>,
+    max_stack: usize,
+) -> Option<BTreeMap<usize, usize>> {
+    let mut depths = BTreeMap::new();
+    let mut pending: Vec<(usize, usize)> = entries.iter().map(|x| (*x, 0)).collect();
+    pending.push((0, 0));
+    while let Some((pc, depth)) = pending.pop() {
+        match depths.insert(pc, depth) {
+            Some(x) if x == depth => continue,
+            Some(_) => return None,
+            None => {}
+        }
+        let (instruction, next_pc) = code.at(pc);
+        let (pops, pushes) = match stack_effect(instruction) {
+            Some(effect) => effect,
+            None => continue,
+        };
+        if depth < pops || depth - pops + pushes > max_stack {
+            return None;
+        }
+        let depth = depth - pops + pushes;
+        if let Some(target) = instruction.branch_target() {
+            pending.push((target, depth));
+        }
+        if !matches!(instruction, Instruction::Goto(_)) {
+            pending.push((next_pc, depth));
+        }
+    }
+    Some(depths)
+}
+
+fn slot(index: usize) -> i32 {
+    index as i32 * 8
+}
+
+struct Templates<'a> {
+    code: &'a DecodedCode,
+    depths: &'a BTreeMap<usize, usize>,
+    max_locals: usize,
+    assembler: Assembler,
+    labels: BTreeMap<usize, Label>,
+    epilogue: Label,
+    /// Where backward branches jump to to spend fuel, with the pc they branch to.
+    backward_branches: Vec<(Label, usize)>,
+}
+
+impl<'a> Templates<'a> {
+    fn new(code: &'a DecodedCode, depths: &'a BTreeMap<usize, usize>, max_locals: usize) -> Self {
+        let mut assembler = Assembler::new();
+        let labels = depths.keys().map(|x| (*x, assembler.new_label())).collect();
+        let epilogue = assembler.new_label();
+        Templates {
+            code,
+            depths,
+            max_locals,
+            assembler,
+            labels,
+            epilogue,
+            backward_branches: Vec::new(),
+        }
+    }
+
+    /// The machine code of a function taking the `Context` and the pc to enter at, and
+    /// returning the pc to continue at. `None` if an instruction uses a local variable
+    /// out of the method's range.
+    fn emit(mut self, entries: &BTreeSet<usize>) -> Option<Vec<u8>> {
+        for reg in [CONTEXT, LOCALS, STACK, DIRTY] {
+            self.assembler.push(reg);
+        }
+        self.assembler.mov(CONTEXT, Reg::Rdi);
+        self.assembler.load(LOCALS, CONTEXT, LOCALS_OFFSET);
+        self.assembler.load(STACK, CONTEXT, STACK_OFFSET);
+        self.assembler.load(DIRTY, CONTEXT, DIRTY_OFFSET);
+        for entry in entries {
+            self.assembler.cmp64_imm(Reg::Rsi, *entry as i32);
+            self.assembler.jcc(Condition::Equal, self.labels[entry]);
+        }
+        self.assembler.ud2();
+
+        let depths = self.depths;
+        for (pc, depth) in depths {
+            self.assembler.bind(self.labels[pc]);
+            let (instruction, _) = self.code.at(*pc);
+            self.template(instruction, *pc, *depth)?;
+        }
+
+        for (label, target) in std::mem::take(&mut self.backward_branches) {
+            self.assembler.bind(label);
+            self.assembler.sub_mem_imm8(CONTEXT, FUEL_OFFSET, 1);
+            self.assembler
+                .jcc(Condition::NotEqual, self.labels[&target]);
+            self.exit(target, self.depths[&target]);
+        }
+
+        self.assembler.bind(self.epilogue);
+        for reg in [DIRTY, STACK, LOCALS, CONTEXT] {
+            self.assembler.pop(reg);
+        }
+        self.assembler.ret();
+        Some(self.assembler.finish())
+    }
+
+    fn template(&mut self, instruction: Instruction, pc: usize, depth: usize) -> Option<()> {
+        let a = &mut self.assembler;
+        match instruction {
+            Instruction::Nop | Instruction::Pop | Instruction::Pop2 => {}
+            Instruction::IconstM1 => a.store_imm(STACK, slot(depth), -1),
+            Instruction::Iconst0 => a.store_imm(STACK, slot(depth), 0),
+            Instruction::Iconst1 => a.store_imm(STACK, slot(depth), 1),
+            Instruction::Iconst2 => a.store_imm(STACK, slot(depth), 2),
+            Instruction::Iconst3 => a.store_imm(STACK, slot(depth), 3),
+            Instruction::Iconst4 => a.store_imm(STACK, slot(depth), 4),
+            Instruction::Iconst5 => a.store_imm(STACK, slot(depth), 5),
+            Instruction::Bipush(val) => a.store_imm(STACK, slot(depth), val),
+            Instruction::Lconst0 | Instruction::Lconst1 => {
+                let val = (instruction == Instruction::Lconst1) as i32;
+                a.store_imm(STACK, slot(depth), val);
+                a.store_imm(STACK, slot(depth + 1), 0);
+            }
+            Instruction::Iload(index) => self.load(index, 1, depth)?,
+            Instruction::Iload0 => self.load(0, 1, depth)?,
+            Instruction::Iload1 => self.load(1, 1, depth)?,
+            Instruction::Iload2 => self.load(2, 1, depth)?,
+            Instruction::Iload3 => self.load(3, 1, depth)?,
+            Instruction::Lload(index) => self.load(index, 2, depth)?,
+            Instruction::Lload0 => self.load(0, 2, depth)?,
+            Instruction::Lload1 => self.load(1, 2, depth)?,
+            Instruction::Lload2 => self.load(2, 2, depth)?,
+            Instruction::Lload3 => self.load(3, 2, depth)?,
+            Instruction::Istore(index) => self.store(index, 1, depth)?,
+            Instruction::Istore0 => self.store(0, 1, depth)?,
+            Instruction::Istore1 => self.store(1, 1, depth)?,
+            Instruction::Istore2 => self.store(2, 1, depth)?,
+            Instruction::Istore3 => self.store(3, 1, depth)?,
+            Instruction::Lstore(index) => self.store(index, 2, depth)?,
+            Instruction::Lstore0 => self.store(0, 2, depth)?,
+            Instruction::Lstore1 => self.store(1, 2, depth)?,
+            Instruction::Lstore2 => self.store(2, 2, depth)?,
+            Instruction::Lstore3 => self.store(3, 2, depth)?,
+            Instruction::Iinc(index, delta) => {
+                if index >= self.max_locals {
+                    return None;
+                }
+                a.load(Reg::Rax, LOCALS, slot(index));
+                a.add32_imm(Reg::Rax, delta);
+                a.movsxd(Reg::Rax, Reg::Rax);
+                a.store(LOCALS, slot(index), Reg::Rax);
+                a.store_byte(DIRTY, index as i32, Kind::Num as u8);
+            }
+            Instruction::Dup => {
+                a.load(Reg::Rax, STACK, slot(depth - 1));
+                a.store(STACK, slot(depth), Reg::Rax);
+            }
+            Instruction::Iadd | Instruction::Imul | Instruction::Iand => {
+                a.load(Reg::Rax, STACK, slot(depth - 2));
+                a.load(Reg::Rcx, STACK, slot(depth - 1));
+                match instruction {
+                    Instruction::Iadd => a.add32(Reg::Rax, Reg::Rcx),
+                    Instruction::Imul => a.imul32(Reg::Rax, Reg::Rcx),
+                    _ => a.and32(Reg::Rax, Reg::Rcx),
+                }
+                a.movsxd(Reg::Rax, Reg::Rax);
+                a.store(STACK, slot(depth - 2), Reg::Rax);
+            }
+            Instruction::Ineg => {
+                a.load(Reg::Rax, STACK, slot(depth - 1));
+                a.neg32(Reg::Rax);
+                a.movsxd(Reg::Rax, Reg::Rax);
+                a.store(STACK, slot(depth - 1), Reg::Rax);
+            }
+            Instruction::Lmul | Instruction::Land => {
+                self.load_long(Reg::Rax, depth - 4);
+                self.load_long(Reg::Rcx, depth - 2);
+                let a = &mut self.assembler;
+                match instruction {
+                    Instruction::Lmul => a.imul64(Reg::Rax, Reg::Rcx),
+                    _ => a.and64(Reg::Rax, Reg::Rcx),
+                }
+                self.store_long(depth - 4);
+            }
+            Instruction::Lneg => {
+                self.load_long(Reg::Rax, depth - 2);
+                self.assembler.neg64(Reg::Rax);
+                self.store_long(depth - 2);
+            }
+            Instruction::Lcmp => {
+                self.load_long(Reg::Rax, depth - 4);
+                self.load_long(Reg::Rcx, depth - 2);
+                let a = &mut self.assembler;
+                a.cmp64(Reg::Rax, Reg::Rcx);
+                a.set(Condition::Greater, Reg::Rdx);
+                a.set(Condition::Less, Reg::Rcx);
+                a.movzx8(Reg::Rax, Reg::Rdx);
+                a.movzx8(Reg::Rcx, Reg::Rcx);
+                a.sub32(Reg::Rax, Reg::Rcx);
+                a.movsxd(Reg::Rax, Reg::Rax);
+                a.store(STACK, slot(depth - 4), Reg::Rax);
+            }
+            Instruction::Ifeq(target)
+            | Instruction::Ifne(target)
+            | Instruction::Iflt(target)
+            | Instruction::Ifge(target)
+            | Instruction::Ifgt(target)
+            | Instruction::Ifle(target) => {
+                a.load(Reg::Rax, STACK, slot(depth - 1));
+                a.test32(Reg::Rax, Reg::Rax);
+                self.branch(Some(condition(instruction)), pc, target);
+            }
+            Instruction::IfIcmpeq(target)
+            | Instruction::IfIcmpne(target)
+            | Instruction::IfIcmplt(target)
+            | Instruction::IfIcmpge(target)
+            | Instruction::IfIcmpgt(target)
+            | Instruction::IfIcmple(target) => {
+                a.load(Reg::Rax, STACK, slot(depth - 2));
+                a.load(Reg::Rcx, STACK, slot(depth - 1));
+                a.cmp32(Reg::Rax, Reg::Rcx);
+                self.branch(Some(condition(instruction)), pc, target);
+            }
+            Instruction::Goto(target) => self.branch(None, pc, target),
+            _ => self.exit(pc, depth),
+        }
+        Some(())
+    }
+
+    /// Pushes the `size` slots of the local variables from `index`.
+    fn load(&mut self, index: usize, size: usize, depth: usize) -> Option<()> {
+        if index + size > self.max_locals {
+            return None;
+        }
+        for i in 0..size {
+            self.assembler.load(Reg::Rax, LOCALS, slot(index + i));
+            self.assembler.store(STACK, slot(depth + i), Reg::Rax);
+        }
+        Some(())
+    }
+
+    /// Pops `size` slots into the local variables from `index`.
+    fn store(&mut self, index: usize, size: usize, depth: usize) -> Option<()> {
+        if index + size > self.max_locals {
+            return None;
+        }
+        for i in 0..size {
+            self.assembler.load(Reg::Rax, STACK, slot(depth - size + i));
+            self.assembler.store(LOCALS, slot(index + i), Reg::Rax);
+            self.assembler
+                .store_byte(DIRTY, (index + i) as i32, Kind::Num as u8);
+        }
+        Some(())
+    }
+
+    /// Loads the long in the stack slots from `index` into `dst`, the low half first.
+    fn load_long(&mut self, dst: Reg, index: usize) {
+        let a = &mut self.assembler;
+        a.load32(dst, STACK, slot(index));
+        a.load(Reg::Rdx, STACK, slot(index + 1));
+        a.shl64(Reg::Rdx, 32);
+        a.or64(dst, Reg::Rdx);
+    }
+
+    /// Stores the long in rax to the stack slots from `index`, each half sign-extended.
+    fn store_long(&mut self, index: usize) {
+        let a = &mut self.assembler;
+        a.movsxd(Reg::Rdx, Reg::Rax);
+        a.store(STACK, slot(index), Reg::Rdx);
+        a.sar64(Reg::Rax, 32);
+        a.store(STACK, slot(index + 1), Reg::Rax);
+    }
+
+    /// Jumps to `target` if `condition` holds, always without one. Backward branches go
+    /// through a stub spending fuel.
+    fn branch(&mut self, condition: Option<Condition>, pc: usize, target: usize) {
+        let label = if target <= pc {
+            let stub = self.assembler.new_label();
+            self.backward_branches.push((stub, target));
+            stub
+        } else {
+            self.labels[&target]
+        };
+        match condition {
+            Some(condition) => self.assembler.jcc(condition, label),
+            None => self.assembler.jmp(label),
+        }
+    }
+
+    /// Returns to the interpreter at `pc` with `depth` slots on the stack.
+    fn exit(&mut self, pc: usize, depth: usize) {
+        self.assembler
+            .store_imm(CONTEXT, DEPTH_OFFSET, depth as i32);
+        self.assembler.mov_imm(Reg::Rax, pc as i32);
+        self.assembler.jmp(self.epilogue);
+    }
+}
+
+/// The condition a conditional branch jumps on, comparing with 0 or the other value.
+fn condition(instruction: Instruction) -> Condition {
+    match instruction {
+        Instruction::Ifeq(_) | Instruction::IfIcmpeq(_) => Condition::Equal,
+        Instruction::Ifne(_) | Instruction::IfIcmpne(_) => Condition::NotEqual,
+        Instruction::Iflt(_) | Instruction::IfIcmplt(_) => Condition::Less,
+        Instruction::Ifge(_) | Instruction::IfIcmpge(_) => Condition::GreaterOrEqual,
+        Instruction::Ifgt(_) | Instruction::IfIcmpgt(_) => Condition::Greater,
+        _ => Condition::LessOrEqual,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::jit::compiler::*;
+    use crate::rtda::heap::class_loader::ClassLoader;
+
+    fn method(name: &str, descriptor: &str) -> Rc<Method> {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("jit/Loops");
+        class.lookup_method(name, descriptor).unwrap()
+    }
+
+    fn slots(method: &Method) -> (Vec<i64>, Vec<i64>, Vec<u8>) {
+        (
+            vec![0; method.max_locals],
+            vec![0; method.max_stack],
+            vec![0; method.max_locals],
+        )
+    }
+
+    // sum(I)I: the loop runs compiled and the code exits at ireturn
+    #[test]
+    fn compile_loop() {
+        let method = method("sum", "(I)I");
+        let compiled = compile(&method).unwrap();
+        assert!(compiled.is_entry(0));
+        let (mut locals, mut stack, mut dirty) = slots(&method);
+        locals[0] = 100;
+        let exit = compiled.run(0, &mut locals, &mut stack, &mut dirty, 1000);
+        let (instruction, _) = method.decoded_code().at(exit.pc);
+        assert_eq!(instruction, Instruction::Ireturn);
+        assert_eq!((exit.depth, stack[0]), (1, 5050));
+        assert_eq!(dirty, vec![0, 1, 1]);
+    }
+
+    // power(JI)J: longs split over two slots
+    #[test]
+    fn compile_long() {
+        let method = method("power", "(JI)J");
+        let compiled = compile(&method).unwrap();
+        let (mut locals, mut stack, mut dirty) = slots(&method);
+        locals[0] = 3;
+        locals[2] = 30;
+        let exit = compiled.run(0, &mut locals, &mut stack, &mut dirty, 1000);
+        assert_eq!(exit.depth, 2);
+        let val = (stack[0] as u32 as i64) | (stack[1] << 32);
+        assert_eq!(val, 3i64.pow(30));
+    }
+
+    // Without fuel the code exits at the head of the loop, where it can be entered again
+    #[test]
+    fn out_of_fuel() {
+        let method = method("sum", "(I)I");
+        let compiled = compile(&method).unwrap();
+        let (mut locals, mut stack, mut dirty) = slots(&method);
+        locals[0] = 100;
+        let exit = compiled.run(0, &mut locals, &mut stack, &mut dirty, 10);
+        assert!(compiled.is_entry(exit.pc));
+        assert_eq!((exit.depth, exit.backedges), (0, 10));
+        assert_eq!(locals[2], 11);
+        let exit = compiled.run(exit.pc, &mut locals, &mut stack, &mut dirty, 1000);
+        assert_eq!(stack[..exit.depth], [5050]);
+    }
+
+    // sumOfSquares(I)I: the call is an exit, the loop head after it an entry
+    #[test]
+    fn exit_at_call() {
+        let method = method("sumOfSquares", "(I)I");
+        let compiled = compile(&method).unwrap();
+        let (mut locals, mut stack, mut dirty) = slots(&method);
+        locals[0] = 20;
+        let exit = compiled.run(0, &mut locals, &mut stack, &mut dirty, 1000);
+        let (instruction, _) = method.decoded_code().at(exit.pc);
+        assert!(matches!(instruction, Instruction::Invokestatic(_)));
+        assert_eq!(exit.depth, 2);
+    }
+
+    #[test]
+    fn not_compiled() {
+        // <init> is aload_0, invokespecial and return
+        assert!(compile(&method("<init>", "()V")).is_none());
+    }
+}
@@ -0,0 +1,96 @@
+//! Runs each program of the test data, every class with a `main` method, interpreted and
+//! then with the register interpreter and the compilers taking over as early as they can,
+//! and compares what the runs leave in the static fields of the main class.
+
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::classpath::classpath::parse;
+use crate::interpreter::{interpret, Schedule};
+use crate::rtda::frame::Frame;
+use crate::rtda::heap::class_loader::ClassLoader;
+use crate::rtda::thread::Thread;
+
+const TEST_DATA: &str = "src/test_data";
+
+/// How a program is run.
+#[derive(Debug, Clone, Copy)]
+enum Tier {
+    /// By the stack interpreter only.
+    Interpreted,
+    /// By the template compiler from the first invocation or loop.
+    Compiled,
+    /// By the optimizing compiler once a method took that many invocations and loops.
+    #[cfg(feature = "cranelift")]
+    Optimized(u32),
+}
+
+/// The names of the classes with a `main` method, found from their sources.
+fn programs(dir: &Path, programs: &mut Vec<String>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.ends_with("jre") {
+            continue;
+        }
+        if path.is_dir() {
+            self::programs(&path, programs);
+        } else if path.extension().is_some_and(|x| x == "java")
+            && fs::read_to_string(&path)
+                .unwrap()
+                .contains("static void main(String[] args)")
+        {
+            let name = path.strip_prefix(TEST_DATA).unwrap().with_extension("");
+            programs.push(name.to_str().unwrap().replace('\\', "/"));
+        }
+    }
+}
+
+/// The static fields of the main class of `program` once it ran.
+fn run(program: &str, tier: Tier) -> String {
+    let class_path = parse(
+        Some("src/test_data/jre".to_string()),
+        Some(TEST_DATA.to_string()),
+    );
+    let class_loader = ClassLoader::new(class_path);
+    let class = class_loader.load(program);
+    let mut thread = Thread::new();
+    let jit = &thread.vm.jit;
+    match tier {
+        Tier::Interpreted => {
+            jit.enabled.set(false);
+            thread.vm.stack_only.set(true);
+        }
+        Tier::Compiled => {
+            jit.compile_threshold.set(1);
+            #[cfg(feature = "cranelift")]
+            jit.optimize_threshold.set(u32::MAX);
+        }
+        #[cfg(feature = "cranelift")]
+        Tier::Optimized(threshold) => {
+            jit.compile_threshold.set(1);
+            jit.optimize_threshold.set(threshold);
+        }
+    }
+    thread.push_frame(Frame::new(Rc::clone(&class), class.main_method()));
+    interpret(&class_loader, thread, Schedule::default());
+    let static_vars = class.static_vars.borrow();
+    format!("{:?}", static_vars)
+}
+
+#[test]
+fn compiled_like_interpreted() {
+    let mut programs = Vec::new();
+    self::programs(Path::new(TEST_DATA), &mut programs);
+    assert!(programs.iter().any(|x| x == "jit/Shapes"));
+    #[allow(unused_mut)]
+    let mut tiers = vec![Tier::Compiled];
+    #[cfg(feature = "cranelift")]
+    tiers.extend([Tier::Optimized(1), Tier::Optimized(100)]);
+    for program in &programs {
+        let expected = run(program, Tier::Interpreted);
+        for tier in &tiers {
+            assert_eq!(run(program, *tier), expected, "{} run {:?}", program, tier);
+        }
+    }
+}
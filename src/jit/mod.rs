@@ -0,0 +1,251 @@
+//! Tiered execution: methods start out interpreted and the hot ones, those invoked or
+//! looping more than a threshold, get compiled to x86-64 machine code by the template
+//! compiler. The interpreter enters compiled code where it can and compiled code returns
+//! to the interpreter, deoptimizing, wherever it has no template for an instruction.
+//! With the `cranelift` feature, the methods that get hotter still are compiled again by
+//! the optimizing compiler, which takes over from the template compiler.
+//!
+//! Only built with the `jit` feature, on x86-64 Linux. `-Xint` turns it off at startup.
+
+#[cfg(not(all(target_arch = "x86_64", target_os = "linux")))]
+compile_error!("The jit feature needs x86-64 Linux");
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::rtda::frame::Frame;
+use crate::rtda::heap::object::ObjectRef;
+use crate::rtda::slot::Slot;
+use crate::rtda::thread::Thread;
+
+pub mod assembler;
+mod code_buffer;
+pub mod compiler;
+#[cfg(test)]
+mod differential;
+#[cfg(feature = "cranelift")]
+pub mod optimizer;
+pub mod profile;
+#[cfg(feature = "cranelift")]
+mod runtime;
+
+/// How many invocations and backward branches taken make a method hot.
+pub const COMPILE_THRESHOLD: u32 = 1000;
+
+/// How many make a method hot enough for the optimizing compiler.
+#[cfg(feature = "cranelift")]
+pub const OPTIMIZE_THRESHOLD: u32 = 10_000;
+
+/// Backward branches compiled code takes before it returns to the interpreter, so that
+/// a long loop doesn't keep other threads from running.
+pub const FUEL: u64 = 10_000;
+
+/// What compiled code holds in a slot, and marks the local variables it stores to with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Kind {
+    Num = 1,
+    Ref = 2,
+}
+
+/// The null reference in compiled code, which holds the others as their index.
+pub const NULL: i64 = -1;
+
+pub fn encode_ref(reference: Option<ObjectRef>) -> i64 {
+    reference.map_or(NULL, |x| x.0 as i64)
+}
+
+pub fn decode_ref(val: i64) -> Option<ObjectRef> {
+    (val != NULL).then_some(ObjectRef(val as usize))
+}
+
+/// Settings and statistics of the compilers, shared by every thread of the VM.
+#[derive(Debug)]
+pub struct Jit {
+    pub enabled: Cell<bool>,
+    pub compile_threshold: Cell<u32>,
+    /// Methods compiled so far.
+    pub compilations: Cell<usize>,
+    #[cfg(feature = "cranelift")]
+    pub optimize_threshold: Cell<u32>,
+    /// Methods the optimizing compiler compiled so far.
+    #[cfg(feature = "cranelift")]
+    pub optimizations: Cell<usize>,
+}
+
+impl Default for Jit {
+    fn default() -> Self {
+        Jit {
+            enabled: Cell::new(true),
+            compile_threshold: Cell::new(COMPILE_THRESHOLD),
+            compilations: Cell::new(0),
+            #[cfg(feature = "cranelift")]
+            optimize_threshold: Cell::new(OPTIMIZE_THRESHOLD),
+            #[cfg(feature = "cranelift")]
+            optimizations: Cell::new(0),
+        }
+    }
+}
+
+/// Counts a backward branch the interpreter took in the current frame.
+pub fn count_backedge(thread: &Thread) {
+    let method = &thread.current_frame().method;
+    method.profile.count_backedge(method, &thread.vm.jit);
+}
+
+/// Runs the compiled code of the current method if there's some to enter at `pc`, and
+/// returns the pc the interpreter continues at.
+///
+/// The local variables and the operand stack are copied in, then the stack the code
+/// leaves and the variables it stored to are copied back to the frame. The template
+/// compiler's code is only entered with an empty stack.
+pub fn run_compiled(pc: usize, thread: &mut Thread) -> Option<usize> {
+    if !thread.vm.jit.enabled.get() {
+        return None;
+    }
+    let vm = Rc::clone(&thread.vm);
+    #[cfg(feature = "cranelift")]
+    let heap = Rc::clone(&thread.heap);
+    let frame = thread.current_frame_mut();
+    let method = Rc::clone(&frame.method);
+
+    #[cfg(feature = "cranelift")]
+    if let Some(optimized) = method.profile.optimized() {
+        let depth = optimized.stack_kinds(pc).len();
+        if optimized.is_entry(pc) && frame.operand_stack.len() == depth {
+            let mut locals = copy_locals(frame);
+            let mut stack = vec![0; method.max_stack];
+            let mut dirty = vec![0; method.max_locals];
+            for (val, slot) in stack.iter_mut().zip(frame.operand_stack.pop_slots(depth)) {
+                *val = match slot {
+                    Slot::Num(val) => val as i64,
+                    Slot::Ref(val) => encode_ref(val),
+                };
+            }
+            let exit = optimized.run(pc, &mut locals, &mut stack, &mut dirty, FUEL, &heap);
+            copy_back(frame, &locals, &dirty);
+            let kinds = optimized.stack_kinds(exit.pc);
+            for (val, kind) in stack[..exit.depth].iter().zip(kinds) {
+                frame.operand_stack.push_slot(slot(*val, *kind));
+            }
+            let backedges = exit.backedges.try_into().unwrap_or(u32::MAX);
+            method.profile.count_backedges(backedges, &method, &vm.jit);
+            return Some(exit.pc);
+        }
+    }
+
+    let compiled = method.profile.compiled()?;
+    if !compiled.is_entry(pc) || !frame.operand_stack.is_empty() {
+        return None;
+    }
+    let mut locals = copy_locals(frame);
+    let mut stack = vec![0; method.max_stack];
+    let mut dirty = vec![0; method.max_locals];
+    let exit = compiled.run(pc, &mut locals, &mut stack, &mut dirty, FUEL);
+    copy_back(frame, &locals, &dirty);
+    for val in &stack[..exit.depth] {
+        frame.operand_stack.push_int(*val as i32);
+    }
+    let backedges = exit.backedges.try_into().unwrap_or(u32::MAX);
+    method.profile.count_backedges(backedges, &method, &vm.jit);
+    Some(exit.pc)
+}
+
+fn copy_locals(frame: &Frame) -> Vec<i64> {
+    (0..frame.method.max_locals)
+        .map(|x| match frame.local_vars.try_get_slot(x) {
+            Some(Slot::Num(val)) => val as i64,
+            Some(Slot::Ref(val)) => encode_ref(val),
+            None => 0,
+        })
+        .collect()
+}
+
+/// Copies back the local variables compiled code stored to.
+fn copy_back(frame: &mut Frame, locals: &[i64], dirty: &[u8]) {
+    for (index, val) in locals.iter().enumerate() {
+        match dirty[index] {
+            0 => {}
+            x if x == Kind::Ref as u8 => frame.local_vars.set_slot(index, slot(*val, Kind::Ref)),
+            _ => frame.local_vars.set_int(index, *val as i32),
+        }
+    }
+}
+
+fn slot(val: i64, kind: Kind) -> Slot {
+    match kind {
+        Kind::Num => Slot::Num(val as i32),
+        Kind::Ref => Slot::Ref(decode_ref(val)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::interpreter::{interpret, Schedule};
+    use crate::jit::*;
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class_loader::ClassLoader;
+
+    // Loops.main stores what the loops computed in its static fields
+    fn run_main(compile_threshold: Option<u32>) -> (i32, i64, usize) {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("jit/Loops");
+        let mut thread = Thread::new();
+        match compile_threshold {
+            Some(threshold) => thread.vm.jit.compile_threshold.set(threshold),
+            None => thread.vm.jit.enabled.set(false),
+        }
+        thread.push_frame(Frame::new(Rc::clone(&class), class.main_method()));
+        let vm = Rc::clone(&thread.vm);
+        interpret(&class_loader, thread, Schedule::default());
+        let field = class.lookup_field("longResult", "J").unwrap();
+        let static_vars = class.static_vars.borrow();
+        (
+            static_vars.get_int(0),
+            static_vars.get_long(field.slot_id),
+            vm.jit.compilations.get(),
+        )
+    }
+
+    #[test]
+    fn interpreted() {
+        assert_eq!(run_main(None), (7404, 205891132094649, 0));
+    }
+
+    #[test]
+    fn compiled_like_interpreted() {
+        for threshold in [1, 5, 50] {
+            let (result, long_result, compilations) = run_main(Some(threshold));
+            assert_eq!((result, long_result), (7404, 205891132094649));
+            assert!(compilations > 0);
+        }
+    }
+
+    #[test]
+    fn profile() {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("jit/Loops");
+        let thread = Thread::new();
+        let method = class.lookup_method("sum", "(I)I").unwrap();
+        method.profile.count_invocation(&method, &thread.vm.jit);
+        method.profile.count_backedge(&method, &thread.vm.jit);
+        assert_eq!(method.profile.invocations(), 1);
+        assert_eq!(method.profile.backedges(), 1);
+        assert!(method.profile.compiled().is_none());
+        thread.vm.jit.compile_threshold.set(3);
+        method.profile.count_backedge(&method, &thread.vm.jit);
+        assert!(method.profile.compiled().is_some());
+        assert_eq!(thread.vm.jit.compilations.get(), 1);
+    }
+}
@@ -0,0 +1,1133 @@
+//! The optimizing tier: the methods that stay hot once compiled by the template compiler
+//! are translated from bytecode to Cranelift IR and compiled again by Cranelift. The
+//! local variables and the operand stack slots become variables of the IR, which
+//! Cranelift turns into SSA values, so they stay in registers between the entry and the
+//! exit of the code.
+//!
+//! Besides the instructions of the template compiler, the code handles references, null
+//! checks and `getfield`, which calls into the VM runtime. An invocation is an exit: the
+//! interpreter performs it and enters the code again right after it returns. The
+//! exception is an `invokevirtual` that only ever saw one class of receiver, calling a
+//! small method without loops or calls of its own: the target is inlined behind a guard
+//! on the class of the receiver, and the code deoptimizes at the call site when the
+//! guard fails or the inlined body can't go on.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::mem;
+use std::rc::Rc;
+
+use cranelift_codegen::entity::EntityRef;
+use cranelift_codegen::ir::condcodes::IntCC;
+use cranelift_codegen::ir::types::{I32, I64};
+use cranelift_codegen::ir::{
+    AbiParam, Block, InstBuilder, MemFlags, SigRef, Signature, TrapCode, UserFuncName, Value,
+};
+use cranelift_codegen::settings::{self, Configurable};
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext, Switch, Variable};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{default_libcall_names, Linkage, Module};
+
+use crate::classfile::constant_info::ConstantInfo;
+use crate::gc::heap::Heap;
+use crate::instruction::decoder::Instruction;
+use crate::jit::compiler::Exit;
+use crate::jit::{runtime, Kind, NULL};
+use crate::rtda::heap::class::Class;
+use crate::rtda::heap::field::Field;
+use crate::rtda::heap::method::Method;
+
+/// The longest code, in bytes, of a method inlined at a call site.
+pub const MAX_INLINE_SIZE: usize = 35;
+
+// Offsets of the fields of `Context`
+const LOCALS_OFFSET: i32 = 0;
+const STACK_OFFSET: i32 = 8;
+const DIRTY_OFFSET: i32 = 16;
+const FUEL_OFFSET: i32 = 24;
+const DEPTH_OFFSET: i32 = 32;
+const HEAP_OFFSET: i32 = 40;
+
+#[repr(C)]
+struct Context {
+    locals: *mut i64,
+    stack: *mut i64,
+    /// Set to the kind of each local variable the code stores to.
+    dirty: *mut u8,
+    /// Backward branches the code may still take before it returns to the interpreter.
+    fuel: u64,
+    /// The operand stack depth when the code returned.
+    depth: u64,
+    heap: *const RefCell<Heap>,
+}
+
+pub struct OptimizedMethod {
+    /// Owns the memory the code is in.
+    _module: JITModule,
+    code: *const u8,
+    /// The pcs the code can be entered at.
+    entries: BTreeSet<usize>,
+    /// The kinds of the operand stack slots before each instruction the code reaches.
+    stack_kinds: BTreeMap<usize, Vec<Kind>>,
+    /// Call sites the code inlined the target of.
+    inlined: usize,
+    max_locals: usize,
+    max_stack: usize,
+}
+
+impl fmt::Debug for OptimizedMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("OptimizedMethod")
+            .field("code", &self.code)
+            .field("entries", &self.entries)
+            .field("inlined", &self.inlined)
+            .finish()
+    }
+}
+
+impl OptimizedMethod {
+    pub fn is_entry(&self, pc: usize) -> bool {
+        self.entries.contains(&pc)
+    }
+
+    /// The operand stack the code is entered with at `pc`, or leaves when it exits there.
+    pub fn stack_kinds(&self, pc: usize) -> &[Kind] {
+        self.stack_kinds.get(&pc).map_or(&[], |x| x)
+    }
+
+    pub fn inlined(&self) -> usize {
+        self.inlined
+    }
+
+    /// Runs the code from the entry `pc`, with the operand stack in the first slots of
+    /// `stack`, until it exits or it took `fuel` backward branches. `dirty` tells which
+    /// of `locals` it stored to, and what.
+    pub fn run(
+        &self,
+        pc: usize,
+        locals: &mut [i64],
+        stack: &mut [i64],
+        dirty: &mut [u8],
+        fuel: u64,
+        heap: &Rc<RefCell<Heap>>,
+    ) -> Exit {
+        assert!(
+            self.is_entry(pc),
+            "No optimized code is entered at pc {}",
+            pc
+        );
+        assert!(locals.len() >= self.max_locals && dirty.len() >= self.max_locals);
+        assert!(stack.len() >= self.max_stack);
+        let fuel = fuel.max(1);
+        let mut context = Context {
+            locals: locals.as_mut_ptr(),
+            stack: stack.as_mut_ptr(),
+            dirty: dirty.as_mut_ptr(),
+            fuel,
+            depth: 0,
+            heap: Rc::as_ptr(heap),
+        };
+        // The code only touches the slots the method has room for, and the heap isn't
+        // borrowed while it runs.
+        let pc = unsafe {
+            let code: extern "C" fn(*mut Context, i64) -> i64 = mem::transmute(self.code);
+            code(&mut context, pc as i64)
+        };
+        Exit {
+            pc: pc as usize,
+            depth: context.depth as usize,
+            backedges: (fuel - context.fuel) as usize,
+        }
+    }
+}
+
+/// Compiles `method` with Cranelift, `None` if no part of it can be.
+pub fn optimize(method: &Method) -> Option<OptimizedMethod> {
+    if method.is_native() || method.is_abstract() || method.code.is_empty() {
+        return None;
+    }
+    let class = method.class();
+    let code = method.decoded_code();
+    let kinds = stack_kinds(&class, method)?;
+    let mut candidates: BTreeSet<usize> = code
+        .iter()
+        .filter_map(|(pc, instruction, _)| instruction.branch_target().filter(|x| *x <= pc))
+        .collect();
+    candidates.insert(0);
+    for (pc, stack) in &kinds {
+        let (instruction, next_pc) = code.at(*pc);
+        if let Some(Step::Invoke) = transfer(&class, instruction, &mut stack.clone()) {
+            candidates.insert(next_pc);
+        }
+    }
+    // Entering right at an exit would get nowhere
+    let entries: BTreeSet<usize> = candidates
+        .into_iter()
+        .filter(|x| {
+            kinds
+                .get(x)
+                .is_some_and(|stack| enterable(&class, code.at(*x).0, stack))
+        })
+        .collect();
+    if entries.is_empty() {
+        return None;
+    }
+
+    let mut module = module()?;
+    let mut ctx = module.make_context();
+    ctx.func.signature.params = vec![AbiParam::new(I64); 2];
+    ctx.func.signature.returns = vec![AbiParam::new(I64)];
+    let name = format!("{}.{}{}", class.name, method.name(), method.descriptor());
+    let id = module
+        .declare_function(&name, Linkage::Local, &ctx.func.signature)
+        .ok()?;
+    ctx.func.name = UserFuncName::user(0, id.as_u32());
+    let runtime_signature = |args: usize| {
+        let mut signature = module.make_signature();
+        signature.params = vec![AbiParam::new(I64); args];
+        signature.returns = vec![AbiParam::new(I64)];
+        signature
+    };
+    let signatures = [runtime_signature(2), runtime_signature(3)];
+    let mut builder_ctx = FunctionBuilderContext::new();
+    let builder = FunctionBuilder::new(&mut ctx.func, &mut builder_ctx);
+    let inlined =
+        Translator::new(builder, signatures).translate(method, kinds.clone(), &entries)?;
+    module.define_function(id, &mut ctx).ok()?;
+    module.clear_context(&mut ctx);
+    module.finalize_definitions().ok()?;
+    let code = module.get_finalized_function(id);
+    Some(OptimizedMethod {
+        _module: module,
+        code,
+        entries,
+        stack_kinds: kinds,
+        inlined,
+        max_locals: method.max_locals,
+        max_stack: method.max_stack,
+    })
+}
+
+fn module() -> Option<JITModule> {
+    let mut flags = settings::builder();
+    flags.set("use_colocated_libcalls", "false").ok()?;
+    flags.set("is_pic", "false").ok()?;
+    flags.set("opt_level", "speed").ok()?;
+    let isa = cranelift_native::builder()
+        .ok()?
+        .finish(settings::Flags::new(flags))
+        .ok()?;
+    Some(JITModule::new(JITBuilder::with_isa(
+        isa,
+        default_libcall_names(),
+    )))
+}
+
+/// Where an instruction the code translates goes on to.
+enum Step {
+    /// The next instruction, or the target of a branch.
+    Next,
+    /// The next instruction once the interpreter invoked a method, already resolved.
+    Invoke,
+    /// Out of the method.
+    Return,
+}
+
+/// Applies `instruction`, from a method of `class`, to the kinds of `stack`. `None` if
+/// it's an exit, or if the stack is too shallow for it.
+fn transfer(class: &Class, instruction: Instruction, stack: &mut Vec<Kind>) -> Option<Step> {
+    use Kind::{Num, Ref};
+    let (pops, pushes): (usize, &[Kind]) = match instruction {
+        Instruction::Nop | Instruction::Iinc(..) | Instruction::Goto(_) => (0, &[]),
+        Instruction::IconstM1
+        | Instruction::Iconst0
+        | Instruction::Iconst1
+        | Instruction::Iconst2
+        | Instruction::Iconst3
+        | Instruction::Iconst4
+        | Instruction::Iconst5
+        | Instruction::Bipush(_)
+        | Instruction::Iload(_)
+        | Instruction::Iload0
+        | Instruction::Iload1
+        | Instruction::Iload2
+        | Instruction::Iload3 => (0, &[Num]),
+        Instruction::Lconst0
+        | Instruction::Lconst1
+        | Instruction::Lload(_)
+        | Instruction::Lload0
+        | Instruction::Lload1
+        | Instruction::Lload2
+        | Instruction::Lload3 => (0, &[Num, Num]),
+        Instruction::AconstNull
+        | Instruction::Aload(_)
+        | Instruction::Aload0
+        | Instruction::Aload1
+        | Instruction::Aload2
+        | Instruction::Aload3 => (0, &[Ref]),
+        Instruction::Istore(_)
+        | Instruction::Istore0
+        | Instruction::Istore1
+        | Instruction::Istore2
+        | Instruction::Istore3
+        | Instruction::Astore(_)
+        | Instruction::Astore0
+        | Instruction::Astore1
+        | Instruction::Astore2
+        | Instruction::Astore3
+        | Instruction::Pop
+        | Instruction::Ifeq(_)
+        | Instruction::Ifne(_)
+        | Instruction::Iflt(_)
+        | Instruction::Ifge(_)
+        | Instruction::Ifgt(_)
+        | Instruction::Ifle(_)
+        | Instruction::Ifnull(_)
+        | Instruction::Ifnonnull(_) => (1, &[]),
+        Instruction::Lstore(_)
+        | Instruction::Lstore0
+        | Instruction::Lstore1
+        | Instruction::Lstore2
+        | Instruction::Lstore3
+        | Instruction::Pop2
+        | Instruction::IfIcmpeq(_)
+        | Instruction::IfIcmpne(_)
+        | Instruction::IfIcmplt(_)
+        | Instruction::IfIcmpge(_)
+        | Instruction::IfIcmpgt(_)
+        | Instruction::IfIcmple(_)
+        | Instruction::IfAcmpeq(_)
+        | Instruction::IfAcmpne(_) => (2, &[]),
+        Instruction::Dup => {
+            let top = *stack.last()?;
+            stack.push(top);
+            return Some(Step::Next);
+        }
+        Instruction::Iadd | Instruction::Imul | Instruction::Iand => (2, &[Num]),
+        Instruction::Ineg => (1, &[Num]),
+        Instruction::Lmul | Instruction::Land => (4, &[Num, Num]),
+        Instruction::Lneg => (2, &[Num, Num]),
+        Instruction::Lcmp => (4, &[Num]),
+        Instruction::Getfield(index) => {
+            let field = own_field(class, index)?;
+            let kind = if field.is_ref() { Ref } else { Num };
+            pop_push(stack, 1, &vec![kind; field.slot_count()])?;
+            return Some(Step::Next);
+        }
+        Instruction::Invokevirtual(index)
+        | Instruction::Invokespecial(index)
+        | Instruction::Invokestatic(index)
+        | Instruction::Invokeinterface(index) => {
+            let method = class.resolved_method_refs.borrow().get(index).cloned()?;
+            pop_push(
+                stack,
+                method.arg_slot_count,
+                return_kinds(method.descriptor()),
+            )?;
+            return Some(Step::Invoke);
+        }
+        Instruction::Ireturn | Instruction::Freturn | Instruction::Areturn => {
+            return (!stack.is_empty()).then_some(Step::Return)
+        }
+        Instruction::Lreturn | Instruction::Dreturn => {
+            return (stack.len() >= 2).then_some(Step::Return)
+        }
+        Instruction::Return => return Some(Step::Return),
+        _ => return None,
+    };
+    pop_push(stack, pops, pushes)?;
+    Some(Step::Next)
+}
+
+fn pop_push(stack: &mut Vec<Kind>, pops: usize, pushes: &[Kind]) -> Option<()> {
+    stack.truncate(stack.len().checked_sub(pops)?);
+    stack.extend_from_slice(pushes);
+    Some(())
+}
+
+fn return_kinds(descriptor: &str) -> &'static [Kind] {
+    match descriptor.rsplit(')').next() {
+        Some("V") => &[],
+        Some("J") | Some("D") => &[Kind::Num, Kind::Num],
+        Some(x) if x.starts_with('L') || x.starts_with('[') => &[Kind::Ref],
+        _ => &[Kind::Num],
+    }
+}
+
+/// Whether the code can be entered at `instruction`: not at an exit, and not at an
+/// instruction that may exit without getting anywhere.
+fn enterable(class: &Class, instruction: Instruction, stack: &[Kind]) -> bool {
+    !matches!(instruction, Instruction::Getfield(_))
+        && matches!(
+            transfer(class, instruction, &mut stack.to_vec()),
+            Some(Step::Next)
+        )
+}
+
+/// The instance field `index` refers to, when it's a field of `class` itself or one it
+/// inherits. Compiling doesn't load or resolve anything, any other `getfield` is an exit.
+fn own_field(class: &Class, index: usize) -> Option<Rc<Field>> {
+    let (class_index, name_and_type_index) = match class.constant_pool.get(index) {
+        ConstantInfo::FieldRef {
+            class_index,
+            name_and_type_index,
+        } => (*class_index as usize, *name_and_type_index as usize),
+        _ => return None,
+    };
+    if class.constant_pool.get_class_name(class_index) != class.name {
+        return None;
+    }
+    let (name, descriptor) = class.constant_pool.get_name_and_type(name_and_type_index);
+    class
+        .lookup_field(name, descriptor)
+        .filter(|x| !x.is_static() && x.is_accessible_to(class))
+}
+
+/// The kinds of the operand stack before each instruction reached from the start of
+/// `method` or from the target of a backward branch, entered with an empty stack. `None`
+/// if the paths to an instruction don't agree or the stack overflows, which verified
+/// code never does.
+fn stack_kinds(class: &Class, method: &Method) -> Option<BTreeMap<usize, Vec<Kind>>> {
+    let code = method.decoded_code();
+    let mut kinds: BTreeMap<usize, Vec<Kind>> = BTreeMap::new();
+    let mut pending: Vec<(usize, Vec<Kind>)> = code
+        .iter()
+        .filter_map(|(pc, instruction, _)| instruction.branch_target().filter(|x| *x <= pc))
+        .map(|x| (x, Vec::new()))
+        .collect();
+    pending.push((0, Vec::new()));
+    while let Some((pc, stack)) = pending.pop() {
+        match kinds.get(&pc) {
+            Some(x) if *x == stack => continue,
+            Some(_) => return None,
+            None => {}
+        }
+        kinds.insert(pc, stack.clone());
+        let (instruction, next_pc) = code.at(pc);
+        let mut stack = stack;
+        let step = match transfer(class, instruction, &mut stack) {
+            Some(step) => step,
+            None => continue,
+        };
+        if stack.len() > method.max_stack {
+            return None;
+        }
+        match step {
+            Step::Next => {
+                if let Some(target) = instruction.branch_target() {
+                    pending.push((target, stack.clone()));
+                }
+                if !matches!(instruction, Instruction::Goto(_)) {
+                    pending.push((next_pc, stack));
+                }
+            }
+            Step::Invoke => pending.push((next_pc, stack)),
+            Step::Return => {}
+        }
+    }
+    Some(kinds)
+}
+
+/// A method inlined at a call site.
+struct InlineTarget {
+    /// The only class of receiver the inline cache of the call site saw.
+    receiver: Rc<Class>,
+    method: Rc<Method>,
+    kinds: BTreeMap<usize, Vec<Kind>>,
+}
+
+/// The method to inline at the `invokevirtual` at `pc` in `method`. It has to be small,
+/// to return to the call site in a straight line, and to translate entirely.
+fn inline_target(method: &Method, pc: usize, index: usize) -> Option<InlineTarget> {
+    let resolved = method
+        .class()
+        .resolved_method_refs
+        .borrow()
+        .get(index)
+        .cloned()?;
+    let receiver = method.decoded_code().call_cache(pc).monomorphic()?;
+    let target = match resolved.vtable_index {
+        Some(vtable_index) => Rc::clone(receiver.vtable.get(vtable_index)?),
+        None if resolved.is_private() => resolved,
+        None => return None,
+    };
+    if target.is_native()
+        || target.is_abstract()
+        || target.is_synchronized()
+        || target.code.is_empty()
+        || target.code.len() > MAX_INLINE_SIZE
+    {
+        return None;
+    }
+    let class = target.class();
+    let kinds = stack_kinds(&class, &target)?;
+    let code = target.decoded_code();
+    for (pc, stack) in &kinds {
+        let (instruction, _) = code.at(*pc);
+        if instruction.branch_target().is_some_and(|x| x <= *pc) {
+            return None;
+        }
+        match transfer(&class, instruction, &mut stack.clone()) {
+            Some(Step::Next) | Some(Step::Return) => {}
+            _ => return None,
+        }
+    }
+    Some(InlineTarget {
+        receiver,
+        method: target,
+        kinds,
+    })
+}
+
+/// A method being translated, the compiled one or one inlined into it.
+struct Scope<'m> {
+    method: &'m Method,
+    class: Rc<Class>,
+    kinds: BTreeMap<usize, Vec<Kind>>,
+    blocks: BTreeMap<usize, Block>,
+    /// The variable of the first local variable.
+    locals: usize,
+    /// The variable of the first operand stack slot.
+    stack: usize,
+    inlined: Option<Inlined>,
+}
+
+/// Where an inlined method goes when it's done.
+struct Inlined {
+    /// Deoptimizes at the call site, with the state of the caller before the call.
+    deopt: Block,
+    /// The instruction after the call site.
+    continuation: Block,
+    /// The variable of the caller's slot the result goes to.
+    result: usize,
+}
+
+impl Scope<'_> {
+    fn local(&self, index: usize) -> Variable {
+        Variable::new(self.locals + index)
+    }
+
+    fn slot(&self, index: usize) -> Variable {
+        Variable::new(self.stack + index)
+    }
+}
+
+struct Translator<'a> {
+    builder: FunctionBuilder<'a>,
+    /// The signatures of the runtime functions taking 2 and 3 arguments.
+    signatures: [SigRef; 2],
+    context: Option<Value>,
+    variables: usize,
+    inlined: usize,
+}
+
+impl<'a> Translator<'a> {
+    fn new(mut builder: FunctionBuilder<'a>, signatures: [Signature; 2]) -> Self {
+        let signatures = signatures.map(|x| builder.import_signature(x));
+        Translator {
+            builder,
+            signatures,
+            context: None,
+            variables: 0,
+            inlined: 0,
+        }
+    }
+
+    /// Builds the function taking the `Context` and the pc to enter at, returning the pc
+    /// to continue at. Returns how many call sites it inlined, `None` if an instruction
+    /// uses a local variable out of the method's range.
+    fn translate(
+        mut self,
+        method: &Method,
+        kinds: BTreeMap<usize, Vec<Kind>>,
+        entries: &BTreeSet<usize>,
+    ) -> Option<usize> {
+        let entry = self.builder.create_block();
+        self.builder.append_block_params_for_function_params(entry);
+        self.builder.switch_to_block(entry);
+        let context = self.builder.block_params(entry)[0];
+        let pc = self.builder.block_params(entry)[1];
+        self.context = Some(context);
+        let scope = self.scope(method.class(), method, kinds, None);
+
+        let locals = self.load(LOCALS_OFFSET);
+        for index in 0..method.max_locals {
+            let val = self
+                .builder
+                .ins()
+                .load(I64, MemFlags::trusted(), locals, slot(index));
+            self.builder.def_var(scope.local(index), val);
+        }
+        let mut switch = Switch::new();
+        let starts: Vec<(usize, Block)> = entries
+            .iter()
+            .map(|x| (*x, self.builder.create_block()))
+            .collect();
+        for (pc, block) in &starts {
+            switch.set_entry(*pc as u128, *block);
+        }
+        let invalid = self.builder.create_block();
+        switch.emit(&mut self.builder, pc, invalid);
+        for (pc, block) in starts {
+            self.builder.switch_to_block(block);
+            let stack = self.load(STACK_OFFSET);
+            for index in 0..scope.kinds[&pc].len() {
+                let val = self
+                    .builder
+                    .ins()
+                    .load(I64, MemFlags::trusted(), stack, slot(index));
+                self.builder.def_var(scope.slot(index), val);
+            }
+            self.builder.ins().jump(scope.blocks[&pc], &[]);
+        }
+        self.builder.switch_to_block(invalid);
+        self.builder.ins().trap(TrapCode::unwrap_user(1));
+
+        self.scope_body(&scope)?;
+        self.builder.seal_all_blocks();
+        self.builder.finalize();
+        Some(self.inlined)
+    }
+
+    fn scope<'m>(
+        &mut self,
+        class: Rc<Class>,
+        method: &'m Method,
+        kinds: BTreeMap<usize, Vec<Kind>>,
+        inlined: Option<Inlined>,
+    ) -> Scope<'m> {
+        let locals = self.variables;
+        let stack = locals + method.max_locals;
+        self.variables = stack + method.max_stack;
+        for index in locals..self.variables {
+            self.builder.declare_var(Variable::new(index), I64);
+        }
+        let blocks = kinds
+            .keys()
+            .map(|x| (*x, self.builder.create_block()))
+            .collect();
+        Scope {
+            method,
+            class,
+            kinds,
+            blocks,
+            locals,
+            stack,
+            inlined,
+        }
+    }
+
+    fn scope_body(&mut self, scope: &Scope) -> Option<()> {
+        for pc in scope.kinds.keys() {
+            self.builder.switch_to_block(scope.blocks[pc]);
+            self.instruction(scope, *pc)?;
+        }
+        Some(())
+    }
+
+    fn instruction(&mut self, scope: &Scope, pc: usize) -> Option<()> {
+        let depth = scope.kinds[&pc].len();
+        let (instruction, next_pc) = scope.method.decoded_code().at(pc);
+        let constant = match instruction {
+            Instruction::IconstM1 => Some(-1),
+            Instruction::Iconst0 | Instruction::Lconst0 => Some(0),
+            Instruction::Iconst1 | Instruction::Lconst1 => Some(1),
+            Instruction::Iconst2 => Some(2),
+            Instruction::Iconst3 => Some(3),
+            Instruction::Iconst4 => Some(4),
+            Instruction::Iconst5 => Some(5),
+            Instruction::Bipush(val) => Some(val as i64),
+            Instruction::AconstNull => Some(NULL),
+            _ => None,
+        };
+        if let Some(val) = constant {
+            let val = self.builder.ins().iconst(I64, val);
+            self.builder.def_var(scope.slot(depth), val);
+            if matches!(instruction, Instruction::Lconst0 | Instruction::Lconst1) {
+                let high = self.builder.ins().iconst(I64, 0);
+                self.builder.def_var(scope.slot(depth + 1), high);
+            }
+            self.builder.ins().jump(scope.blocks[&next_pc], &[]);
+            return Some(());
+        }
+
+        match instruction {
+            Instruction::Nop | Instruction::Pop | Instruction::Pop2 => {}
+            Instruction::Iload(index) => self.load_local(scope, index, 1, depth)?,
+            Instruction::Iload0 | Instruction::Aload0 => self.load_local(scope, 0, 1, depth)?,
+            Instruction::Iload1 | Instruction::Aload1 => self.load_local(scope, 1, 1, depth)?,
+            Instruction::Iload2 | Instruction::Aload2 => self.load_local(scope, 2, 1, depth)?,
+            Instruction::Iload3 | Instruction::Aload3 => self.load_local(scope, 3, 1, depth)?,
+            Instruction::Aload(index) => self.load_local(scope, index, 1, depth)?,
+            Instruction::Lload(index) => self.load_local(scope, index, 2, depth)?,
+            Instruction::Lload0 => self.load_local(scope, 0, 2, depth)?,
+            Instruction::Lload1 => self.load_local(scope, 1, 2, depth)?,
+            Instruction::Lload2 => self.load_local(scope, 2, 2, depth)?,
+            Instruction::Lload3 => self.load_local(scope, 3, 2, depth)?,
+            Instruction::Istore(index) => self.store_local(scope, index, 1, pc)?,
+            Instruction::Istore0 | Instruction::Astore0 => self.store_local(scope, 0, 1, pc)?,
+            Instruction::Istore1 | Instruction::Astore1 => self.store_local(scope, 1, 1, pc)?,
+            Instruction::Istore2 | Instruction::Astore2 => self.store_local(scope, 2, 1, pc)?,
+            Instruction::Istore3 | Instruction::Astore3 => self.store_local(scope, 3, 1, pc)?,
+            Instruction::Astore(index) => self.store_local(scope, index, 1, pc)?,
+            Instruction::Lstore(index) => self.store_local(scope, index, 2, pc)?,
+            Instruction::Lstore0 => self.store_local(scope, 0, 2, pc)?,
+            Instruction::Lstore1 => self.store_local(scope, 1, 2, pc)?,
+            Instruction::Lstore2 => self.store_local(scope, 2, 2, pc)?,
+            Instruction::Lstore3 => self.store_local(scope, 3, 2, pc)?,
+            Instruction::Iinc(index, delta) => {
+                if index >= scope.method.max_locals {
+                    return None;
+                }
+                let val = self.builder.use_var(scope.local(index));
+                let val = self.builder.ins().iadd_imm(val, delta as i64);
+                let val = self.wrap(val);
+                self.builder.def_var(scope.local(index), val);
+                self.mark_dirty(scope, index, Kind::Num);
+            }
+            Instruction::Dup => {
+                let val = self.builder.use_var(scope.slot(depth - 1));
+                self.builder.def_var(scope.slot(depth), val);
+            }
+            Instruction::Iadd | Instruction::Imul | Instruction::Iand => {
+                let a = self.builder.use_var(scope.slot(depth - 2));
+                let b = self.builder.use_var(scope.slot(depth - 1));
+                let val = match instruction {
+                    Instruction::Iadd => self.builder.ins().iadd(a, b),
+                    Instruction::Imul => self.builder.ins().imul(a, b),
+                    _ => self.builder.ins().band(a, b),
+                };
+                let val = self.wrap(val);
+                self.builder.def_var(scope.slot(depth - 2), val);
+            }
+            Instruction::Ineg => {
+                let val = self.builder.use_var(scope.slot(depth - 1));
+                let val = self.builder.ins().ineg(val);
+                let val = self.wrap(val);
+                self.builder.def_var(scope.slot(depth - 1), val);
+            }
+            Instruction::Lmul | Instruction::Land => {
+                let a = self.long(scope, depth - 4);
+                let b = self.long(scope, depth - 2);
+                let val = match instruction {
+                    Instruction::Lmul => self.builder.ins().imul(a, b),
+                    _ => self.builder.ins().band(a, b),
+                };
+                self.set_long(scope, depth - 4, val);
+            }
+            Instruction::Lneg => {
+                let val = self.long(scope, depth - 2);
+                let val = self.builder.ins().ineg(val);
+                self.set_long(scope, depth - 2, val);
+            }
+            Instruction::Lcmp => {
+                let a = self.long(scope, depth - 4);
+                let b = self.long(scope, depth - 2);
+                let greater = self.builder.ins().icmp(IntCC::SignedGreaterThan, a, b);
+                let less = self.builder.ins().icmp(IntCC::SignedLessThan, a, b);
+                let greater = self.builder.ins().uextend(I64, greater);
+                let less = self.builder.ins().uextend(I64, less);
+                let val = self.builder.ins().isub(greater, less);
+                self.builder.def_var(scope.slot(depth - 4), val);
+            }
+            Instruction::Ifeq(target)
+            | Instruction::Ifne(target)
+            | Instruction::Iflt(target)
+            | Instruction::Ifge(target)
+            | Instruction::Ifgt(target)
+            | Instruction::Ifle(target) => {
+                let val = self.builder.use_var(scope.slot(depth - 1));
+                let taken = self.builder.ins().icmp_imm(condition(instruction), val, 0);
+                return self.branch(scope, Some(taken), pc, target, next_pc);
+            }
+            Instruction::Ifnull(target) | Instruction::Ifnonnull(target) => {
+                let val = self.builder.use_var(scope.slot(depth - 1));
+                let condition = match instruction {
+                    Instruction::Ifnull(_) => IntCC::Equal,
+                    _ => IntCC::NotEqual,
+                };
+                let taken = self.builder.ins().icmp_imm(condition, val, NULL);
+                return self.branch(scope, Some(taken), pc, target, next_pc);
+            }
+            Instruction::IfIcmpeq(target)
+            | Instruction::IfIcmpne(target)
+            | Instruction::IfIcmplt(target)
+            | Instruction::IfIcmpge(target)
+            | Instruction::IfIcmpgt(target)
+            | Instruction::IfIcmple(target)
+            | Instruction::IfAcmpeq(target)
+            | Instruction::IfAcmpne(target) => {
+                let a = self.builder.use_var(scope.slot(depth - 2));
+                let b = self.builder.use_var(scope.slot(depth - 1));
+                let taken = self.builder.ins().icmp(condition(instruction), a, b);
+                return self.branch(scope, Some(taken), pc, target, next_pc);
+            }
+            Instruction::Goto(target) => return self.branch(scope, None, pc, target, next_pc),
+            Instruction::Getfield(index) => {
+                let field = own_field(&scope.class, index)?;
+                let reference = self.builder.use_var(scope.slot(depth - 1));
+                self.null_check(scope, reference, pc, depth);
+                for i in 0..field.slot_count() {
+                    let heap = self.load(HEAP_OFFSET);
+                    let slot_id = self.builder.ins().iconst(I64, (field.slot_id + i) as i64);
+                    let val = self.call(
+                        runtime::get_field as *const () as usize,
+                        &[heap, reference, slot_id],
+                    );
+                    self.builder.def_var(scope.slot(depth - 1 + i), val);
+                }
+            }
+            Instruction::Invokevirtual(index) if scope.inlined.is_none() => {
+                match inline_target(scope.method, pc, index) {
+                    Some(target) => return self.inline(scope, pc, next_pc, target),
+                    None => self.exit(scope, pc, depth),
+                }
+                return Some(());
+            }
+            Instruction::Ireturn
+            | Instruction::Freturn
+            | Instruction::Areturn
+            | Instruction::Lreturn
+            | Instruction::Dreturn
+            | Instruction::Return
+                if scope.inlined.is_some() =>
+            {
+                let inlined = scope.inlined.as_ref().unwrap();
+                let size = match instruction {
+                    Instruction::Return => 0,
+                    Instruction::Lreturn | Instruction::Dreturn => 2,
+                    _ => 1,
+                };
+                for i in 0..size {
+                    let val = self.builder.use_var(scope.slot(depth - size + i));
+                    self.builder.def_var(Variable::new(inlined.result + i), val);
+                }
+                self.builder.ins().jump(inlined.continuation, &[]);
+                return Some(());
+            }
+            _ => {
+                self.exit(scope, pc, depth);
+                return Some(());
+            }
+        }
+        self.builder.ins().jump(scope.blocks[&next_pc], &[]);
+        Some(())
+    }
+
+    /// Inlines `target` at the call site `pc`, behind a guard that the receiver is of
+    /// the class it was profiled with.
+    fn inline(
+        &mut self,
+        scope: &Scope,
+        pc: usize,
+        next_pc: usize,
+        InlineTarget {
+            receiver,
+            method: target,
+            kinds,
+        }: InlineTarget,
+    ) -> Option<()> {
+        let depth = scope.kinds[&pc].len();
+        if target.max_locals < target.arg_slot_count {
+            return None;
+        }
+        // The slot of the receiver, where the result goes too
+        let base = depth - target.arg_slot_count;
+        let heap = self.load(HEAP_OFFSET);
+        let this = self.builder.use_var(scope.slot(base));
+        let class = self.call(runtime::class_of as *const () as usize, &[heap, this]);
+        let expected = Rc::as_ptr(&receiver) as i64;
+        let guarded = self.builder.ins().icmp_imm(IntCC::Equal, class, expected);
+        let body = self.builder.create_block();
+        let deopt = self.builder.create_block();
+        self.builder.ins().brif(guarded, body, &[], deopt, &[]);
+        self.builder.switch_to_block(deopt);
+        self.exit(scope, pc, depth);
+
+        self.builder.switch_to_block(body);
+        let inlined = Inlined {
+            deopt,
+            continuation: scope.blocks[&next_pc],
+            result: scope.stack + base,
+        };
+        let callee = self.scope(target.class(), &target, kinds, Some(inlined));
+        for i in 0..target.arg_slot_count {
+            let val = self.builder.use_var(scope.slot(base + i));
+            self.builder.def_var(callee.local(i), val);
+        }
+        self.builder.ins().jump(callee.blocks[&0], &[]);
+        self.scope_body(&callee)?;
+        self.inlined += 1;
+        Some(())
+    }
+
+    /// Pushes the `size` slots of the local variables from `index`.
+    fn load_local(&mut self, scope: &Scope, index: usize, size: usize, depth: usize) -> Option<()> {
+        if index + size > scope.method.max_locals {
+            return None;
+        }
+        for i in 0..size {
+            let val = self.builder.use_var(scope.local(index + i));
+            self.builder.def_var(scope.slot(depth + i), val);
+        }
+        Some(())
+    }
+
+    /// Pops `size` slots into the local variables from `index`.
+    fn store_local(&mut self, scope: &Scope, index: usize, size: usize, pc: usize) -> Option<()> {
+        if index + size > scope.method.max_locals {
+            return None;
+        }
+        let stack = &scope.kinds[&pc];
+        for i in 0..size {
+            let slot = stack.len() - size + i;
+            let val = self.builder.use_var(scope.slot(slot));
+            self.builder.def_var(scope.local(index + i), val);
+            self.mark_dirty(scope, index + i, stack[slot]);
+        }
+        Some(())
+    }
+
+    /// Records that the local variable `index` of the compiled method holds a `kind`
+    /// now, which inlined methods don't need to.
+    fn mark_dirty(&mut self, scope: &Scope, index: usize, kind: Kind) {
+        if scope.inlined.is_some() {
+            return;
+        }
+        let dirty = self.load(DIRTY_OFFSET);
+        let kind = self.builder.ins().iconst(I64, kind as i64);
+        self.builder
+            .ins()
+            .istore8(MemFlags::trusted(), kind, dirty, index as i32);
+    }
+
+    /// The long in the stack slots from `index`, the low half first.
+    fn long(&mut self, scope: &Scope, index: usize) -> Value {
+        let low = self.builder.use_var(scope.slot(index));
+        let high = self.builder.use_var(scope.slot(index + 1));
+        let low = self.builder.ins().band_imm(low, 0xFFFF_FFFF);
+        let high = self.builder.ins().ishl_imm(high, 32);
+        self.builder.ins().bor(high, low)
+    }
+
+    /// Stores `val` to the stack slots from `index`, each half sign-extended.
+    fn set_long(&mut self, scope: &Scope, index: usize, val: Value) {
+        let low = self.wrap(val);
+        let high = self.builder.ins().sshr_imm(val, 32);
+        self.builder.def_var(scope.slot(index), low);
+        self.builder.def_var(scope.slot(index + 1), high);
+    }
+
+    /// Sign-extends the low 32 bits of `val`, the int it holds.
+    fn wrap(&mut self, val: Value) -> Value {
+        let val = self.builder.ins().ireduce(I32, val);
+        self.builder.ins().sextend(I64, val)
+    }
+
+    /// Jumps to `target` if `taken` is set, always without it, and to `next_pc`
+    /// otherwise. Backward branches go through a block spending fuel.
+    fn branch(
+        &mut self,
+        scope: &Scope,
+        taken: Option<Value>,
+        pc: usize,
+        target: usize,
+        next_pc: usize,
+    ) -> Option<()> {
+        let backward = target <= pc;
+        let target_block = if backward {
+            self.builder.create_block()
+        } else {
+            scope.blocks[&target]
+        };
+        match taken {
+            Some(taken) => {
+                self.builder
+                    .ins()
+                    .brif(taken, target_block, &[], scope.blocks[&next_pc], &[]);
+            }
+            None => {
+                self.builder.ins().jump(target_block, &[]);
+            }
+        }
+        if backward {
+            self.builder.switch_to_block(target_block);
+            let context = self.context?;
+            let fuel = self.load(FUEL_OFFSET);
+            let fuel = self.builder.ins().iadd_imm(fuel, -1);
+            self.builder
+                .ins()
+                .store(MemFlags::trusted(), fuel, context, FUEL_OFFSET);
+            let out_of_fuel = self.builder.create_block();
+            self.builder
+                .ins()
+                .brif(fuel, scope.blocks[&target], &[], out_of_fuel, &[]);
+            self.builder.switch_to_block(out_of_fuel);
+            self.exit(scope, target, scope.kinds[&target].len());
+        }
+        Some(())
+    }
+
+    /// Exits at `pc` if `reference` is null, for the interpreter to throw.
+    fn null_check(&mut self, scope: &Scope, reference: Value, pc: usize, depth: usize) {
+        let null = self.builder.ins().icmp_imm(IntCC::Equal, reference, NULL);
+        let exit = self.builder.create_block();
+        let not_null = self.builder.create_block();
+        self.builder.ins().brif(null, exit, &[], not_null, &[]);
+        self.builder.switch_to_block(exit);
+        self.exit(scope, pc, depth);
+        self.builder.switch_to_block(not_null);
+    }
+
+    /// Returns to the interpreter at `pc` with `depth` slots on the stack, from the call
+    /// site if `scope` is inlined.
+    fn exit(&mut self, scope: &Scope, pc: usize, depth: usize) {
+        if let Some(inlined) = &scope.inlined {
+            self.builder.ins().jump(inlined.deopt, &[]);
+            return;
+        }
+        let locals = self.load(LOCALS_OFFSET);
+        for index in 0..scope.method.max_locals {
+            let val = self.builder.use_var(scope.local(index));
+            self.builder
+                .ins()
+                .store(MemFlags::trusted(), val, locals, slot(index));
+        }
+        let stack = self.load(STACK_OFFSET);
+        for index in 0..depth {
+            let val = self.builder.use_var(scope.slot(index));
+            self.builder
+                .ins()
+                .store(MemFlags::trusted(), val, stack, slot(index));
+        }
+        let context = self.context.expect("No context outside of the function");
+        let depth = self.builder.ins().iconst(I64, depth as i64);
+        self.builder
+            .ins()
+            .store(MemFlags::trusted(), depth, context, DEPTH_OFFSET);
+        let pc = self.builder.ins().iconst(I64, pc as i64);
+        self.builder.ins().return_(&[pc]);
+    }
+
+    /// Loads the field at `offset` of the `Context`.
+    fn load(&mut self, offset: i32) -> Value {
+        let context = self.context.expect("No context outside of the function");
+        self.builder
+            .ins()
+            .load(I64, MemFlags::trusted(), context, offset)
+    }
+
+    /// Calls the runtime function at `address`, which returns an `i64`.
+    fn call(&mut self, address: usize, args: &[Value]) -> Value {
+        let signature = self.signatures[args.len() - 2];
+        let callee = self.builder.ins().iconst(I64, address as i64);
+        let call = self.builder.ins().call_indirect(signature, callee, args);
+        self.builder.inst_results(call)[0]
+    }
+}
+
+fn slot(index: usize) -> i32 {
+    index as i32 * 8
+}
+
+/// The condition a conditional branch jumps on, comparing with 0 or the other value.
+fn condition(instruction: Instruction) -> IntCC {
+    match instruction {
+        Instruction::Ifeq(_) | Instruction::IfIcmpeq(_) | Instruction::IfAcmpeq(_) => IntCC::Equal,
+        Instruction::Ifne(_) | Instruction::IfIcmpne(_) | Instruction::IfAcmpne(_) => {
+            IntCC::NotEqual
+        }
+        Instruction::Iflt(_) | Instruction::IfIcmplt(_) => IntCC::SignedLessThan,
+        Instruction::Ifge(_) | Instruction::IfIcmpge(_) => IntCC::SignedGreaterThanOrEqual,
+        Instruction::Ifgt(_) | Instruction::IfIcmpgt(_) => IntCC::SignedGreaterThan,
+        _ => IntCC::SignedLessThanOrEqual,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::interpreter::{interpret, Schedule};
+    use crate::jit::optimizer::*;
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::thread::Thread;
+
+    fn class_loader() -> Rc<ClassLoader> {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        ClassLoader::new(class_path)
+    }
+
+    // sum(I)I adds up to its argument in a loop
+    #[test]
+    fn optimize_loop() {
+        let class_loader = class_loader();
+        let method = class_loader
+            .load("jit/Loops")
+            .lookup_method("sum", "(I)I")
+            .unwrap();
+        let optimized = optimize(&method).unwrap();
+        assert!(optimized.is_entry(0));
+        let thread = Thread::new();
+        let mut locals = vec![100, 0, 0];
+        let mut stack = vec![0; method.max_stack];
+        let mut dirty = vec![0; method.max_locals];
+        let exit = optimized.run(0, &mut locals, &mut stack, &mut dirty, 1000, &thread.heap);
+        let (instruction, _) = method.decoded_code().at(exit.pc);
+        assert_eq!(instruction, Instruction::Ireturn);
+        assert_eq!((exit.depth, stack[0], exit.backedges), (1, 5050, 100));
+        assert_eq!(dirty, vec![0, Kind::Num as u8, Kind::Num as u8]);
+    }
+
+    // totalArea only sees Shapes receivers until Wide turns up, failing the guard
+    #[test]
+    fn guarded_inlining() {
+        let class_loader = class_loader();
+        let class = class_loader.load("jit/Shapes");
+        let mut thread = Thread::new();
+        thread.vm.jit.compile_threshold.set(1);
+        thread.vm.jit.optimize_threshold.set(10);
+        thread.push_frame(Frame::new(Rc::clone(&class), class.main_method()));
+        let vm = Rc::clone(&thread.vm);
+        interpret(&class_loader, thread, Schedule::default());
+        let static_vars = class.static_vars.borrow();
+        assert_eq!(
+            (static_vars.get_int(0), static_vars.get_int(1)),
+            (18000, 60)
+        );
+
+        let total_area = class
+            .lookup_method("totalArea", "(Ljit/Shapes;I)I")
+            .unwrap();
+        assert_eq!(total_area.profile.optimized().unwrap().inlined(), 1);
+        // main is entered again after each call, with the stack it left
+        let main = class.main_method();
+        let optimized = main.profile.optimized().unwrap();
+        assert!(optimized.is_entry(26));
+        assert_eq!(optimized.stack_kinds(26), [Kind::Num, Kind::Num]);
+        assert!(vm.jit.optimizations.get() >= 2);
+    }
+
+    #[test]
+    fn polymorphic_not_inlined() {
+        let class_loader = class_loader();
+        let shapes = class_loader.load("jit/Shapes");
+        let wide = class_loader.load("jit/Wide");
+        let method = shapes
+            .lookup_method("totalArea", "(Ljit/Shapes;I)I")
+            .unwrap();
+        let cache = method.decoded_code().call_cache(11);
+        cache.update(&shapes, &shapes.lookup_method("area", "()I").unwrap());
+        assert!(Rc::ptr_eq(&cache.monomorphic().unwrap(), &shapes));
+        cache.update(&wide, &wide.lookup_method("area", "()I").unwrap());
+        assert!(cache.monomorphic().is_none());
+        // Without a receiver nor a resolved reference, the call is an exit
+        assert_eq!(optimize(&method).unwrap().inlined(), 0);
+    }
+}
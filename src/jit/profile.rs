@@ -0,0 +1,82 @@
+use std::cell::{Cell, OnceCell};
+
+use crate::jit::compiler::{compile, CompiledMethod};
+#[cfg(feature = "cranelift")]
+use crate::jit::optimizer::{optimize, OptimizedMethod};
+use crate::jit::Jit;
+use crate::rtda::heap::method::Method;
+
+/// How hot a method is, and its compiled code once it got hot enough.
+#[derive(Debug, Default)]
+pub struct Profile {
+    invocations: Cell<u32>,
+    backedges: Cell<u32>,
+    /// Set when the method crosses the threshold, to `None` if it can't be compiled.
+    compiled: OnceCell<Option<CompiledMethod>>,
+    /// Set when the method crosses the threshold of the optimizing compiler.
+    #[cfg(feature = "cranelift")]
+    optimized: OnceCell<Option<OptimizedMethod>>,
+}
+
+impl Profile {
+    pub fn count_invocation(&self, method: &Method, jit: &Jit) {
+        self.invocations
+            .set(self.invocations.get().saturating_add(1));
+        self.compile_if_hot(method, jit);
+    }
+
+    pub fn count_backedge(&self, method: &Method, jit: &Jit) {
+        self.count_backedges(1, method, jit);
+    }
+
+    /// Counts the backward branches compiled code took, as well as the interpreter's.
+    pub fn count_backedges(&self, count: u32, method: &Method, jit: &Jit) {
+        if count == 0 {
+            return;
+        }
+        self.backedges
+            .set(self.backedges.get().saturating_add(count));
+        self.compile_if_hot(method, jit);
+    }
+
+    pub fn invocations(&self) -> u32 {
+        self.invocations.get()
+    }
+
+    pub fn backedges(&self) -> u32 {
+        self.backedges.get()
+    }
+
+    pub fn compiled(&self) -> Option<&CompiledMethod> {
+        self.compiled.get()?.as_ref()
+    }
+
+    #[cfg(feature = "cranelift")]
+    pub fn optimized(&self) -> Option<&OptimizedMethod> {
+        self.optimized.get()?.as_ref()
+    }
+
+    fn compile_if_hot(&self, method: &Method, jit: &Jit) {
+        let count = self.invocations.get().saturating_add(self.backedges.get());
+        if !jit.enabled.get() || count < jit.compile_threshold.get() {
+            return;
+        }
+        self.compiled.get_or_init(|| {
+            let compiled = compile(method);
+            if compiled.is_some() {
+                jit.compilations.set(jit.compilations.get() + 1);
+            }
+            compiled
+        });
+        #[cfg(feature = "cranelift")]
+        if count >= jit.optimize_threshold.get() {
+            self.optimized.get_or_init(|| {
+                let optimized = optimize(method);
+                if optimized.is_some() {
+                    jit.optimizations.set(jit.optimizations.get() + 1);
+                }
+                optimized
+            });
+        }
+    }
+}
@@ -0,0 +1,28 @@
+//! What optimized code calls into the VM for. The heap is passed along as a pointer to
+//! the `RefCell` of the running thread, which nothing borrows while compiled code runs.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::gc::heap::Heap;
+use crate::jit::{decode_ref, encode_ref};
+use crate::rtda::slot::Slot;
+
+/// The address of the class of the object `reference`, 0 for null.
+pub extern "C" fn class_of(heap: *const RefCell<Heap>, reference: i64) -> i64 {
+    let heap = unsafe { &*heap }.borrow();
+    match decode_ref(reference) {
+        Some(reference) => Rc::as_ptr(&heap.get(reference).class) as i64,
+        None => 0,
+    }
+}
+
+/// The slot `slot_id` of the fields of the object `reference`, which isn't null.
+pub extern "C" fn get_field(heap: *const RefCell<Heap>, reference: i64, slot_id: i64) -> i64 {
+    let heap = unsafe { &*heap }.borrow();
+    let reference = decode_ref(reference).expect("java.lang.NullPointerException");
+    match heap.get(reference).fields()[slot_id as usize] {
+        Slot::Num(val) => val as i64,
+        Slot::Ref(val) => encode_ref(val),
+    }
+}
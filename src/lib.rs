@@ -1,8 +1,23 @@
 extern crate vec_map;
 
+/// `println!` unless tracing is turned off with `interpreter::set_trace`.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::interpreter::is_tracing() {
+            println!($($arg)*);
+        }
+    };
+}
+
 pub mod classfile;
 pub mod classpath;
+pub mod gc;
 pub mod instruction;
+pub mod interpreter;
+#[cfg(feature = "jit")]
+pub mod jit;
+pub mod native;
 pub mod rtda;
 pub mod shell;
 pub mod util;
+pub mod verifier;
@@ -0,0 +1,60 @@
+use std::rc::Rc;
+
+use crate::native::{class_loader, int_arg, mirrored_class, this};
+use crate::rtda::heap::object::{ArrayData, Object};
+use crate::rtda::slot::Slot;
+use crate::rtda::thread::Thread;
+
+/// The `java.lang.reflect.Field`s of the fields the class `this` mirrors declares, only
+/// the public ones if `publicOnly`.
+pub fn get_declared_fields0(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let mirror = this(&args);
+    let public_only = int_arg(&args, 1) != 0;
+    let loader = class_loader(thread, mirror);
+    let class = mirrored_class(thread, Some(mirror));
+    let field_class = loader.load("java/lang/reflect/Field");
+    let slot_id = |name, descriptor| {
+        field_class
+            .lookup_field(name, descriptor)
+            .unwrap_or_else(|| panic!("java.lang.NoSuchFieldError: {}", name))
+            .slot_id
+    };
+    let clazz = slot_id("clazz", "Ljava/lang/Class;");
+    let name = slot_id("name", "Ljava/lang/String;");
+    let modifiers = slot_id("modifiers", "I");
+
+    let mut heap = thread.heap.borrow_mut();
+    let mut fields = Vec::new();
+    for field in class
+        .fields
+        .iter()
+        .filter(|x| !public_only || x.class_member.is_public())
+    {
+        let field_name = heap
+            .intern(
+                field.name(),
+                loader.load("java/lang/String"),
+                loader.load("[C"),
+            )
+            .expect("java.lang.OutOfMemoryError: Java heap space");
+        let mut object = Object::new(Rc::clone(&field_class));
+        object.fields_mut()[clazz] = Slot::Ref(Some(mirror));
+        object.fields_mut()[name] = Slot::Ref(Some(field_name));
+        object.fields_mut()[modifiers] = Slot::Num(field.class_member.access_flags as i32);
+        let object = heap
+            .allocate(object)
+            .expect("java.lang.OutOfMemoryError: Java heap space");
+        heap.write_barrier(object, Slot::Ref(Some(mirror)));
+        heap.write_barrier(object, Slot::Ref(Some(field_name)));
+        fields.push(Some(object));
+    }
+    let mut array = Object::new_array(loader.load("[Ljava/lang/reflect/Field;"), fields.len());
+    *array.array_mut() = ArrayData::Refs(fields.clone());
+    let array = heap
+        .allocate(array)
+        .expect("java.lang.OutOfMemoryError: Java heap space");
+    for field in fields {
+        heap.write_barrier(array, Slot::Ref(field));
+    }
+    vec![Slot::Ref(Some(array))]
+}
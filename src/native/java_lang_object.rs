@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use crate::native::{long_arg, this};
+use crate::rtda::monitor::Lock;
+use crate::rtda::slot::Slot;
+use crate::rtda::thread::{Thread, ThreadState};
+
+pub fn notify(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let lock = Lock::Object(this(&args));
+    thread.vm.monitors.borrow_mut().notify(&lock, thread.id);
+    vec![]
+}
+
+pub fn notify_all(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let lock = Lock::Object(this(&args));
+    thread.vm.monitors.borrow_mut().notify_all(&lock, thread.id);
+    vec![]
+}
+
+/// Releases the monitor and parks the thread, the scheduler enters the monitor again
+/// before resuming it, with an `InterruptedException` pending if it was interrupted.
+pub fn wait(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let lock = Lock::Object(this(&args));
+    let timeout = long_arg(&args, 1);
+    if timeout < 0 {
+        panic!("java.lang.IllegalArgumentException: timeout value is negative")
+    }
+    if !thread.vm.monitors.borrow().is_owner(&lock, thread.id) {
+        panic!("java.lang.IllegalMonitorStateException")
+    }
+    if thread.take_interrupt() {
+        panic!("java.lang.InterruptedException")
+    }
+    let count = thread.vm.monitors.borrow_mut().wait(&lock, thread.id);
+    let deadline = match timeout {
+        0 => None,
+        millis => Some(thread.vm.clock.now() + Duration::from_millis(millis as u64)),
+    };
+    let state = ThreadState::Waiting {
+        lock,
+        count,
+        deadline,
+    };
+    thread.state = state;
+    vec![]
+}
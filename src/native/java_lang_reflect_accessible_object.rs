@@ -0,0 +1,34 @@
+use crate::native::{int_arg, mirrored_class, ref_arg, this};
+use crate::rtda::heap::access_flags::ACC_PUBLIC;
+use crate::rtda::slot::Slot;
+use crate::rtda::thread::Thread;
+
+/// Whether `caller` may make the member `this` of `declaringClass` accessible once modules
+/// are resolved, the module of `declaringClass` opening its package to the module of
+/// `caller`, or exporting it if both are public. Throws `IllegalAccessError` if not
+/// when `throwExceptionIfDenied`.
+pub fn check_can_set_accessible(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let member = this(&args);
+    let caller = mirrored_class(thread, ref_arg(&args, 1));
+    let declaring_class = mirrored_class(thread, ref_arg(&args, 2));
+    let throw = int_arg(&args, 3) != 0;
+    let modifiers = {
+        let heap = thread.heap.borrow();
+        let object = heap.get(member);
+        let slot_id = object
+            .class
+            .lookup_field("modifiers", "I")
+            .expect("java.lang.NoSuchFieldError: modifiers")
+            .slot_id;
+        match object.fields()[slot_id] {
+            Slot::Num(val) => val as u16,
+            Slot::Ref(_) => panic!("Not Num"),
+        }
+    };
+    let public = modifiers & ACC_PUBLIC != 0;
+    match declaring_class.check_reflective_access(&caller, public) {
+        Ok(()) => vec![Slot::Num(1)],
+        Err(message) if throw => panic!("java.lang.IllegalAccessError: {}", message),
+        Err(_) => vec![Slot::Num(0)],
+    }
+}
@@ -0,0 +1,8 @@
+use crate::native::this;
+use crate::rtda::slot::Slot;
+use crate::rtda::thread::Thread;
+
+pub fn intern(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let string = thread.heap.borrow_mut().intern_string(this(&args));
+    vec![Slot::Ref(Some(string))]
+}
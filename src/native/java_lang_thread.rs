@@ -0,0 +1,76 @@
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::instruction::reference::invoke::invoke_method;
+use crate::native::{long_arg, this};
+use crate::rtda::slot::Slot;
+use crate::rtda::thread::{Thread, ThreadState};
+
+pub fn current_thread(thread: &mut Thread, _: Vec<Slot>) -> Vec<Slot> {
+    let java_thread = thread.java_thread();
+    vec![Slot::Ref(java_thread)]
+}
+
+pub fn yield_(thread: &mut Thread, _: Vec<Slot>) -> Vec<Slot> {
+    thread.state = ThreadState::Yielding;
+    vec![]
+}
+
+/// Puts the thread to sleep, the scheduler wakes it up with an `InterruptedException`
+/// pending if it's interrupted before the time is up.
+pub fn sleep(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let millis = long_arg(&args, 0);
+    if millis < 0 {
+        panic!("java.lang.IllegalArgumentException: timeout value is negative")
+    }
+    if thread.take_interrupt() {
+        panic!("java.lang.InterruptedException: sleep interrupted")
+    }
+    let deadline = thread.vm.clock.now() + Duration::from_millis(millis as u64);
+    thread.state = ThreadState::Sleeping { deadline };
+    vec![]
+}
+
+/// Creates a thread calling `run()` on `this`, which the scheduler picks up after the
+/// current instruction.
+pub fn start0(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let java_thread = this(&args);
+    if thread.vm.thread_id(java_thread).is_some() {
+        panic!("java.lang.IllegalThreadStateException")
+    }
+    let class = Rc::clone(&thread.heap.borrow().get(java_thread).class);
+    let method = class
+        .lookup_method("run", "()V")
+        .expect("java.lang.NoSuchMethodError: run");
+    let mut started = thread.spawn();
+    invoke_method(&mut started, method, args);
+    thread
+        .vm
+        .java_threads
+        .borrow_mut()
+        .insert(started.id, java_thread);
+    thread.vm.started.borrow_mut().push(started);
+    vec![]
+}
+
+pub fn is_alive(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let alive = thread.vm.thread_id(this(&args)).is_some();
+    vec![Slot::Num(alive as i32)]
+}
+
+pub fn interrupt0(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    if let Some(id) = thread.vm.thread_id(this(&args)) {
+        thread.vm.interrupted.borrow_mut().insert(id);
+    }
+    vec![]
+}
+
+pub fn is_interrupted(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let clear_interrupted = args[1] != Slot::Num(0);
+    let interrupted = match thread.vm.thread_id(this(&args)) {
+        Some(id) if clear_interrupted => thread.vm.interrupted.borrow_mut().remove(&id),
+        Some(id) => thread.vm.interrupted.borrow().contains(&id),
+        None => false,
+    };
+    vec![Slot::Num(interrupted as i32)]
+}
@@ -0,0 +1,186 @@
+use std::rc::Rc;
+
+use crate::gc::heap::Heap;
+use crate::instruction::reference::invoke::invoke_method;
+use crate::native::ref_arg;
+use crate::rtda::heap::class_loader::ClassLoader;
+use crate::rtda::heap::object::{ArrayData, Object, ObjectRef};
+use crate::rtda::slot::Slot;
+use crate::rtda::thread::Thread;
+
+/// The URL of the resource in the first directory or jar of the class path that has it,
+/// or null.
+pub fn find_resource(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let (loader, name) = loader_and_name(thread, &args, 0);
+    let url = loader
+        .class_path()
+        .resource(&name)
+        .map(|x| new_url(&mut thread.heap.borrow_mut(), &loader, &x));
+    vec![Slot::Ref(url)]
+}
+
+/// The URLs of the resource in every directory and jar of the class path that has it, as
+/// the `Enumeration` of the elements of a `java.util.Vector` holding them.
+///
+/// `elements()` is invoked on the caller's behalf, so the caller gets the enumeration once
+/// it returns.
+pub fn find_resources(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let (loader, name) = loader_and_name(thread, &args, 0);
+    let vector_class = loader.load("java/util/Vector");
+    let slot_id = |name, descriptor| {
+        vector_class
+            .lookup_field(name, descriptor)
+            .unwrap_or_else(|| panic!("java.lang.NoSuchFieldError: {}", name))
+            .slot_id
+    };
+    let element_data = slot_id("elementData", "[Ljava/lang/Object;");
+    let element_count = slot_id("elementCount", "I");
+    let elements = vector_class
+        .lookup_method("elements", "()Ljava/util/Enumeration;")
+        .expect("java.lang.NoSuchMethodError: elements");
+
+    let mut heap = thread.heap.borrow_mut();
+    let urls: Vec<Option<ObjectRef>> = loader
+        .class_path()
+        .resources(&name)
+        .iter()
+        .map(|x| Some(new_url(&mut heap, &loader, x)))
+        .collect();
+    let mut array = Object::new_array(loader.load("[Ljava/lang/Object;"), urls.len());
+    *array.array_mut() = ArrayData::Refs(urls.clone());
+    let array = heap
+        .allocate(array)
+        .expect("java.lang.OutOfMemoryError: Java heap space");
+    for url in urls.iter() {
+        heap.write_barrier(array, Slot::Ref(*url));
+    }
+    let mut vector = Object::new(Rc::clone(&vector_class));
+    vector.fields_mut()[element_data] = Slot::Ref(Some(array));
+    vector.fields_mut()[element_count] = Slot::Num(urls.len() as i32);
+    let vector = heap
+        .allocate(vector)
+        .expect("java.lang.OutOfMemoryError: Java heap space");
+    heap.write_barrier(vector, Slot::Ref(Some(array)));
+    drop(heap);
+    invoke_method(thread, elements, vec![Slot::Ref(Some(vector))]);
+    vec![]
+}
+
+/// A `java.io.ByteArrayInputStream` of the resource in the first directory or jar of the
+/// class path that has it, or null. The class path has no modules, so the module name is
+/// ignored.
+pub fn find_resource_as_stream(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let (loader, name) = loader_and_name(thread, &args, 1);
+    let Ok(data) = loader.class_path().read_resource(&name) else {
+        return vec![Slot::Ref(None)];
+    };
+    let stream_class = loader.load("java/io/ByteArrayInputStream");
+    let slot_id = |name, descriptor| {
+        stream_class
+            .lookup_field(name, descriptor)
+            .unwrap_or_else(|| panic!("java.lang.NoSuchFieldError: {}", name))
+            .slot_id
+    };
+    let buf = slot_id("buf", "[B");
+    let count = slot_id("count", "I");
+
+    let mut heap = thread.heap.borrow_mut();
+    let len = data.len();
+    let mut array = Object::new_array(loader.load("[B"), len);
+    *array.array_mut() = ArrayData::Bytes(data.into_iter().map(|x| x as i8).collect());
+    let array = heap
+        .allocate(array)
+        .expect("java.lang.OutOfMemoryError: Java heap space");
+    let mut stream = Object::new(stream_class);
+    stream.fields_mut()[buf] = Slot::Ref(Some(array));
+    stream.fields_mut()[count] = Slot::Num(len as i32);
+    let stream = heap
+        .allocate(stream)
+        .expect("java.lang.OutOfMemoryError: Java heap space");
+    heap.write_barrier(stream, Slot::Ref(Some(array)));
+    vec![Slot::Ref(Some(stream))]
+}
+
+/// The loader of the bootstrap classes, the one of `BootLoader` itself, and the resource
+/// name of the argument at `index`.
+fn loader_and_name(thread: &Thread, args: &[Slot], index: usize) -> (Rc<ClassLoader>, String) {
+    let loader = thread.current_frame().class.loader.upgrade();
+    let loader = loader.expect("ClassLoader is dropped");
+    let name = ref_arg(args, index).expect("java.lang.NullPointerException");
+    let name = thread.heap.borrow().java_string(name);
+    (loader, name)
+}
+
+/// The `java.net.URL` of `url`, like `jar:file:/lib/rt.jar!/pkg/A.class`, which has no
+/// host, port or query: its file and path are all that follows the protocol.
+fn new_url(heap: &mut Heap, loader: &Rc<ClassLoader>, url: &str) -> ObjectRef {
+    let class = loader.load("java/net/URL");
+    let (protocol, file) = url.split_once(':').expect("java.net.MalformedURLException");
+    let mut object = Object::new(Rc::clone(&class));
+    let mut strings = Vec::new();
+    for (name, val) in [
+        ("protocol", protocol),
+        ("host", ""),
+        ("file", file),
+        ("path", file),
+    ] {
+        let string = heap
+            .intern(val, loader.load("java/lang/String"), loader.load("[C"))
+            .expect("java.lang.OutOfMemoryError: Java heap space");
+        let field = class
+            .lookup_field(name, "Ljava/lang/String;")
+            .unwrap_or_else(|| panic!("java.lang.NoSuchFieldError: {}", name));
+        object.fields_mut()[field.slot_id] = Slot::Ref(Some(string));
+        strings.push(string);
+    }
+    let port = class
+        .lookup_field("port", "I")
+        .expect("java.lang.NoSuchFieldError: port");
+    object.fields_mut()[port.slot_id] = Slot::Num(-1);
+    let object = heap
+        .allocate(object)
+        .expect("java.lang.OutOfMemoryError: Java heap space");
+    for string in strings {
+        heap.write_barrier(object, Slot::Ref(Some(string)));
+    }
+    object
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::interpreter::{interpret, Schedule};
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::thread::Thread;
+
+    // services/Main stores what each method computed in its only static field
+    fn run_main(name: &str) -> i32 {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("services/Main");
+        let method = class.lookup_method(name, "()V").unwrap();
+        let mut thread = Thread::new();
+        thread.push_frame(Frame::new(Rc::clone(&class), method));
+        interpret(&class_loader, thread, Schedule::default());
+        let result = class.static_vars.borrow().get_int(0);
+        result
+    }
+
+    #[test]
+    fn system_resources() {
+        assert_eq!(run_main("resource"), 1);
+        assert_eq!(run_main("resources"), 1);
+    }
+
+    #[test]
+    fn system_resource_as_stream() {
+        assert_eq!(run_main("resourceAsStream"), 89);
+        assert_eq!(run_main("missingResource"), 1);
+    }
+}
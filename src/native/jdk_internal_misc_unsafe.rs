@@ -0,0 +1,738 @@
+//! `Unsafe` natives, shared by `sun.misc.Unsafe` and `jdk.internal.misc.Unsafe`.
+//!
+//! An offset into an object is `HEADER_SIZE` plus 4 bytes per field slot, and an offset
+//! into an array is `HEADER_SIZE` plus the index times the element size. With a null
+//! base object the offset is an address in the off-heap memory. There's a single host
+//! thread, so volatile and ordered accesses are plain ones and compare-and-swap is a
+//! read followed by a write.
+
+use std::rc::Rc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::native::{int_arg, long_arg, mirrored_class, ref_arg, NativeMethod};
+use crate::rtda::heap::class::Class;
+use crate::rtda::heap::object::{ArrayData, ObjectData, ObjectRef, HEADER_SIZE};
+use crate::rtda::off_heap::fault;
+use crate::rtda::slot::Slot;
+use crate::rtda::thread::{Thread, ThreadState};
+use crate::util::converter;
+
+pub fn find(name: &str, descriptor: &str) -> Option<NativeMethod> {
+    let native_method: NativeMethod = match (name, descriptor) {
+        ("getInt" | "getIntVolatile", "(Ljava/lang/Object;J)I") => get_int,
+        (
+            "putInt" | "putIntVolatile" | "putOrderedInt" | "putIntRelease",
+            "(Ljava/lang/Object;JI)V",
+        ) => put_int,
+        ("getLong" | "getLongVolatile", "(Ljava/lang/Object;J)J") => get_long,
+        (
+            "putLong" | "putLongVolatile" | "putOrderedLong" | "putLongRelease",
+            "(Ljava/lang/Object;JJ)V",
+        ) => put_long,
+        (
+            "getObject" | "getObjectVolatile" | "getReference" | "getReferenceVolatile",
+            "(Ljava/lang/Object;J)Ljava/lang/Object;",
+        ) => get_reference,
+        (
+            "putObject"
+            | "putObjectVolatile"
+            | "putOrderedObject"
+            | "putReference"
+            | "putReferenceVolatile"
+            | "putReferenceRelease",
+            "(Ljava/lang/Object;JLjava/lang/Object;)V",
+        ) => put_reference,
+        ("compareAndSwapInt" | "compareAndSetInt", "(Ljava/lang/Object;JII)Z") => {
+            compare_and_set_int
+        }
+        ("compareAndSwapLong" | "compareAndSetLong", "(Ljava/lang/Object;JJJ)Z") => {
+            compare_and_set_long
+        }
+        (
+            "compareAndSwapObject" | "compareAndSetReference",
+            "(Ljava/lang/Object;JLjava/lang/Object;Ljava/lang/Object;)Z",
+        ) => compare_and_set_reference,
+        ("objectFieldOffset", "(Ljava/lang/reflect/Field;)J") => object_field_offset,
+        ("objectFieldOffset1", "(Ljava/lang/Class;Ljava/lang/String;)J") => object_field_offset1,
+        ("arrayBaseOffset" | "arrayBaseOffset0", "(Ljava/lang/Class;)I") => array_base_offset,
+        ("arrayIndexScale" | "arrayIndexScale0", "(Ljava/lang/Class;)I") => array_index_scale,
+        ("addressSize" | "addressSize0", "()I") => address_size,
+        ("allocateMemory" | "allocateMemory0", "(J)J") => allocate_memory,
+        ("freeMemory" | "freeMemory0", "(J)V") => free_memory,
+        ("getByte", "(J)B") => get_byte_at,
+        ("putByte", "(JB)V") => put_byte_at,
+        ("getInt", "(J)I") => get_int_at,
+        ("putInt", "(JI)V") => put_int_at,
+        ("getLong", "(J)J") => get_long_at,
+        ("putLong", "(JJ)V") => put_long_at,
+        ("park", "(ZJ)V") => park,
+        ("unpark", "(Ljava/lang/Object;)V") => unpark,
+        _ => return None,
+    };
+    Some(native_method)
+}
+
+/// Where an offset from a base object points to.
+enum Location {
+    Field(ObjectRef, usize),
+    Element(ObjectRef, usize),
+    Address(u64),
+}
+
+fn locate(thread: &Thread, base: Option<ObjectRef>, offset: i64) -> Location {
+    let base = match base {
+        Some(base) => base,
+        None => return Location::Address(offset as u64),
+    };
+    let offset = match (offset as usize).checked_sub(HEADER_SIZE) {
+        Some(offset) if offset < isize::MAX as usize => offset,
+        _ => fault(),
+    };
+    match thread.heap.borrow().get(base).data {
+        ObjectData::Fields(_) if offset % 4 == 0 => Location::Field(base, offset / 4),
+        ObjectData::Array(ref array) if offset % element_size(array) == 0 => {
+            Location::Element(base, offset / element_size(array))
+        }
+        _ => fault(),
+    }
+}
+
+fn element_size(array: &ArrayData) -> usize {
+    match array {
+        ArrayData::Bytes(_) => 1,
+        ArrayData::Shorts(_) | ArrayData::Chars(_) => 2,
+        ArrayData::Ints(_) | ArrayData::Floats(_) | ArrayData::Refs(_) => 4,
+        ArrayData::Longs(_) | ArrayData::Doubles(_) => 8,
+    }
+}
+
+fn read_int(thread: &Thread, base: Option<ObjectRef>, offset: i64) -> i32 {
+    let heap = thread.heap.borrow();
+    match locate(thread, base, offset) {
+        Location::Field(object, index) => match heap.get(object).fields().get(index) {
+            Some(Slot::Num(val)) => *val,
+            _ => fault(),
+        },
+        Location::Element(array, index) => match heap.get(array).array() {
+            ArrayData::Ints(vec) => *vec.get(index).unwrap_or_else(|| fault()),
+            ArrayData::Floats(vec) => {
+                converter::f32_to_i32(*vec.get(index).unwrap_or_else(|| fault()))
+            }
+            _ => fault(),
+        },
+        Location::Address(address) => i32::from_le_bytes(thread.vm.off_heap.borrow().read(address)),
+    }
+}
+
+fn write_int(thread: &Thread, base: Option<ObjectRef>, offset: i64, val: i32) {
+    let location = locate(thread, base, offset);
+    let mut heap = thread.heap.borrow_mut();
+    match location {
+        Location::Field(object, index) => match heap.get_mut(object).fields_mut().get_mut(index) {
+            Some(slot @ Slot::Num(_)) => *slot = Slot::Num(val),
+            _ => fault(),
+        },
+        Location::Element(array, index) => match heap.get_mut(array).array_mut() {
+            ArrayData::Ints(vec) => *vec.get_mut(index).unwrap_or_else(|| fault()) = val,
+            ArrayData::Floats(vec) => {
+                *vec.get_mut(index).unwrap_or_else(|| fault()) = converter::i32_to_f32(val)
+            }
+            _ => fault(),
+        },
+        Location::Address(address) => thread
+            .vm
+            .off_heap
+            .borrow_mut()
+            .write(address, val.to_le_bytes()),
+    }
+}
+
+fn read_long(thread: &Thread, base: Option<ObjectRef>, offset: i64) -> i64 {
+    let heap = thread.heap.borrow();
+    match locate(thread, base, offset) {
+        Location::Field(object, index) => {
+            let fields = heap.get(object).fields();
+            match (fields.get(index), fields.get(index + 1)) {
+                (Some(Slot::Num(a)), Some(Slot::Num(b))) => converter::i32seq_to_i64([*a, *b]),
+                _ => fault(),
+            }
+        }
+        Location::Element(array, index) => match heap.get(array).array() {
+            ArrayData::Longs(vec) => *vec.get(index).unwrap_or_else(|| fault()),
+            ArrayData::Doubles(vec) => vec.get(index).unwrap_or_else(|| fault()).to_bits() as i64,
+            _ => fault(),
+        },
+        Location::Address(address) => i64::from_le_bytes(thread.vm.off_heap.borrow().read(address)),
+    }
+}
+
+fn write_long(thread: &Thread, base: Option<ObjectRef>, offset: i64, val: i64) {
+    let location = locate(thread, base, offset);
+    let mut heap = thread.heap.borrow_mut();
+    match location {
+        Location::Field(object, index) => {
+            let fields = heap.get_mut(object).fields_mut();
+            match fields.get(index..index + 2) {
+                Some([Slot::Num(_), Slot::Num(_)]) => {
+                    let [a, b] = converter::i64_to_i32seq(val);
+                    fields[index] = Slot::Num(a);
+                    fields[index + 1] = Slot::Num(b);
+                }
+                _ => fault(),
+            }
+        }
+        Location::Element(array, index) => match heap.get_mut(array).array_mut() {
+            ArrayData::Longs(vec) => *vec.get_mut(index).unwrap_or_else(|| fault()) = val,
+            ArrayData::Doubles(vec) => {
+                *vec.get_mut(index).unwrap_or_else(|| fault()) = f64::from_bits(val as u64)
+            }
+            _ => fault(),
+        },
+        Location::Address(address) => thread
+            .vm
+            .off_heap
+            .borrow_mut()
+            .write(address, val.to_le_bytes()),
+    }
+}
+
+fn read_reference(thread: &Thread, base: Option<ObjectRef>, offset: i64) -> Option<ObjectRef> {
+    let heap = thread.heap.borrow();
+    match locate(thread, base, offset) {
+        Location::Field(object, index) => match heap.get(object).fields().get(index) {
+            Some(Slot::Ref(val)) => *val,
+            _ => fault(),
+        },
+        Location::Element(array, index) => match heap.get(array).array() {
+            ArrayData::Refs(vec) => *vec.get(index).unwrap_or_else(|| fault()),
+            _ => fault(),
+        },
+        // References can't leave the heap, the collector wouldn't see them
+        Location::Address(_) => fault(),
+    }
+}
+
+fn write_reference(thread: &Thread, base: Option<ObjectRef>, offset: i64, val: Option<ObjectRef>) {
+    let location = locate(thread, base, offset);
+    let mut heap = thread.heap.borrow_mut();
+    let target = match location {
+        Location::Field(object, index) => {
+            match heap.get_mut(object).fields_mut().get_mut(index) {
+                Some(slot @ Slot::Ref(_)) => *slot = Slot::Ref(val),
+                _ => fault(),
+            }
+            object
+        }
+        Location::Element(array, index) => {
+            match heap.get_mut(array).array_mut() {
+                ArrayData::Refs(vec) => *vec.get_mut(index).unwrap_or_else(|| fault()) = val,
+                _ => fault(),
+            }
+            array
+        }
+        Location::Address(_) => fault(),
+    };
+    heap.write_barrier(target, Slot::Ref(val));
+}
+
+fn long_result(val: i64) -> Vec<Slot> {
+    let [a, b] = converter::i64_to_i32seq(val);
+    vec![Slot::Num(a), Slot::Num(b)]
+}
+
+fn get_int(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let val = read_int(thread, ref_arg(&args, 1), long_arg(&args, 2));
+    vec![Slot::Num(val)]
+}
+
+fn put_int(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    write_int(
+        thread,
+        ref_arg(&args, 1),
+        long_arg(&args, 2),
+        int_arg(&args, 4),
+    );
+    vec![]
+}
+
+fn get_long(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let val = read_long(thread, ref_arg(&args, 1), long_arg(&args, 2));
+    long_result(val)
+}
+
+fn put_long(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    write_long(
+        thread,
+        ref_arg(&args, 1),
+        long_arg(&args, 2),
+        long_arg(&args, 4),
+    );
+    vec![]
+}
+
+fn get_reference(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let val = read_reference(thread, ref_arg(&args, 1), long_arg(&args, 2));
+    vec![Slot::Ref(val)]
+}
+
+fn put_reference(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    write_reference(
+        thread,
+        ref_arg(&args, 1),
+        long_arg(&args, 2),
+        ref_arg(&args, 4),
+    );
+    vec![]
+}
+
+fn compare_and_set_int(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let (base, offset) = (ref_arg(&args, 1), long_arg(&args, 2));
+    let swapped = read_int(thread, base, offset) == int_arg(&args, 4);
+    if swapped {
+        write_int(thread, base, offset, int_arg(&args, 5));
+    }
+    vec![Slot::Num(swapped as i32)]
+}
+
+fn compare_and_set_long(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let (base, offset) = (ref_arg(&args, 1), long_arg(&args, 2));
+    let swapped = read_long(thread, base, offset) == long_arg(&args, 4);
+    if swapped {
+        write_long(thread, base, offset, long_arg(&args, 6));
+    }
+    vec![Slot::Num(swapped as i32)]
+}
+
+fn compare_and_set_reference(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let (base, offset) = (ref_arg(&args, 1), long_arg(&args, 2));
+    let swapped = read_reference(thread, base, offset) == ref_arg(&args, 4);
+    if swapped {
+        write_reference(thread, base, offset, ref_arg(&args, 5));
+    }
+    vec![Slot::Num(swapped as i32)]
+}
+
+fn field_offset(class: &Class, name: &str) -> i64 {
+    let mut c = Some(class);
+    while let Some(x) = c {
+        if let Some(field) = x.fields.iter().find(|x| x.name() == name) {
+            if field.is_static() {
+                panic!("java.lang.IllegalArgumentException: {} is static", name)
+            }
+            return (HEADER_SIZE + field.slot_id * 4) as i64;
+        }
+        c = x.super_class.as_deref();
+    }
+    panic!("java.lang.InternalError: {}", name)
+}
+
+/// The offset of the field a `java.lang.reflect.Field` reflects, read from its `clazz`
+/// and `name` fields.
+fn object_field_offset(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let field = ref_arg(&args, 1).expect("java.lang.NullPointerException");
+    let (mirror, name) = {
+        let heap = thread.heap.borrow();
+        let object = heap.get(field);
+        let slot = |name, descriptor| {
+            let slot_id = object
+                .class
+                .lookup_field(name, descriptor)
+                .expect("java.lang.NoSuchFieldError")
+                .slot_id;
+            match object.fields()[slot_id] {
+                Slot::Ref(val) => val,
+                Slot::Num(_) => panic!("Not Ref"),
+            }
+        };
+        let name = slot("name", "Ljava/lang/String;").expect("java.lang.NullPointerException");
+        (slot("clazz", "Ljava/lang/Class;"), heap.java_string(name))
+    };
+    let offset = field_offset(&mirrored_class(thread, mirror), &name);
+    long_result(offset)
+}
+
+fn object_field_offset1(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let class = mirrored_class(thread, ref_arg(&args, 1));
+    let name = ref_arg(&args, 2).expect("java.lang.NullPointerException");
+    let name = thread.heap.borrow().java_string(name);
+    let offset = field_offset(&class, &name);
+    long_result(offset)
+}
+
+fn array_class(thread: &Thread, mirror: Option<ObjectRef>) -> Rc<Class> {
+    let class = mirrored_class(thread, mirror);
+    if !class.is_array() {
+        panic!(
+            "java.lang.IllegalArgumentException: {} isn't an array class",
+            class.java_name()
+        )
+    }
+    class
+}
+
+fn array_base_offset(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    array_class(thread, ref_arg(&args, 1));
+    vec![Slot::Num(HEADER_SIZE as i32)]
+}
+
+fn array_index_scale(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let scale = match array_class(thread, ref_arg(&args, 1)).name.as_str() {
+        "[Z" | "[B" => 1,
+        "[S" | "[C" => 2,
+        "[J" | "[D" => 8,
+        _ => 4,
+    };
+    vec![Slot::Num(scale)]
+}
+
+fn address_size(_: &mut Thread, _: Vec<Slot>) -> Vec<Slot> {
+    vec![Slot::Num(8)]
+}
+
+fn allocate_memory(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let size = long_arg(&args, 1);
+    if size < 0 {
+        panic!("java.lang.IllegalArgumentException")
+    }
+    let address = thread.vm.off_heap.borrow_mut().allocate(size as usize);
+    long_result(address as i64)
+}
+
+fn free_memory(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    thread
+        .vm
+        .off_heap
+        .borrow_mut()
+        .free(long_arg(&args, 1) as u64);
+    vec![]
+}
+
+fn get_byte_at(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let [val] = thread.vm.off_heap.borrow().read(long_arg(&args, 1) as u64);
+    vec![Slot::Num(val as i8 as i32)]
+}
+
+fn put_byte_at(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let val = int_arg(&args, 3) as u8;
+    thread
+        .vm
+        .off_heap
+        .borrow_mut()
+        .write(long_arg(&args, 1) as u64, [val]);
+    vec![]
+}
+
+fn get_int_at(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let val = read_int(thread, None, long_arg(&args, 1));
+    vec![Slot::Num(val)]
+}
+
+fn put_int_at(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    write_int(thread, None, long_arg(&args, 1), int_arg(&args, 3));
+    vec![]
+}
+
+fn get_long_at(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let val = read_long(thread, None, long_arg(&args, 1));
+    long_result(val)
+}
+
+fn put_long_at(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    write_long(thread, None, long_arg(&args, 1), long_arg(&args, 3));
+    vec![]
+}
+
+/// Consumes the permit of the current thread, or parks it until it's unparked,
+/// interrupted or the time is up: milliseconds since the epoch if `isAbsolute`,
+/// otherwise nanoseconds from now, 0 meaning forever.
+fn park(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    let is_absolute = int_arg(&args, 1) != 0;
+    let time = long_arg(&args, 2);
+    if thread.vm.permits.borrow_mut().remove(&thread.id)
+        || thread.vm.interrupted.borrow().contains(&thread.id)
+    {
+        return vec![];
+    }
+    let now = thread.vm.clock.now();
+    let deadline = match (is_absolute, time) {
+        (false, 0) => None,
+        (false, nanos) if nanos > 0 => Some(now + Duration::from_nanos(nanos as u64)),
+        (true, millis) if millis > 0 => {
+            let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+            Some(now + Duration::from_millis(millis as u64).saturating_sub(since_epoch))
+        }
+        _ => return vec![],
+    };
+    thread.state = ThreadState::Parked { deadline };
+    vec![]
+}
+
+/// Gives a permit to the thread `thread` is the `java.lang.Thread` object of, which
+/// unparks it if it's parked.
+fn unpark(thread: &mut Thread, args: Vec<Slot>) -> Vec<Slot> {
+    if let Some(id) = ref_arg(&args, 1).and_then(|x| thread.vm.thread_id(x)) {
+        thread.vm.permits.borrow_mut().insert(id);
+    }
+    vec![]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    use crate::classpath::classpath::parse;
+    use crate::interpreter::{interpret, Schedule};
+    use crate::native::find_native_method;
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::heap::object::{ArrayData, Object, ObjectRef};
+    use crate::rtda::slot::Slot;
+    use crate::rtda::thread::{Thread, ThreadState};
+    use crate::rtda::vm::Vm;
+    use crate::util::converter;
+
+    fn class_loader() -> Rc<ClassLoader> {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        ClassLoader::new(class_path)
+    }
+
+    fn new_object(class_loader: &Rc<ClassLoader>, thread: &Thread, name: &str) -> ObjectRef {
+        let object = Object::new(class_loader.load(name));
+        thread.heap.borrow_mut().allocate(object).unwrap()
+    }
+
+    fn new_array(class_loader: &Rc<ClassLoader>, thread: &Thread, name: &str) -> ObjectRef {
+        let array = Object::new_array(class_loader.load(name), 3);
+        thread.heap.borrow_mut().allocate(array).unwrap()
+    }
+
+    fn call(thread: &mut Thread, name: &str, descriptor: &str, args: Vec<Slot>) -> Vec<Slot> {
+        let native = find_native_method("jdk/internal/misc/Unsafe", name, descriptor).unwrap();
+        // The receiver isn't used by any of them
+        let args = [vec![Slot::Ref(None)], args].concat();
+        native(thread, args)
+    }
+
+    fn long(val: i64) -> [Slot; 2] {
+        let [a, b] = converter::i64_to_i32seq(val);
+        [Slot::Num(a), Slot::Num(b)]
+    }
+
+    fn to_long(slots: &[Slot]) -> i64 {
+        match slots {
+            [Slot::Num(a), Slot::Num(b)] => converter::i32seq_to_i64([*a, *b]),
+            _ => panic!("Not long"),
+        }
+    }
+
+    // AtomicCounter's fields: Unsafe u, long valueOffset, int value
+    #[test]
+    fn fields() {
+        let class_loader = class_loader();
+        let mut thread = Thread::new();
+        let counter = new_object(&class_loader, &thread, "unsafe/AtomicCounter");
+        let mirror = {
+            let class = class_loader.load("unsafe/AtomicCounter");
+            let mut heap = thread.heap.borrow_mut();
+            let mirror = class_loader.mirror(&class, &mut heap).unwrap();
+            let name = heap.intern(
+                "value",
+                class_loader.load("java/lang/String"),
+                class_loader.load("[C"),
+            );
+            (mirror, name.unwrap())
+        };
+        let args = vec![Slot::Ref(Some(mirror.0)), Slot::Ref(Some(mirror.1))];
+        let offset = call(
+            &mut thread,
+            "objectFieldOffset1",
+            "(Ljava/lang/Class;Ljava/lang/String;)J",
+            args,
+        );
+        assert_eq!(to_long(&offset), 16 + 3 * 4);
+        let at = |offset: i64| [vec![Slot::Ref(Some(counter))], long(offset).to_vec()].concat();
+
+        let cas = "(Ljava/lang/Object;JII)Z";
+        let args = [at(28), vec![Slot::Num(0), Slot::Num(7)]].concat();
+        let swapped = call(&mut thread, "compareAndSetInt", cas, args);
+        assert_eq!(swapped, vec![Slot::Num(1)]);
+        let args = [at(28), vec![Slot::Num(0), Slot::Num(8)]].concat();
+        let swapped = call(&mut thread, "compareAndSwapInt", cas, args);
+        assert_eq!(swapped, vec![Slot::Num(0)]);
+        let val = call(
+            &mut thread,
+            "getIntVolatile",
+            "(Ljava/lang/Object;J)I",
+            at(28),
+        );
+        assert_eq!(val, vec![Slot::Num(7)]);
+
+        let args = [at(20), long(-5).to_vec(), long(1 << 40).to_vec()].concat();
+        let swapped = call(
+            &mut thread,
+            "compareAndSetLong",
+            "(Ljava/lang/Object;JJJ)Z",
+            args,
+        );
+        assert_eq!(swapped, vec![Slot::Num(0)]);
+        let args = [at(20), long(1 << 40).to_vec()].concat();
+        call(
+            &mut thread,
+            "putOrderedLong",
+            "(Ljava/lang/Object;JJ)V",
+            args,
+        );
+        let val = call(&mut thread, "getLong", "(Ljava/lang/Object;J)J", at(20));
+        assert_eq!(to_long(&val), 1 << 40);
+
+        let cas = "(Ljava/lang/Object;JLjava/lang/Object;Ljava/lang/Object;)Z";
+        let args = [at(16), vec![Slot::Ref(None), Slot::Ref(Some(counter))]].concat();
+        let swapped = call(&mut thread, "compareAndSetReference", cas, args);
+        assert_eq!(swapped, vec![Slot::Num(1)]);
+        let get = "(Ljava/lang/Object;J)Ljava/lang/Object;";
+        let val = call(&mut thread, "getReferenceVolatile", get, at(16));
+        assert_eq!(val, vec![Slot::Ref(Some(counter))]);
+        assert_eq!(thread.heap.borrow().get(counter).fields()[3], Slot::Num(7));
+    }
+
+    #[test]
+    #[should_panic(expected = "java.lang.InternalError")]
+    fn field_out_of_bounds() {
+        let class_loader = class_loader();
+        let mut thread = Thread::new();
+        let counter = new_object(&class_loader, &thread, "unsafe/AtomicCounter");
+        let args = [vec![Slot::Ref(Some(counter))], long(32).to_vec()].concat();
+        call(&mut thread, "getInt", "(Ljava/lang/Object;J)I", args);
+    }
+
+    #[test]
+    fn arrays() {
+        let class_loader = class_loader();
+        let mut thread = Thread::new();
+        let ints = new_array(&class_loader, &thread, "[I");
+        let longs = new_array(&class_loader, &thread, "[J");
+        let mirror = {
+            let class = class_loader.load("[J");
+            class_loader
+                .mirror(&class, &mut thread.heap.borrow_mut())
+                .unwrap()
+        };
+        let args = vec![Slot::Ref(Some(mirror))];
+        let base = call(
+            &mut thread,
+            "arrayBaseOffset0",
+            "(Ljava/lang/Class;)I",
+            args.clone(),
+        );
+        assert_eq!(base, vec![Slot::Num(16)]);
+        let scale = call(&mut thread, "arrayIndexScale", "(Ljava/lang/Class;)I", args);
+        assert_eq!(scale, vec![Slot::Num(8)]);
+
+        let args = [
+            vec![Slot::Ref(Some(ints))],
+            long(16 + 2 * 4).to_vec(),
+            vec![Slot::Num(9)],
+        ]
+        .concat();
+        call(
+            &mut thread,
+            "putIntVolatile",
+            "(Ljava/lang/Object;JI)V",
+            args,
+        );
+        let args = [
+            vec![Slot::Ref(Some(longs))],
+            long(16 + 8).to_vec(),
+            long(-3).to_vec(),
+        ]
+        .concat();
+        call(&mut thread, "putLong", "(Ljava/lang/Object;JJ)V", args);
+        let heap = thread.heap.borrow();
+        assert!(matches!(heap.get(ints).array(), ArrayData::Ints(x) if x == &vec![0, 0, 9]));
+        assert!(matches!(heap.get(longs).array(), ArrayData::Longs(x) if x == &vec![0, -3, 0]));
+    }
+
+    #[test]
+    fn off_heap() {
+        let mut thread = Thread::new();
+        let address = call(&mut thread, "allocateMemory0", "(J)J", long(8).to_vec());
+        let address = to_long(&address);
+        let args = [long(address).to_vec(), long(0x0102030405060708).to_vec()].concat();
+        call(&mut thread, "putLong", "(JJ)V", args);
+        let val = call(&mut thread, "getByte", "(J)B", long(address + 7).to_vec());
+        assert_eq!(val, vec![Slot::Num(1)]);
+        let val = call(&mut thread, "getInt", "(J)I", long(address).to_vec());
+        assert_eq!(val, vec![Slot::Num(0x05060708)]);
+        call(&mut thread, "freeMemory0", "(J)V", long(address).to_vec());
+        assert!(thread.vm.off_heap.borrow().is_empty());
+    }
+
+    #[test]
+    fn park_consumes_permit() {
+        let mut thread = Thread::new();
+        let args = [vec![Slot::Num(0)], long(0).to_vec()].concat();
+        thread.vm.permits.borrow_mut().insert(thread.id);
+        call(&mut thread, "park", "(ZJ)V", args.clone());
+        assert_eq!(thread.state, ThreadState::Runnable);
+        call(&mut thread, "park", "(ZJ)V", args);
+        assert_eq!(thread.state, ThreadState::Parked { deadline: None });
+    }
+
+    // unsafe/Main stores what each scenario computed in its only static field
+    fn run_main(name: &str, schedule: Schedule) -> (i32, Rc<Vm>) {
+        let class_loader = class_loader();
+        let class = class_loader.load("unsafe/Main");
+        let method = class
+            .lookup_method(name, "(Ljdk/internal/misc/Unsafe;)V")
+            .unwrap();
+        let mut thread = Thread::new();
+        let u = new_object(&class_loader, &thread, "jdk/internal/misc/Unsafe");
+        let mut frame = Frame::new(Rc::clone(&class), method);
+        frame.local_vars.set_ref(0, Some(u));
+        thread.push_frame(frame);
+        let vm = Rc::clone(&thread.vm);
+        interpret(&class_loader, thread, schedule);
+        let result = class.static_vars.borrow().get_int(0);
+        (result, vm)
+    }
+
+    fn seeded(seed: u64, time_slice: usize) -> Schedule {
+        Schedule {
+            time_slice,
+            seed: Some(seed),
+        }
+    }
+
+    #[test]
+    fn atomic_counter() {
+        for seed in 0..8 {
+            assert_eq!(run_main("atomic", seeded(seed, 2)).0, 40);
+        }
+    }
+
+    #[test]
+    fn park_and_unpark() {
+        assert_eq!(run_main("park", Schedule::default()).0, 1);
+        for seed in 0..5 {
+            let (result, vm) = run_main("park", seeded(seed, 3));
+            assert_eq!(result, 1);
+            assert!(vm.java_threads.borrow().is_empty());
+        }
+    }
+
+    #[test]
+    fn timed_park() {
+        let (result, vm) = run_main("timedPark", seeded(0, 10));
+        assert_eq!(result, 1);
+        assert!(vm.clock.now() >= Duration::from_millis(5));
+    }
+
+    #[test]
+    fn off_heap_from_java() {
+        assert_eq!(run_main("offHeap", Schedule::default()).0, 42);
+    }
+}
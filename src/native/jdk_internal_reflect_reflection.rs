@@ -0,0 +1,17 @@
+use crate::rtda::slot::Slot;
+use crate::rtda::thread::Thread;
+
+/// The class of the method that called the method calling `getCallerClass`. Natives run
+/// on the frame of the method calling them, so it's the one right below.
+pub fn get_caller_class(thread: &mut Thread, _: Vec<Slot>) -> Vec<Slot> {
+    let class = thread
+        .caller_frame(1)
+        .expect("java.lang.InternalError: no caller frame")
+        .method
+        .class();
+    let loader = class.loader.upgrade().expect("ClassLoader is dropped");
+    let mirror = loader
+        .mirror(&class, &mut thread.heap.borrow_mut())
+        .expect("java.lang.OutOfMemoryError: Java heap space");
+    vec![Slot::Ref(Some(mirror))]
+}
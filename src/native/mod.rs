@@ -0,0 +1,117 @@
+use std::rc::Rc;
+
+use crate::rtda::heap::class::Class;
+use crate::rtda::heap::class_loader::ClassLoader;
+use crate::rtda::heap::object::ObjectRef;
+use crate::rtda::slot::Slot;
+use crate::rtda::thread::Thread;
+use crate::util::converter;
+
+mod java_lang_class;
+mod java_lang_object;
+mod java_lang_reflect_accessible_object;
+mod java_lang_string;
+mod java_lang_thread;
+mod jdk_internal_loader_boot_loader;
+mod jdk_internal_misc_unsafe;
+mod jdk_internal_reflect_reflection;
+
+/// Takes the arguments of the call, `this` first for instance methods, and returns the
+/// slots of the result to push on the caller's operand stack.
+pub type NativeMethod = fn(&mut Thread, Vec<Slot>) -> Vec<Slot>;
+
+pub fn find_native_method(class_name: &str, name: &str, descriptor: &str) -> Option<NativeMethod> {
+    let native_method: NativeMethod = match (class_name, name, descriptor) {
+        (_, "registerNatives", "()V") => register_natives,
+        ("java/lang/Class", "getDeclaredFields0", "(Z)[Ljava/lang/reflect/Field;") => {
+            java_lang_class::get_declared_fields0
+        }
+        ("java/lang/Object", "notify", "()V") => java_lang_object::notify,
+        ("java/lang/Object", "notifyAll", "()V") => java_lang_object::notify_all,
+        ("java/lang/Object", "wait", "(J)V") => java_lang_object::wait,
+        ("java/lang/String", "intern", "()Ljava/lang/String;") => java_lang_string::intern,
+        ("java/lang/Thread", "currentThread", "()Ljava/lang/Thread;") => {
+            java_lang_thread::current_thread
+        }
+        ("java/lang/Thread", "yield", "()V") => java_lang_thread::yield_,
+        ("java/lang/Thread", "sleep", "(J)V") => java_lang_thread::sleep,
+        ("java/lang/Thread", "start0", "()V") => java_lang_thread::start0,
+        ("java/lang/Thread", "isAlive", "()Z") => java_lang_thread::is_alive,
+        ("java/lang/Thread", "interrupt0", "()V") => java_lang_thread::interrupt0,
+        ("java/lang/Thread", "isInterrupted", "(Z)Z") => java_lang_thread::is_interrupted,
+        (
+            "java/lang/reflect/AccessibleObject",
+            "checkCanSetAccessible",
+            "(Ljava/lang/Class;Ljava/lang/Class;Z)Z",
+        ) => java_lang_reflect_accessible_object::check_can_set_accessible,
+        (
+            "jdk/internal/loader/BootLoader",
+            "findResource",
+            "(Ljava/lang/String;)Ljava/net/URL;",
+        ) => jdk_internal_loader_boot_loader::find_resource,
+        (
+            "jdk/internal/loader/BootLoader",
+            "findResources",
+            "(Ljava/lang/String;)Ljava/util/Enumeration;",
+        ) => jdk_internal_loader_boot_loader::find_resources,
+        (
+            "jdk/internal/loader/BootLoader",
+            "findResourceAsStream",
+            "(Ljava/lang/String;Ljava/lang/String;)Ljava/io/InputStream;",
+        ) => jdk_internal_loader_boot_loader::find_resource_as_stream,
+        ("sun/misc/Unsafe" | "jdk/internal/misc/Unsafe", name, descriptor) => {
+            return jdk_internal_misc_unsafe::find(name, descriptor)
+        }
+        ("jdk/internal/reflect/Reflection", "getCallerClass", "()Ljava/lang/Class;") => {
+            jdk_internal_reflect_reflection::get_caller_class
+        }
+        _ => return None,
+    };
+    Some(native_method)
+}
+
+fn register_natives(_: &mut Thread, _: Vec<Slot>) -> Vec<Slot> {
+    vec![]
+}
+
+/// The loader of the bootstrap classes, which mirrors belong to.
+fn class_loader(thread: &Thread, object: ObjectRef) -> Rc<ClassLoader> {
+    let class = Rc::clone(&thread.heap.borrow().get(object).class);
+    class.loader.upgrade().expect("ClassLoader is dropped")
+}
+
+fn mirrored_class(thread: &Thread, mirror: Option<ObjectRef>) -> Rc<Class> {
+    let mirror = mirror.expect("java.lang.NullPointerException");
+    class_loader(thread, mirror)
+        .class_of_mirror(mirror)
+        .expect("java.lang.IllegalArgumentException: not a class mirror")
+}
+
+fn this(args: &[Slot]) -> ObjectRef {
+    match args[0] {
+        Slot::Ref(Some(object)) => object,
+        Slot::Ref(None) => panic!("java.lang.NullPointerException"),
+        _ => panic!("Not Ref"),
+    }
+}
+
+fn ref_arg(args: &[Slot], index: usize) -> Option<ObjectRef> {
+    match args[index] {
+        Slot::Ref(val) => val,
+        Slot::Num(_) => panic!("Not Ref"),
+    }
+}
+
+fn int_arg(args: &[Slot], index: usize) -> i32 {
+    match args[index] {
+        Slot::Num(val) => val,
+        Slot::Ref(_) => panic!("Not Num"),
+    }
+}
+
+fn long_arg(args: &[Slot], index: usize) -> i64 {
+    match (args[index], args[index + 1]) {
+        (Slot::Num(a), Slot::Num(b)) => converter::i32seq_to_i64([a, b]),
+        _ => panic!("Not Num"),
+    }
+}
@@ -0,0 +1,80 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+/// How much virtual time passes per instruction.
+const INSTRUCTION_TIME: Duration = Duration::from_micros(1);
+
+/// Time since the VM started, as `Thread.sleep` and timed `Object.wait` see it.
+///
+/// It's the wall clock unless switched to virtual time, which only advances as
+/// instructions run and jumps ahead when every thread is asleep, so that timeouts don't
+/// depend on how fast the host is.
+pub struct Clock {
+    start: Instant,
+    virtual_time: Cell<Option<Duration>>,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Clock::new()
+    }
+}
+
+impl Clock {
+    pub fn new() -> Clock {
+        Clock {
+            start: Instant::now(),
+            virtual_time: Cell::new(None),
+        }
+    }
+
+    pub fn use_virtual_time(&self) {
+        self.virtual_time.set(Some(Duration::ZERO));
+    }
+
+    pub fn is_virtual(&self) -> bool {
+        self.virtual_time.get().is_some()
+    }
+
+    pub fn now(&self) -> Duration {
+        self.virtual_time
+            .get()
+            .unwrap_or_else(|| self.start.elapsed())
+    }
+
+    /// Accounts for one executed instruction.
+    pub fn tick(&self) {
+        if let Some(time) = self.virtual_time.get() {
+            self.virtual_time.set(Some(time + INSTRUCTION_TIME));
+        }
+    }
+
+    /// Blocks the host thread until `deadline`, or just moves virtual time there.
+    pub fn sleep_until(&self, deadline: Duration) {
+        match self.virtual_time.get() {
+            Some(time) => self.virtual_time.set(Some(time.max(deadline))),
+            None => std::thread::sleep(deadline.saturating_sub(self.now())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::rtda::clock::Clock;
+
+    #[test]
+    fn virtual_time() {
+        let clock = Clock::new();
+        clock.use_virtual_time();
+        assert_eq!(clock.now(), Duration::ZERO);
+        clock.tick();
+        clock.tick();
+        assert_eq!(clock.now(), Duration::from_micros(2));
+        clock.sleep_until(Duration::from_secs(60));
+        assert_eq!(clock.now(), Duration::from_secs(60));
+        clock.sleep_until(Duration::from_secs(1));
+        assert_eq!(clock.now(), Duration::from_secs(60));
+    }
+}
@@ -11,6 +11,8 @@ pub struct Frame {
     pub operand_stack: OperandStack,
     pub method: Rc<Method>,
     pub class: Rc<Class>,
+    /// Where to resume this frame once the callee on top of it returns.
+    pub next_pc: usize,
 }
 
 impl Frame {
@@ -28,13 +30,15 @@ impl Frame {
             local_vars,
             operand_stack,
             method,
+            next_pc: 0,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::rc::Rc;
+    use std::cell::RefCell;
+    use std::rc::{Rc, Weak};
 
     use vec_map::VecMap;
 
@@ -64,38 +68,44 @@ mod tests {
             },
             fields: Vec::new(),
             methods: Vec::new(),
+            loader: Weak::new(),
             super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
             instance_slot_count: 0usize,
             static_slot_count: 0usize,
-            static_vars: Vars::new(2),
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
         });
-        let frame = Frame::new(class, method);
-        local_vars(frame.local_vars);
-        operand_stack(frame.operand_stack);
+        let mut frame = Frame::new(class, method);
+        local_vars(&mut frame.local_vars);
+        operand_stack(&mut frame.operand_stack);
     }
 
-    fn local_vars(local_vars: Vars) {
-        let local_vars = local_vars.set_int(0, 100);
-        let local_vars = local_vars.set_int(1, -100);
+    fn local_vars(local_vars: &mut Vars) {
+        local_vars.set_int(0, 100);
+        local_vars.set_int(1, -100);
         assert_eq!(local_vars.get_int(0), 100);
         assert_eq!(local_vars.get_int(1), -100);
     }
 
-    fn operand_stack(operand_stack: OperandStack) {
-        let operand_stack = operand_stack.push_int(100);
-        let operand_stack = operand_stack.push_double(2.71828182845f64);
-        let operand_stack = operand_stack.push_int(-100);
-        let operand_stack = operand_stack.push_long(2997924580);
-        let operand_stack = operand_stack.push_float(3.1415926);
-        let (val, operand_stack) = operand_stack.pop_float();
+    fn operand_stack(operand_stack: &mut OperandStack) {
+        operand_stack.push_int(100);
+        operand_stack.push_double(2.71828182845f64);
+        operand_stack.push_int(-100);
+        operand_stack.push_long(2997924580);
+        operand_stack.push_float(3.1415926);
+        let val = operand_stack.pop_float();
         assert_eq!(val, 3.1415926);
-        let (val, operand_stack) = operand_stack.pop_long();
+        let val = operand_stack.pop_long();
         assert_eq!(val, 2997924580);
-        let (val, operand_stack) = operand_stack.pop_int();
+        let val = operand_stack.pop_int();
         assert_eq!(val, -100);
-        let (val, operand_stack) = operand_stack.pop_double();
+        let val = operand_stack.pop_double();
         assert_eq!(val, 2.71828182845f64);
-        let (val, _) = operand_stack.pop_int();
+        let val = operand_stack.pop_int();
         assert_eq!(val, 100);
     }
 }
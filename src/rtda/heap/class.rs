@@ -1,8 +1,17 @@
-use std::rc::Rc;
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use vec_map::VecMap;
 
 use crate::classfile::constant_pool::ConstantPool;
+use crate::classpath::classpath::CodeSource;
+use crate::rtda::heap::access_flags::*;
+use crate::rtda::heap::class_loader::ClassLoader;
 use crate::rtda::heap::field::Field;
+use crate::rtda::heap::field_ref::FieldRef;
+use crate::rtda::heap::interface_method_ref::InterfaceMethodRef;
 use crate::rtda::heap::method::Method;
+use crate::rtda::heap::method_ref::MethodRef;
 use crate::rtda::vars::Vars;
 
 #[derive(Debug)]
@@ -12,14 +21,22 @@ pub struct Class {
     //    pub super_class_name: String,
     //    interface_names: Vec<String>,
     pub constant_pool: ConstantPool,
-    pub fields: Vec<Field>,
+    pub fields: Vec<Rc<Field>>,
     pub methods: Vec<Rc<Method>>,
-    //    loader * ClassLoader
+    pub loader: Weak<ClassLoader>,
     pub super_class: Option<Rc<Class>>,
-    //    interfaces        [] * Class
+    pub interfaces: Vec<Rc<Class>>,
+    pub component_class: Option<Rc<Class>>,
+    /// Virtual methods indexed by `Method::vtable_index`, built when the class is linked.
+    pub vtable: Vec<Rc<Method>>,
+    /// Methods already resolved from `MethodRef` and `InterfaceMethodRef` constants, indexed
+    /// by constant pool index.
+    pub resolved_method_refs: RefCell<VecMap<Rc<Method>>>,
     pub instance_slot_count: usize,
     pub static_slot_count: usize,
-    pub static_vars: Vars,
+    pub static_vars: RefCell<Vars>,
+    /// Where the class file was read from, `None` for array and primitive classes.
+    pub source: Option<CodeSource>,
 }
 
 impl Class {
@@ -40,4 +57,420 @@ impl Class {
             .expect("Method not found");
         Rc::clone(reference)
     }
+
+    /// Method lookup in this class and its superclasses, then its superinterfaces.
+    ///
+    /// See [§5.4.3.3](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-5.html#jvms-5.4.3.3).
+    pub fn lookup_method(&self, name: &str, descriptor: &str) -> Option<Rc<Method>> {
+        let mut class = Some(self);
+        while let Some(c) = class {
+            let method = c
+                .methods
+                .iter()
+                .find(|x| x.name() == name && x.descriptor() == descriptor);
+            if let Some(method) = method {
+                return Some(Rc::clone(method));
+            }
+            class = c.super_class.as_deref();
+        }
+        self.lookup_method_in_interfaces(name, descriptor)
+    }
+
+    fn lookup_method_in_interfaces(&self, name: &str, descriptor: &str) -> Option<Rc<Method>> {
+        let mut class = Some(self);
+        while let Some(c) = class {
+            for iface in c.interfaces.iter() {
+                let method = iface
+                    .methods
+                    .iter()
+                    .find(|x| x.name() == name && x.descriptor() == descriptor)
+                    .cloned()
+                    .or_else(|| iface.lookup_method_in_interfaces(name, descriptor));
+                if method.is_some() {
+                    return method;
+                }
+            }
+            class = c.super_class.as_deref();
+        }
+        None
+    }
+
+    /// Field lookup in this class, its superinterfaces, then its superclass.
+    ///
+    /// See [§5.4.3.2](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-5.html#jvms-5.4.3.2).
+    pub fn lookup_field(&self, name: &str, descriptor: &str) -> Option<Rc<Field>> {
+        self.fields
+            .iter()
+            .find(|x| x.name() == name && x.descriptor() == descriptor)
+            .cloned()
+            .or_else(|| {
+                self.interfaces
+                    .iter()
+                    .find_map(|x| x.lookup_field(name, descriptor))
+            })
+            .or_else(|| {
+                self.super_class
+                    .as_ref()
+                    .and_then(|x| x.lookup_field(name, descriptor))
+            })
+    }
+
+    pub fn resolved_method_ref(&self, index: usize) -> Rc<Method> {
+        if let Some(method) = self.resolved_method_refs.borrow().get(index) {
+            return Rc::clone(method);
+        }
+        let method = MethodRef::new(&self.constant_pool, index).resolved_method(self);
+        self.resolved_method_refs
+            .borrow_mut()
+            .insert(index, Rc::clone(&method));
+        method
+    }
+
+    pub fn resolved_interface_method_ref(&self, index: usize) -> Rc<Method> {
+        if let Some(method) = self.resolved_method_refs.borrow().get(index) {
+            return Rc::clone(method);
+        }
+        let method =
+            InterfaceMethodRef::new(&self.constant_pool, index).resolved_interface_method(self);
+        self.resolved_method_refs
+            .borrow_mut()
+            .insert(index, Rc::clone(&method));
+        method
+    }
+
+    pub fn resolved_field_ref(&self, index: usize) -> Rc<Field> {
+        FieldRef::new(&self.constant_pool, index).resolved_field(self)
+    }
+
+    pub fn is_public(&self) -> bool {
+        self.access_flags & ACC_PUBLIC != 0
+    }
+
+    pub fn is_abstract(&self) -> bool {
+        self.access_flags & ACC_ABSTRACT != 0
+    }
+
+    pub fn is_super(&self) -> bool {
+        self.access_flags & ACC_SUPER != 0
+    }
+
+    pub fn is_interface(&self) -> bool {
+        self.access_flags & ACC_INTERFACE != 0
+    }
+
+    pub fn is_array(&self) -> bool {
+        self.name.starts_with('[')
+    }
+
+    pub fn is_primitive(&self) -> bool {
+        matches!(
+            self.name.as_str(),
+            "void" | "boolean" | "byte" | "char" | "short" | "int" | "long" | "float" | "double"
+        )
+    }
+
+    pub fn java_name(&self) -> String {
+        self.name.replace('/', ".")
+    }
+
+    pub fn package_name(&self) -> &str {
+        match self.name.rfind('/') {
+            Some(index) => &self.name[..index],
+            None => "",
+        }
+    }
+
+    pub fn is_accessible_to(&self, other: &Class) -> bool {
+        self.package_name() == other.package_name()
+            || (self.is_public() && self.check_module_access(other).is_ok())
+    }
+
+    /// Checks that the module of `other` reads the module of this class and that it
+    /// exports its package to it, which it always does until modules are resolved.
+    pub fn check_module_access(&self, other: &Class) -> Result<(), String> {
+        match self.loader.upgrade() {
+            Some(loader) => match loader.modules() {
+                Some(modules) => modules.check_access(&other.name, &self.name),
+                None => Ok(()),
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that `other` may reflect on a member of this class, a public one if `public`:
+    /// the package of this class must be opened to the module of `other`, or only exported
+    /// to it if both the class and the member are public.
+    pub fn check_reflective_access(&self, other: &Class, public: bool) -> Result<(), String> {
+        match self.loader.upgrade() {
+            Some(loader) => match loader.modules() {
+                Some(modules) => {
+                    let public = public && self.is_public();
+                    modules.check_reflective_access(&other.name, &self.name, public)
+                }
+                None => Ok(()),
+            },
+            None => Ok(()),
+        }
+    }
+
+    fn is_java_lang_object(&self) -> bool {
+        self.name == "java/lang/Object"
+    }
+
+    fn is_java_lang_cloneable(&self) -> bool {
+        self.name == "java/lang/Cloneable"
+    }
+
+    fn is_java_io_serializable(&self) -> bool {
+        self.name == "java/io/Serializable"
+    }
+
+    pub fn is_sub_class_of(&self, other: &Class) -> bool {
+        let mut super_class = self.super_class.as_ref();
+        while let Some(class) = super_class {
+            if std::ptr::eq(class.as_ref(), other) {
+                return true;
+            }
+            super_class = class.super_class.as_ref();
+        }
+        false
+    }
+
+    pub fn is_implements(&self, iface: &Class) -> bool {
+        let mut class = Some(self);
+        while let Some(c) = class {
+            let found = c
+                .interfaces
+                .iter()
+                .any(|x| std::ptr::eq(x.as_ref(), iface) || x.is_sub_interface_of(iface));
+            if found {
+                return true;
+            }
+            class = c.super_class.as_deref();
+        }
+        false
+    }
+
+    pub fn is_sub_interface_of(&self, iface: &Class) -> bool {
+        self.interfaces
+            .iter()
+            .any(|x| std::ptr::eq(x.as_ref(), iface) || x.is_sub_interface_of(iface))
+    }
+
+    /// Whether a value of class `other` can be assigned to a variable of this class,
+    /// following the rules of checkcast and instanceof.
+    ///
+    /// See [§6.5](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-6.html#jvms-6.5.checkcast).
+    pub fn is_assignable_from(&self, other: &Class) -> bool {
+        if std::ptr::eq(self, other) {
+            return true;
+        }
+        if !other.is_array() {
+            match (other.is_interface(), self.is_interface()) {
+                (false, false) => other.is_sub_class_of(self),
+                (false, true) => other.is_implements(self),
+                (true, false) => self.is_java_lang_object(),
+                (true, true) => other.is_sub_interface_of(self),
+            }
+        } else if !self.is_array() {
+            if self.is_interface() {
+                self.is_java_lang_cloneable() || self.is_java_io_serializable()
+            } else {
+                self.is_java_lang_object()
+            }
+        } else {
+            let sc = other
+                .component_class
+                .as_ref()
+                .expect("Array without component");
+            let tc = self
+                .component_class
+                .as_ref()
+                .expect("Array without component");
+            if sc.is_primitive() || tc.is_primitive() {
+                sc.name == tc.name
+            } else {
+                tc.is_assignable_from(sc)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::{Rc, Weak};
+
+    use vec_map::VecMap;
+
+    use crate::classfile::constant_pool::ConstantPool;
+    use crate::rtda::heap::access_flags::*;
+    use crate::rtda::heap::class::Class;
+    use crate::rtda::vars::Vars;
+
+    fn class(
+        name: &str,
+        access_flags: u16,
+        super_class: Option<Rc<Class>>,
+        interfaces: Vec<Rc<Class>>,
+        component_class: Option<Rc<Class>>,
+    ) -> Rc<Class> {
+        Rc::new(Class {
+            access_flags,
+            name: name.to_string(),
+            constant_pool: ConstantPool {
+                vec_map: VecMap::new(),
+            },
+            fields: Vec::new(),
+            methods: Vec::new(),
+            loader: Weak::new(),
+            super_class,
+            interfaces,
+            component_class,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
+            instance_slot_count: 0usize,
+            static_slot_count: 0usize,
+            static_vars: RefCell::new(Vars::new(2)),
+            source: None,
+        })
+    }
+
+    struct Hierarchy {
+        object: Rc<Class>,
+        cloneable: Rc<Class>,
+        serializable: Rc<Class>,
+        animal: Rc<Class>,
+        dog: Rc<Class>,
+        pet: Rc<Class>,
+        guide: Rc<Class>,
+    }
+
+    fn hierarchy() -> Hierarchy {
+        let object = class("java/lang/Object", ACC_PUBLIC, None, vec![], None);
+        let cloneable = class(
+            "java/lang/Cloneable",
+            ACC_PUBLIC | ACC_INTERFACE | ACC_ABSTRACT,
+            Some(object.clone()),
+            vec![],
+            None,
+        );
+        let serializable = class(
+            "java/io/Serializable",
+            ACC_PUBLIC | ACC_INTERFACE | ACC_ABSTRACT,
+            Some(object.clone()),
+            vec![],
+            None,
+        );
+        let pet = class(
+            "Pet",
+            ACC_PUBLIC | ACC_INTERFACE | ACC_ABSTRACT,
+            Some(object.clone()),
+            vec![],
+            None,
+        );
+        let guide = class(
+            "Guide",
+            ACC_PUBLIC | ACC_INTERFACE | ACC_ABSTRACT,
+            Some(object.clone()),
+            vec![pet.clone()],
+            None,
+        );
+        let animal = class("Animal", ACC_PUBLIC, Some(object.clone()), vec![], None);
+        let dog = class(
+            "Dog",
+            ACC_PUBLIC,
+            Some(animal.clone()),
+            vec![guide.clone()],
+            None,
+        );
+        Hierarchy {
+            object,
+            cloneable,
+            serializable,
+            animal,
+            dog,
+            pet,
+            guide,
+        }
+    }
+
+    fn array(name: &str, component: &Rc<Class>, h: &Hierarchy) -> Rc<Class> {
+        class(
+            name,
+            ACC_PUBLIC,
+            Some(h.object.clone()),
+            vec![h.cloneable.clone(), h.serializable.clone()],
+            Some(component.clone()),
+        )
+    }
+
+    #[test]
+    fn is_assignable_from_class() {
+        let h = hierarchy();
+        assert!(h.animal.is_assignable_from(&h.animal));
+        assert!(h.animal.is_assignable_from(&h.dog));
+        assert!(h.object.is_assignable_from(&h.dog));
+        assert!(!h.dog.is_assignable_from(&h.animal));
+        assert!(!h.dog.is_assignable_from(&h.object));
+    }
+
+    #[test]
+    fn is_assignable_from_interface() {
+        let h = hierarchy();
+        assert!(h.guide.is_assignable_from(&h.dog));
+        assert!(h.pet.is_assignable_from(&h.dog));
+        assert!(h.pet.is_assignable_from(&h.guide));
+        assert!(!h.guide.is_assignable_from(&h.pet));
+        assert!(!h.pet.is_assignable_from(&h.animal));
+        assert!(h.object.is_assignable_from(&h.pet));
+        assert!(!h.animal.is_assignable_from(&h.pet));
+    }
+
+    #[test]
+    fn is_assignable_from_reference_array() {
+        let h = hierarchy();
+        let objects = array("[Ljava/lang/Object;", &h.object, &h);
+        let animals = array("[LAnimal;", &h.animal, &h);
+        let dogs = array("[LDog;", &h.dog, &h);
+        let pets = array("[LPet;", &h.pet, &h);
+        let dogs_2d = array("[[LDog;", &dogs, &h);
+        let animals_2d = array("[[LAnimal;", &animals, &h);
+
+        assert!(animals.is_assignable_from(&dogs));
+        assert!(!dogs.is_assignable_from(&animals));
+        assert!(pets.is_assignable_from(&dogs));
+        assert!(objects.is_assignable_from(&pets));
+        assert!(objects.is_assignable_from(&dogs_2d));
+        assert!(animals_2d.is_assignable_from(&dogs_2d));
+        assert!(!animals.is_assignable_from(&dogs_2d));
+
+        assert!(h.object.is_assignable_from(&dogs));
+        assert!(h.cloneable.is_assignable_from(&dogs));
+        assert!(h.serializable.is_assignable_from(&dogs));
+        assert!(!h.pet.is_assignable_from(&dogs));
+        assert!(!h.animal.is_assignable_from(&dogs));
+        assert!(!dogs.is_assignable_from(&h.object));
+    }
+
+    #[test]
+    fn is_assignable_from_primitive_array() {
+        let h = hierarchy();
+        let int = class("int", ACC_PUBLIC, None, vec![], None);
+        let long = class("long", ACC_PUBLIC, None, vec![], None);
+        let ints = array("[I", &int, &h);
+        let other_ints = array("[I", &int, &h);
+        let longs = array("[J", &long, &h);
+        let objects = array("[Ljava/lang/Object;", &h.object, &h);
+        let ints_2d = array("[[I", &ints, &h);
+
+        assert!(ints.is_assignable_from(&other_ints));
+        assert!(!ints.is_assignable_from(&longs));
+        assert!(!objects.is_assignable_from(&ints));
+        assert!(!ints.is_assignable_from(&objects));
+        assert!(objects.is_assignable_from(&ints_2d));
+        assert!(h.object.is_assignable_from(&ints));
+        assert!(h.cloneable.is_assignable_from(&ints));
+        assert!(h.serializable.is_assignable_from(&ints));
+    }
 }
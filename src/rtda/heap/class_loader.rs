@@ -1,19 +1,38 @@
+use std::cell::{Cell, OnceCell, RefCell};
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
+
+use vec_map::VecMap;
 
 use crate::classfile::class_file::ClassFile;
 use crate::classfile::class_reader::ClassReader;
 use crate::classfile::constant_info::ConstantInfo;
 use crate::classfile::constant_pool::ConstantPool;
-use crate::classpath::classpath::ClassPath;
+use crate::classfile::member_info::MemberInfo;
+use crate::classpath::classpath::{ClassOrigin, ClassPath, CodeSource};
+use crate::gc::heap::Heap;
+use crate::rtda::heap::access_flags::{ACC_INTERFACE, ACC_PUBLIC};
 use crate::rtda::heap::class::Class;
 use crate::rtda::heap::field::Field;
 use crate::rtda::heap::method::Method;
+use crate::rtda::heap::module::{ModuleGraph, ModuleOptions};
+use crate::rtda::heap::object::{Object, ObjectRef};
 use crate::rtda::vars::Vars;
+use crate::verifier::{verify, Verification};
 
 pub struct ClassLoader {
     class_path: ClassPath,
-    class_map: HashMap<String, Rc<Class>>,
+    class_map: RefCell<HashMap<String, Rc<Class>>>,
+    /// The `java.lang.Class` object of each loaded class, by class name, created on first use.
+    mirrors: RefCell<HashMap<String, ObjectRef>>,
+    verification: Cell<Verification>,
+    /// Whether each class is printed with where it was read from as it's loaded, like
+    /// `-verbose:class` does.
+    verbose_class: Cell<bool>,
+    /// The resolved modules, all classes being in the unnamed module until then.
+    modules: OnceCell<ModuleGraph>,
+    /// The provider classes of each service looked up, by service name.
+    service_providers: RefCell<HashMap<String, Vec<String>>>,
 }
 
 struct Acc {
@@ -21,40 +40,240 @@ struct Acc {
     next_instance_field_slot_id: usize,
     next_static_field_slot_id: usize,
     static_vars: Vars,
+    fields: Vec<Field>,
+}
+
+const PRIMITIVE_TYPES: [(&str, &str); 9] = [
+    ("V", "void"),
+    ("Z", "boolean"),
+    ("B", "byte"),
+    ("C", "char"),
+    ("S", "short"),
+    ("I", "int"),
+    ("J", "long"),
+    ("F", "float"),
+    ("D", "double"),
+];
+
+/// `[[I` -> `[I`, `[I` -> `int`, `[Ljava/lang/Object;` -> `java/lang/Object`
+fn component_class_name(array_class_name: &str) -> String {
+    let descriptor = &array_class_name[1..];
+    if descriptor.starts_with('[') {
+        descriptor.to_owned()
+    } else if descriptor.starts_with('L') && descriptor.ends_with(';') {
+        descriptor[1..descriptor.len() - 1].to_owned()
+    } else {
+        PRIMITIVE_TYPES
+            .iter()
+            .find(|(x, _)| *x == descriptor)
+            .map(|(_, name)| name.to_string())
+            .expect("Invalid array class name")
+    }
 }
 
 impl ClassLoader {
-    pub fn new(class_path: ClassPath) -> ClassLoader {
-        ClassLoader {
+    pub fn new(class_path: ClassPath) -> Rc<ClassLoader> {
+        Rc::new(ClassLoader {
             class_path,
-            class_map: HashMap::new(),
+            class_map: RefCell::new(HashMap::new()),
+            mirrors: RefCell::new(HashMap::new()),
+            verification: Cell::new(Verification::Remote),
+            verbose_class: Cell::new(false),
+            modules: OnceCell::new(),
+            service_providers: RefCell::new(HashMap::new()),
+        })
+    }
+
+    /// Chooses which classes are verified as they're loaded, `Remote` by default.
+    pub fn set_verification(&self, verification: Verification) {
+        self.verification.set(verification);
+    }
+
+    pub fn set_verbose_class(&self, verbose_class: bool) {
+        self.verbose_class.set(verbose_class);
+    }
+
+    /// Resolves the module graph of `options`, once, before classes are loaded.
+    pub fn resolve_modules(&self, options: &ModuleOptions) {
+        let graph = ModuleGraph::resolve(&self.class_path, options);
+        if self.modules.set(graph).is_err() {
+            panic!("Modules are already resolved");
         }
     }
 
-    pub fn load(self, name: String) -> (Rc<Class>, ClassLoader) {
-        println!("load {}", &name);
-        if self.class_map.contains_key(&name) {
-            let class = Rc::clone(self.class_map.get(&name).unwrap());
-            (class, self)
+    pub fn modules(&self) -> Option<&ModuleGraph> {
+        self.modules.get()
+    }
+
+    pub fn class_path(&self) -> &ClassPath {
+        &self.class_path
+    }
+
+    /// The provider classes of the service `service`, like `pkg/Impl`, which the
+    /// `META-INF/services/` files named after it list one per line. Each comes once, in the
+    /// order `ServiceLoader` finds them. The files are only read the first time.
+    pub fn service_providers(&self, service: &str) -> Vec<String> {
+        let mut service_providers = self.service_providers.borrow_mut();
+        let providers = service_providers
+            .entry(service.to_owned())
+            .or_insert_with(|| self.read_service_providers(service));
+        providers.clone()
+    }
+
+    fn read_service_providers(&self, service: &str) -> Vec<String> {
+        let name = format!("META-INF/services/{}", service.replace('/', "."));
+        let mut providers: Vec<String> = Vec::new();
+        for data in self.class_path.read_resources(&name) {
+            for line in String::from_utf8_lossy(&data).lines() {
+                let provider = line.split('#').next().unwrap().trim().replace('.', "/");
+                if !provider.is_empty() && !providers.contains(&provider) {
+                    providers.push(provider);
+                }
+            }
+        }
+        providers
+    }
+
+    pub fn load(self: &Rc<Self>, name: &str) -> Rc<Class> {
+        trace!("load {}", name);
+        if let Some(class) = self.class_map.borrow().get(name) {
+            return Rc::clone(class);
+        }
+        let class = if name.starts_with('[') {
+            self.load_array_class(name)
+        } else if PRIMITIVE_TYPES.iter().any(|(_, x)| *x == name) {
+            self.load_primitive_class(name)
         } else {
-            let data = self.read(&name);
-            let (class, mut class_loader) = ClassLoader::define(self, data);
-            let class_copy = Rc::clone(&class);
-            class_loader.class_map.insert(name, class);
-            (class_copy, class_loader)
+            let (data, origin, source) = self.read(name);
+            let class_file = data.parse();
+            let major_version = class_file.major_version;
+            let class = self.define(class_file, source);
+            if self.verbose_class.get() {
+                let source = class.source.as_ref().unwrap();
+                println!("[Loaded {} from {}]", class.java_name(), source);
+            }
+            self.class_map
+                .borrow_mut()
+                .insert(name.to_owned(), Rc::clone(&class));
+            self.verify(&class, major_version, origin);
+            return class;
+        };
+        self.class_map
+            .borrow_mut()
+            .insert(name.to_owned(), Rc::clone(&class));
+        class
+    }
+
+    /// Verifies a class that's just been loaded, and put in the class map already so that
+    /// its methods can refer to it.
+    fn verify(&self, class: &Class, major_version: u16, origin: ClassOrigin) {
+        let verified = match self.verification.get() {
+            Verification::None => false,
+            Verification::Remote => origin != ClassOrigin::Boot,
+            Verification::All => true,
+        };
+        if verified {
+            if let Err(e) = verify(class, major_version) {
+                self.class_map.borrow_mut().remove(&class.name);
+                panic!("java.lang.VerifyError: {}", e);
+            }
         }
     }
 
-    fn read(&self, name: &str) -> Vec<u8> {
+    /// References held by the static fields of every loaded class, and the classes'
+    /// `java.lang.Class` objects.
+    pub fn static_roots(&self) -> Vec<ObjectRef> {
+        self.class_map
+            .borrow()
+            .values()
+            .flat_map(|x| x.static_vars.borrow().refs().collect::<Vec<_>>())
+            .chain(self.mirrors.borrow().values().copied())
+            .collect()
+    }
+
+    /// The `java.lang.Class` object of `class`, or `None` if the heap has no room for it.
+    pub fn mirror(self: &Rc<Self>, class: &Class, heap: &mut Heap) -> Option<ObjectRef> {
+        if let Some(mirror) = self.mirrors.borrow().get(&class.name) {
+            return Some(*mirror);
+        }
+        let mirror = heap.allocate(Object::new(self.load("java/lang/Class")))?;
+        self.mirrors.borrow_mut().insert(class.name.clone(), mirror);
+        Some(mirror)
+    }
+
+    /// Every loaded class, by name.
+    pub fn classes(&self) -> Vec<Rc<Class>> {
+        let mut classes: Vec<Rc<Class>> = self.class_map.borrow().values().cloned().collect();
+        classes.sort_by(|x, y| x.name.cmp(&y.name));
+        classes
+    }
+
+    /// The class `mirror` is the `java.lang.Class` object of.
+    pub fn class_of_mirror(&self, mirror: ObjectRef) -> Option<Rc<Class>> {
+        let mirrors = self.mirrors.borrow();
+        let (name, _) = mirrors.iter().find(|(_, x)| **x == mirror)?;
+        self.class_map.borrow().get(name).cloned()
+    }
+
+    fn read(&self, name: &str) -> (Vec<u8>, ClassOrigin, CodeSource) {
         self.class_path
-            .read_class(name)
+            .find_class_source(name)
             .expect("java.lang.ClassNotFoundException")
     }
 
-    fn define(class_loader: ClassLoader, data: Vec<u8>) -> (Rc<Class>, ClassLoader) {
-        let class_file = data.parse();
+    fn load_primitive_class(self: &Rc<Self>, name: &str) -> Rc<Class> {
+        Rc::new(Class {
+            access_flags: ACC_PUBLIC,
+            name: name.to_owned(),
+            constant_pool: ConstantPool {
+                vec_map: VecMap::new(),
+            },
+            fields: Vec::new(),
+            methods: Vec::new(),
+            loader: Rc::downgrade(self),
+            super_class: None,
+            interfaces: Vec::new(),
+            component_class: None,
+            vtable: Vec::new(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
+            instance_slot_count: 0,
+            static_slot_count: 0,
+            static_vars: RefCell::new(Vars::new(1)),
+            source: None,
+        })
+    }
+
+    fn load_array_class(self: &Rc<Self>, name: &str) -> Rc<Class> {
+        let component_class = self.load(&component_class_name(name));
+        let object_class = self.load("java/lang/Object");
+        Rc::new(Class {
+            access_flags: ACC_PUBLIC,
+            name: name.to_owned(),
+            constant_pool: ConstantPool {
+                vec_map: VecMap::new(),
+            },
+            fields: Vec::new(),
+            methods: Vec::new(),
+            loader: Rc::downgrade(self),
+            vtable: object_class.vtable.clone(),
+            resolved_method_refs: RefCell::new(VecMap::new()),
+            super_class: Some(object_class),
+            interfaces: vec![
+                self.load("java/lang/Cloneable"),
+                self.load("java/io/Serializable"),
+            ],
+            component_class: Some(component_class),
+            instance_slot_count: 0,
+            static_slot_count: 0,
+            static_vars: RefCell::new(Vars::new(1)),
+            source: None,
+        })
+    }
+
+    fn define(self: &Rc<Self>, class_file: ClassFile, source: CodeSource) -> Rc<Class> {
         let name = class_file.class_name().to_owned();
         let super_class_name = class_file.super_class_name().to_owned();
+        let interface_names = class_file.interface_names();
         let ClassFile {
             access_flags,
             methods,
@@ -63,67 +282,75 @@ impl ClassLoader {
             ..
         } = class_file;
 
-        let methods: Vec<Rc<Method>> = methods
-            .into_iter()
-            .map(|x| Rc::new(Method::new(x)))
-            .collect();
-
-        let (super_class, class_loader) = if name != "java/lang/Object" {
-            let (class, class_loader) = class_loader.load(super_class_name);
-            (Some(class), class_loader)
+        let super_class = if name != "java/lang/Object" {
+            Some(self.load(&super_class_name))
         } else {
-            (None, class_loader)
+            None
         };
+        let interfaces: Vec<Rc<Class>> = interface_names.iter().map(|x| self.load(x)).collect();
 
-        fn fold_func(acc: Acc, field: &Field) -> Acc {
+        fn fold_func(acc: Acc, field: Field) -> Acc {
             let Acc {
                 next_instance_field_slot_id: instance_field_slot_id,
                 next_static_field_slot_id: static_field_slot_id,
                 static_vars,
                 constant_pool,
+                fields,
             } = acc;
             let slot_id_delta: usize = if field.is_long_or_double() { 2 } else { 1 };
-            let (next_instance_field_slot_id, next_static_field_slot_id, static_vars) =
+            let (slot_id, next_instance_field_slot_id, next_static_field_slot_id, static_vars) =
                 if field.is_static() {
-                    let static_vars: Vars = if field.is_final() {
-                        let constant_value_index = field.constant_value_index;
-                        if constant_value_index.is_some() {
-                            let constant_value_index = constant_value_index.unwrap();
-                            match field.class_member.descriptor.as_str() {
-                                // todo: Complete  Z B C S I J D Ljava/lang/String
-                                "F" => {
-                                    let val = match constant_pool.get(constant_value_index) {
-                                        ConstantInfo::Integer(val) => *val,
-                                        _ => panic!("Not Integer"),
-                                    };
-                                    static_vars.set_int(static_field_slot_id, val)
+                    let mut static_vars = static_vars;
+                    static_vars.set_slot(static_field_slot_id, field.default_value());
+                    if field.is_long_or_double() {
+                        static_vars.set_slot(static_field_slot_id + 1, field.default_value())
+                    }
+                    match field.constant_value_index {
+                        Some(constant_value_index) if field.is_final() => {
+                            let constant_info = constant_pool.get(constant_value_index);
+                            match (field.class_member.descriptor.as_str(), constant_info) {
+                                ("Z" | "B" | "C" | "S" | "I", ConstantInfo::Integer(val)) => {
+                                    static_vars.set_int(static_field_slot_id, *val)
                                 }
-                                _ => panic!("TODO"),
+                                ("J", ConstantInfo::Long(val)) => {
+                                    static_vars.set_long(static_field_slot_id, *val)
+                                }
+                                ("F", ConstantInfo::Float(val)) => {
+                                    static_vars.set_float(static_field_slot_id, *val)
+                                }
+                                ("D", ConstantInfo::Double(val)) => {
+                                    static_vars.set_double(static_field_slot_id, *val)
+                                }
+                                // todo: Ljava/lang/String
+                                ("Ljava/lang/String;", ConstantInfo::String(_)) => {}
+                                _ => panic!("java.lang.ClassFormatError"),
                             }
-                        } else {
-                            static_vars
                         }
-                    } else {
-                        static_vars
+                        _ => {}
                     };
                     (
+                        static_field_slot_id,
                         instance_field_slot_id,
                         static_field_slot_id + slot_id_delta,
                         static_vars,
                     )
                 } else {
                     (
+                        instance_field_slot_id,
                         instance_field_slot_id + slot_id_delta,
                         static_field_slot_id,
                         static_vars,
                     )
                 };
+            let mut fields = fields;
+            fields.push(Field { slot_id, ..field });
 
             Acc {
                 next_instance_field_slot_id,
                 next_static_field_slot_id,
                 static_vars,
                 constant_pool,
+                fields,
             }
         }
         let next_static_field_slot_id: usize = 0;
@@ -133,33 +360,271 @@ impl ClassLoader {
             .unwrap_or(0);
         let static_vars = Vars::new(10);
 
-        let fields: Vec<Field> = fields.into_iter().map(|x| Field::new(x)).collect();
         let Acc {
             next_instance_field_slot_id: instance_slot_count,
             next_static_field_slot_id: static_slot_count,
             static_vars,
             constant_pool,
-        } = fields.iter().fold(
+            fields,
+        } = fields.into_iter().map(Field::new).fold(
             Acc {
                 next_instance_field_slot_id,
                 next_static_field_slot_id,
                 constant_pool,
                 static_vars,
+                fields: Vec::new(),
             },
             fold_func,
         );
-        let class = Rc::new(Class {
-            access_flags,
-            fields,
-            name,
-            super_class,
-            methods,
-            instance_slot_count,
-            static_slot_count,
-            static_vars,
-            constant_pool,
-        });
+        Rc::new_cyclic(|this| {
+            let (methods, vtable) = if access_flags & ACC_INTERFACE != 0 {
+                let methods = methods
+                    .into_iter()
+                    .map(|x| new_method(this, x))
+                    .map(Rc::new)
+                    .collect();
+                (methods, Vec::new())
+            } else {
+                let package_name = match name.rfind('/') {
+                    Some(index) => &name[..index],
+                    None => "",
+                };
+                prepare_vtable(
+                    this,
+                    package_name,
+                    super_class.as_deref(),
+                    &interfaces,
+                    methods,
+                )
+            };
+            let fields = fields
+                .into_iter()
+                .map(|x| {
+                    Rc::new(Field {
+                        class: Weak::clone(this),
+                        ..x
+                    })
+                })
+                .collect();
+            Class {
+                access_flags,
+                fields,
+                name,
+                super_class,
+                methods,
+                loader: Rc::downgrade(self),
+                interfaces,
+                component_class: None,
+                vtable,
+                resolved_method_refs: RefCell::new(VecMap::new()),
+                instance_slot_count,
+                static_slot_count,
+                static_vars: RefCell::new(static_vars),
+                source: Some(source),
+                constant_pool,
+            }
+        })
+    }
+}
 
-        (class, class_loader)
+fn new_method(class: &Weak<Class>, member_info: MemberInfo) -> Method {
+    let mut method = Method::new(member_info);
+    method.class = Weak::clone(class);
+    method
+}
+
+/// Builds the vtable of a class during preparation: the superclass's slots come first and an
+/// overriding method reuses the slot of every method it overrides (§5.4.5), other virtual
+/// methods get a new slot. Interface methods without an implementation are appended last,
+/// so default and abstract (miranda) methods can be dispatched as well.
+fn prepare_vtable(
+    class: &Weak<Class>,
+    package_name: &str,
+    super_class: Option<&Class>,
+    interfaces: &[Rc<Class>],
+    methods: Vec<MemberInfo>,
+) -> (Vec<Rc<Method>>, Vec<Rc<Method>>) {
+    let mut vtable: Vec<Rc<Method>> = super_class.map(|x| x.vtable.clone()).unwrap_or_default();
+    let methods: Vec<Rc<Method>> = methods
+        .into_iter()
+        .map(|x| {
+            let mut method = new_method(class, x);
+            if method.is_static() || method.is_private() || method.is_initializer() {
+                return Rc::new(method);
+            }
+            let overridden: Vec<usize> = vtable
+                .iter()
+                .enumerate()
+                .filter(|(_, x)| method.can_override(x, package_name))
+                .map(|(i, _)| i)
+                .collect();
+            method.vtable_index = Some(overridden.first().cloned().unwrap_or(vtable.len()));
+            let method = Rc::new(method);
+            if overridden.is_empty() {
+                vtable.push(Rc::clone(&method));
+            }
+            for i in overridden {
+                vtable[i] = Rc::clone(&method);
+            }
+            method
+        })
+        .collect();
+
+    let mut pending: Vec<Rc<Class>> = interfaces.to_vec();
+    let mut i = 0;
+    while i < pending.len() {
+        let iface = Rc::clone(&pending[i]);
+        for method in iface.methods.iter() {
+            let is_virtual =
+                !method.is_static() && !method.is_private() && !method.is_initializer();
+            let implemented = vtable
+                .iter()
+                .any(|x| x.name() == method.name() && x.descriptor() == method.descriptor());
+            if is_virtual && !implemented {
+                vtable.push(Rc::clone(method));
+            }
+        }
+        pending.extend(iface.interfaces.iter().cloned());
+        i += 1;
+    }
+
+    (methods, vtable)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::rtda::heap::class::Class;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::heap::method::Method;
+    use crate::rtda::slot::Slot;
+
+    fn method(class: &Class, name: &str) -> Rc<Method> {
+        class
+            .methods
+            .iter()
+            .find(|x| x.name() == name)
+            .cloned()
+            .unwrap()
+    }
+
+    fn slot(class: &Class, name: &str) -> usize {
+        method(class, name).vtable_index.unwrap()
+    }
+
+    #[test]
+    fn vtable() {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let object = class_loader.load("java/lang/Object");
+        let a = class_loader.load("vtable/a/A");
+        let b = class_loader.load("vtable/a/B");
+        let c = class_loader.load("vtable/b/C");
+
+        // A keeps the slots of Object and appends pub, pkg, prot, callPub and callPkg
+        assert_eq!(a.vtable.len(), object.vtable.len() + 5);
+        for (x, y) in object.vtable.iter().zip(a.vtable.iter()) {
+            assert!(Rc::ptr_eq(x, y));
+        }
+        for name in ["priv", "stat", "<init>"] {
+            assert!(method(&a, name).vtable_index.is_none());
+            assert!(a.vtable.iter().all(|x| x.name() != name));
+        }
+
+        // B.pkg overrides A.pkg, they are in the same package
+        assert_eq!(b.vtable.len(), a.vtable.len());
+        assert_eq!(slot(&b, "pkg"), slot(&a, "pkg"));
+        assert!(Rc::ptr_eq(&b.vtable[slot(&a, "pkg")], &method(&b, "pkg")));
+
+        // C.pkg can't override the package-private B.pkg, so it gets a new slot
+        assert_eq!(c.vtable.len(), b.vtable.len() + 1);
+        assert_eq!(slot(&c, "pub"), slot(&a, "pub"));
+        assert_eq!(slot(&c, "prot"), slot(&a, "prot"));
+        assert_eq!(slot(&c, "pkg"), b.vtable.len());
+        assert!(Rc::ptr_eq(&c.vtable[slot(&a, "pkg")], &method(&b, "pkg")));
+        assert!(Rc::ptr_eq(
+            &c.vtable[slot(&a, "callPkg")],
+            &method(&a, "callPkg")
+        ));
+    }
+
+    #[test]
+    fn array_vtable() {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let object = class_loader.load("java/lang/Object");
+        let array = class_loader.load("[Lvtable/a/A;");
+        assert_eq!(array.vtable.len(), object.vtable.len());
+    }
+
+    #[test]
+    fn fields() {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let class = class_loader.load("gc/Node");
+        let slot_id = |name: &str| {
+            class
+                .fields
+                .iter()
+                .find(|x| x.name() == name)
+                .unwrap()
+                .slot_id
+        };
+
+        // next, weight (2 slots) and value
+        assert_eq!(class.instance_slot_count, 4);
+        assert_eq!(slot_id("next"), 0);
+        assert_eq!(slot_id("weight"), 1);
+        assert_eq!(slot_id("value"), 3);
+
+        let static_vars = class.static_vars.borrow();
+        assert_eq!(static_vars.get_int(slot_id("ANSWER")), 42);
+        assert_eq!(static_vars.get_long(slot_id("SEED")), 2997924580);
+        assert_eq!(static_vars.get_float(slot_id("SCALE")), 1.5);
+        assert_eq!(static_vars.get_double(slot_id("RATIO")), 1.6180339887);
+        assert_eq!(static_vars.get_slot(slot_id("head")), Slot::Ref(None));
+        assert_eq!(static_vars.get_int(slot_id("count")), 0);
+    }
+
+    #[test]
+    fn code_source() {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        class_loader.set_verbose_class(true);
+        let source = class_loader.load("vtable/a/A").source.clone().unwrap();
+        assert_eq!(source.path, Path::new("src/test_data"));
+        assert_eq!(source.entry, None);
+        let source = class_loader.load("java/lang/Object").source.clone().unwrap();
+        assert_eq!(source.to_string(), "src/test_data/jre/lib/rt.jar!/java/lang/Object.class");
+        assert!(class_loader.load("[I").source.is_none());
+    }
+
+    #[test]
+    fn service_providers() {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let class_loader = ClassLoader::new(class_path);
+        let providers = class_loader.service_providers("services/Greeter");
+        assert_eq!(providers, vec!["services/Hello", "services/Hi"]);
+        assert_eq!(class_loader.service_providers("services/Greeter"), providers);
+        assert!(class_loader.service_providers("services/Missing").is_empty());
+        assert_eq!(class_loader.service_providers.borrow().len(), 2);
     }
 }
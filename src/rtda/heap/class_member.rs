@@ -29,4 +29,16 @@ impl ClassMember {
     pub fn is_final(&self) -> bool {
         self.access_flags & ACC_FINAL != 0
     }
+
+    pub fn is_public(&self) -> bool {
+        self.access_flags & ACC_PUBLIC != 0
+    }
+
+    pub fn is_protected(&self) -> bool {
+        self.access_flags & ACC_PROTECTED != 0
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.access_flags & ACC_PRIVATE != 0
+    }
 }
@@ -1,18 +1,21 @@
 use std::rc::Rc;
 
-use crate::classfile::constant_info::ConstantInfo;
 use crate::classfile::constant_pool::ConstantPool;
+use crate::rtda::heap::class::Class;
 use crate::rtda::heap::symbol_ref::SymbolRef;
 
-struct ClassRef {
+pub struct ClassRef {
     symbol_ref: SymbolRef,
 }
 
 impl ClassRef {
-    fn new(constant_pool: Rc<ConstantPool>, constant_class_info: ConstantInfo) -> ClassRef {
-        let symbol_ref = SymbolRef{
-            constant_pool,
+    pub fn new(constant_pool: &ConstantPool, index: usize) -> ClassRef {
+        let class_name = constant_pool.get_class_name(index).to_owned();
+        let symbol_ref = SymbolRef { class_name };
+        ClassRef { symbol_ref }
+    }
 
-        }
+    pub fn resolved_class(&self, referrer: &Class) -> Rc<Class> {
+        self.symbol_ref.resolved_class(referrer)
     }
 }
@@ -1,11 +1,17 @@
+use std::rc::{Rc, Weak};
+
 use crate::classfile::attribute_info::AttributeInfo;
 use crate::classfile::member_info::MemberInfo;
+use crate::rtda::heap::class::Class;
 use crate::rtda::heap::class_member::ClassMember;
+use crate::rtda::slot::Slot;
 
 #[derive(Debug)]
 pub struct Field {
     pub class_member: ClassMember,
+    pub class: Weak<Class>,
     pub constant_value_index: Option<usize>,
+    pub slot_id: usize,
 }
 
 impl Field {
@@ -19,7 +25,9 @@ impl Field {
         });
         Field {
             class_member,
+            class: Weak::new(),
             constant_value_index,
+            slot_id: 0,
         }
     }
 
@@ -32,7 +40,57 @@ impl Field {
         descriptor == "J" || descriptor == "D"
     }
 
+    pub fn is_ref(&self) -> bool {
+        let descriptor = &self.class_member.descriptor;
+        descriptor.starts_with('L') || descriptor.starts_with('[')
+    }
+
+    pub fn slot_count(&self) -> usize {
+        if self.is_long_or_double() {
+            2
+        } else {
+            1
+        }
+    }
+
+    pub fn default_value(&self) -> Slot {
+        if self.is_ref() {
+            Slot::Ref(None)
+        } else {
+            Slot::Num(0)
+        }
+    }
+
     pub fn is_final(&self) -> bool {
         self.class_member.is_final()
     }
+
+    pub fn name(&self) -> &str {
+        &self.class_member.name
+    }
+
+    pub fn descriptor(&self) -> &str {
+        &self.class_member.descriptor
+    }
+
+    pub fn class(&self) -> Rc<Class> {
+        self.class.upgrade().expect("Class is dropped")
+    }
+
+    /// See [§5.4.4](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-5.html#jvms-5.4.4).
+    pub fn is_accessible_to(&self, other: &Class) -> bool {
+        if self.class_member.is_public() {
+            return true;
+        }
+        let class = self.class();
+        if self.class_member.is_private() {
+            return std::ptr::eq(class.as_ref(), other);
+        }
+        let same_package = class.package_name() == other.package_name();
+        if self.class_member.is_protected() {
+            same_package || std::ptr::eq(class.as_ref(), other) || other.is_sub_class_of(&class)
+        } else {
+            same_package
+        }
+    }
 }
@@ -0,0 +1,50 @@
+use std::rc::Rc;
+
+use crate::classfile::constant_info::ConstantInfo;
+use crate::classfile::constant_pool::ConstantPool;
+use crate::rtda::heap::class::Class;
+use crate::rtda::heap::field::Field;
+use crate::rtda::heap::symbol_ref::SymbolRef;
+
+pub struct FieldRef {
+    symbol_ref: SymbolRef,
+    name: String,
+    descriptor: String,
+}
+
+impl FieldRef {
+    pub fn new(constant_pool: &ConstantPool, index: usize) -> FieldRef {
+        let (class_index, name_and_type_index) = match constant_pool.get(index) {
+            ConstantInfo::FieldRef {
+                class_index,
+                name_and_type_index,
+            } => (*class_index as usize, *name_and_type_index as usize),
+            _ => panic!("index isn't to FieldRef"),
+        };
+        let class_name = constant_pool.get_class_name(class_index).to_owned();
+        let (name, descriptor) = constant_pool.get_name_and_type(name_and_type_index);
+        FieldRef {
+            symbol_ref: SymbolRef { class_name },
+            name: name.to_owned(),
+            descriptor: descriptor.to_owned(),
+        }
+    }
+
+    /// See [§5.4.3.2](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-5.html#jvms-5.4.3.2).
+    pub fn resolved_field(&self, referrer: &Class) -> Rc<Field> {
+        let class = self.symbol_ref.resolved_class(referrer);
+        let field = class
+            .lookup_field(&self.name, &self.descriptor)
+            .unwrap_or_else(|| {
+                panic!(
+                    "java.lang.NoSuchFieldError: {}.{}",
+                    class.java_name(),
+                    self.name
+                )
+            });
+        if !field.is_accessible_to(referrer) {
+            panic!("java.lang.IllegalAccessError")
+        }
+        field
+    }
+}
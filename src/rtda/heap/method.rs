@@ -1,46 +1,185 @@
-use std::rc::Rc;
+use std::cell::OnceCell;
+use std::rc::{Rc, Weak};
 
-use crate::classfile::attribute_info::AttributeInfo;
+use crate::classfile::attribute_info::{AttributeInfo, ExceptionTableEntry, StackMapFrame};
 use crate::classfile::member_info::MemberInfo;
+use crate::instruction::decoder::DecodedCode;
+use crate::instruction::register::{translate, RegisterCode};
+#[cfg(feature = "jit")]
+use crate::jit::profile::Profile;
+use crate::rtda::heap::access_flags::*;
+use crate::rtda::heap::class::Class;
 use crate::rtda::heap::class_member::ClassMember;
+use crate::rtda::heap::class_ref::ClassRef;
 
 #[derive(Debug)]
 pub struct Method {
     class_member: ClassMember,
+    pub class: Weak<Class>,
     pub max_locals: usize,
     pub max_stack: usize,
     pub code: Rc<Vec<u8>>,
+    /// `code` decoded on the first invocation.
+    decoded_code: OnceCell<DecodedCode>,
+    /// `code` translated to register instructions on the first run, `None` if it can't be.
+    register_code: OnceCell<Option<RegisterCode>>,
+    pub exception_table: Vec<ExceptionTableEntry>,
+    /// The frames of the `StackMapTable` attribute, if the code has one.
+    pub stack_map_table: Option<Vec<StackMapFrame>>,
+    pub arg_slot_count: usize,
+    pub vtable_index: Option<usize>,
+    #[cfg(feature = "jit")]
+    pub profile: Profile,
+}
+
+fn calc_arg_slot_count(descriptor: &str) -> usize {
+    let params = match descriptor.find(')') {
+        Some(end) => &descriptor[1..end],
+        None => "",
+    };
+    let mut count = 0;
+    let mut chars = params.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            'J' | 'D' => count += 2,
+            'L' => {
+                chars.by_ref().find(|x| *x == ';');
+                count += 1;
+            }
+            '[' => {
+                let mut c = chars.next();
+                while c == Some('[') {
+                    c = chars.next();
+                }
+                if c == Some('L') {
+                    chars.by_ref().find(|x| *x == ';');
+                }
+                count += 1;
+            }
+            _ => count += 1,
+        }
+    }
+    count
 }
 
 impl Method {
     pub fn new(member_info: MemberInfo) -> Method {
         let class_member = ClassMember::new(&member_info);
+        let arg_slot_count = calc_arg_slot_count(&class_member.descriptor)
+            + if class_member.is_static() { 0 } else { 1 };
         let code_attribute = member_info.code_attribute();
         match code_attribute {
             Some(AttributeInfo::Code {
                 max_stack,
                 max_locals,
                 code,
-                ..
+                exception_table,
+                attributes,
             }) => Method {
                 class_member,
+                class: Weak::new(),
                 max_stack: *max_stack as usize,
                 max_locals: *max_locals as usize,
                 code: Rc::clone(code),
+                decoded_code: OnceCell::new(),
+                register_code: OnceCell::new(),
+                exception_table: exception_table.clone(),
+                stack_map_table: attributes.iter().find_map(|x| match x {
+                    AttributeInfo::StackMapTable { entries } => Some(entries.clone()),
+                    _ => None,
+                }),
+                arg_slot_count,
+                vtable_index: None,
+                #[cfg(feature = "jit")]
+                profile: Profile::default(),
             },
             None => Method {
                 class_member,
+                class: Weak::new(),
                 max_stack: 0,
-                max_locals: 1,
+                max_locals: arg_slot_count.max(1),
                 code: Rc::new(Vec::new()),
+                decoded_code: OnceCell::new(),
+                register_code: OnceCell::new(),
+                exception_table: Vec::new(),
+                stack_map_table: None,
+                arg_slot_count,
+                vtable_index: None,
+                #[cfg(feature = "jit")]
+                profile: Profile::default(),
             },
             _ => panic!(),
         }
     }
+    /// The pc of the handler of an exception of class `exception` thrown at `pc`: that of
+    /// the first entry of the exception table covering `pc` which catches any exception
+    /// or `exception`'s class or a superclass of it.
+    pub fn find_exception_handler(&self, exception: &Class, pc: usize) -> Option<usize> {
+        let class = self.class();
+        let entry = self.exception_table.iter().find(|x| {
+            if !(x.start_pc as usize..x.end_pc as usize).contains(&pc) {
+                return false;
+            }
+            if x.catch_type == 0 {
+                return true;
+            }
+            let catch_class = ClassRef::new(&class.constant_pool, x.catch_type as usize)
+                .resolved_class(&class);
+            std::ptr::eq(catch_class.as_ref(), exception) || exception.is_sub_class_of(&catch_class)
+        })?;
+        Some(entry.handler_pc as usize)
+    }
+
+    pub fn decoded_code(&self) -> &DecodedCode {
+        self.decoded_code
+            .get_or_init(|| DecodedCode::decode(&self.code))
+    }
+
+    pub fn register_code(&self) -> Option<&RegisterCode> {
+        self.register_code.get_or_init(|| translate(self)).as_ref()
+    }
+
+    /// The decoded code if the method ran before.
+    pub fn decoded(&self) -> Option<&DecodedCode> {
+        self.decoded_code.get()
+    }
+
     pub fn is_static(&self) -> bool {
         self.class_member.is_static()
     }
 
+    pub fn is_final(&self) -> bool {
+        self.class_member.is_final()
+    }
+
+    pub fn is_public(&self) -> bool {
+        self.class_member.is_public()
+    }
+
+    pub fn is_protected(&self) -> bool {
+        self.class_member.is_protected()
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.class_member.is_private()
+    }
+
+    pub fn is_abstract(&self) -> bool {
+        self.class_member.access_flags & ACC_ABSTRACT != 0
+    }
+
+    pub fn is_synchronized(&self) -> bool {
+        self.class_member.access_flags & ACC_SYNCHRONIZED != 0
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.class_member.access_flags & ACC_NATIVE != 0
+    }
+
+    pub fn is_initializer(&self) -> bool {
+        self.name() == "<init>" || self.name() == "<clinit>"
+    }
+
     pub fn name(&self) -> &str {
         &self.class_member.name
     }
@@ -48,4 +187,55 @@ impl Method {
     pub fn descriptor(&self) -> &str {
         &self.class_member.descriptor
     }
+
+    pub fn class(&self) -> Rc<Class> {
+        self.class.upgrade().expect("Class is dropped")
+    }
+
+    /// See [§5.4.4](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-5.html#jvms-5.4.4).
+    pub fn is_accessible_to(&self, other: &Class) -> bool {
+        if self.is_public() {
+            return true;
+        }
+        let class = self.class();
+        if self.is_private() {
+            return std::ptr::eq(class.as_ref(), other);
+        }
+        let same_package = class.package_name() == other.package_name();
+        if self.is_protected() {
+            same_package || std::ptr::eq(class.as_ref(), other) || other.is_sub_class_of(&class)
+        } else {
+            same_package
+        }
+    }
+
+    /// Whether this method overrides `other` in the sense of
+    /// [§5.4.5](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-5.html#jvms-5.4.5),
+    /// given that `other` belongs to a superclass.
+    pub fn can_override(&self, other: &Method, package_name: &str) -> bool {
+        if self.name() != other.name() || self.descriptor() != other.descriptor() {
+            return false;
+        }
+        if other.is_private() || other.is_static() {
+            return false;
+        }
+        if other.is_public() || other.is_protected() {
+            return true;
+        }
+        other.class().package_name() == package_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rtda::heap::method::calc_arg_slot_count;
+
+    #[test]
+    fn arg_slot_count() {
+        assert_eq!(calc_arg_slot_count("()V"), 0);
+        assert_eq!(calc_arg_slot_count("(I)I"), 1);
+        assert_eq!(calc_arg_slot_count("(JD)V"), 4);
+        assert_eq!(calc_arg_slot_count("(Ljava/lang/String;I)V"), 2);
+        assert_eq!(calc_arg_slot_count("([[J[Ljava/lang/Object;Z)V"), 3);
+    }
 }
@@ -0,0 +1,54 @@
+use std::rc::Rc;
+
+use crate::classfile::constant_info::ConstantInfo;
+use crate::classfile::constant_pool::ConstantPool;
+use crate::rtda::heap::class::Class;
+use crate::rtda::heap::method::Method;
+use crate::rtda::heap::symbol_ref::SymbolRef;
+
+pub struct MethodRef {
+    symbol_ref: SymbolRef,
+    name: String,
+    descriptor: String,
+}
+
+impl MethodRef {
+    pub fn new(constant_pool: &ConstantPool, index: usize) -> MethodRef {
+        let (class_index, name_and_type_index) = match constant_pool.get(index) {
+            ConstantInfo::MethodRef {
+                class_index,
+                name_and_type_index,
+            } => (*class_index as usize, *name_and_type_index as usize),
+            _ => panic!("index isn't to MethodRef"),
+        };
+        let class_name = constant_pool.get_class_name(class_index).to_owned();
+        let (name, descriptor) = constant_pool.get_name_and_type(name_and_type_index);
+        MethodRef {
+            symbol_ref: SymbolRef { class_name },
+            name: name.to_owned(),
+            descriptor: descriptor.to_owned(),
+        }
+    }
+
+    /// See [§5.4.3.3](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-5.html#jvms-5.4.3.3).
+    pub fn resolved_method(&self, referrer: &Class) -> Rc<Method> {
+        let class = self.symbol_ref.resolved_class(referrer);
+        if class.is_interface() {
+            panic!("java.lang.IncompatibleClassChangeError")
+        }
+        let method = class
+            .lookup_method(&self.name, &self.descriptor)
+            .unwrap_or_else(|| {
+                panic!(
+                    "java.lang.NoSuchMethodError: {}.{}{}",
+                    class.java_name(),
+                    self.name,
+                    self.descriptor
+                )
+            });
+        if !method.is_accessible_to(referrer) {
+            panic!("java.lang.IllegalAccessError")
+        }
+        method
+    }
+}
@@ -1,7 +1,13 @@
-mod access_flags;
+pub mod access_flags;
 pub mod class;
 pub mod class_loader;
 mod class_member;
-mod field;
+pub mod class_ref;
+pub mod field;
+mod field_ref;
+mod interface_method_ref;
 pub mod method;
-mod object;
+mod method_ref;
+pub mod module;
+pub mod object;
+mod symbol_ref;
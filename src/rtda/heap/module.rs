@@ -0,0 +1,707 @@
+//! The modules of the Java Platform Module System. The graph is resolved from the main
+//! module and the ones of `--add-modules`, reading `module-info.class` from the module path
+//! and the runtime image, and tells which module reads which and which packages each one
+//! exports and opens. Classes of packages no resolved module has are in the unnamed
+//! module, like the ones of the class path, which reads every module and exports and opens
+//! all its packages.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+
+use crate::classfile::attribute_info::{AttributeInfo, ModulePackage};
+use crate::classfile::class_file::ClassFile;
+use crate::classfile::class_reader::ClassReader;
+use crate::classpath::classpath::{ClassPath, ModuleLocation};
+
+/// The name `--add-exports` and `--add-opens` give the unnamed module.
+pub const ALL_UNNAMED: &str = "ALL-UNNAMED";
+/// The root of `--add-modules` that stands for every module on the module path.
+pub const ALL_MODULE_PATH: &str = "ALL-MODULE-PATH";
+const JAVA_BASE: &str = "java.base";
+
+const ACC_OPEN: u16 = 0x0020;
+const ACC_TRANSITIVE: u16 = 0x0020;
+const ACC_STATIC_PHASE: u16 = 0x0040;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Requires {
+    pub name: String,
+    pub transitive: bool,
+    /// Required at compile time only, resolved if something else requires it.
+    pub is_static: bool,
+}
+
+/// A package exported or opened, like `java/lang`, to `targets` or to every module if
+/// there's none.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageAccess {
+    pub package: String,
+    pub targets: Vec<String>,
+}
+
+/// What a `module-info.class` declares, or what an automatic module is.
+#[derive(Debug, Clone)]
+pub struct ModuleDescriptor {
+    pub name: String,
+    /// Opens all its packages.
+    pub open: bool,
+    /// A jar without `module-info.class`, which reads every module and exports and opens
+    /// all its packages.
+    pub automatic: bool,
+    pub requires: Vec<Requires>,
+    pub exports: Vec<PackageAccess>,
+    pub opens: Vec<PackageAccess>,
+    pub packages: Vec<String>,
+    pub main_class: Option<String>,
+}
+
+impl ModuleDescriptor {
+    pub fn new(class_file: &ClassFile) -> ModuleDescriptor {
+        let constant_pool = &class_file.constant_pool;
+        let accesses = |packages: &[ModulePackage]| {
+            packages
+                .iter()
+                .map(|x| PackageAccess {
+                    package: constant_pool
+                        .get_package_name(x.package_index as usize)
+                        .to_owned(),
+                    targets: x
+                        .to_index
+                        .iter()
+                        .map(|x| constant_pool.get_module_name(*x as usize).to_owned())
+                        .collect(),
+                })
+                .collect::<Vec<PackageAccess>>()
+        };
+        let mut descriptor = None;
+        let mut packages = Vec::new();
+        let mut main_class = None;
+        for attribute in &class_file.attributes {
+            match attribute {
+                AttributeInfo::Module {
+                    module_name_index,
+                    module_flags,
+                    requires,
+                    exports,
+                    opens,
+                    ..
+                } => {
+                    descriptor = Some(ModuleDescriptor {
+                        name: constant_pool
+                            .get_module_name(*module_name_index as usize)
+                            .to_owned(),
+                        open: module_flags & ACC_OPEN != 0,
+                        automatic: false,
+                        requires: requires
+                            .iter()
+                            .map(|x| Requires {
+                                name: constant_pool
+                                    .get_module_name(x.requires_index as usize)
+                                    .to_owned(),
+                                transitive: x.requires_flags & ACC_TRANSITIVE != 0,
+                                is_static: x.requires_flags & ACC_STATIC_PHASE != 0,
+                            })
+                            .collect(),
+                        exports: accesses(exports),
+                        opens: accesses(opens),
+                        packages: Vec::new(),
+                        main_class: None,
+                    })
+                }
+                AttributeInfo::ModulePackages { package_index } => {
+                    packages = package_index
+                        .iter()
+                        .map(|x| constant_pool.get_package_name(*x as usize).to_owned())
+                        .collect();
+                }
+                AttributeInfo::ModuleMainClass { main_class_index } => {
+                    main_class = Some(
+                        constant_pool
+                            .get_class_name(*main_class_index as usize)
+                            .to_owned(),
+                    );
+                }
+                _ => {}
+            }
+        }
+        let mut descriptor = descriptor.unwrap_or_else(|| {
+            panic!("java.lang.module.InvalidModuleDescriptorError: No Module attribute")
+        });
+        descriptor.packages = packages;
+        descriptor.main_class = main_class;
+        descriptor
+    }
+
+    /// The module of a jar on the module path without `module-info.class`, named after the
+    /// jar without its version, like `foo.bar` for `foo-bar-1.2.jar`.
+    pub fn automatic(path: &Path, packages: Vec<String>) -> ModuleDescriptor {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let end = stem
+            .char_indices()
+            .find(|(i, c)| *c == '-' && stem[i + 1..].starts_with(|x: char| x.is_ascii_digit()))
+            .map_or(stem.len(), |(i, _)| i);
+        let name: String = stem[..end]
+            .chars()
+            .map(|x| if x.is_ascii_alphanumeric() { x } else { '.' })
+            .collect();
+        let name: Vec<&str> = name.split('.').filter(|x| !x.is_empty()).collect();
+        ModuleDescriptor {
+            name: name.join("."),
+            open: true,
+            automatic: true,
+            requires: Vec::new(),
+            exports: Vec::new(),
+            opens: Vec::new(),
+            packages,
+            main_class: None,
+        }
+    }
+
+    /// The java.base of a runtime without modules, made of the `packages` of its boot class
+    /// path, which are all exported and open like before modules.
+    fn boot(packages: Vec<String>) -> ModuleDescriptor {
+        ModuleDescriptor {
+            name: JAVA_BASE.to_owned(),
+            open: true,
+            automatic: false,
+            requires: Vec::new(),
+            exports: packages
+                .iter()
+                .map(|x| PackageAccess {
+                    package: x.clone(),
+                    targets: Vec::new(),
+                })
+                .collect(),
+            opens: Vec::new(),
+            packages,
+            main_class: None,
+        }
+    }
+
+    fn read(location: &ModuleLocation) -> ModuleDescriptor {
+        let mut descriptor = match &location.module_info {
+            Some(data) => ModuleDescriptor::new(&data.parse()),
+            None => ModuleDescriptor::automatic(&location.path, location.packages.clone()),
+        };
+        if descriptor.packages.is_empty() {
+            descriptor.packages = location.packages.clone();
+        }
+        descriptor
+    }
+}
+
+/// The module options of the launcher.
+#[derive(Debug, Default, Clone)]
+pub struct ModuleOptions {
+    /// The module of `-m`, without the main class that may follow it.
+    pub main_module: Option<String>,
+    /// The roots of `--add-modules`, each a module name or `ALL-MODULE-PATH`.
+    pub add_modules: Vec<String>,
+    /// `--add-exports` values, like `java.base/sun.nio.ch=ALL-UNNAMED`.
+    pub add_exports: Vec<String>,
+    /// `--add-opens` values, in the format of `--add-exports`.
+    pub add_opens: Vec<String>,
+}
+
+/// Parses an `--add-exports` or `--add-opens` value, `module/package=target,...`, the
+/// package in internal form.
+fn parse_package_option(option: &str) -> (String, String, Vec<String>) {
+    let invalid = || panic!("Invalid module option {}", option);
+    let (source, targets) = option.split_once('=').unwrap_or_else(invalid);
+    let (module, package) = source.split_once('/').unwrap_or_else(invalid);
+    let targets = targets
+        .split(',')
+        .filter(|x| !x.is_empty())
+        .map(|x| x.to_owned())
+        .collect();
+    (module.to_owned(), package.replace('.', "/"), targets)
+}
+
+/// Which modules a package is exported or opened to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Targets {
+    All,
+    /// Module names, with `ALL_UNNAMED` for the unnamed module.
+    Some(HashSet<String>),
+}
+
+impl Targets {
+    fn add(&mut self, targets: &[String]) {
+        match self {
+            Targets::All => {}
+            Targets::Some(_) if targets.is_empty() => *self = Targets::All,
+            Targets::Some(names) => names.extend(targets.iter().cloned()),
+        }
+    }
+
+    fn contains(&self, module: Option<&str>) -> bool {
+        match self {
+            Targets::All => true,
+            Targets::Some(names) => names.contains(module.unwrap_or(ALL_UNNAMED)),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Module {
+    descriptor: ModuleDescriptor,
+    reads: HashSet<String>,
+    exports: HashMap<String, Targets>,
+    opens: HashMap<String, Targets>,
+}
+
+impl Module {
+    fn access(map: &mut HashMap<String, Targets>, package: &str, targets: &[String]) {
+        map.entry(package.to_owned())
+            .or_insert_with(|| Targets::Some(HashSet::new()))
+            .add(targets);
+    }
+}
+
+/// The resolved modules, with the packages of each.
+#[derive(Debug, Default)]
+pub struct ModuleGraph {
+    modules: HashMap<String, Module>,
+    /// The module of each package of a resolved module.
+    packages: HashMap<String, String>,
+}
+
+/// `java.lang.String` for `java/lang/String`.
+fn java_name(name: &str) -> String {
+    name.replace('/', ".")
+}
+
+fn package_of(class_name: &str) -> &str {
+    class_name.rsplit_once('/').map_or("", |(x, _)| x)
+}
+
+fn describe(module: Option<&str>) -> String {
+    match module {
+        Some(name) => format!("module {}", name),
+        None => "unnamed module".to_owned(),
+    }
+}
+
+impl ModuleGraph {
+    /// Resolves the graph of the modules `options` names and the ones they require, first
+    /// found in the runtime image and then on the module path. The unnamed module reads
+    /// all of them.
+    pub fn resolve(class_path: &ClassPath, options: &ModuleOptions) -> ModuleGraph {
+        let module_path: Vec<ModuleDescriptor> = class_path
+            .module_path()
+            .iter()
+            .map(ModuleDescriptor::read)
+            .collect();
+        let find = |name: &str| {
+            class_path
+                .system_module_info(name)
+                .map(|x| ModuleDescriptor::new(&x.parse()))
+                .or_else(|| module_path.iter().find(|x| x.name == name).cloned())
+        };
+        let mut roots: Vec<String> = options.main_module.iter().cloned().collect();
+        for root in options.add_modules.iter().flat_map(|x| x.split(',')) {
+            if root == ALL_MODULE_PATH {
+                roots.extend(module_path.iter().map(|x| x.name.clone()));
+            } else if !root.is_empty() {
+                roots.push(root.to_owned());
+            }
+        }
+        let mut graph = ModuleGraph::default();
+        let mut queue: VecDeque<(String, Option<String>)> =
+            roots.into_iter().map(|x| (x, None)).collect();
+        queue.push_back((JAVA_BASE.to_owned(), None));
+        while let Some((name, required_by)) = queue.pop_front() {
+            if graph.modules.contains_key(&name) {
+                continue;
+            }
+            let Some(descriptor) = find(&name) else {
+                // A runtime without modules has its java.base on the boot class path
+                if name == JAVA_BASE {
+                    graph.add(ModuleDescriptor::boot(class_path.boot_packages()));
+                    continue;
+                }
+                match required_by {
+                    Some(module) => panic!(
+                        "java.lang.module.FindException: Module {} not found, required by {}",
+                        name, module
+                    ),
+                    None => panic!("java.lang.module.FindException: Module {} not found", name),
+                }
+            };
+            for requires in descriptor.requires.iter().filter(|x| !x.is_static) {
+                queue.push_back((requires.name.clone(), Some(name.clone())));
+            }
+            // Automatic modules read each other, so resolving one resolves them all
+            if descriptor.automatic {
+                for automatic in module_path.iter().filter(|x| x.automatic) {
+                    queue.push_back((automatic.name.clone(), None));
+                }
+            }
+            graph.add(descriptor);
+        }
+        graph.link();
+        for (option, opens) in options
+            .add_exports
+            .iter()
+            .map(|x| (x, false))
+            .chain(options.add_opens.iter().map(|x| (x, true)))
+        {
+            let (module, package, targets) = parse_package_option(option);
+            if let Some(module) = graph.modules.get_mut(&module) {
+                let map = if opens {
+                    &mut module.opens
+                } else {
+                    &mut module.exports
+                };
+                Module::access(map, &package, &targets);
+            }
+        }
+        graph
+    }
+
+    fn add(&mut self, descriptor: ModuleDescriptor) {
+        let mut module = Module {
+            reads: HashSet::new(),
+            exports: HashMap::new(),
+            opens: HashMap::new(),
+            descriptor,
+        };
+        let descriptor = &module.descriptor;
+        let packages = descriptor
+            .packages
+            .iter()
+            .chain(descriptor.exports.iter().map(|x| &x.package))
+            .chain(descriptor.opens.iter().map(|x| &x.package));
+        for package in packages {
+            self.packages
+                .entry(package.clone())
+                .or_insert_with(|| descriptor.name.clone());
+        }
+        for access in &descriptor.exports {
+            Module::access(&mut module.exports, &access.package, &access.targets);
+        }
+        for access in &descriptor.opens {
+            Module::access(&mut module.opens, &access.package, &access.targets);
+        }
+        if descriptor.automatic || descriptor.open {
+            for package in &descriptor.packages {
+                Module::access(&mut module.opens, package, &[]);
+                if descriptor.automatic {
+                    Module::access(&mut module.exports, package, &[]);
+                }
+            }
+        }
+        self.modules.insert(descriptor.name.clone(), module);
+    }
+
+    /// Each module reads the modules it requires and the ones those require transitively,
+    /// and java.base.
+    fn link(&mut self) {
+        let names: Vec<String> = self.modules.keys().cloned().collect();
+        for name in &names {
+            let mut reads = HashSet::new();
+            for requires in &self.modules[name].descriptor.requires {
+                self.read_transitively(&requires.name, &mut reads);
+            }
+            if self.modules.contains_key(JAVA_BASE) {
+                reads.insert(JAVA_BASE.to_owned());
+            }
+            self.modules.get_mut(name).unwrap().reads = reads;
+        }
+    }
+
+    fn read_transitively(&self, name: &str, reads: &mut HashSet<String>) {
+        let Some(module) = self.modules.get(name) else {
+            return;
+        };
+        if !reads.insert(name.to_owned()) {
+            return;
+        }
+        for requires in module.descriptor.requires.iter().filter(|x| x.transitive) {
+            self.read_transitively(&requires.name, reads);
+        }
+    }
+
+    /// The module of the package `package`, like `java/lang`, `None` for the unnamed one.
+    pub fn module_of(&self, package: &str) -> Option<&str> {
+        self.packages.get(package).map(|x| x.as_str())
+    }
+
+    pub fn descriptor(&self, module: &str) -> Option<&ModuleDescriptor> {
+        self.modules.get(module).map(|x| &x.descriptor)
+    }
+
+    pub fn reads(&self, from: Option<&str>, to: Option<&str>) -> bool {
+        match (from, to) {
+            (None, _) => true,
+            (Some(from), Some(to)) if from == to => true,
+            (Some(from), to) => {
+                let module = &self.modules[from];
+                module.descriptor.automatic || to.is_some_and(|x| module.reads.contains(x))
+            }
+        }
+    }
+
+    /// Whether `module` exports `package` to the module `to`, `None` being unnamed.
+    pub fn is_exported(&self, module: Option<&str>, package: &str, to: Option<&str>) -> bool {
+        self.is_accessible(module, package, to, |x| &x.exports)
+    }
+
+    /// Whether `module` opens `package` to the module `to` for deep reflection.
+    pub fn is_opened(&self, module: Option<&str>, package: &str, to: Option<&str>) -> bool {
+        self.is_accessible(module, package, to, |x| &x.opens)
+    }
+
+    fn is_accessible(
+        &self,
+        module: Option<&str>,
+        package: &str,
+        to: Option<&str>,
+        map: impl Fn(&Module) -> &HashMap<String, Targets>,
+    ) -> bool {
+        let Some(name) = module else {
+            return true;
+        };
+        module == to
+            || map(&self.modules[name])
+                .get(package)
+                .is_some_and(|x| x.contains(to))
+    }
+
+    /// Checks that the code of the class `from` may access the public class `to`, both
+    /// in internal form: its module must read the module of `to`, which must export its
+    /// package to it. The error tells why not, like the message of Java's
+    /// `IllegalAccessError`.
+    pub fn check_access(&self, from: &str, to: &str) -> Result<(), String> {
+        let from_module = self.module_of(package_of(from));
+        let package = package_of(to);
+        let to_module = self.module_of(package);
+        let reason = if !self.reads(from_module, to_module) {
+            format!(
+                "{} does not read {}",
+                describe(from_module),
+                describe(to_module)
+            )
+        } else if !self.is_exported(to_module, package, from_module) {
+            format!(
+                "{} does not export {} to {}",
+                describe(to_module),
+                java_name(package),
+                describe(from_module)
+            )
+        } else {
+            return Ok(());
+        };
+        Err(format!(
+            "class {} (in {}) cannot access class {} (in {}) because {}",
+            java_name(from),
+            describe(from_module),
+            java_name(to),
+            describe(to_module),
+            reason
+        ))
+    }
+
+    /// Checks that the code of the class `from` may reflect on a member of the class `to`,
+    /// on a public member of a public class if `public`, else through `setAccessible`,
+    /// which needs the package of `to` opened to it.
+    pub fn check_reflective_access(
+        &self,
+        from: &str,
+        to: &str,
+        public: bool,
+    ) -> Result<(), String> {
+        let from_module = self.module_of(package_of(from));
+        let package = package_of(to);
+        let to_module = self.module_of(package);
+        if self.is_opened(to_module, package, from_module)
+            || (public && self.is_exported(to_module, package, from_module))
+        {
+            return Ok(());
+        }
+        Err(format!(
+            "Unable to make a member of {} accessible: {} does not \"opens {}\" to {}",
+            java_name(to),
+            describe(to_module),
+            java_name(package),
+            describe(from_module)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse_with_module_path;
+    use crate::gc::heap::Heap;
+    use crate::interpreter::{interpret, Schedule};
+    use crate::rtda::frame::Frame;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::rtda::heap::module::*;
+    use crate::rtda::thread::Thread;
+
+    fn class_path() -> ClassPath {
+        parse_with_module_path(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+            Some("src/test_data/modules".to_string()),
+        )
+    }
+
+    fn options(main_module: &str) -> ModuleOptions {
+        ModuleOptions {
+            main_module: Some(main_module.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn descriptors() {
+        let location = class_path()
+            .module_path()
+            .into_iter()
+            .find(|x| x.path.ends_with("lib"))
+            .unwrap();
+        let descriptor = ModuleDescriptor::read(&location);
+        assert_eq!(descriptor.name, "lib");
+        assert!(!descriptor.open && !descriptor.automatic);
+        assert_eq!(descriptor.requires[0].name, "java.base");
+        assert_eq!(
+            descriptor.exports,
+            vec![PackageAccess {
+                package: "lib/api".to_owned(),
+                targets: vec![],
+            }]
+        );
+        assert_eq!(descriptor.opens[0].targets, vec!["app".to_owned()]);
+        assert_eq!(descriptor.packages, vec!["lib/api", "lib/internal"]);
+        let automatic = ModuleDescriptor::automatic(Path::new("libs/foo-bar_baz-1.2.jar"), vec![]);
+        assert_eq!(automatic.name, "foo.bar.baz");
+    }
+
+    #[test]
+    fn resolve() {
+        let graph = ModuleGraph::resolve(&class_path(), &options("app"));
+        assert!(graph.descriptor("app").is_some() && graph.descriptor("lib").is_some());
+        // other is on the module path, but nothing requires it
+        assert!(graph.descriptor("other").is_none());
+        assert_eq!(graph.module_of("lib/internal"), Some("lib"));
+        assert_eq!(graph.module_of("other/api"), None);
+        assert!(graph.reads(Some("app"), Some("lib")));
+        assert!(!graph.reads(Some("lib"), Some("app")));
+        assert!(!graph.reads(Some("app"), None));
+        assert!(graph.reads(None, Some("lib")));
+        assert!(graph.check_access("app/Main", "lib/api/Api").is_ok());
+        assert_eq!(
+            graph.check_access("app/Sneaky", "lib/internal/Hidden"),
+            Err(
+                "class app.Sneaky (in module app) cannot access class lib.internal.Hidden \
+                 (in module lib) because module lib does not export lib.internal to module app"
+                    .to_owned()
+            )
+        );
+        assert!(graph
+            .check_access("Unnamed", "lib/internal/Hidden")
+            .is_err());
+        assert!(graph.check_access("lib/api/Api", "app/Main").is_err());
+        assert!(graph
+            .check_reflective_access("app/Main", "lib/internal/Hidden", false)
+            .is_ok());
+        assert!(graph
+            .check_reflective_access("Unnamed", "lib/internal/Hidden", true)
+            .is_err());
+        assert!(graph
+            .check_reflective_access("Unnamed", "lib/api/Api", true)
+            .is_ok());
+        assert!(graph
+            .check_reflective_access("Unnamed", "lib/api/Api", false)
+            .is_err());
+    }
+
+    #[test]
+    fn add_modules_exports_and_opens() {
+        let options = ModuleOptions {
+            add_modules: vec![ALL_MODULE_PATH.to_owned()],
+            add_exports: vec!["lib/lib.internal=app,ALL-UNNAMED".to_owned()],
+            add_opens: vec!["other/other.api=ALL-UNNAMED".to_owned()],
+            ..Default::default()
+        };
+        let graph = ModuleGraph::resolve(&class_path(), &options);
+        assert!(graph.descriptor("other").is_some());
+        assert!(graph
+            .check_access("app/Sneaky", "lib/internal/Hidden")
+            .is_ok());
+        assert!(graph.check_access("Unnamed", "lib/internal/Hidden").is_ok());
+        assert!(graph
+            .check_access("other/api/Other", "lib/internal/Hidden")
+            .is_err());
+        assert_eq!(
+            graph.check_access("app/Stranger", "other/api/Other"),
+            Err(
+                "class app.Stranger (in module app) cannot access class other.api.Other \
+                 (in module other) because module app does not read module other"
+                    .to_owned()
+            )
+        );
+        assert!(graph
+            .check_reflective_access("Unnamed", "other/api/Other", false)
+            .is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "java.lang.module.FindException: Module missing not found")]
+    fn missing_module() {
+        ModuleGraph::resolve(&class_path(), &options("missing"));
+    }
+
+    fn run_main(name: &str) -> (Rc<ClassLoader>, Rc<RefCell<Heap>>) {
+        let class_loader = ClassLoader::new(class_path());
+        class_loader.resolve_modules(&options("app"));
+        let class = class_loader.load(name);
+        let mut thread = Thread::new();
+        thread.push_frame(Frame::new(Rc::clone(&class), class.main_method()));
+        let heap = Rc::clone(&thread.heap);
+        interpret(&class_loader, thread, Schedule::default());
+        (class_loader, heap)
+    }
+
+    #[test]
+    fn run_in_module() {
+        let (class_loader, _) = run_main("app/Main");
+        let class = class_loader.load("app/Main");
+        assert_eq!(class.static_vars.borrow().get_int(0), 42);
+    }
+
+    // Interpreting Sneaky or Stranger ends their thread with the IllegalAccessError
+    #[test]
+    fn class_access() {
+        let (class_loader, _) = run_main("app/Main");
+        let load = |x: &str| class_loader.load(x);
+        let (sneaky, stranger) = (load("app/Sneaky"), load("app/Stranger"));
+        assert!(load("lib/api/Api").is_accessible_to(&sneaky));
+        assert!(!load("lib/internal/Hidden").is_accessible_to(&sneaky));
+        let other = load("other/api/Other");
+        assert!(!other.is_accessible_to(&stranger));
+        assert!(other
+            .check_module_access(&stranger)
+            .unwrap_err()
+            .ends_with("because module app does not read unnamed module"));
+        // The unnamed module reads every module
+        assert!(other.is_accessible_to(&load("MyObject")));
+    }
+
+    #[test]
+    fn reflective_access() {
+        let (class_loader, heap) = run_main("app/Reflector");
+        let class = class_loader.load("app/Reflector");
+        assert_eq!(class.static_vars.borrow().get_int(0), 2);
+        let message = class.static_vars.borrow().get_ref(1).unwrap();
+        assert_eq!(
+            heap.borrow().java_string(message),
+            "Unable to make a member of lib.api.Api accessible: \
+             module lib does not \"opens lib.api\" to module app"
+        );
+    }
+}
@@ -1,7 +1,141 @@
+use std::rc::Rc;
+
 use crate::rtda::heap::class::Class;
 use crate::rtda::slot::Slot;
 
-struct Object {
-    class: Class,
-    fields: Vec<Slot>,
+/// A handle to an object in the garbage-collected heap. Handles stay valid until the
+/// object they point to is collected, even when a collector moves the object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObjectRef(pub usize);
+
+/// Bytes an object takes besides its fields or elements, used for heap accounting.
+pub const HEADER_SIZE: usize = 16;
+
+#[derive(Debug)]
+pub struct Object {
+    pub class: Rc<Class>,
+    pub data: ObjectData,
+}
+
+#[derive(Debug)]
+pub enum ObjectData {
+    Fields(Vec<Slot>),
+    Array(ArrayData),
+}
+
+#[derive(Debug)]
+pub enum ArrayData {
+    Bytes(Vec<i8>),
+    Shorts(Vec<i16>),
+    Chars(Vec<u16>),
+    Ints(Vec<i32>),
+    Longs(Vec<i64>),
+    Floats(Vec<f32>),
+    Doubles(Vec<f64>),
+    Refs(Vec<Option<ObjectRef>>),
+}
+
+impl Object {
+    /// A new instance whose fields, including inherited ones, hold their default values.
+    pub fn new(class: Rc<Class>) -> Object {
+        let mut fields = vec![Slot::Num(0); class.instance_slot_count];
+        let mut c = Some(class.as_ref());
+        while let Some(x) = c {
+            for field in x.fields.iter().filter(|x| !x.is_static()) {
+                fields[field.slot_id] = field.default_value();
+            }
+            c = x.super_class.as_deref();
+        }
+        Object {
+            class,
+            data: ObjectData::Fields(fields),
+        }
+    }
+
+    /// A new array of `class` with `len` default elements.
+    pub fn new_array(class: Rc<Class>, len: usize) -> Object {
+        let array = match class.name.as_str() {
+            "[Z" | "[B" => ArrayData::Bytes(vec![0; len]),
+            "[S" => ArrayData::Shorts(vec![0; len]),
+            "[C" => ArrayData::Chars(vec![0; len]),
+            "[I" => ArrayData::Ints(vec![0; len]),
+            "[J" => ArrayData::Longs(vec![0; len]),
+            "[F" => ArrayData::Floats(vec![0.0; len]),
+            "[D" => ArrayData::Doubles(vec![0.0; len]),
+            name if name.starts_with('[') => ArrayData::Refs(vec![None; len]),
+            name => panic!("{} isn't an array class", name),
+        };
+        Object {
+            class,
+            data: ObjectData::Array(array),
+        }
+    }
+
+    pub fn is_instance_of(&self, class: &Class) -> bool {
+        class.is_assignable_from(&self.class)
+    }
+
+    pub fn fields(&self) -> &Vec<Slot> {
+        match self.data {
+            ObjectData::Fields(ref fields) => fields,
+            ObjectData::Array(_) => panic!("Not an instance"),
+        }
+    }
+
+    pub fn fields_mut(&mut self) -> &mut Vec<Slot> {
+        match self.data {
+            ObjectData::Fields(ref mut fields) => fields,
+            ObjectData::Array(_) => panic!("Not an instance"),
+        }
+    }
+
+    pub fn array(&self) -> &ArrayData {
+        match self.data {
+            ObjectData::Array(ref array) => array,
+            ObjectData::Fields(_) => panic!("Not an array"),
+        }
+    }
+
+    pub fn array_mut(&mut self) -> &mut ArrayData {
+        match self.data {
+            ObjectData::Array(ref mut array) => array,
+            ObjectData::Fields(_) => panic!("Not an array"),
+        }
+    }
+
+    /// The objects this object points to.
+    pub fn references(&self) -> Vec<ObjectRef> {
+        match self.data {
+            ObjectData::Fields(ref fields) => fields
+                .iter()
+                .filter_map(|x| match x {
+                    Slot::Ref(val) => *val,
+                    Slot::Num(_) => None,
+                })
+                .collect(),
+            ObjectData::Array(ArrayData::Refs(ref refs)) => {
+                refs.iter().flatten().cloned().collect()
+            }
+            ObjectData::Array(_) => Vec::new(),
+        }
+    }
+
+    /// Approximate size in bytes: a header plus 4 bytes per slot or per element of
+    /// 4 bytes or less, and 8 bytes per long or double element.
+    pub fn size(&self) -> usize {
+        let body = match self.data {
+            ObjectData::Fields(ref fields) => fields.len() * 4,
+            ObjectData::Array(ref array) => match array {
+                ArrayData::Bytes(vec) => vec.len(),
+                ArrayData::Shorts(vec) => vec.len() * 2,
+                ArrayData::Chars(vec) => vec.len() * 2,
+                ArrayData::Ints(vec) => vec.len() * 4,
+                ArrayData::Longs(vec) => vec.len() * 8,
+                ArrayData::Floats(vec) => vec.len() * 4,
+                ArrayData::Doubles(vec) => vec.len() * 8,
+                ArrayData::Refs(vec) => vec.len() * 4,
+            },
+        };
+        HEADER_SIZE + body
+    }
 }
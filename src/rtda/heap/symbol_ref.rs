@@ -1,17 +1,21 @@
 use std::rc::Rc;
 
-use crate::classfile::constant_info::ConstantInfo;
-use crate::classfile::constant_pool::ConstantPool;
 use crate::rtda::heap::class::Class;
 
 pub struct SymbolRef {
-    constant_pool: Rc<ConstantPool>,
-    class_name: String,
-    class: Rc<Class>,
+    pub class_name: String,
 }
 
 impl SymbolRef {
-    fn new(constant_pool: Rc<ConstantPool>, constant_class_info: ConstantInfo) -> ClassRef {
-        let class_name =
+    pub fn resolved_class(&self, referrer: &Class) -> Rc<Class> {
+        let loader = referrer.loader.upgrade().expect("ClassLoader is dropped");
+        let class = loader.load(&self.class_name);
+        if !class.is_accessible_to(referrer) {
+            match class.check_module_access(referrer) {
+                Err(e) => panic!("java.lang.IllegalAccessError: {}", e),
+                Ok(()) => panic!("java.lang.IllegalAccessError"),
+            }
+        }
+        class
     }
 }
@@ -1,7 +1,11 @@
+pub mod clock;
 pub mod frame;
 pub mod heap;
+pub mod monitor;
+pub mod off_heap;
 pub mod operand_stack;
-mod slot;
+pub mod slot;
 mod stack;
 pub mod thread;
 pub mod vars;
+pub mod vm;
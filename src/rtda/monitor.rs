@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use crate::rtda::heap::object::ObjectRef;
+
+/// What a monitor belongs to. Static synchronized methods lock their class, which may
+/// have no `java.lang.Class` object yet, so it's identified by name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Lock {
+    Object(ObjectRef),
+    Class(String),
+}
+
+#[derive(Debug, Default)]
+struct Monitor {
+    owner: Option<usize>,
+    /// How many times the owner entered it without exiting.
+    count: usize,
+    /// Ids of the threads in `Object.wait`, oldest first.
+    wait_set: Vec<usize>,
+}
+
+/// The monitors of every object and class, created on first use and dropped once
+/// nobody owns or waits on them.
+#[derive(Debug, Default)]
+pub struct Monitors {
+    monitors: HashMap<Lock, Monitor>,
+}
+
+impl Monitors {
+    pub fn new() -> Monitors {
+        Monitors::default()
+    }
+
+    /// Enters `lock` on behalf of `thread_id` `count` times, unless another thread owns it.
+    pub fn try_enter(&mut self, lock: &Lock, thread_id: usize, count: usize) -> bool {
+        let monitor = self.monitors.entry(lock.clone()).or_default();
+        match monitor.owner {
+            Some(owner) if owner != thread_id => false,
+            _ => {
+                monitor.owner = Some(thread_id);
+                monitor.count += count;
+                true
+            }
+        }
+    }
+
+    pub fn exit(&mut self, lock: &Lock, thread_id: usize) {
+        let monitor = self.owned_mut(lock, thread_id);
+        monitor.count -= 1;
+        if monitor.count == 0 {
+            monitor.owner = None;
+        }
+        self.remove_if_idle(lock);
+    }
+
+    pub fn is_owner(&self, lock: &Lock, thread_id: usize) -> bool {
+        self.monitors
+            .get(lock)
+            .is_some_and(|x| x.owner == Some(thread_id))
+    }
+
+    /// Releases `lock` entirely and adds `thread_id` to its wait set. Returns the count
+    /// to enter it again with once the thread is woken up.
+    pub fn wait(&mut self, lock: &Lock, thread_id: usize) -> usize {
+        let monitor = self.owned_mut(lock, thread_id);
+        let count = monitor.count;
+        monitor.owner = None;
+        monitor.count = 0;
+        monitor.wait_set.push(thread_id);
+        count
+    }
+
+    pub fn is_waiting(&self, lock: &Lock, thread_id: usize) -> bool {
+        self.monitors
+            .get(lock)
+            .is_some_and(|x| x.wait_set.contains(&thread_id))
+    }
+
+    /// Takes `thread_id` out of the wait set after a timeout or an interrupt.
+    pub fn cancel_wait(&mut self, lock: &Lock, thread_id: usize) {
+        if let Some(monitor) = self.monitors.get_mut(lock) {
+            monitor.wait_set.retain(|x| *x != thread_id);
+        }
+        self.remove_if_idle(lock);
+    }
+
+    pub fn notify(&mut self, lock: &Lock, thread_id: usize) {
+        let monitor = self.owned_mut(lock, thread_id);
+        if !monitor.wait_set.is_empty() {
+            monitor.wait_set.remove(0);
+        }
+    }
+
+    pub fn notify_all(&mut self, lock: &Lock, thread_id: usize) {
+        self.owned_mut(lock, thread_id).wait_set.clear();
+    }
+
+    /// Releases every monitor owned by a thread that terminated abruptly.
+    pub fn release_all(&mut self, thread_id: usize) {
+        for monitor in self.monitors.values_mut() {
+            if monitor.owner == Some(thread_id) {
+                monitor.owner = None;
+                monitor.count = 0;
+            }
+            monitor.wait_set.retain(|x| *x != thread_id);
+        }
+        self.monitors
+            .retain(|_, x| x.owner.is_some() || !x.wait_set.is_empty());
+    }
+
+    /// Objects whose monitor is in use, which must outlive it.
+    pub fn roots(&self) -> impl Iterator<Item = ObjectRef> + '_ {
+        self.monitors.keys().filter_map(|x| match x {
+            Lock::Object(object) => Some(*object),
+            Lock::Class(_) => None,
+        })
+    }
+
+    fn owned_mut(&mut self, lock: &Lock, thread_id: usize) -> &mut Monitor {
+        match self.monitors.get_mut(lock) {
+            Some(monitor) if monitor.owner == Some(thread_id) => monitor,
+            _ => panic!("java.lang.IllegalMonitorStateException"),
+        }
+    }
+
+    fn remove_if_idle(&mut self, lock: &Lock) {
+        let idle = self
+            .monitors
+            .get(lock)
+            .is_some_and(|x| x.owner.is_none() && x.wait_set.is_empty());
+        if idle {
+            self.monitors.remove(lock);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rtda::heap::object::ObjectRef;
+    use crate::rtda::monitor::*;
+
+    #[test]
+    fn reentrant() {
+        let lock = Lock::Object(ObjectRef(1));
+        let mut monitors = Monitors::new();
+        assert!(monitors.try_enter(&lock, 0, 1));
+        assert!(monitors.try_enter(&lock, 0, 1));
+        assert!(!monitors.try_enter(&lock, 1, 1));
+        monitors.exit(&lock, 0);
+        assert!(!monitors.try_enter(&lock, 1, 1));
+        monitors.exit(&lock, 0);
+        assert!(monitors.try_enter(&lock, 1, 1));
+        assert!(monitors.is_owner(&lock, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "java.lang.IllegalMonitorStateException")]
+    fn exit_not_owner() {
+        let lock = Lock::Class("A".to_string());
+        let mut monitors = Monitors::new();
+        assert!(monitors.try_enter(&lock, 0, 1));
+        monitors.exit(&lock, 1);
+    }
+
+    #[test]
+    fn wait_and_notify() {
+        let lock = Lock::Object(ObjectRef(1));
+        let mut monitors = Monitors::new();
+        monitors.try_enter(&lock, 0, 2);
+        assert_eq!(monitors.wait(&lock, 0), 2);
+        assert!(monitors.try_enter(&lock, 1, 1));
+        monitors.notify(&lock, 1);
+        assert!(!monitors.is_waiting(&lock, 0));
+        assert!(!monitors.try_enter(&lock, 0, 2));
+        monitors.exit(&lock, 1);
+        assert!(monitors.try_enter(&lock, 0, 2));
+        assert_eq!(monitors.roots().collect::<Vec<_>>(), vec![ObjectRef(1)]);
+    }
+
+    #[test]
+    fn release_all() {
+        let lock = Lock::Object(ObjectRef(1));
+        let mut monitors = Monitors::new();
+        monitors.try_enter(&lock, 0, 3);
+        monitors.release_all(0);
+        assert!(monitors.try_enter(&lock, 1, 1));
+        monitors.exit(&lock, 1);
+        assert_eq!(monitors.roots().count(), 0);
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+
+/// Where the first block is placed, so that no block starts at the null address.
+const FIRST_ADDRESS: u64 = 0x1000;
+
+/// Native memory outside the garbage-collected heap, allocated and freed explicitly
+/// through `Unsafe`. Addresses are 8-byte aligned and never reused.
+#[derive(Debug)]
+pub struct OffHeap {
+    /// Blocks by start address.
+    blocks: BTreeMap<u64, Vec<u8>>,
+    next_address: u64,
+}
+
+impl Default for OffHeap {
+    fn default() -> Self {
+        OffHeap::new()
+    }
+}
+
+impl OffHeap {
+    pub fn new() -> OffHeap {
+        OffHeap {
+            blocks: BTreeMap::new(),
+            next_address: FIRST_ADDRESS,
+        }
+    }
+
+    /// A zeroed block of `size` bytes. A block of 0 bytes gets the null address.
+    pub fn allocate(&mut self, size: usize) -> u64 {
+        if size == 0 {
+            return 0;
+        }
+        let address = self.next_address;
+        self.next_address += (size as u64 + 7) & !7;
+        self.blocks.insert(address, vec![0; size]);
+        address
+    }
+
+    /// Frees the block starting at `address`. Freeing the null address does nothing.
+    pub fn free(&mut self, address: u64) {
+        if address != 0 && self.blocks.remove(&address).is_none() {
+            panic!("java.lang.InternalError: freeing an address that wasn't allocated")
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    pub fn read<const N: usize>(&self, address: u64) -> [u8; N] {
+        let (start, block) = self.block(address, N);
+        let offset = (address - start) as usize;
+        block[offset..offset + N].try_into().unwrap()
+    }
+
+    pub fn write<const N: usize>(&mut self, address: u64, bytes: [u8; N]) {
+        let (start, _) = self.block(address, N);
+        let block = self.blocks.get_mut(&start).unwrap();
+        let offset = (address - start) as usize;
+        block[offset..offset + N].copy_from_slice(&bytes);
+    }
+
+    /// The block `len` bytes at `address` lie in.
+    fn block(&self, address: u64, len: usize) -> (u64, &Vec<u8>) {
+        match self.blocks.range(..=address).next_back() {
+            Some((start, block)) if address + len as u64 <= start + block.len() as u64 => {
+                (*start, block)
+            }
+            _ => fault(),
+        }
+    }
+}
+
+/// What an `Unsafe` access outside of any object or block throws.
+pub fn fault() -> ! {
+    panic!("java.lang.InternalError: a fault occurred in an unsafe memory access")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rtda::off_heap::OffHeap;
+
+    #[test]
+    fn read_write() {
+        let mut off_heap = OffHeap::new();
+        let a = off_heap.allocate(12);
+        let b = off_heap.allocate(8);
+        assert_ne!(a, 0);
+        assert_eq!(a % 8, 0);
+        assert_eq!(b, a + 16);
+        off_heap.write(a + 4, 0x0102030405060708u64.to_le_bytes());
+        assert_eq!(u64::from_le_bytes(off_heap.read(a + 4)), 0x0102030405060708);
+        assert_eq!(off_heap.read::<1>(a + 11), [0x01]);
+        assert_eq!(off_heap.read::<8>(b), [0; 8]);
+    }
+
+    #[test]
+    #[should_panic(expected = "java.lang.InternalError")]
+    fn out_of_bounds() {
+        let mut off_heap = OffHeap::new();
+        let a = off_heap.allocate(4);
+        off_heap.read::<8>(a);
+    }
+
+    #[test]
+    #[should_panic(expected = "java.lang.InternalError")]
+    fn use_after_free() {
+        let mut off_heap = OffHeap::new();
+        let a = off_heap.allocate(4);
+        off_heap.free(a);
+        off_heap.read::<4>(a);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::rtda::heap::object::ObjectRef;
 use crate::rtda::slot::Slot;
 use crate::util::converter;
 
@@ -13,62 +14,105 @@ impl OperandStack {
         }
     }
 
-    pub fn push_int(mut self, val: i32) -> OperandStack {
+    pub fn push_int(&mut self, val: i32) {
         self.vec.push(Slot::Num(val));
-        self
     }
 
-    pub fn pop_int(mut self) -> (i32, OperandStack) {
+    pub fn pop_int(&mut self) -> i32 {
         let val = self.vec.pop().unwrap();
         match val {
-            Slot::Num(val) => (val, self),
+            Slot::Num(val) => val,
+            _ => panic!("Not Num"),
         }
     }
 
-    pub fn push_long(mut self, val: i64) -> OperandStack {
+    pub fn push_long(&mut self, val: i64) {
         let [a, b] = converter::i64_to_i32seq(val);
         self.vec.push(Slot::Num(a));
         self.vec.push(Slot::Num(b));
-        self
     }
 
-    pub fn pop_long(mut self) -> (i64, OperandStack) {
+    pub fn pop_long(&mut self) -> i64 {
         let b = match self.vec.pop().unwrap() {
             Slot::Num(val) => val,
+            _ => panic!("Not Num"),
         };
         let a = match self.vec.pop().unwrap() {
             Slot::Num(val) => val,
+            _ => panic!("Not Num"),
         };
-        (converter::i32seq_to_i64([a, b]), self)
+        converter::i32seq_to_i64([a, b])
     }
 
-    pub fn push_double(mut self, val: f64) -> OperandStack {
+    pub fn push_double(&mut self, val: f64) {
         let [a, b] = converter::f64_to_i32seq(val);
         self.vec.push(Slot::Num(a));
         self.vec.push(Slot::Num(b));
-        self
     }
 
-    pub fn pop_double(mut self) -> (f64, OperandStack) {
+    pub fn pop_double(&mut self) -> f64 {
         let b = match self.vec.pop().unwrap() {
             Slot::Num(val) => val,
+            _ => panic!("Not Num"),
         };
         let a = match self.vec.pop().unwrap() {
             Slot::Num(val) => val,
+            _ => panic!("Not Num"),
         };
-        (converter::i32seq_to_f64([a, b]), self)
+        converter::i32seq_to_f64([a, b])
     }
 
-    pub fn push_float(mut self, val: f32) -> OperandStack {
+    pub fn push_float(&mut self, val: f32) {
         let val = converter::f32_to_i32(val);
         self.vec.push(Slot::Num(val));
-        self
     }
 
-    pub fn pop_float(mut self) -> (f32, OperandStack) {
+    pub fn pop_float(&mut self) -> f32 {
+        let val = self.vec.pop().unwrap();
+        match val {
+            Slot::Num(val) => converter::i32_to_f32(val),
+            _ => panic!("Not Num"),
+        }
+    }
+
+    pub fn push_ref(&mut self, val: Option<ObjectRef>) {
+        self.vec.push(Slot::Ref(val));
+    }
+
+    pub fn pop_ref(&mut self) -> Option<ObjectRef> {
         let val = self.vec.pop().unwrap();
         match val {
-            Slot::Num(val) => (converter::i32_to_f32(val), self),
+            Slot::Ref(val) => val,
+            _ => panic!("Not Ref"),
         }
     }
+
+    pub fn clear(&mut self) {
+        self.vec.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    pub fn push_slot(&mut self, val: Slot) {
+        self.vec.push(val);
+    }
+
+    pub fn pop_slots(&mut self, n: usize) -> Vec<Slot> {
+        let len = self.vec.len();
+        self.vec.split_off(len - n)
+    }
+
+    /// Non-null references on the stack, GC roots while the frame is live.
+    pub fn refs(&self) -> impl Iterator<Item = ObjectRef> + '_ {
+        self.vec.iter().filter_map(|x| match x {
+            Slot::Ref(val) => *val,
+            Slot::Num(_) => None,
+        })
+    }
 }
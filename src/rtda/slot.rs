@@ -1,4 +1,7 @@
-#[derive(Debug)]
+use crate::rtda::heap::object::ObjectRef;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Slot {
     Num(i32),
+    Ref(Option<ObjectRef>),
 }
@@ -11,21 +11,34 @@ impl Stack {
         Stack { max_size, vec }
     }
 
-    pub fn push(mut self, frame: Frame) -> Stack {
+    pub fn push(&mut self, frame: Frame) {
         if self.vec.len() == self.max_size {
             panic!("java.lang.StackOverflowError")
-        } else {
-            self.vec.push(frame);
-            self
         }
+        self.vec.push(frame);
     }
 
-    pub fn pop(mut self) -> (Frame, Stack) {
-        let frame = self.vec.pop().unwrap();
-        (frame, self)
+    pub fn pop(&mut self) -> Frame {
+        self.vec.pop().unwrap()
+    }
+
+    pub fn top(&self) -> Option<&Frame> {
+        self.vec.last()
+    }
+
+    pub fn top_mut(&mut self) -> Option<&mut Frame> {
+        self.vec.last_mut()
+    }
+
+    pub fn clear(&mut self) {
+        self.vec.clear();
     }
 
     pub fn is_empty(&self) -> bool {
         self.vec.is_empty()
     }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Frame> {
+        self.vec.iter()
+    }
 }
@@ -1,34 +1,177 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::gc::heap::Heap;
 use crate::rtda::frame::Frame;
+use crate::rtda::heap::object::ObjectRef;
+use crate::rtda::monitor::Lock;
 use crate::rtda::stack::Stack;
+use crate::rtda::vm::Vm;
 
 const STACK_SIZE: usize = 1024;
 
+/// Why a thread isn't running, checked by the scheduler before resuming it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThreadState {
+    Runnable,
+    /// Gave up the rest of its time slice with `Thread.yield`.
+    Yielding,
+    /// Waiting to enter `lock` `count` times, on `monitorenter`, on a synchronized
+    /// method or on the way out of `Object.wait`.
+    Blocked {
+        lock: Lock,
+        count: usize,
+    },
+    /// In `Object.wait` until notified, interrupted or past `deadline`, which like every
+    /// deadline is a time of the VM's clock.
+    Waiting {
+        lock: Lock,
+        count: usize,
+        deadline: Option<Duration>,
+    },
+    /// In `Thread.sleep` until interrupted or past `deadline`.
+    Sleeping {
+        deadline: Duration,
+    },
+    /// In `Unsafe.park` until unparked, interrupted or past `deadline`.
+    Parked {
+        deadline: Option<Duration>,
+    },
+}
+
 pub struct Thread {
     stack: Stack,
+    pub heap: Rc<RefCell<Heap>>,
+    pub vm: Rc<Vm>,
+    pub id: usize,
+    pub state: ThreadState,
+    /// Monitors entered by the synchronized methods on the stack, innermost last.
+    synchronized_frames: Vec<Lock>,
+    /// The exception the native the thread is blocked in throws once it runs again, such
+    /// as the `InterruptedException` of an interrupted sleep.
+    pub pending_exception: Option<String>,
+}
+
+impl Default for Thread {
+    fn default() -> Self {
+        Thread::new()
+    }
 }
 
 impl Thread {
     pub fn new() -> Thread {
+        Thread::with_heap(Rc::new(RefCell::new(Heap::default())))
+    }
+
+    pub fn with_heap(heap: Rc<RefCell<Heap>>) -> Thread {
+        let vm = Rc::new(Vm::new());
         Thread {
             stack: Stack::new(STACK_SIZE),
+            heap,
+            id: vm.next_thread_id(),
+            vm,
+            state: ThreadState::Runnable,
+            synchronized_frames: Vec::new(),
+            pending_exception: None,
         }
     }
 
-    pub fn push_frame(self, frame: Frame) -> Thread {
-        let Thread { stack } = self;
+    /// A new thread with an empty stack, sharing the heap and the VM of this one.
+    pub fn spawn(&self) -> Thread {
         Thread {
-            stack: stack.push(frame),
+            stack: Stack::new(STACK_SIZE),
+            heap: Rc::clone(&self.heap),
+            vm: Rc::clone(&self.vm),
+            id: self.vm.next_thread_id(),
+            state: ThreadState::Runnable,
+            synchronized_frames: Vec::new(),
+            pending_exception: None,
+        }
+    }
+
+    pub fn push_frame(&mut self, frame: Frame) {
+        self.stack.push(frame);
+    }
+
+    pub fn pop_frame(&mut self) -> Frame {
+        self.stack.pop()
+    }
+
+    /// Drops every frame, as an uncaught exception does, and forgets the monitors of the
+    /// synchronized methods among them, which the caller releases.
+    pub fn clear_stack(&mut self) {
+        self.stack.clear();
+        self.synchronized_frames.clear();
+        self.state = ThreadState::Runnable;
+    }
+
+    /// The frame on top of the stack, which must not be empty.
+    pub fn current_frame(&self) -> &Frame {
+        self.stack.top().expect("The stack is empty")
+    }
+
+    /// The frame `depth` frames below the current one, if the stack is that deep.
+    pub fn caller_frame(&self, depth: usize) -> Option<&Frame> {
+        self.stack.iter().rev().nth(depth)
+    }
+
+    /// The frame on top of the stack, which must not be empty.
+    pub fn current_frame_mut(&mut self) -> &mut Frame {
+        self.stack.top_mut().expect("The stack is empty")
+    }
+
+    /// The current frame along with the heap, for instructions that need both at once.
+    pub fn current_frame_and_heap(&mut self) -> (&mut Frame, &RefCell<Heap>) {
+        let frame = self.stack.top_mut().expect("The stack is empty");
+        (frame, &self.heap)
+    }
+
+    /// Enters `lock`, or blocks until the scheduler can enter it on this thread's behalf.
+    pub fn enter_monitor(&mut self, lock: Lock) {
+        let entered = self.vm.monitors.borrow_mut().try_enter(&lock, self.id, 1);
+        if !entered {
+            self.state = ThreadState::Blocked { lock, count: 1 };
         }
     }
 
-    pub fn pop_frame(self) -> (Frame, Thread) {
-        let Thread { stack } = self;
-        let (frame, stack) = stack.pop();
-        let thread = Thread { stack };
-        (frame, thread)
+    pub fn exit_monitor(&mut self, lock: &Lock) {
+        self.vm.monitors.borrow_mut().exit(lock, self.id);
+    }
+
+    /// Enters the monitor of a synchronized method whose frame was just pushed.
+    pub fn enter_synchronized(&mut self, lock: Lock) {
+        self.enter_monitor(lock.clone());
+        self.synchronized_frames.push(lock);
+    }
+
+    /// Exits the monitor of the synchronized method whose frame was just popped.
+    pub fn exit_synchronized(&mut self) {
+        let lock = self
+            .synchronized_frames
+            .pop()
+            .expect("No synchronized frame");
+        self.exit_monitor(&lock);
+    }
+
+    /// Clears the interrupt status, returning whether it was set.
+    pub fn take_interrupt(&self) -> bool {
+        self.vm.interrupted.borrow_mut().remove(&self.id)
+    }
+
+    pub fn java_thread(&self) -> Option<ObjectRef> {
+        self.vm.java_threads.borrow().get(&self.id).copied()
     }
 
     pub fn is_stack_empty(&self) -> bool {
         self.stack.is_empty()
     }
+
+    /// References held by the local variables and operand stacks of every frame.
+    pub fn roots(&self) -> Vec<ObjectRef> {
+        self.stack
+            .iter()
+            .flat_map(|x| x.local_vars.refs().chain(x.operand_stack.refs()))
+            .collect()
+    }
 }
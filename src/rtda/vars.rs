@@ -1,32 +1,91 @@
 extern crate vec_map;
 
+use crate::rtda::heap::object::ObjectRef;
 use crate::rtda::slot::Slot;
+use crate::util::converter;
 
 use self::vec_map::VecMap;
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Vars {
     vec_map: VecMap<Slot>,
 }
 
 impl Vars {
     pub fn new(max_locals: usize) -> Vars {
-        if max_locals > 0 {
-            let vec_map = VecMap::with_capacity(max_locals);
-            Vars { vec_map }
-        } else {
-            panic!("max_locals < 0")
-        }
+        let vec_map = VecMap::with_capacity(max_locals);
+        Vars { vec_map }
     }
 
-    pub fn set_int(mut self, index: usize, val: i32) -> Vars {
+    pub fn set_int(&mut self, index: usize, val: i32) {
         self.vec_map.insert(index, Slot::Num(val));
-        self
     }
 
     pub fn get_int(&self, index: usize) -> i32 {
         match self.vec_map[index] {
             Slot::Num(val) => val,
+            _ => panic!("Not Num"),
         }
     }
+
+    pub fn set_long(&mut self, index: usize, val: i64) {
+        let [a, b] = converter::i64_to_i32seq(val);
+        self.vec_map.insert(index, Slot::Num(a));
+        self.vec_map.insert(index + 1, Slot::Num(b));
+    }
+
+    pub fn get_long(&self, index: usize) -> i64 {
+        converter::i32seq_to_i64([self.get_int(index), self.get_int(index + 1)])
+    }
+
+    pub fn set_float(&mut self, index: usize, val: f32) {
+        self.vec_map
+            .insert(index, Slot::Num(converter::f32_to_i32(val)));
+    }
+
+    pub fn get_float(&self, index: usize) -> f32 {
+        converter::i32_to_f32(self.get_int(index))
+    }
+
+    pub fn set_double(&mut self, index: usize, val: f64) {
+        let [a, b] = converter::f64_to_i32seq(val);
+        self.vec_map.insert(index, Slot::Num(a));
+        self.vec_map.insert(index + 1, Slot::Num(b));
+    }
+
+    pub fn get_double(&self, index: usize) -> f64 {
+        converter::i32seq_to_f64([self.get_int(index), self.get_int(index + 1)])
+    }
+
+    pub fn set_ref(&mut self, index: usize, val: Option<ObjectRef>) {
+        self.vec_map.insert(index, Slot::Ref(val));
+    }
+
+    pub fn get_ref(&self, index: usize) -> Option<ObjectRef> {
+        match self.vec_map[index] {
+            Slot::Ref(val) => val,
+            _ => panic!("Not Ref"),
+        }
+    }
+
+    pub fn set_slot(&mut self, index: usize, val: Slot) {
+        self.vec_map.insert(index, val);
+    }
+
+    pub fn get_slot(&self, index: usize) -> Slot {
+        self.vec_map[index]
+    }
+
+    /// The slot at `index`, `None` if the variable hasn't been set yet.
+    pub fn try_get_slot(&self, index: usize) -> Option<Slot> {
+        self.vec_map.get(index).copied()
+    }
+
+    /// Non-null references held by these variables, GC roots while the frame is live.
+    pub fn refs(&self) -> impl Iterator<Item = ObjectRef> + '_ {
+        self.vec_map.values().filter_map(|x| match x {
+            Slot::Ref(val) => *val,
+            Slot::Num(_) => None,
+        })
+    }
 }
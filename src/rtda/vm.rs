@@ -0,0 +1,57 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "jit")]
+use crate::jit::Jit;
+use crate::rtda::clock::Clock;
+use crate::rtda::heap::object::ObjectRef;
+use crate::rtda::monitor::Monitors;
+use crate::rtda::off_heap::OffHeap;
+use crate::rtda::thread::Thread;
+
+/// State shared by every thread of the VM.
+#[derive(Default)]
+pub struct Vm {
+    pub monitors: RefCell<Monitors>,
+    /// Threads created by `Thread.start0` that the scheduler hasn't picked up yet.
+    pub started: RefCell<Vec<Thread>>,
+    /// The `java.lang.Thread` object of each live thread, by thread id.
+    pub java_threads: RefCell<HashMap<usize, ObjectRef>>,
+    /// Ids of the threads whose interrupt status is set.
+    pub interrupted: RefCell<HashSet<usize>>,
+    /// Ids of the threads given a permit by `Unsafe.unpark`, which their next
+    /// `Unsafe.park` consumes instead of parking.
+    pub permits: RefCell<HashSet<usize>>,
+    /// Memory handed out by `Unsafe.allocateMemory`.
+    pub off_heap: RefCell<OffHeap>,
+    /// Ids of the terminated threads still to notify their joiners.
+    pub exiting: RefCell<Vec<usize>>,
+    pub clock: Clock,
+    /// Whether every method runs on the operand stack, even those that the register
+    /// interpreter could run.
+    pub stack_only: Cell<bool>,
+    #[cfg(feature = "jit")]
+    pub jit: Jit,
+    next_thread_id: Cell<usize>,
+}
+
+impl Vm {
+    pub fn new() -> Vm {
+        Vm::default()
+    }
+
+    pub fn next_thread_id(&self) -> usize {
+        let id = self.next_thread_id.get();
+        self.next_thread_id.set(id + 1);
+        id
+    }
+
+    /// The id of the live thread `java_thread` is the `java.lang.Thread` object of.
+    pub fn thread_id(&self, java_thread: ObjectRef) -> Option<usize> {
+        self.java_threads
+            .borrow()
+            .iter()
+            .find(|(_, x)| **x == java_thread)
+            .map(|(id, _)| *id)
+    }
+}
@@ -1,6 +1,276 @@
+pub const USAGE: &str = "Usage: jvm [options] class [args...]
+           (to run a class)
+   or  jvm [options] -jar jarfile [args...]
+           (to run a jar file)
+   or  jvm [options] -m module[/mainclass] [args...]
+           (to run the main class of a module)
+
+Options:
+    -cp, -classpath, --class-path <path>
+                  directories and jars to search for classes
+    -jre <path>   the JRE or JDK home whose classes are the boot classes
+    -p, --module-path <path>
+                  .jmod files, jars, exploded modules and directories of those
+    -m, --module <module>[/<mainclass>]
+                  the main module, and its main class unless the module names one
+    --add-modules <module>(,<module>)*
+                  root modules to resolve besides the main module
+    --add-exports <module>/<package>=<target-module>(,<target-module>)*
+    --add-opens <module>/<package>=<target-module>(,<target-module>)*
+                  export or open a package to other modules, ALL-UNNAMED for the class path
+    -Djdk.util.jar.version=<version>
+                  the feature version multi-release jars are read for
+    -verbose:class
+                  print each class as it's loaded, with where it was read from
+    -Xint         only interpret, never compile hot methods
+    -Xmx<size>    the maximum heap size, like 64m
+    -Xverify:<mode>
+                  bytecode verification: none, remote (the default) or all
+    -XX:+UseMarkSweepGC, -XX:+UseGenerationalGC
+                  the garbage collector, mark-sweep by default
+    -XX:TimeSlice=<n>
+                  instructions a thread runs before it's preempted
+    -XX:SchedulerSeed=<n>
+                  seed of the deterministic scheduler
+    -XX:+PrintInlineCaches
+                  print the counters of the inline caches when the program ends";
+
+#[derive(Debug, Default)]
 pub struct Command<'a> {
+    /// The main class in internal form, like `pkg/Main`, empty when `-jar` or `-m` gives it.
     pub class_name: String,
     pub cp_opt: Option<String>,
+    /// The jar of `-jar`, which is then the whole class path and whose manifest names the
+    /// main class.
+    pub jar_opt: Option<String>,
+    /// The modules given to `--module-path`: `.jmod` files, jars, exploded modules or
+    /// directories of those.
+    pub module_path_opt: Option<String>,
+    /// The main module of `-m`, with the main class after a `/` unless the module names
+    /// one.
+    pub module_opt: Option<String>,
+    /// The root modules of `--add-modules`, comma separated.
+    pub add_modules: Vec<String>,
+    /// The packages of `--add-exports`, like `java.base/sun.nio.ch=ALL-UNNAMED`.
+    pub add_exports: Vec<String>,
+    /// The packages of `--add-opens`, in the format of `--add-exports`.
+    pub add_opens: Vec<String>,
+    /// The feature version multi-release jars are read for, as given to
+    /// `-Djdk.util.jar.version`.
+    pub jar_version_opt: Option<String>,
     pub jre_opt: Option<String>,
+    /// Maximum heap size, e.g. `64m` as given to `-Xmx`.
+    pub xmx_opt: Option<String>,
+    /// Garbage collector, `mark-sweep` (the default) or `generational`.
+    pub gc_opt: Option<String>,
+    /// Instructions a thread runs before it's preempted.
+    pub time_slice_opt: Option<String>,
+    /// Seed of the deterministic scheduler, which interleaves threads reproducibly.
+    pub seed_opt: Option<String>,
+    /// Bytecode verification as given to `-Xverify`: `none`, `remote` (the default) or `all`.
+    pub verify_opt: Option<String>,
+    /// Whether `-Xint` was given, which only interprets and never compiles hot methods.
+    pub xint: bool,
+    /// Whether `-verbose:class` was given, which prints each class as it's loaded with the
+    /// directory or jar it was read from.
+    pub verbose_class: bool,
+    /// Whether `-XX:+PrintInlineCaches` was given, which prints the counters of the inline
+    /// caches of every call site and field access once the program ends.
+    pub print_inline_caches: bool,
     pub args: Vec<&'a str>,
 }
+
+impl<'a> Command<'a> {
+    /// Parses the arguments after the program name like `java` does: options, then the
+    /// main class, `-jar` and a jar or `-m` and a module, then the arguments of `main`.
+    /// The `--`, `-XX:` and `-D` options take their value after a `=` too, like
+    /// `--module-path=mods` or `-XX:TimeSlice=100`.
+    pub fn parse(args: &'a [String]) -> Result<Command<'a>, String> {
+        let mut command = Command::default();
+        let mut args = args.iter();
+        let mut launched = false;
+        while let Some(arg) = args.next() {
+            let (option, inline) = match arg.split_once('=') {
+                Some((option, inline))
+                    if arg.starts_with("--")
+                        || arg.starts_with("-XX:")
+                        || arg.starts_with("-D") =>
+                {
+                    (option, Some(inline))
+                }
+                _ => (arg.as_str(), None),
+            };
+            let mut argument = || match inline {
+                Some(inline) => Ok(inline.to_owned()),
+                None => value(&mut args, option),
+            };
+            match option {
+                "-cp" | "-classpath" | "--class-path" => command.cp_opt = Some(argument()?),
+                "-jre" => command.jre_opt = Some(argument()?),
+                "-p" | "--module-path" => command.module_path_opt = Some(argument()?),
+                "--add-modules" => command.add_modules.push(argument()?),
+                "--add-exports" => command.add_exports.push(argument()?),
+                "--add-opens" => command.add_opens.push(argument()?),
+                "-Djdk.util.jar.version" => command.jar_version_opt = Some(argument()?),
+                // The VM reads no other system property
+                _ if option.starts_with("-D") => {}
+                "-verbose:class" => command.verbose_class = true,
+                "-Xint" => command.xint = true,
+                _ if option.starts_with("-Xmx") => {
+                    command.xmx_opt = Some(option["-Xmx".len()..].to_owned());
+                }
+                _ if option.starts_with("-Xverify:") => {
+                    command.verify_opt = Some(option["-Xverify:".len()..].to_owned());
+                }
+                "-XX:+UseMarkSweepGC" => command.gc_opt = Some("mark-sweep".to_owned()),
+                "-XX:+UseGenerationalGC" => command.gc_opt = Some("generational".to_owned()),
+                "-XX:TimeSlice" => command.time_slice_opt = Some(argument()?),
+                "-XX:SchedulerSeed" => command.seed_opt = Some(argument()?),
+                "-XX:+PrintInlineCaches" => command.print_inline_caches = true,
+                "-jar" => {
+                    command.jar_opt = Some(argument()?);
+                    launched = true;
+                    break;
+                }
+                "-m" | "--module" => {
+                    command.module_opt = Some(argument()?);
+                    launched = true;
+                    break;
+                }
+                _ if arg.starts_with('-') => return Err(format!("Unrecognized option: {}", arg)),
+                _ => {
+                    command.class_name = arg.replace('.', "/");
+                    launched = true;
+                    break;
+                }
+            }
+        }
+        if !launched {
+            return Err("No main class given".to_owned());
+        }
+        command.args = args.map(|x| x.as_str()).collect();
+        Ok(command)
+    }
+}
+
+/// The argument after the option `option`.
+fn value(args: &mut std::slice::Iter<String>, option: &str) -> Result<String, String> {
+    args.next()
+        .cloned()
+        .ok_or_else(|| format!("{} requires an argument", option))
+}
+
+/// Parses a memory size like `-Xmx` accepts it: bytes, or a number followed by
+/// `k`, `m` or `g` in either case.
+pub fn parse_memory_size(size: &str) -> Option<usize> {
+    let (digits, unit) = match size.char_indices().last()? {
+        (i, c) if c.is_ascii_alphabetic() => (&size[..i], c.to_ascii_lowercase()),
+        _ => (size, 'b'),
+    };
+    let multiplier: usize = match unit {
+        'b' => 1,
+        'k' => 1024,
+        'm' => 1024 * 1024,
+        'g' => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    digits.parse::<usize>().ok()?.checked_mul(multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shell::command::{parse_memory_size, Command};
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(|x| x.to_owned()).collect()
+    }
+
+    #[test]
+    fn parse() {
+        let line = args("-cp lib/a.jar:classes -jre /opt/jre pkg.Main one two");
+        let command = Command::parse(&line).unwrap();
+        assert_eq!(command.cp_opt.as_deref(), Some("lib/a.jar:classes"));
+        assert_eq!(command.jre_opt.as_deref(), Some("/opt/jre"));
+        assert_eq!(command.class_name, "pkg/Main");
+        assert_eq!(command.jar_opt, None);
+        assert_eq!(command.args, vec!["one", "two"]);
+
+        let line = args("--class-path=classes Main -cp x");
+        let command = Command::parse(&line).unwrap();
+        assert_eq!(command.cp_opt.as_deref(), Some("classes"));
+        assert_eq!(command.args, vec!["-cp", "x"]);
+
+        let line = args("-jar app.jar -cp ignored");
+        let command = Command::parse(&line).unwrap();
+        assert_eq!(command.jar_opt.as_deref(), Some("app.jar"));
+        assert_eq!(command.class_name, "");
+        assert_eq!(command.args, vec!["-cp", "ignored"]);
+
+        let error = |x: &str| Command::parse(&args(x)).unwrap_err();
+        assert_eq!(error("-cp classes"), "No main class given");
+        assert_eq!(error("-cp"), "-cp requires an argument");
+        assert_eq!(error("-jar"), "-jar requires an argument");
+        assert_eq!(error("-foo Main"), "Unrecognized option: -foo");
+    }
+
+    #[test]
+    fn parse_module_options() {
+        let line = args(
+            "-p mods --add-modules=lib,other --add-exports lib/lib.internal=app \
+             --add-opens=other/other.api=ALL-UNNAMED -m app/app.Main one",
+        );
+        let command = Command::parse(&line).unwrap();
+        assert_eq!(command.module_path_opt.as_deref(), Some("mods"));
+        assert_eq!(command.add_modules, vec!["lib,other"]);
+        assert_eq!(command.add_exports, vec!["lib/lib.internal=app"]);
+        assert_eq!(command.add_opens, vec!["other/other.api=ALL-UNNAMED"]);
+        assert_eq!(command.module_opt.as_deref(), Some("app/app.Main"));
+        assert_eq!(command.class_name, "");
+        assert_eq!(command.args, vec!["one"]);
+
+        let line = args("--module-path=mods --module app");
+        let command = Command::parse(&line).unwrap();
+        assert_eq!(command.module_path_opt.as_deref(), Some("mods"));
+        assert_eq!(command.module_opt.as_deref(), Some("app"));
+    }
+
+    #[test]
+    fn parse_vm_options() {
+        let line = args(
+            "-Xint -Xmx64m -XX:+UseGenerationalGC -XX:TimeSlice=100 -XX:SchedulerSeed=42 \
+             -Xverify:all -XX:+PrintInlineCaches -Djdk.util.jar.version=11 -Dother=x \
+             -verbose:class Main",
+        );
+        let command = Command::parse(&line).unwrap();
+        assert!(command.xint);
+        assert_eq!(command.xmx_opt.as_deref(), Some("64m"));
+        assert_eq!(command.gc_opt.as_deref(), Some("generational"));
+        assert_eq!(command.time_slice_opt.as_deref(), Some("100"));
+        assert_eq!(command.seed_opt.as_deref(), Some("42"));
+        assert_eq!(command.verify_opt.as_deref(), Some("all"));
+        assert!(command.print_inline_caches);
+        assert_eq!(command.jar_version_opt.as_deref(), Some("11"));
+        assert!(command.verbose_class);
+        assert_eq!(command.class_name, "Main");
+
+        let line = args("Main");
+        let command = Command::parse(&line).unwrap();
+        assert!(!command.xint);
+        assert!(!command.verbose_class);
+        assert!(!command.print_inline_caches);
+        assert_eq!(command.xmx_opt, None);
+        assert_eq!(command.gc_opt, None);
+        assert_eq!(command.verify_opt, None);
+    }
+
+    #[test]
+    fn memory_size() {
+        assert_eq!(parse_memory_size("4096"), Some(4096));
+        assert_eq!(parse_memory_size("64k"), Some(64 * 1024));
+        assert_eq!(parse_memory_size("64M"), Some(64 * 1024 * 1024));
+        assert_eq!(parse_memory_size("1g"), Some(1024 * 1024 * 1024));
+        assert_eq!(parse_memory_size("1x"), None);
+        assert_eq!(parse_memory_size("m"), None);
+        assert_eq!(parse_memory_size(""), None);
+    }
+}
@@ -0,0 +1,57 @@
+package exceptions;
+
+public class Handlers implements Runnable {
+    static int result;
+    int value;
+
+    public static void raised() {
+        Handlers handlers = null;
+        try {
+            result = handlers.value;
+        } catch (NullPointerException e) {
+            result = 1;
+        }
+    }
+
+    public static void thrown() {
+        try {
+            call();
+        } catch (RuntimeException e) {
+            result = e.getMessage() == "failed" ? 1 : 0;
+        }
+    }
+
+    public static void unlocked() throws InterruptedException {
+        Handlers handlers = new Handlers();
+        try {
+            synchronized (handlers) {
+                fail();
+            }
+        } catch (RuntimeException e) {
+        }
+        Thread thread = new Thread(handlers);
+        thread.start();
+        thread.join();
+        result = handlers.value;
+    }
+
+    public static void uncaught() {
+        result = 1;
+        call();
+        result = 2;
+    }
+
+    static void call() {
+        fail();
+    }
+
+    static void fail() {
+        throw new RuntimeException("failed");
+    }
+
+    public void run() {
+        synchronized (this) {
+            value = 1;
+        }
+    }
+}
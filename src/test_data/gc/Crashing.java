@@ -0,0 +1,7 @@
+package gc;
+
+public class Crashing {
+    protected void finalize() {
+        Finalizers.crash(Thread.currentThread());
+    }
+}
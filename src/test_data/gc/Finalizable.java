@@ -0,0 +1,11 @@
+package gc;
+
+public class Finalizable {
+    static int finalized;
+
+    Node node;
+
+    protected void finalize() {
+        finalized = finalized + 1;
+    }
+}
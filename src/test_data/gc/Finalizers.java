@@ -0,0 +1,28 @@
+package gc;
+
+// Run with a heap small enough that the garbage is collected and queued for finalization
+public class Finalizers {
+    static int result;
+    static int finalized;
+    static Thread finalizer;
+
+    static void run() {
+        new Crashing();
+        while (finalized == 0) {
+            new Node();
+        }
+        result = finalizedElsewhere(Thread.currentThread());
+    }
+
+    // Throws holding the monitor of Finalizers, which main needs to finish
+    static synchronized void crash(Thread thread) {
+        finalized = finalized + 1;
+        finalizer = thread;
+        Node node = null;
+        node.value = 1;
+    }
+
+    static synchronized int finalizedElsewhere(Thread thread) {
+        return finalizer != thread ? finalized : -1;
+    }
+}
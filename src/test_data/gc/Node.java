@@ -0,0 +1,33 @@
+package gc;
+
+public class Node {
+    static final int ANSWER = 42;
+    static final long SEED = 2997924580L;
+    static final float SCALE = 1.5f;
+    static final double RATIO = 1.6180339887;
+    static final String NAME = "node";
+
+    static Node head;
+    static int count;
+
+    Node next;
+    long weight;
+    int value;
+
+    static String greet() {
+        return "hello";
+    }
+
+    static void push(int value) {
+        Node node = new Node();
+        node.value = value;
+        node.weight = value;
+        node.next = head;
+        head = node;
+        count = count + 1;
+    }
+
+    int sum() {
+        return next == null ? value : value + next.sum();
+    }
+}
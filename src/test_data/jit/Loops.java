@@ -0,0 +1,58 @@
+package jit;
+
+public class Loops {
+    static int result;
+    static long longResult;
+
+    static int sum(int n) {
+        int sum = 0;
+        for (int i = 1; i <= n; i++) {
+            sum += i;
+        }
+        return sum;
+    }
+
+    static long power(long base, int n) {
+        long product = 1L;
+        for (int i = 0; i < n; i++) {
+            product *= base;
+        }
+        return product;
+    }
+
+    static int mask(int n) {
+        int bits = 0;
+        for (int i = 0; i < n; i++) {
+            bits = (bits + i * 7) & 127;
+        }
+        return -bits;
+    }
+
+    // The long constant isn't compiled, so each iteration deoptimizes once
+    static int doublings(long a, long b) {
+        int count = 0;
+        while (a < b) {
+            a *= 2L;
+            count++;
+        }
+        return count;
+    }
+
+    static int square(int x) {
+        return x * x;
+    }
+
+    // The call leaves compiled code in the middle of the loop
+    static int sumOfSquares(int n) {
+        int sum = 0;
+        for (int i = 0; i < n; i++) {
+            sum += square(i);
+        }
+        return sum;
+    }
+
+    public static void main(String[] args) {
+        result = sum(100) + mask(50) + doublings(3L, 4000L) + sumOfSquares(20);
+        longResult = power(3L, 30);
+    }
+}
@@ -0,0 +1,46 @@
+package jit;
+
+public class Shapes {
+    static int result;
+    static int polymorphicResult;
+
+    int width;
+
+    Shapes(int width) {
+        this.width = width;
+    }
+
+    int area() {
+        return width * width;
+    }
+
+    // Only ever called on Shapes until Wide turns up
+    static int totalArea(Shapes shape, int n) {
+        int total = 0;
+        for (int i = 0; i < n; i++) {
+            total += shape.area();
+        }
+        return total;
+    }
+
+    // The interpreter has no sipush, so the 2000 areas are 20 calls of 100
+    public static void main(String[] args) {
+        Shapes shape = new Shapes(3);
+        int total = 0;
+        for (int i = 0; i < 20; i++) {
+            total += totalArea(shape, 100);
+        }
+        result = total;
+        polymorphicResult = totalArea(new Wide(2), 10);
+    }
+}
+
+class Wide extends Shapes {
+    Wide(int width) {
+        super(width);
+    }
+
+    int area() {
+        return width * 3;
+    }
+}
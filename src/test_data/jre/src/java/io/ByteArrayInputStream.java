@@ -0,0 +1,22 @@
+package java.io;
+
+public class ByteArrayInputStream extends InputStream {
+    protected byte[] buf;
+    protected int pos;
+    protected int mark;
+    protected int count;
+
+    public ByteArrayInputStream(byte[] buf) {
+        this.buf = buf;
+        this.count = buf.length;
+    }
+
+    public int read() {
+        if (pos < count) {
+            int b = buf[pos] & 0xff;
+            pos = pos + 1;
+            return b;
+        }
+        return -1;
+    }
+}
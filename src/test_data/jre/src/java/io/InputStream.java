@@ -0,0 +1,8 @@
+package java.io;
+
+public abstract class InputStream {
+    public InputStream() {
+    }
+
+    public abstract int read() throws IOException;
+}
@@ -0,0 +1,20 @@
+package java.lang;
+
+import java.lang.reflect.Field;
+
+public final class Class<T> {
+    private Class() {
+    }
+
+    public Field getDeclaredField(String name) throws NoSuchFieldException {
+        String internedName = name.intern();
+        for (Field field : getDeclaredFields0(false)) {
+            if (field.getName() == internedName) {
+                return field;
+            }
+        }
+        throw new NoSuchFieldException(name);
+    }
+
+    private native Field[] getDeclaredFields0(boolean publicOnly);
+}
@@ -0,0 +1,50 @@
+package java.lang;
+
+import java.io.IOException;
+import java.io.InputStream;
+import java.net.URL;
+import java.util.Enumeration;
+import jdk.internal.loader.BootLoader;
+
+// Every class comes from the boot loader, which searches the whole class path, so no
+// loader has a parent or finds resources of its own
+public abstract class ClassLoader {
+    protected ClassLoader() {
+    }
+
+    public URL getResource(String name) {
+        URL url = BootLoader.findResource(name);
+        if (url == null) {
+            url = findResource(name);
+        }
+        return url;
+    }
+
+    public Enumeration<URL> getResources(String name) throws IOException {
+        return BootLoader.findResources(name);
+    }
+
+    protected URL findResource(String name) {
+        return null;
+    }
+
+    public InputStream getResourceAsStream(String name) {
+        return getSystemResourceAsStream(name);
+    }
+
+    public static URL getSystemResource(String name) {
+        return BootLoader.findResource(name);
+    }
+
+    public static Enumeration<URL> getSystemResources(String name) throws IOException {
+        return BootLoader.findResources(name);
+    }
+
+    public static InputStream getSystemResourceAsStream(String name) {
+        try {
+            return BootLoader.findResourceAsStream(null, name);
+        } catch (IOException e) {
+            return null;
+        }
+    }
+}
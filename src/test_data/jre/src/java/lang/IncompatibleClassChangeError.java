@@ -0,0 +1,10 @@
+package java.lang;
+
+public class IncompatibleClassChangeError extends LinkageError {
+    public IncompatibleClassChangeError() {
+    }
+
+    public IncompatibleClassChangeError(String message) {
+        super(message);
+    }
+}
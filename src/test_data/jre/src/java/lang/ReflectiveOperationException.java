@@ -0,0 +1,10 @@
+package java.lang;
+
+public class ReflectiveOperationException extends Exception {
+    public ReflectiveOperationException() {
+    }
+
+    public ReflectiveOperationException(String message) {
+        super(message);
+    }
+}
@@ -0,0 +1,12 @@
+package java.lang;
+
+public final class String implements java.io.Serializable {
+    private final char[] value;
+    private int hash;
+
+    public String() {
+        this.value = new char[0];
+    }
+
+    public native String intern();
+}
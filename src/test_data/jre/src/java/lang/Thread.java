@@ -0,0 +1,63 @@
+package java.lang;
+
+public class Thread implements Runnable {
+    private volatile String name;
+    private Runnable target;
+
+    public Thread() {
+    }
+
+    public Thread(Runnable target) {
+        this.target = target;
+    }
+
+    public Thread(String name) {
+        this.name = name;
+    }
+
+    public static native Thread currentThread();
+
+    public static native void yield();
+
+    public static native void sleep(long millis) throws InterruptedException;
+
+    public synchronized void start() {
+        start0();
+    }
+
+    private native void start0();
+
+    public void run() {
+        if (target != null) {
+            target.run();
+        }
+    }
+
+    public void interrupt() {
+        interrupt0();
+    }
+
+    public static boolean interrupted() {
+        return currentThread().isInterrupted(true);
+    }
+
+    public boolean isInterrupted() {
+        return isInterrupted(false);
+    }
+
+    private native boolean isInterrupted(boolean clearInterrupted);
+
+    public final native boolean isAlive();
+
+    public final synchronized void join() throws InterruptedException {
+        while (isAlive()) {
+            wait(0);
+        }
+    }
+
+    public final String getName() {
+        return name;
+    }
+
+    private native void interrupt0();
+}
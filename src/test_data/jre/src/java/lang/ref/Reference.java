@@ -0,0 +1,27 @@
+package java.lang.ref;
+
+public abstract class Reference<T> {
+    private T referent;
+    volatile ReferenceQueue<? super T> queue;
+    volatile Reference next;
+    private transient Reference<T> discovered;
+
+    private static Reference<Object> pending = null;
+
+    Reference(T referent) {
+        this(referent, null);
+    }
+
+    Reference(T referent, ReferenceQueue<? super T> queue) {
+        this.referent = referent;
+        this.queue = queue;
+    }
+
+    public T get() {
+        return this.referent;
+    }
+
+    public void clear() {
+        this.referent = null;
+    }
+}
@@ -0,0 +1,4 @@
+package java.lang.ref;
+
+public class ReferenceQueue<T> {
+}
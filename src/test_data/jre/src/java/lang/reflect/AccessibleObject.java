@@ -0,0 +1,34 @@
+package java.lang.reflect;
+
+import jdk.internal.reflect.Reflection;
+
+public class AccessibleObject {
+    boolean override;
+
+    public void setAccessible(boolean flag) {
+        if (flag) {
+            checkCanSetAccessible(Reflection.getCallerClass());
+        }
+        setAccessible0(flag);
+    }
+
+    public boolean isAccessible() {
+        return override;
+    }
+
+    void checkCanSetAccessible(Class<?> caller) {
+    }
+
+    final void checkCanSetAccessible(Class<?> caller, Class<?> declaringClass) {
+        checkCanSetAccessible(caller, declaringClass, true);
+    }
+
+    // Checked by the VM, which knows the modules
+    private native boolean checkCanSetAccessible(Class<?> caller, Class<?> declaringClass,
+                                                 boolean throwExceptionIfDenied);
+
+    boolean setAccessible0(boolean flag) {
+        this.override = flag;
+        return flag;
+    }
+}
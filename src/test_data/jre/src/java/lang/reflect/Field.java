@@ -0,0 +1,29 @@
+package java.lang.reflect;
+
+public final class Field extends AccessibleObject {
+    private Class<?> clazz;
+    private String name;
+    private int modifiers;
+
+    Field(Class<?> clazz, String name, int modifiers) {
+        this.clazz = clazz;
+        this.name = name;
+        this.modifiers = modifiers;
+    }
+
+    public Class<?> getDeclaringClass() {
+        return clazz;
+    }
+
+    public String getName() {
+        return name;
+    }
+
+    public int getModifiers() {
+        return modifiers;
+    }
+
+    void checkCanSetAccessible(Class<?> caller) {
+        checkCanSetAccessible(caller, clazz);
+    }
+}
@@ -0,0 +1,32 @@
+package java.net;
+
+public final class URL implements java.io.Serializable {
+    private String protocol;
+    private String host;
+    private int port;
+    private String file;
+    private transient String path;
+
+    private URL() {
+    }
+
+    public String getProtocol() {
+        return protocol;
+    }
+
+    public String getHost() {
+        return host;
+    }
+
+    public int getPort() {
+        return port;
+    }
+
+    public String getFile() {
+        return file;
+    }
+
+    public String getPath() {
+        return path;
+    }
+}
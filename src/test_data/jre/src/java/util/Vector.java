@@ -0,0 +1,28 @@
+package java.util;
+
+public class Vector<E> {
+    protected Object[] elementData;
+    protected int elementCount;
+
+    public Vector() {
+    }
+
+    public Enumeration<E> elements() {
+        return new Enumeration<E>() {
+            int count = 0;
+
+            public boolean hasMoreElements() {
+                return count < elementCount;
+            }
+
+            public E nextElement() {
+                if (count < elementCount) {
+                    E element = (E) elementData[count];
+                    count = count + 1;
+                    return element;
+                }
+                throw new NoSuchElementException("Vector Enumeration");
+            }
+        };
+    }
+}
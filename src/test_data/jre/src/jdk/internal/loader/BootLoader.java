@@ -0,0 +1,19 @@
+package jdk.internal.loader;
+
+import java.io.IOException;
+import java.io.InputStream;
+import java.net.URL;
+import java.util.Enumeration;
+
+// The VM searches the class path itself rather than through module readers
+public class BootLoader {
+    private BootLoader() {
+    }
+
+    public static native URL findResource(String name);
+
+    public static native Enumeration<URL> findResources(String name) throws IOException;
+
+    public static native InputStream findResourceAsStream(String mn, String name)
+        throws IOException;
+}
@@ -0,0 +1,80 @@
+package jdk.internal.misc;
+
+public final class Unsafe {
+    private Unsafe() {
+    }
+
+    public native int getInt(Object o, long offset);
+
+    public native void putInt(Object o, long offset, int x);
+
+    public native long getLong(Object o, long offset);
+
+    public native void putLong(Object o, long offset, long x);
+
+    public native Object getReference(Object o, long offset);
+
+    public native void putReference(Object o, long offset, Object x);
+
+    public native int getIntVolatile(Object o, long offset);
+
+    public native void putIntVolatile(Object o, long offset, int x);
+
+    public void putIntRelease(Object o, long offset, int x) {
+        putIntVolatile(o, offset, x);
+    }
+
+    public native Object getReferenceVolatile(Object o, long offset);
+
+    public native boolean compareAndSetInt(Object o, long offset, int expected, int x);
+
+    public native boolean compareAndSetLong(Object o, long offset, long expected, long x);
+
+    public native boolean compareAndSetReference(Object o, long offset, Object expected, Object x);
+
+    public long objectFieldOffset(Class<?> c, String name) {
+        return objectFieldOffset1(c, name);
+    }
+
+    private native long objectFieldOffset1(Class<?> c, String name);
+
+    public int arrayBaseOffset(Class<?> arrayClass) {
+        return arrayBaseOffset0(arrayClass);
+    }
+
+    private native int arrayBaseOffset0(Class<?> arrayClass);
+
+    public int arrayIndexScale(Class<?> arrayClass) {
+        return arrayIndexScale0(arrayClass);
+    }
+
+    private native int arrayIndexScale0(Class<?> arrayClass);
+
+    public long allocateMemory(long bytes) {
+        return allocateMemory0(bytes);
+    }
+
+    private native long allocateMemory0(long bytes);
+
+    public void freeMemory(long address) {
+        freeMemory0(address);
+    }
+
+    private native void freeMemory0(long address);
+
+    public native byte getByte(long address);
+
+    public native void putByte(long address, byte x);
+
+    public native int getInt(long address);
+
+    public native void putInt(long address, int x);
+
+    public native long getLong(long address);
+
+    public native void putLong(long address, long x);
+
+    public native void park(boolean isAbsolute, long time);
+
+    public native void unpark(Object thread);
+}
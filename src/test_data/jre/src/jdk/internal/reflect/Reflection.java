@@ -0,0 +1,6 @@
+package jdk.internal.reflect;
+
+public class Reflection {
+    // The class of the method that called the method calling this one
+    public static native Class<?> getCallerClass();
+}
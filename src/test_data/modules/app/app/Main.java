@@ -0,0 +1,9 @@
+package app;
+
+public class Main {
+    static int result;
+
+    public static void main(String[] args) {
+        result = lib.api.Api.answer();
+    }
+}
@@ -0,0 +1,20 @@
+package app;
+
+// Makes fields of lib accessible, lib opening lib.internal to app and only exporting
+// lib.api
+public class Reflector {
+    static int result;
+    static String message;
+
+    public static void main(String[] args) throws Exception {
+        lib.api.Api.hidden().getDeclaredField("key").setAccessible(true);
+        result = 1;
+        lib.api.Api.class.getDeclaredField("calls").setAccessible(true);
+        result = 2;
+        try {
+            lib.api.Api.class.getDeclaredField("secret").setAccessible(true);
+        } catch (IllegalAccessError e) {
+            message = e.getMessage();
+        }
+    }
+}
@@ -0,0 +1,10 @@
+package app;
+
+// Reads lib, which doesn't export lib.internal
+public class Sneaky {
+    static int result;
+
+    public static void main(String[] args) {
+        result = lib.internal.Hidden.secret();
+    }
+}
@@ -0,0 +1,10 @@
+package app;
+
+// Compiled against the class path, app doesn't read other
+public class Stranger {
+    static int result;
+
+    public static void main(String[] args) {
+        result = other.api.Other.value();
+    }
+}
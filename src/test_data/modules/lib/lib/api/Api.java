@@ -0,0 +1,15 @@
+package lib.api;
+
+public class Api {
+    public int calls;
+    private int secret;
+
+    public static int answer() {
+        return 42;
+    }
+
+    // Package lib opens to app without exporting it
+    public static Class<?> hidden() {
+        return lib.internal.Hidden.class;
+    }
+}
@@ -0,0 +1,10 @@
+package lib.internal;
+
+// Public, but in a package lib doesn't export
+public class Hidden {
+    private int key;
+
+    public static int secret() {
+        return 7;
+    }
+}
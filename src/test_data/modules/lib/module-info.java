@@ -0,0 +1,4 @@
+module lib {
+    exports lib.api;
+    opens lib.internal to app;
+}
@@ -0,0 +1,7 @@
+package other.api;
+
+public class Other {
+    public static int value() {
+        return 1;
+    }
+}
@@ -0,0 +1,52 @@
+package register;
+
+// Every method but main only computes with ints and longs, so they run on registers
+public class Sums {
+    static int gauss;
+    static long power;
+    static int fibonacci;
+    static int masked;
+
+    static int gauss(int n) {
+        int sum = 0;
+        for (int i = 1; i <= n; i++) {
+            sum += i;
+        }
+        return sum;
+    }
+
+    static long power(long base, int n) {
+        long result = 1;
+        for (int i = 0; i < n; i++) {
+            result *= base;
+        }
+        return result;
+    }
+
+    static int fibonacci(int n) {
+        int a = 0;
+        int b = 1;
+        while (n > 0) {
+            int next = a + b;
+            a = b;
+            b = next;
+            n--;
+        }
+        return a;
+    }
+
+    static int masked(int n) {
+        int bits = -1;
+        for (int i = 0; i < n; i++) {
+            bits = -(bits & (i * 3 + 1));
+        }
+        return bits;
+    }
+
+    public static void main(String[] args) {
+        gauss = gauss(100);
+        power = power(3L, 39);
+        fibonacci = fibonacci(40);
+        masked = masked(50);
+    }
+}
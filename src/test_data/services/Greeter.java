@@ -0,0 +1,5 @@
+package services;
+
+public interface Greeter {
+    int greet();
+}
@@ -0,0 +1,13 @@
+package services;
+
+public class Hello implements Greeter {
+    private final int greeting;
+
+    public Hello() {
+        greeting = 1;
+    }
+
+    public int greet() {
+        return greeting;
+    }
+}
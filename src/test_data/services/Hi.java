@@ -0,0 +1,7 @@
+package services;
+
+public class Hi implements Greeter {
+    public int greet() {
+        return 10;
+    }
+}
@@ -0,0 +1,40 @@
+package services;
+
+import java.io.IOException;
+import java.io.InputStream;
+import java.net.URL;
+import java.util.Enumeration;
+
+public class Main {
+    static int result;
+
+    public static void resource() {
+        URL url = ClassLoader.getSystemResource("META-INF/services/services.Greeter");
+        if (url != null && url.getProtocol() == "file" && url.getHost() == "") {
+            result = 1;
+        }
+    }
+
+    public static void resources() throws IOException {
+        Enumeration<URL> urls = ClassLoader.getSystemResources("java/lang/Object.class");
+        while (urls.hasMoreElements()) {
+            if (urls.nextElement().getProtocol() == "jar") {
+                result = result + 1;
+            }
+        }
+    }
+
+    // Counts the bytes of the service file
+    public static void resourceAsStream() throws IOException {
+        InputStream in = ClassLoader.getSystemResourceAsStream("META-INF/services/services.Greeter");
+        while (in.read() != -1) {
+            result = result + 1;
+        }
+    }
+
+    public static void missingResource() {
+        if (ClassLoader.getSystemResourceAsStream("missing") == null) {
+            result = 1;
+        }
+    }
+}
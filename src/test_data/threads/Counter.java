@@ -0,0 +1,15 @@
+package threads;
+
+public class Counter {
+    int value;
+
+    synchronized void increment() {
+        value = value + 1;
+    }
+
+    void add(int n) {
+        synchronized (this) {
+            value = value + n;
+        }
+    }
+}
@@ -0,0 +1,8 @@
+package threads;
+
+public class Crasher implements Runnable {
+    public void run() {
+        Counter counter = null;
+        counter.value = 1;
+    }
+}
@@ -0,0 +1,18 @@
+package threads;
+
+public class Incrementer implements Runnable {
+    private final Counter counter;
+    private final int times;
+
+    Incrementer(Counter counter, int times) {
+        this.counter = counter;
+        this.times = times;
+    }
+
+    public void run() {
+        for (int i = 0; i < times; i++) {
+            counter.increment();
+            counter.add(2);
+        }
+    }
+}
@@ -0,0 +1,24 @@
+package threads;
+
+public class Mailbox {
+    private int message;
+    private boolean full;
+
+    synchronized void put(int message) throws InterruptedException {
+        while (full) {
+            wait();
+        }
+        this.message = message;
+        full = true;
+        notifyAll();
+    }
+
+    synchronized int take() throws InterruptedException {
+        while (!full) {
+            wait();
+        }
+        full = false;
+        notifyAll();
+        return message;
+    }
+}
@@ -0,0 +1,79 @@
+package threads;
+
+public class Main {
+    static int result;
+
+    public static void counter() throws InterruptedException {
+        Counter counter = new Counter();
+        Thread a = new Thread(new Incrementer(counter, 50));
+        Thread b = new Thread(new Incrementer(counter, 50));
+        a.start();
+        b.start();
+        a.join();
+        b.join();
+        result = counter.value;
+    }
+
+    public static void producerConsumer() throws InterruptedException {
+        Mailbox mailbox = new Mailbox();
+        Producer producer = new Producer(mailbox, 10);
+        producer.start();
+        int sum = 0;
+        for (int i = 0; i < 10; i++) {
+            sum = sum + mailbox.take();
+        }
+        producer.join();
+        result = sum;
+    }
+
+    public static void sleep() throws InterruptedException {
+        Sleeper sleeper = new Sleeper(20);
+        sleeper.start();
+        sleeper.join();
+        result = sleeper.isAlive() ? 0 : 1;
+    }
+
+    public static void interrupt() throws InterruptedException {
+        Sleeper sleeper = new Sleeper(60000);
+        sleeper.start();
+        Thread.yield();
+        sleeper.interrupt();
+        sleeper.join();
+        result = 1;
+    }
+
+    public static void interruptTwice() throws InterruptedException {
+        Napper napper = new Napper();
+        napper.start();
+        Thread.yield();
+        napper.interrupt();
+        while (napper.naps == 0) {
+            Thread.yield();
+        }
+        napper.interrupt();
+        napper.join();
+        result = napper.naps;
+    }
+
+    public static void uncaught() throws InterruptedException {
+        Thread crasher = new Thread(new Crasher());
+        crasher.start();
+        crasher.join();
+        result = 1;
+    }
+
+    public static void race() throws InterruptedException {
+        Counter counter = new Counter();
+        Thread a = new Thread(new Racer(counter));
+        Thread b = new Thread(new Racer(counter));
+        a.start();
+        b.start();
+        a.join();
+        b.join();
+        result = counter.value;
+    }
+
+    public static void current() {
+        result = Thread.currentThread() == Thread.currentThread() ? 1 : 0;
+    }
+}
@@ -0,0 +1,15 @@
+package threads;
+
+public class Napper extends Thread {
+    int naps;
+
+    public void run() {
+        for (int i = 0; i < 2; i++) {
+            try {
+                Thread.sleep(60000);
+            } catch (InterruptedException e) {
+                naps = naps + 1;
+            }
+        }
+    }
+}
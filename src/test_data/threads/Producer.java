@@ -0,0 +1,20 @@
+package threads;
+
+public class Producer extends Thread {
+    private final Mailbox mailbox;
+    private final int count;
+
+    Producer(Mailbox mailbox, int count) {
+        this.mailbox = mailbox;
+        this.count = count;
+    }
+
+    public void run() {
+        try {
+            for (int i = 1; i <= count; i++) {
+                mailbox.put(i);
+            }
+        } catch (InterruptedException e) {
+        }
+    }
+}
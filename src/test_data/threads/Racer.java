@@ -0,0 +1,15 @@
+package threads;
+
+public class Racer implements Runnable {
+    private final Counter counter;
+
+    Racer(Counter counter) {
+        this.counter = counter;
+    }
+
+    public void run() {
+        for (int i = 0; i < 20; i++) {
+            counter.value = counter.value + 1;
+        }
+    }
+}
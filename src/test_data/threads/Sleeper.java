@@ -0,0 +1,16 @@
+package threads;
+
+public class Sleeper extends Thread {
+    private final long millis;
+
+    Sleeper(long millis) {
+        this.millis = millis;
+    }
+
+    public void run() {
+        try {
+            Thread.sleep(millis);
+        } catch (InterruptedException e) {
+        }
+    }
+}
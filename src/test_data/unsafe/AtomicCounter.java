@@ -0,0 +1,26 @@
+package unsafe;
+
+import jdk.internal.misc.Unsafe;
+
+public class AtomicCounter {
+    private final Unsafe u;
+    private final long valueOffset;
+    private volatile int value;
+
+    AtomicCounter(Unsafe u) {
+        this.u = u;
+        this.valueOffset = u.objectFieldOffset(AtomicCounter.class, "value");
+    }
+
+    int incrementAndGet() {
+        int v;
+        do {
+            v = u.getIntVolatile(this, valueOffset);
+        } while (!u.compareAndSetInt(this, valueOffset, v, v + 1));
+        return v + 1;
+    }
+
+    int get() {
+        return value;
+    }
+}
@@ -0,0 +1,15 @@
+package unsafe;
+
+public class Incrementer implements Runnable {
+    private final AtomicCounter counter;
+
+    Incrementer(AtomicCounter counter) {
+        this.counter = counter;
+    }
+
+    public void run() {
+        for (int i = 0; i < 20; i++) {
+            counter.incrementAndGet();
+        }
+    }
+}
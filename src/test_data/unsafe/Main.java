@@ -0,0 +1,39 @@
+package unsafe;
+
+import jdk.internal.misc.Unsafe;
+
+public class Main {
+    static int result;
+
+    public static void atomic(Unsafe u) throws InterruptedException {
+        AtomicCounter counter = new AtomicCounter(u);
+        Thread a = new Thread(new Incrementer(counter));
+        Thread b = new Thread(new Incrementer(counter));
+        a.start();
+        b.start();
+        a.join();
+        b.join();
+        result = counter.get();
+    }
+
+    public static void park(Unsafe u) throws InterruptedException {
+        Parker parker = new Parker(u);
+        parker.start();
+        Thread.yield();
+        u.unpark(parker);
+        parker.join();
+        result = 1;
+    }
+
+    public static void timedPark(Unsafe u) {
+        u.park(false, 5000000L);
+        result = 1;
+    }
+
+    public static void offHeap(Unsafe u) {
+        long address = u.allocateMemory(4);
+        u.putInt(address, 42);
+        result = u.getInt(address);
+        u.freeMemory(address);
+    }
+}
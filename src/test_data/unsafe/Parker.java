@@ -0,0 +1,15 @@
+package unsafe;
+
+import jdk.internal.misc.Unsafe;
+
+public class Parker extends Thread {
+    private final Unsafe u;
+
+    Parker(Unsafe u) {
+        this.u = u;
+    }
+
+    public void run() {
+        u.park(false, 0L);
+    }
+}
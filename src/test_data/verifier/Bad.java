@@ -0,0 +1,8 @@
+package verifier;
+
+/** Bad.class is patched to return `aconst_null` from `one`, which the verifier rejects. */
+public class Bad {
+    static int one() {
+        return 1;
+    }
+}
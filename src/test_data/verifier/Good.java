@@ -0,0 +1,63 @@
+package verifier;
+
+public class Good {
+    private long total;
+    private Object last;
+
+    public Good(long total) {
+        this.total = total;
+    }
+
+    static int sum(int[] values) {
+        int sum = 0;
+        for (int i = 0; i < values.length; i++) {
+            sum += values[i];
+        }
+        return sum;
+    }
+
+    long add(long value, double factor) {
+        total += (long) (value * factor);
+        return total;
+    }
+
+    Object pick(boolean first, String a, Good b) {
+        Object picked;
+        if (first) {
+            picked = a;
+        } else {
+            picked = b;
+        }
+        last = picked;
+        return picked;
+    }
+
+    static int parse(String s) {
+        try {
+            return s.hashCode();
+        } finally {
+            sum(new int[] {1, 2});
+        }
+    }
+
+    static Good[][] grid(int n) {
+        Good[][] grid = new Good[n][n];
+        for (Good[] row : grid) {
+            row[0] = new Good(n);
+        }
+        return grid;
+    }
+
+    synchronized int choose(int key) {
+        switch (key) {
+            case 1: return 10;
+            case 5: return 50;
+            case 1000: return 0;
+            default:
+                if (key > 0) {
+                    return key;
+                }
+                return -key;
+        }
+    }
+}
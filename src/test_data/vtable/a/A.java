@@ -0,0 +1,33 @@
+package vtable.a;
+
+public class A {
+
+    public int pub() {
+        return 1;
+    }
+
+    int pkg() {
+        return 1;
+    }
+
+    protected int prot() {
+        return 1;
+    }
+
+    private int priv() {
+        return 1;
+    }
+
+    public static int stat() {
+        return 1;
+    }
+
+    public int callPub() {
+        return pub();
+    }
+
+    public int callPkg() {
+        return pkg();
+    }
+
+}
@@ -0,0 +1,9 @@
+package vtable.a;
+
+public class B extends A {
+
+    int pkg() { // overrides A.pkg, same package
+        return 2;
+    }
+
+}
@@ -0,0 +1,19 @@
+package vtable.b;
+
+import vtable.a.B;
+
+public class C extends B {
+
+    public int pub() {
+        return 3;
+    }
+
+    int pkg() { // doesn't override B.pkg, different package
+        return 3;
+    }
+
+    protected int prot() {
+        return 3;
+    }
+
+}
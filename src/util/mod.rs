@@ -1,3 +1,2 @@
-pub mod code_reader;
 pub mod converter;
 pub mod modified_utf8;
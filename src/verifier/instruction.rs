@@ -0,0 +1,735 @@
+//! What each instruction does to the types of the locals and of the operand stack, see
+//! [§4.10.1.9](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-4.html#jvms-4.10.1.9).
+
+use crate::classfile::constant_info::ConstantInfo;
+use crate::verifier::types::{parse_field_type, parse_method_descriptor, TypeState, VType};
+use crate::verifier::Context;
+
+/// The state after an instruction and where control goes next.
+pub struct Effect {
+    pub state: TypeState,
+    /// Whether the next instruction can run after this one.
+    pub falls_through: bool,
+    /// Branch targets, which see `state` as well.
+    pub targets: Vec<usize>,
+}
+
+/// The length of the instruction at `pc`, operands included.
+pub fn instruction_length(code: &[u8], pc: usize) -> Result<usize, String> {
+    let length = match code[pc] {
+        0x10 | 0x12 | 0x15..=0x19 | 0x36..=0x3A | 0xA9 | 0xBC => 2,
+        0x11
+        | 0x13
+        | 0x14
+        | 0x84
+        | 0x99..=0xA8
+        | 0xB2..=0xB8
+        | 0xBB
+        | 0xBD
+        | 0xC0
+        | 0xC1
+        | 0xC6
+        | 0xC7 => 3,
+        0xC5 => 4,
+        0xB9 | 0xBA | 0xC8 | 0xC9 => 5,
+        0xC4 => match code.get(pc + 1) {
+            Some(0x84) => 6,
+            Some(0x15..=0x19 | 0x36..=0x3A | 0xA9) => 4,
+            _ => return Err("Bad wide instruction".to_string()),
+        },
+        0xAA => {
+            let base = (pc + 4) & !3;
+            let low = read_i32(code, base + 4)?;
+            let high = read_i32(code, base + 8)?;
+            if low > high {
+                return Err("Bad tableswitch bounds".to_string());
+            }
+            base + 12 + (high as i64 - low as i64 + 1) as usize * 4 - pc
+        }
+        0xAB => {
+            let base = (pc + 4) & !3;
+            let npairs = read_i32(code, base + 4)?;
+            if npairs < 0 {
+                return Err("Bad lookupswitch count".to_string());
+            }
+            base + 8 + npairs as usize * 8 - pc
+        }
+        0x00..=0xC9 => 1,
+        opcode => return Err(format!("Bad instruction: {:#04x}", opcode)),
+    };
+    if pc + length > code.len() {
+        return Err("Instruction runs past the end of the code".to_string());
+    }
+    Ok(length)
+}
+
+/// The pcs at which instructions start, in order.
+pub fn instruction_starts(code: &[u8]) -> Result<Vec<usize>, String> {
+    let mut starts = Vec::new();
+    let mut pc = 0;
+    while pc < code.len() {
+        starts.push(pc);
+        pc += instruction_length(code, pc)?;
+    }
+    Ok(starts)
+}
+
+fn read_u8(code: &[u8], at: usize) -> usize {
+    code[at] as usize
+}
+
+fn read_u16(code: &[u8], at: usize) -> usize {
+    (code[at] as usize) << 8 | code[at + 1] as usize
+}
+
+fn read_i16(code: &[u8], at: usize) -> i32 {
+    read_u16(code, at) as i16 as i32
+}
+
+fn read_i32(code: &[u8], at: usize) -> Result<i32, String> {
+    match code.get(at..at + 4) {
+        Some(bytes) => Ok(i32::from_be_bytes(bytes.try_into().unwrap())),
+        None => Err("Instruction runs past the end of the code".to_string()),
+    }
+}
+
+fn branch_target(pc: usize, offset: i32) -> Result<usize, String> {
+    usize::try_from(pc as i64 + offset as i64)
+        .map_err(|_| "Illegal target of jump or branch".to_string())
+}
+
+/// The types of locals and of the operand stack as an instruction changes them.
+struct Frame<'a> {
+    context: &'a Context<'a>,
+    state: TypeState,
+}
+
+impl Frame<'_> {
+    fn push(&mut self, vtype: VType) -> Result<(), String> {
+        if self.state.stack_size() + vtype.size() > self.context.method.max_stack {
+            return Err("Operand stack overflow".to_string());
+        }
+        self.state.stack.push(vtype);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<VType, String> {
+        self.state
+            .stack
+            .pop()
+            .ok_or_else(|| "Operand stack underflow".to_string())
+    }
+
+    /// Pops a value assignable to `expected`.
+    fn pop_as(&mut self, expected: &VType) -> Result<VType, String> {
+        let vtype = self.pop()?;
+        if !self.context.hierarchy.is_assignable(&vtype, expected) {
+            return Err(format!(
+                "Bad type on operand stack: {:?} isn't assignable to {:?}",
+                vtype, expected
+            ));
+        }
+        Ok(vtype)
+    }
+
+    fn pop_reference(&mut self) -> Result<VType, String> {
+        let vtype = self.pop()?;
+        if !vtype.is_reference() {
+            return Err(format!(
+                "Bad type on operand stack: {:?} isn't a reference",
+                vtype
+            ));
+        }
+        Ok(vtype)
+    }
+
+    /// Pops an initialized reference.
+    fn pop_object(&mut self) -> Result<VType, String> {
+        match self.pop_reference()? {
+            VType::UninitializedThis | VType::Uninitialized(_) => {
+                Err("Bad type on operand stack: uninitialized object".to_string())
+            }
+            vtype => Ok(vtype),
+        }
+    }
+
+    /// Pops values taking `words` words of the stack, returned bottom first.
+    fn pop_words(&mut self, words: usize) -> Result<Vec<VType>, String> {
+        let mut values = Vec::new();
+        let mut popped = 0;
+        while popped < words {
+            let vtype = self.pop()?;
+            popped += vtype.size();
+            values.insert(0, vtype);
+        }
+        if popped != words {
+            return Err("Bad type on operand stack: splitting a long or double".to_string());
+        }
+        Ok(values)
+    }
+
+    fn push_all(&mut self, values: &[VType]) -> Result<(), String> {
+        values.iter().try_for_each(|x| self.push(x.clone()))
+    }
+
+    fn local(&self, index: usize) -> Result<&VType, String> {
+        self.state
+            .locals
+            .get(index)
+            .ok_or_else(|| format!("Illegal local variable number {}", index))
+    }
+
+    /// The type of the local at `index`, which must be `expected` or any reference if
+    /// `expected` is one.
+    fn local_as(&self, index: usize, expected: &VType) -> Result<VType, String> {
+        let vtype = self.local(index)?.clone();
+        let matches = match expected {
+            VType::Reference(_) => vtype.is_reference(),
+            expected => vtype == *expected,
+        };
+        if !matches {
+            return Err(format!("Bad local variable type: {:?} at {}", vtype, index));
+        }
+        Ok(vtype)
+    }
+
+    fn load(&mut self, index: usize, expected: &VType) -> Result<(), String> {
+        let vtype = self.local_as(index, expected)?;
+        self.push(vtype)
+    }
+
+    fn store(&mut self, index: usize, vtype: VType) -> Result<(), String> {
+        self.local(index + vtype.size() - 1)?;
+        if index > 0 && self.state.locals[index - 1].size() == 2 {
+            self.state.locals[index - 1] = VType::Top;
+        }
+        if vtype.size() == 2 {
+            self.state.locals[index + 1] = VType::Top;
+        }
+        self.state.locals[index] = vtype;
+        Ok(())
+    }
+
+    fn pop_store(&mut self, index: usize, expected: &VType) -> Result<(), String> {
+        let vtype = match expected {
+            VType::Reference(_) => self.pop_reference()?,
+            expected => self.pop_as(expected)?,
+        };
+        self.store(index, vtype)
+    }
+
+    fn binary(&mut self, operand: VType) -> Result<(), String> {
+        self.pop_as(&operand)?;
+        self.pop_as(&operand)?;
+        self.push(operand)
+    }
+
+    fn unary(&mut self, from: VType, to: VType) -> Result<(), String> {
+        self.pop_as(&from)?;
+        self.push(to)
+    }
+
+    /// Pops an array whose type `is_component` accepts the component of, or `null`.
+    /// Returns the component type, `Null` for a `null` array.
+    fn pop_array(&mut self, is_component: impl Fn(&str) -> bool) -> Result<VType, String> {
+        self.pop_as(&VType::Int)?;
+        match self.pop()? {
+            VType::Null => Ok(VType::Null),
+            VType::Reference(name) if name.starts_with('[') && is_component(&name[1..]) => {
+                Ok(parse_field_type(&name[1..])?.0)
+            }
+            vtype => Err(format!(
+                "Bad type on operand stack: {:?} isn't the right array",
+                vtype
+            )),
+        }
+    }
+
+    fn array_load(&mut self, component: &str) -> Result<(), String> {
+        self.pop_array(|x| x == component)?;
+        let vtype = parse_field_type(component)?.0;
+        self.push(vtype)
+    }
+
+    fn array_store(&mut self, component: &str) -> Result<(), String> {
+        let vtype = parse_field_type(component)?.0;
+        self.pop_as(&vtype)?;
+        self.pop_array(|x| x == component)?;
+        Ok(())
+    }
+
+    /// Replaces `uninitialized` with `initialized` everywhere once a constructor is called.
+    fn initialize(&mut self, uninitialized: &VType, initialized: VType) {
+        let TypeState { locals, stack } = &mut self.state;
+        for vtype in locals.iter_mut().chain(stack.iter_mut()) {
+            if vtype == uninitialized {
+                *vtype = initialized.clone();
+            }
+        }
+    }
+}
+
+impl Context<'_> {
+    fn constant(&self, index: usize) -> Result<&ConstantInfo, String> {
+        self.class
+            .constant_pool
+            .vec_map
+            .get(index)
+            .ok_or_else(|| format!("Bad constant pool index {}", index))
+    }
+
+    fn utf8(&self, index: usize) -> Result<&str, String> {
+        match self.constant(index)? {
+            ConstantInfo::UTF8(val) => Ok(val),
+            _ => Err(format!("Constant {} isn't UTF8", index)),
+        }
+    }
+
+    pub fn class_name(&self, index: usize) -> Result<&str, String> {
+        match self.constant(index)? {
+            ConstantInfo::Class { name_index } => self.utf8(*name_index as usize),
+            _ => Err(format!("Constant {} isn't a class", index)),
+        }
+    }
+
+    /// The class, name and descriptor of a field, method or interface method reference.
+    fn member(&self, index: usize) -> Result<(&str, &str, &str), String> {
+        let (class_index, name_and_type_index) = match self.constant(index)? {
+            ConstantInfo::FieldRef {
+                class_index,
+                name_and_type_index,
+            }
+            | ConstantInfo::MethodRef {
+                class_index,
+                name_and_type_index,
+            }
+            | ConstantInfo::InterfaceMethodRef {
+                class_index,
+                name_and_type_index,
+            } => (*class_index as usize, *name_and_type_index as usize),
+            _ => return Err(format!("Constant {} isn't a member reference", index)),
+        };
+        match self.constant(name_and_type_index)? {
+            ConstantInfo::NameAndType {
+                name_index,
+                descriptor_index,
+            } => Ok((
+                self.class_name(class_index)?,
+                self.utf8(*name_index as usize)?,
+                self.utf8(*descriptor_index as usize)?,
+            )),
+            _ => Err(format!(
+                "Constant {} isn't a name and type",
+                name_and_type_index
+            )),
+        }
+    }
+
+    fn return_type(&self) -> Result<Option<VType>, String> {
+        Ok(parse_method_descriptor(self.method.descriptor())?.1)
+    }
+}
+
+/// The state after the instruction at `pc`, given the state before it.
+pub fn execute(context: &Context, pc: usize, state: &TypeState) -> Result<Effect, String> {
+    let code = &context.method.code[..];
+    let mut frame = Frame {
+        context,
+        state: state.clone(),
+    };
+    let mut falls_through = true;
+    let mut targets = Vec::new();
+    let int = VType::Int;
+    let float = VType::Float;
+    let long = VType::Long;
+    let double = VType::Double;
+    let object = VType::object();
+    match code[pc] {
+        // nop
+        0x00 => {}
+        // aconst_null
+        0x01 => frame.push(VType::Null)?,
+        // iconst_<i>, bipush, sipush
+        0x02..=0x08 | 0x10 | 0x11 => frame.push(int)?,
+        // lconst_<l>
+        0x09 | 0x0A => frame.push(long)?,
+        // fconst_<f>
+        0x0B..=0x0D => frame.push(float)?,
+        // dconst_<d>
+        0x0E | 0x0F => frame.push(double)?,
+        // ldc, ldc_w
+        0x12 | 0x13 => {
+            let index = match code[pc] {
+                0x12 => read_u8(code, pc + 1),
+                _ => read_u16(code, pc + 1),
+            };
+            let vtype = match context.constant(index)? {
+                ConstantInfo::Integer(_) => int,
+                ConstantInfo::Float(_) => float,
+                ConstantInfo::String(_) => VType::class("java/lang/String"),
+                ConstantInfo::Class { .. } => VType::class("java/lang/Class"),
+                _ => return Err(format!("Bad constant for ldc: {}", index)),
+            };
+            frame.push(vtype)?
+        }
+        // ldc2_w
+        0x14 => {
+            let vtype = match context.constant(read_u16(code, pc + 1))? {
+                ConstantInfo::Long(_) => long,
+                ConstantInfo::Double(_) => double,
+                _ => return Err("Bad constant for ldc2_w".to_string()),
+            };
+            frame.push(vtype)?
+        }
+        // iload, lload, fload, dload, aload
+        opcode @ 0x15..=0x19 => {
+            let vtype = [&int, &long, &float, &double, &object][(opcode - 0x15) as usize];
+            frame.load(read_u8(code, pc + 1), vtype)?
+        }
+        // <t>load_<n>
+        opcode @ 0x1A..=0x2D => {
+            let vtype = [&int, &long, &float, &double, &object][(opcode - 0x1A) as usize / 4];
+            frame.load((opcode - 0x1A) as usize % 4, vtype)?
+        }
+        // iaload, laload, faload, daload
+        0x2E => frame.array_load("I")?,
+        0x2F => frame.array_load("J")?,
+        0x30 => frame.array_load("F")?,
+        0x31 => frame.array_load("D")?,
+        // aaload
+        0x32 => {
+            let component = frame.pop_array(|x| x.starts_with('L') || x.starts_with('['))?;
+            frame.push(component)?
+        }
+        // baload, caload, saload
+        0x33 => {
+            frame.pop_array(|x| x == "B" || x == "Z")?;
+            frame.push(int)?
+        }
+        0x34 => frame.array_load("C")?,
+        0x35 => frame.array_load("S")?,
+        // istore, lstore, fstore, dstore, astore
+        opcode @ 0x36..=0x3A => {
+            let vtype = [&int, &long, &float, &double, &object][(opcode - 0x36) as usize];
+            frame.pop_store(read_u8(code, pc + 1), vtype)?
+        }
+        // <t>store_<n>
+        opcode @ 0x3B..=0x4E => {
+            let vtype = [&int, &long, &float, &double, &object][(opcode - 0x3B) as usize / 4];
+            frame.pop_store((opcode - 0x3B) as usize % 4, vtype)?
+        }
+        // iastore, lastore, fastore, dastore
+        0x4F => frame.array_store("I")?,
+        0x50 => frame.array_store("J")?,
+        0x51 => frame.array_store("F")?,
+        0x52 => frame.array_store("D")?,
+        // aastore, whose value is checked against the array at run time
+        0x53 => {
+            frame.pop_object()?;
+            frame.pop_array(|x| x.starts_with('L') || x.starts_with('['))?;
+        }
+        // bastore, castore, sastore
+        0x54 => {
+            frame.pop_as(&int)?;
+            frame.pop_array(|x| x == "B" || x == "Z")?;
+        }
+        0x55 => frame.array_store("C")?,
+        0x56 => frame.array_store("S")?,
+        // pop, pop2
+        0x57 => {
+            frame.pop_words(1)?;
+        }
+        0x58 => {
+            frame.pop_words(2)?;
+        }
+        // dup, dup_x1, dup_x2, dup2, dup2_x1, dup2_x2: copy the top `n` words below the
+        // `m` words under them
+        opcode @ 0x59..=0x5E => {
+            let (n, m) = [(1, 0), (1, 1), (1, 2), (2, 0), (2, 1), (2, 2)][(opcode - 0x59) as usize];
+            let top = frame.pop_words(n)?;
+            let under = frame.pop_words(m)?;
+            frame.push_all(&top)?;
+            frame.push_all(&under)?;
+            frame.push_all(&top)?
+        }
+        // swap
+        0x5F => {
+            let top = frame.pop_words(1)?;
+            let under = frame.pop_words(1)?;
+            frame.push_all(&top)?;
+            frame.push_all(&under)?
+        }
+        // <t>add, <t>sub, <t>mul, <t>div, <t>rem
+        opcode @ 0x60..=0x73 => {
+            let vtype = [&int, &long, &float, &double][(opcode - 0x60) as usize % 4];
+            frame.binary(vtype.clone())?
+        }
+        // <t>neg
+        opcode @ 0x74..=0x77 => {
+            let vtype = [&int, &long, &float, &double][(opcode - 0x74) as usize];
+            frame.unary(vtype.clone(), vtype.clone())?
+        }
+        // ishl, lshl, ishr, lshr, iushr, lushr
+        opcode @ 0x78..=0x7D => {
+            let vtype = [&int, &long][(opcode - 0x78) as usize % 2];
+            frame.pop_as(&int)?;
+            frame.unary(vtype.clone(), vtype.clone())?
+        }
+        // iand, land, ior, lor, ixor, lxor
+        opcode @ 0x7E..=0x83 => {
+            let vtype = [&int, &long][(opcode - 0x7E) as usize % 2];
+            frame.binary(vtype.clone())?
+        }
+        // iinc
+        0x84 => {
+            frame.local_as(read_u8(code, pc + 1), &int)?;
+        }
+        // i2l, i2f, i2d, l2i, l2f, l2d, f2i, f2l, f2d, d2i, d2l, d2f
+        opcode @ 0x85..=0x90 => {
+            let types = [&int, &long, &float, &double];
+            let from = (opcode - 0x85) as usize / 3;
+            let to =
+                [[1, 2, 3], [0, 2, 3], [0, 1, 3], [0, 1, 2]][from][(opcode - 0x85) as usize % 3];
+            frame.unary(types[from].clone(), types[to].clone())?
+        }
+        // i2b, i2c, i2s
+        0x91..=0x93 => frame.unary(int.clone(), int)?,
+        // lcmp, fcmpl, fcmpg, dcmpl, dcmpg
+        opcode @ 0x94..=0x98 => {
+            let vtype = [&long, &float, &float, &double, &double][(opcode - 0x94) as usize];
+            frame.pop_as(vtype)?;
+            frame.pop_as(vtype)?;
+            frame.push(int)?
+        }
+        // if<cond>
+        0x99..=0x9E => {
+            frame.pop_as(&int)?;
+            targets.push(branch_target(pc, read_i16(code, pc + 1))?)
+        }
+        // if_icmp<cond>
+        0x9F..=0xA4 => {
+            frame.pop_as(&int)?;
+            frame.pop_as(&int)?;
+            targets.push(branch_target(pc, read_i16(code, pc + 1))?)
+        }
+        // if_acmp<cond>
+        0xA5 | 0xA6 => {
+            frame.pop_reference()?;
+            frame.pop_reference()?;
+            targets.push(branch_target(pc, read_i16(code, pc + 1))?)
+        }
+        // goto
+        0xA7 => {
+            falls_through = false;
+            targets.push(branch_target(pc, read_i16(code, pc + 1))?)
+        }
+        // jsr, ret, jsr_w
+        0xA8 | 0xA9 | 0xC9 => return Err("jsr and ret aren't supported".to_string()),
+        // tableswitch, lookupswitch
+        opcode @ (0xAA | 0xAB) => {
+            frame.pop_as(&int)?;
+            falls_through = false;
+            let base = (pc + 4) & !3;
+            targets.push(branch_target(pc, read_i32(code, base)?)?);
+            let offsets = if opcode == 0xAA {
+                let count = read_i32(code, base + 8)? - read_i32(code, base + 4)? + 1;
+                (0..count as usize)
+                    .map(|i| base + 12 + i * 4)
+                    .collect::<Vec<_>>()
+            } else {
+                let npairs = read_i32(code, base + 4)? as usize;
+                (0..npairs).map(|i| base + 12 + i * 8).collect()
+            };
+            for at in offsets {
+                targets.push(branch_target(pc, read_i32(code, at)?)?);
+            }
+        }
+        // ireturn, lreturn, freturn, dreturn, areturn
+        opcode @ 0xAC..=0xB0 => {
+            let expected = context
+                .return_type()?
+                .ok_or_else(|| "Method expects no return value".to_string())?;
+            let vtype = [&int, &long, &float, &double, &object][(opcode - 0xAC) as usize];
+            let matches = match vtype {
+                VType::Reference(_) => expected.is_reference(),
+                vtype => *vtype == expected,
+            };
+            if !matches {
+                return Err("Wrong return type in function".to_string());
+            }
+            frame.pop_as(&expected)?;
+            falls_through = false
+        }
+        // return
+        0xB1 => {
+            if context.return_type()?.is_some() {
+                return Err("Method expects a return value".to_string());
+            }
+            if frame.state.locals.contains(&VType::UninitializedThis) {
+                return Err("Constructor must call super() or this() before return".to_string());
+            }
+            falls_through = false
+        }
+        // getstatic, putstatic, getfield, putfield
+        opcode @ 0xB2..=0xB5 => {
+            let (class_name, _, descriptor) = context.member(read_u16(code, pc + 1))?;
+            let (vtype, rest) = parse_field_type(descriptor)?;
+            if !rest.is_empty() {
+                return Err(format!("Invalid descriptor {}", descriptor));
+            }
+            let owner = VType::class(class_name);
+            match opcode {
+                0xB2 => frame.push(vtype)?,
+                0xB3 => {
+                    frame.pop_as(&vtype)?;
+                }
+                0xB4 => {
+                    frame.pop_as(&owner)?;
+                    frame.push(vtype)?
+                }
+                _ => {
+                    frame.pop_as(&vtype)?;
+                    // A constructor may set the fields of its own class before calling super()
+                    let receiver = frame.pop()?;
+                    let own_field =
+                        receiver == VType::UninitializedThis && class_name == context.class.name;
+                    if !own_field && !context.hierarchy.is_assignable(&receiver, &owner) {
+                        return Err(format!("Bad type on operand stack: {:?}", receiver));
+                    }
+                }
+            }
+        }
+        // invokevirtual, invokespecial, invokestatic, invokeinterface
+        opcode @ 0xB6..=0xB9 => {
+            let (class_name, name, descriptor) = context.member(read_u16(code, pc + 1))?;
+            let (params, ret) = parse_method_descriptor(descriptor)?;
+            if name.starts_with('<') && (opcode != 0xB7 || name != "<init>") {
+                return Err(format!("Illegal call to {}", name));
+            }
+            for param in params.iter().rev() {
+                frame.pop_as(param)?;
+            }
+            let owner = VType::class(class_name);
+            if name == "<init>" {
+                let receiver = frame.pop_reference()?;
+                let initialized = match receiver {
+                    VType::UninitializedThis => VType::class(&context.class.name),
+                    VType::Uninitialized(new_pc) => {
+                        VType::class(context.class_name(read_u16(code, new_pc + 1))?)
+                    }
+                    _ => return Err("Bad type on operand stack: initialized object".to_string()),
+                };
+                frame.initialize(&receiver, initialized);
+            } else if opcode != 0xB8 {
+                frame.pop_as(&owner)?;
+            }
+            if let Some(ret) = ret {
+                frame.push(ret)?
+            }
+        }
+        // new
+        0xBB => {
+            context.class_name(read_u16(code, pc + 1))?;
+            frame.push(VType::Uninitialized(pc))?
+        }
+        // newarray
+        0xBC => {
+            let descriptor = match code[pc + 1] {
+                4 => "[Z",
+                5 => "[C",
+                6 => "[F",
+                7 => "[D",
+                8 => "[B",
+                9 => "[S",
+                10 => "[I",
+                11 => "[J",
+                atype => return Err(format!("Bad array type {}", atype)),
+            };
+            frame.unary(int, VType::class(descriptor))?
+        }
+        // anewarray
+        0xBD => {
+            let component = VType::class(context.class_name(read_u16(code, pc + 1))?);
+            frame.unary(int, VType::array_of(&component))?
+        }
+        // arraylength
+        0xBE => match frame.pop()? {
+            VType::Null => frame.push(int)?,
+            VType::Reference(name) if name.starts_with('[') => frame.push(int)?,
+            vtype => {
+                return Err(format!(
+                    "Bad type on operand stack: {:?} isn't an array",
+                    vtype
+                ))
+            }
+        },
+        // athrow
+        0xBF => {
+            frame.pop_as(&VType::class("java/lang/Throwable"))?;
+            falls_through = false
+        }
+        // checkcast
+        0xC0 => {
+            let class_name = context.class_name(read_u16(code, pc + 1))?;
+            frame.pop_object()?;
+            frame.push(VType::class(class_name))?
+        }
+        // instanceof
+        0xC1 => {
+            context.class_name(read_u16(code, pc + 1))?;
+            frame.pop_object()?;
+            frame.push(int)?
+        }
+        // monitorenter, monitorexit
+        0xC2 | 0xC3 => {
+            frame.pop_object()?;
+        }
+        // wide
+        0xC4 => {
+            let index = read_u16(code, pc + 2);
+            match code[pc + 1] {
+                opcode @ 0x15..=0x19 => {
+                    let vtype = [&int, &long, &float, &double, &object][(opcode - 0x15) as usize];
+                    frame.load(index, vtype)?
+                }
+                opcode @ 0x36..=0x3A => {
+                    let vtype = [&int, &long, &float, &double, &object][(opcode - 0x36) as usize];
+                    frame.pop_store(index, vtype)?
+                }
+                0x84 => {
+                    frame.local_as(index, &int)?;
+                }
+                _ => return Err("jsr and ret aren't supported".to_string()),
+            }
+        }
+        // multianewarray
+        0xC5 => {
+            let class_name = context.class_name(read_u16(code, pc + 1))?;
+            let dimensions = read_u8(code, pc + 3);
+            if dimensions == 0 || class_name.chars().take_while(|x| *x == '[').count() < dimensions
+            {
+                return Err("Bad dimensions for multianewarray".to_string());
+            }
+            for _ in 0..dimensions {
+                frame.pop_as(&int)?;
+            }
+            frame.push(VType::class(class_name))?
+        }
+        // ifnull, ifnonnull
+        0xC6 | 0xC7 => {
+            frame.pop_reference()?;
+            targets.push(branch_target(pc, read_i16(code, pc + 1))?)
+        }
+        // goto_w
+        0xC8 => {
+            falls_through = false;
+            targets.push(branch_target(pc, read_i32(code, pc + 1)?)?)
+        }
+        opcode => return Err(format!("Bad instruction: {:#04x}", opcode)),
+    }
+    Ok(Effect {
+        state: frame.state,
+        falls_through,
+        targets,
+    })
+}
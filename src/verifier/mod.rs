@@ -0,0 +1,210 @@
+//! Verification of method code when a class is linked, see
+//! [§4.10](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-4.html#jvms-4.10).
+//!
+//! Class files of version 50 and above are checked against their `StackMapTable` by
+//! the type-checking verifier, falling back to the type-inference verifier for version
+//! 50 like HotSpot does. Older ones only go through type inference. Neither supports
+//! `jsr` and `ret`, so code using them is rejected.
+
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use crate::rtda::heap::class::Class;
+use crate::rtda::heap::method::Method;
+use crate::verifier::types::{parse_method_descriptor, Hierarchy, TypeState, VType};
+
+mod instruction;
+mod type_checker;
+mod type_inference;
+pub mod types;
+
+pub use instruction::instruction_length;
+
+/// The first class file version with a `StackMapTable`.
+const TYPE_CHECKING_VERSION: u16 = 50;
+
+/// Which classes get verified, chosen at startup with `-Xverify`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verification {
+    None,
+    /// Every class but those of the boot class path, which are trusted.
+    Remote,
+    All,
+}
+
+impl FromStr for Verification {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "none" => Ok(Verification::None),
+            "remote" => Ok(Verification::Remote),
+            "all" => Ok(Verification::All),
+            _ => Err(format!("Unknown verification mode: {}", name)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyError {
+    pub class: String,
+    pub method: String,
+    pub descriptor: String,
+    pub pc: usize,
+    pub message: String,
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} in method {}.{}{} at pc {}",
+            self.message, self.class, self.method, self.descriptor, self.pc
+        )
+    }
+}
+
+/// What verifying a method needs to know.
+pub struct Context<'a> {
+    pub class: &'a Class,
+    pub method: &'a Method,
+    pub hierarchy: Hierarchy,
+}
+
+impl Context<'_> {
+    fn error(&self, pc: usize, message: String) -> VerifyError {
+        VerifyError {
+            class: self.class.name.clone(),
+            method: self.method.name().to_string(),
+            descriptor: self.method.descriptor().to_string(),
+            pc,
+            message,
+        }
+    }
+
+    /// The types of `this` and of the parameters, each `long` or `double` followed by a
+    /// `Top`, as the method starts.
+    fn parameter_locals(&self) -> Result<Vec<VType>, String> {
+        let mut locals = Vec::new();
+        if !self.method.is_static() {
+            let is_constructor =
+                self.method.name() == "<init>" && self.class.name != "java/lang/Object";
+            locals.push(match is_constructor {
+                true => VType::UninitializedThis,
+                false => VType::class(&self.class.name),
+            });
+        }
+        for param in parse_method_descriptor(self.method.descriptor())?.0 {
+            let size = param.size();
+            locals.push(param);
+            if size == 2 {
+                locals.push(VType::Top);
+            }
+        }
+        if locals.len() > self.method.max_locals {
+            return Err("Arguments can't fit into locals".to_string());
+        }
+        Ok(locals)
+    }
+
+    fn initial_state(&self) -> Result<TypeState, String> {
+        let mut locals = self.parameter_locals()?;
+        locals.resize(self.method.max_locals, VType::Top);
+        Ok(TypeState {
+            locals,
+            stack: Vec::new(),
+        })
+    }
+
+    /// The type of the exception a handler catches.
+    fn catch_type(&self, catch_type: u16) -> Result<VType, String> {
+        Ok(match catch_type {
+            0 => VType::class("java/lang/Throwable"),
+            index => VType::class(self.class_name(index as usize)?),
+        })
+    }
+}
+
+/// Verifies the code of every method of `class`, loaded from a class file of
+/// `major_version`.
+pub fn verify(class: &Class, major_version: u16) -> Result<(), VerifyError> {
+    let loader = class.loader.upgrade().expect("ClassLoader is dropped");
+    for method in class.methods.iter().filter(|x| !x.code.is_empty()) {
+        let context = Context {
+            class,
+            method,
+            hierarchy: Hierarchy::new(Rc::clone(&loader)),
+        };
+        if major_version >= TYPE_CHECKING_VERSION {
+            match type_checker::check(&context) {
+                Err(_) if major_version == TYPE_CHECKING_VERSION => {
+                    type_inference::infer(&context)?
+                }
+                result => result?,
+            }
+        } else {
+            type_inference::infer(&context)?
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic;
+    use std::panic::AssertUnwindSafe;
+    use std::rc::Rc;
+
+    use crate::classpath::classpath::parse;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::verifier::*;
+
+    fn class_loader() -> Rc<ClassLoader> {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        ClassLoader::new(class_path)
+    }
+
+    #[test]
+    fn type_checking_and_inference() {
+        let class_loader = class_loader();
+        class_loader.set_verification(Verification::None);
+        let class = class_loader.load("verifier/Good");
+        assert_eq!(verify(&class, 52), Ok(()));
+        assert_eq!(verify(&class, 49), Ok(()));
+    }
+
+    #[test]
+    fn verify_error() {
+        let class_loader = class_loader();
+        class_loader.set_verification(Verification::None);
+        let class = class_loader.load("verifier/Bad");
+        let error = verify(&class, 52).unwrap_err();
+        assert_eq!(
+            (error.method.as_str(), error.descriptor.as_str(), error.pc),
+            ("one", "()I", 1)
+        );
+        assert_eq!(
+            error.message,
+            "Bad type on operand stack: Null isn't assignable to Int"
+        );
+        assert!(verify(&class, 49).is_err());
+    }
+
+    #[test]
+    fn verification_modes() {
+        let class_loader = class_loader();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| class_loader.load("verifier/Bad")));
+        let message = result.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.starts_with("java.lang.VerifyError: Bad type on operand stack"));
+        assert!(message.ends_with("in method verifier/Bad.one()I at pc 1"));
+
+        class_loader.set_verification(Verification::None);
+        class_loader.load("verifier/Bad");
+        assert_eq!("all".parse(), Ok(Verification::All));
+        assert!("some".parse::<Verification>().is_err());
+    }
+}
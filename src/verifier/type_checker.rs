@@ -0,0 +1,187 @@
+//! The type-checking verifier, which walks the code once and checks the state at each
+//! branch target against the frame the `StackMapTable` declares there, see
+//! [§4.10.1](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-4.html#jvms-4.10.1).
+
+use std::collections::BTreeMap;
+
+use crate::classfile::attribute_info::{StackMapFrame, VerificationTypeInfo};
+use crate::verifier::instruction::{execute, instruction_starts};
+use crate::verifier::types::{TypeState, VType};
+use crate::verifier::{Context, VerifyError};
+
+pub(super) fn check(context: &Context) -> Result<(), VerifyError> {
+    let code = &context.method.code[..];
+    let starts = instruction_starts(code).map_err(|x| context.error(0, x))?;
+    let frames = stack_map_frames(context, &starts)?;
+    let frame_at = |pc: usize, target: usize| {
+        frames.get(&target).ok_or_else(|| {
+            let message = format!("Expecting a stackmap frame at branch target {}", target);
+            context.error(pc, message)
+        })
+    };
+    let mut state = Some(context.initial_state().map_err(|x| context.error(0, x))?);
+    for (i, &pc) in starts.iter().enumerate() {
+        if let Some(frame) = frames.get(&pc) {
+            if let Some(ref state) = state {
+                if !is_assignable(context, state, frame) {
+                    let message = "Instruction type does not match stack map".to_string();
+                    return Err(context.error(pc, message));
+                }
+            }
+            state = Some(frame.clone());
+        }
+        let current = match state {
+            Some(ref state) => state,
+            None => return Err(context.error(pc, "Expecting a stack map frame".to_string())),
+        };
+        for entry in context.method.exception_table.iter() {
+            if (entry.start_pc as usize..entry.end_pc as usize).contains(&pc) {
+                let handler = frame_at(pc, entry.handler_pc as usize)?;
+                let exception = context
+                    .catch_type(entry.catch_type)
+                    .map_err(|x| context.error(pc, x))?;
+                let incoming = TypeState {
+                    locals: current.locals.clone(),
+                    stack: vec![exception],
+                };
+                if !is_assignable(context, &incoming, handler) {
+                    let message =
+                        "Stack map does not match the one at exception handler".to_string();
+                    return Err(context.error(pc, message));
+                }
+            }
+        }
+        let effect = execute(context, pc, current).map_err(|x| context.error(pc, x))?;
+        for target in effect.targets {
+            let frame = frame_at(pc, target)?;
+            if !is_assignable(context, &effect.state, frame) {
+                let message = format!("Inconsistent stack map frames at branch target {}", target);
+                return Err(context.error(pc, message));
+            }
+        }
+        if effect.falls_through && i + 1 == starts.len() {
+            return Err(context.error(pc, "Falling off the end of the code".to_string()));
+        }
+        state = match effect.falls_through {
+            true => Some(effect.state),
+            false => None,
+        };
+    }
+    Ok(())
+}
+
+fn is_assignable(context: &Context, from: &TypeState, to: &TypeState) -> bool {
+    let all_assignable = |from: &[VType], to: &[VType]| {
+        from.len() == to.len()
+            && from
+                .iter()
+                .zip(to)
+                .all(|(from, to)| context.hierarchy.is_assignable(from, to))
+    };
+    all_assignable(&from.locals, &to.locals) && all_assignable(&from.stack, &to.stack)
+}
+
+/// The frames of the `StackMapTable` by pc, with the locals the table leaves out set to
+/// `Top`.
+fn stack_map_frames(
+    context: &Context,
+    starts: &[usize],
+) -> Result<BTreeMap<usize, TypeState>, VerifyError> {
+    let error = |x| context.error(0, x);
+    let mut frames = BTreeMap::new();
+    // Locals as the table lists them, a `long` or `double` being a single entry
+    let mut locals = collapse(&context.parameter_locals().map_err(error)?);
+    let mut pc = None;
+    for frame in context.method.stack_map_table.iter().flatten() {
+        let offset = match pc {
+            None => frame.offset_delta() as usize,
+            Some(pc) => pc + frame.offset_delta() as usize + 1,
+        };
+        if starts.binary_search(&offset).is_err() {
+            let message = format!("StackMapTable error: bad offset {}", offset);
+            return Err(context.error(offset, message));
+        }
+        let stack = match frame {
+            StackMapFrame::Same { .. } => Vec::new(),
+            StackMapFrame::SameLocals1StackItem { stack, .. } => vec![stack.clone()],
+            StackMapFrame::Chop { k, .. } => {
+                let len = locals.len().checked_sub(*k as usize);
+                let len = len.ok_or_else(|| error("StackMapTable error: bad chop".to_string()))?;
+                locals.truncate(len);
+                Vec::new()
+            }
+            StackMapFrame::Append { locals: more, .. } => {
+                locals.extend(to_vtypes(context, more).map_err(error)?);
+                Vec::new()
+            }
+            StackMapFrame::Full {
+                locals: all, stack, ..
+            } => {
+                locals = to_vtypes(context, all).map_err(error)?;
+                stack.clone()
+            }
+        };
+        let state = TypeState {
+            locals: expand(&locals, context.method.max_locals).map_err(error)?,
+            stack: to_vtypes(context, &stack).map_err(error)?,
+        };
+        if state.stack_size() > context.method.max_stack {
+            return Err(error(
+                "StackMapTable error: stack exceeds max_stack".to_string(),
+            ));
+        }
+        frames.insert(offset, state);
+        pc = Some(offset);
+    }
+    Ok(frames)
+}
+
+fn to_vtypes(context: &Context, infos: &[VerificationTypeInfo]) -> Result<Vec<VType>, String> {
+    infos
+        .iter()
+        .map(|x| {
+            Ok(match x {
+                VerificationTypeInfo::Top => VType::Top,
+                VerificationTypeInfo::Integer => VType::Int,
+                VerificationTypeInfo::Float => VType::Float,
+                VerificationTypeInfo::Double => VType::Double,
+                VerificationTypeInfo::Long => VType::Long,
+                VerificationTypeInfo::Null => VType::Null,
+                VerificationTypeInfo::UninitializedThis => VType::UninitializedThis,
+                VerificationTypeInfo::Object { cpool_index } => {
+                    VType::class(context.class_name(*cpool_index as usize)?)
+                }
+                VerificationTypeInfo::Uninitialized { offset } => {
+                    VType::Uninitialized(*offset as usize)
+                }
+            })
+        })
+        .collect()
+}
+
+/// Drops the `Top` after each `long` and `double`.
+fn collapse(locals: &[VType]) -> Vec<VType> {
+    let mut collapsed = Vec::new();
+    let mut i = 0;
+    while i < locals.len() {
+        collapsed.push(locals[i].clone());
+        i += locals[i].size();
+    }
+    collapsed
+}
+
+/// Puts a `Top` after each `long` and `double` and pads with `Top` to `max_locals`.
+fn expand(locals: &[VType], max_locals: usize) -> Result<Vec<VType>, String> {
+    let mut expanded = Vec::with_capacity(max_locals);
+    for vtype in locals {
+        expanded.push(vtype.clone());
+        if vtype.size() == 2 {
+            expanded.push(VType::Top);
+        }
+    }
+    if expanded.len() > max_locals {
+        return Err("StackMapTable error: locals exceed max_locals".to_string());
+    }
+    expanded.resize(max_locals, VType::Top);
+    Ok(expanded)
+}
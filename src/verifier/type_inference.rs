@@ -0,0 +1,85 @@
+//! The type-inference verifier of class files older than version 50, which runs a data
+//! flow analysis over the code to infer the state at each instruction, see
+//! [§4.10.2](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-4.html#jvms-4.10.2).
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::verifier::instruction::{execute, instruction_starts};
+use crate::verifier::types::{TypeState, VType};
+use crate::verifier::{Context, VerifyError};
+
+pub(super) fn infer(context: &Context) -> Result<(), VerifyError> {
+    let code = &context.method.code[..];
+    let starts = instruction_starts(code).map_err(|x| context.error(0, x))?;
+    let initial_state = context.initial_state().map_err(|x| context.error(0, x))?;
+    let mut states = HashMap::new();
+    states.insert(0, initial_state);
+    let mut changed = BTreeSet::from([0]);
+    while let Some(pc) = changed.pop_first() {
+        let state = states[&pc].clone();
+        let mut successors = Vec::new();
+        for entry in context.method.exception_table.iter() {
+            if (entry.start_pc as usize..entry.end_pc as usize).contains(&pc) {
+                let exception = context
+                    .catch_type(entry.catch_type)
+                    .map_err(|x| context.error(pc, x))?;
+                let incoming = TypeState {
+                    locals: state.locals.clone(),
+                    stack: vec![exception],
+                };
+                successors.push((entry.handler_pc as usize, incoming));
+            }
+        }
+        let effect = execute(context, pc, &state).map_err(|x| context.error(pc, x))?;
+        for target in effect.targets {
+            successors.push((target, effect.state.clone()));
+        }
+        if effect.falls_through {
+            let index = starts.binary_search(&pc).unwrap();
+            match starts.get(index + 1) {
+                Some(&next) => successors.push((next, effect.state)),
+                None => {
+                    return Err(context.error(pc, "Falling off the end of the code".to_string()))
+                }
+            }
+        }
+        for (successor, incoming) in successors {
+            if starts.binary_search(&successor).is_err() {
+                let message = format!("Illegal target of jump or branch {}", successor);
+                return Err(context.error(pc, message));
+            }
+            let merged = match states.get(&successor) {
+                None => incoming,
+                Some(current) => {
+                    merge(context, current, &incoming).map_err(|x| context.error(successor, x))?
+                }
+            };
+            if states.get(&successor) != Some(&merged) {
+                states.insert(successor, merged);
+                changed.insert(successor);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The state at an instruction reached with both `a` and `b`.
+fn merge(context: &Context, a: &TypeState, b: &TypeState) -> Result<TypeState, String> {
+    if a.stack.len() != b.stack.len() {
+        return Err("Inconsistent stack height".to_string());
+    }
+    let mut stack = Vec::with_capacity(a.stack.len());
+    for (a, b) in a.stack.iter().zip(&b.stack) {
+        match context.hierarchy.merge(a, b) {
+            VType::Top => return Err("Mismatched stack types".to_string()),
+            merged => stack.push(merged),
+        }
+    }
+    let locals = a
+        .locals
+        .iter()
+        .zip(&b.locals)
+        .map(|(a, b)| context.hierarchy.merge(a, b))
+        .collect();
+    Ok(TypeState { locals, stack })
+}
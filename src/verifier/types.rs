@@ -0,0 +1,280 @@
+use std::rc::Rc;
+
+use crate::rtda::heap::class_loader::ClassLoader;
+
+/// The verification type of a local variable or an operand stack entry, see
+/// [§4.10.1.2](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-4.html#jvms-4.10.1.2).
+/// `boolean`, `byte`, `char` and `short` values are `Int`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VType {
+    Top,
+    Int,
+    Float,
+    Long,
+    Double,
+    Null,
+    /// `this` in a constructor before it calls another constructor.
+    UninitializedThis,
+    /// An object created by the `new` at this pc whose constructor isn't called yet.
+    Uninitialized(usize),
+    /// A class or interface by binary name, or an array by descriptor, like `[I`.
+    Reference(String),
+}
+
+impl VType {
+    /// How many local variables or words of the operand stack a value takes.
+    pub fn size(&self) -> usize {
+        match self {
+            VType::Long | VType::Double => 2,
+            _ => 1,
+        }
+    }
+
+    pub fn is_reference(&self) -> bool {
+        matches!(
+            self,
+            VType::Null | VType::UninitializedThis | VType::Uninitialized(_) | VType::Reference(_)
+        )
+    }
+
+    pub fn object() -> VType {
+        VType::Reference("java/lang/Object".to_string())
+    }
+
+    /// The type of a class constant, whose name is a binary name or an array descriptor.
+    pub fn class(name: &str) -> VType {
+        VType::Reference(name.to_string())
+    }
+
+    /// The type of an array whose components have the `component` type.
+    pub fn array_of(component: &VType) -> VType {
+        let descriptor = match component {
+            VType::Reference(name) if name.starts_with('[') => name.clone(),
+            VType::Reference(name) => format!("L{};", name),
+            VType::Int => "I".to_string(),
+            VType::Float => "F".to_string(),
+            VType::Long => "J".to_string(),
+            VType::Double => "D".to_string(),
+            _ => panic!("No array of {:?}", component),
+        };
+        VType::Reference(format!("[{}", descriptor))
+    }
+}
+
+/// Parses the field descriptor at the start of `descriptor`, returning its type and
+/// the rest of the string.
+pub fn parse_field_type(descriptor: &str) -> Result<(VType, &str), String> {
+    let invalid = || format!("Invalid descriptor {}", descriptor);
+    let first = descriptor.chars().next().ok_or_else(invalid)?;
+    let (vtype, len) = match first {
+        'B' | 'C' | 'I' | 'S' | 'Z' => (VType::Int, 1),
+        'F' => (VType::Float, 1),
+        'J' => (VType::Long, 1),
+        'D' => (VType::Double, 1),
+        'L' => {
+            let end = descriptor.find(';').ok_or_else(invalid)?;
+            (VType::class(&descriptor[1..end]), end + 1)
+        }
+        '[' => {
+            let dimensions = descriptor.chars().take_while(|x| *x == '[').count();
+            let (_, rest) = parse_field_type(&descriptor[dimensions..])?;
+            let len = descriptor.len() - rest.len();
+            (VType::class(&descriptor[..len]), len)
+        }
+        _ => return Err(invalid()),
+    };
+    Ok((vtype, &descriptor[len..]))
+}
+
+/// The parameter types and the return type, `None` for `void`, of a method descriptor.
+pub fn parse_method_descriptor(descriptor: &str) -> Result<(Vec<VType>, Option<VType>), String> {
+    let invalid = || format!("Invalid descriptor {}", descriptor);
+    let mut rest = descriptor.strip_prefix('(').ok_or_else(invalid)?;
+    let mut params = Vec::new();
+    while !rest.starts_with(')') {
+        let (param, next_rest) = parse_field_type(rest)?;
+        params.push(param);
+        rest = next_rest;
+    }
+    let ret = match &rest[1..] {
+        "V" => None,
+        ret => match parse_field_type(ret)? {
+            (ret, "") => Some(ret),
+            _ => return Err(invalid()),
+        },
+    };
+    Ok((params, ret))
+}
+
+/// The types of the local variables and of the operand stack at some instruction. A
+/// `long` or `double` local is followed by a `Top`, while the stack holds one entry per
+/// value whatever its size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeState {
+    pub locals: Vec<VType>,
+    pub stack: Vec<VType>,
+}
+
+impl TypeState {
+    /// Words of the operand stack in use.
+    pub fn stack_size(&self) -> usize {
+        self.stack.iter().map(|x| x.size()).sum()
+    }
+}
+
+/// Answers subtyping questions, loading the classes involved.
+pub struct Hierarchy {
+    loader: Rc<ClassLoader>,
+}
+
+impl Hierarchy {
+    pub fn new(loader: Rc<ClassLoader>) -> Hierarchy {
+        Hierarchy { loader }
+    }
+
+    /// Whether a value of type `from` can be used where `to` is expected. Every reference
+    /// is assignable to an interface, as in the JVMS, since interfaces are checked when
+    /// methods are invoked.
+    pub fn is_assignable(&self, from: &VType, to: &VType) -> bool {
+        match (from, to) {
+            (from, to) if from == to => true,
+            (_, VType::Top) => true,
+            (VType::Null, VType::Reference(_)) => true,
+            (VType::Reference(from), VType::Reference(to)) => self.is_class_assignable(from, to),
+            _ => false,
+        }
+    }
+
+    fn is_class_assignable(&self, from: &str, to: &str) -> bool {
+        if from == to || to == "java/lang/Object" {
+            return true;
+        }
+        if let Some(to_component) = to.strip_prefix('[') {
+            return match from.strip_prefix('[') {
+                Some(from_component) => {
+                    match (reference_name(from_component), reference_name(to_component)) {
+                        (Some(from), Some(to)) => self.is_class_assignable(from, to),
+                        _ => false,
+                    }
+                }
+                None => false,
+            };
+        }
+        let to = self.loader.load(to);
+        if to.is_interface() {
+            return true;
+        }
+        if from.starts_with('[') {
+            return false;
+        }
+        self.loader.load(from).is_sub_class_of(&to)
+    }
+
+    /// The type both `a` and `b` are assignable to that the old verifier merges them
+    /// into, `Top` if there's none.
+    pub fn merge(&self, a: &VType, b: &VType) -> VType {
+        match (a, b) {
+            (a, b) if a == b => a.clone(),
+            (VType::Null, VType::Reference(_)) => b.clone(),
+            (VType::Reference(_), VType::Null) => a.clone(),
+            (VType::Reference(a), VType::Reference(b)) => VType::class(&self.common_super(a, b)),
+            _ => VType::Top,
+        }
+    }
+
+    fn common_super(&self, a: &str, b: &str) -> String {
+        if a == b {
+            return a.to_string();
+        }
+        match (a.strip_prefix('['), b.strip_prefix('[')) {
+            (Some(a), Some(b)) => match (reference_name(a), reference_name(b)) {
+                (Some(a), Some(b)) => {
+                    match VType::array_of(&VType::class(&self.common_super(a, b))) {
+                        VType::Reference(name) => name,
+                        _ => unreachable!(),
+                    }
+                }
+                _ => "java/lang/Object".to_string(),
+            },
+            (None, None) => {
+                let a = self.loader.load(a);
+                let b = self.loader.load(b);
+                if a.is_interface() || b.is_interface() {
+                    return "java/lang/Object".to_string();
+                }
+                let mut class = Some(&a);
+                while let Some(x) = class {
+                    if Rc::ptr_eq(x, &b) || b.is_sub_class_of(x) {
+                        return x.name.clone();
+                    }
+                    class = x.super_class.as_ref();
+                }
+                "java/lang/Object".to_string()
+            }
+            _ => "java/lang/Object".to_string(),
+        }
+    }
+}
+
+/// The class name or array descriptor of a reference component descriptor, `None` for
+/// a primitive one.
+fn reference_name(descriptor: &str) -> Option<&str> {
+    if descriptor.starts_with('[') {
+        Some(descriptor)
+    } else {
+        descriptor.strip_prefix('L')?.strip_suffix(';')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::classpath::classpath::parse;
+    use crate::rtda::heap::class_loader::ClassLoader;
+    use crate::verifier::types::*;
+
+    #[test]
+    fn descriptors() {
+        let (params, ret) = parse_method_descriptor("(IJ[[Ljava/lang/String;Z)[D").unwrap();
+        assert_eq!(
+            params,
+            vec![
+                VType::Int,
+                VType::Long,
+                VType::class("[[Ljava/lang/String;"),
+                VType::Int
+            ]
+        );
+        assert_eq!(ret, Some(VType::class("[D")));
+        assert_eq!(parse_method_descriptor("()V").unwrap(), (vec![], None));
+        assert!(parse_method_descriptor("(Q)V").is_err());
+        assert!(parse_method_descriptor("(Ljava/lang/Object)V").is_err());
+    }
+
+    #[test]
+    fn assignable() {
+        let class_path = parse(
+            Some("src/test_data/jre".to_string()),
+            Some("src/test_data".to_string()),
+        );
+        let hierarchy = Hierarchy::new(ClassLoader::new(class_path));
+        let string = VType::class("java/lang/String");
+        let strings = VType::class("[Ljava/lang/String;");
+        assert!(hierarchy.is_assignable(&VType::Null, &string));
+        assert!(hierarchy.is_assignable(&string, &VType::object()));
+        assert!(hierarchy.is_assignable(&strings, &VType::class("[Ljava/lang/Object;")));
+        assert!(hierarchy.is_assignable(&strings, &VType::class("java/lang/Cloneable")));
+        assert!(!hierarchy.is_assignable(&VType::object(), &string));
+        assert!(!hierarchy.is_assignable(&VType::class("[I"), &strings));
+        assert!(!hierarchy.is_assignable(&VType::Int, &VType::Float));
+        assert!(!hierarchy.is_assignable(&VType::Uninitialized(0), &VType::object()));
+
+        let node = VType::class("gc/Node");
+        assert_eq!(hierarchy.merge(&string, &node), VType::object());
+        assert_eq!(hierarchy.merge(&VType::Null, &node), node);
+        assert_eq!(
+            hierarchy.merge(&strings, &VType::class("[Lgc/Node;")),
+            VType::class("[Ljava/lang/Object;")
+        );
+        assert_eq!(hierarchy.merge(&VType::Int, &VType::Float), VType::Top);
+    }
+}